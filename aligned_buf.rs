@@ -0,0 +1,66 @@
+// באפר בהקצאה מיושרת. Vec<u8> מיושר רק לבייט, ו-O_DIRECT דוחה כתובת שאינה כפולה של
+// גודל הבלוק הלוגי של ההתקן
+use std::alloc::{handle_alloc_error, Layout};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+// O_DIRECT דורש באפר, offset ואורך מיושרים. 4096 מכסה גם סקטורים של 512
+pub(crate) const DIRECT_ALIGN: usize = 4096;
+
+pub struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// בעלות בלעדית על ההקצאה, כמו Vec
+unsafe impl Send for AlignedBuffer {}
+unsafe impl Sync for AlignedBuffer {}
+
+impl AlignedBuffer {
+    // מאופס, כמו vec![0u8; len]. align חזקה של 2; posix_memalign דורש לפחות גודל של מצביע
+    pub fn new(len: usize, align: usize) -> Self {
+        if len == 0 {
+            return Self::default();
+        }
+        let align = align.max(std::mem::size_of::<*mut libc::c_void>());
+        let mut raw = std::ptr::null_mut();
+        let ret = unsafe { libc::posix_memalign(&mut raw, align, len) };
+        let Some(ptr) = NonNull::new(raw as *mut u8).filter(|_| ret == 0) else {
+            handle_alloc_error(Layout::from_size_align(len, align).unwrap());
+        };
+        unsafe { std::ptr::write_bytes(ptr.as_ptr(), 0, len) };
+        Self { ptr, len }
+    }
+}
+
+// ריק ובלי הקצאה - מה שנשאר מאחור אחרי mem::take
+impl Default for AlignedBuffer {
+    fn default() -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            len: 0,
+        }
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            unsafe { libc::free(self.ptr.as_ptr() as *mut libc::c_void) };
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::fs::File;
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::time::Duration;
+
+// SG_IO עם ATA PASS-THROUGH(16) - עובד גם דרך רוב מארזי USB-SATA (SAT)
+const SG_IO: libc::c_ulong = 0x2285;
+const SG_DXFER_NONE: libc::c_int = -1;
+const SG_DXFER_TO_DEV: libc::c_int = -2;
+const SG_DXFER_FROM_DEV: libc::c_int = -3;
+
+const ATA_16: u8 = 0x85;
+const ATA_IDENTIFY_DEVICE: u8 = 0xEC;
+const ATA_SECURITY_SET_PASSWORD: u8 = 0xF1;
+const ATA_SECURITY_ERASE_PREPARE: u8 = 0xF3;
+const ATA_SECURITY_ERASE_UNIT: u8 = 0xF4;
+const ATA_SECURITY_DISABLE_PASSWORD: u8 = 0xF6;
+
+const SECTOR: usize = 512;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[repr(C)]
+struct SgIoHdr {
+    interface_id: libc::c_int,
+    dxfer_direction: libc::c_int,
+    cmd_len: libc::c_uchar,
+    mx_sb_len: libc::c_uchar,
+    iovec_count: libc::c_ushort,
+    dxfer_len: libc::c_uint,
+    dxferp: *mut libc::c_void,
+    cmdp: *const libc::c_uchar,
+    sbp: *mut libc::c_uchar,
+    timeout: libc::c_uint,
+    flags: libc::c_uint,
+    pack_id: libc::c_int,
+    usr_ptr: *mut libc::c_void,
+    status: libc::c_uchar,
+    masked_status: libc::c_uchar,
+    msg_status: libc::c_uchar,
+    sb_len_wr: libc::c_uchar,
+    host_status: libc::c_ushort,
+    driver_status: libc::c_ushort,
+    resid: libc::c_int,
+    duration: libc::c_uint,
+    info: libc::c_uint,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Transfer {
+    None,
+    PioIn,
+    PioOut,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecurityInfo {
+    pub supported: bool,
+    pub enabled: bool,
+    pub locked: bool,
+    pub frozen: bool,
+    pub erase_time: Option<Duration>,
+}
+
+fn ata_command(
+    device: &File,
+    command: u8,
+    transfer: Transfer,
+    data: &mut [u8],
+    timeout: Duration,
+) -> io::Result<()> {
+    // byte 1: protocol, byte 2: t_dir/byt_blok/t_length (אורך בסקטורים מתוך sector count)
+    let (protocol, flags, direction) = match transfer {
+        Transfer::None => (3u8, 0x00u8, SG_DXFER_NONE),
+        Transfer::PioIn => (4, 0x0E, SG_DXFER_FROM_DEV),
+        Transfer::PioOut => (5, 0x06, SG_DXFER_TO_DEV),
+    };
+    let sectors = (data.len() / SECTOR) as u8;
+
+    let mut cdb = [0u8; 16];
+    cdb[0] = ATA_16;
+    cdb[1] = protocol << 1;
+    cdb[2] = flags;
+    cdb[6] = sectors;
+    cdb[14] = command;
+
+    let mut sense = [0u8; 32];
+    let mut hdr = SgIoHdr {
+        interface_id: b'S' as libc::c_int,
+        dxfer_direction: direction,
+        cmd_len: cdb.len() as libc::c_uchar,
+        mx_sb_len: sense.len() as libc::c_uchar,
+        iovec_count: 0,
+        dxfer_len: data.len() as libc::c_uint,
+        dxferp: data.as_mut_ptr() as *mut libc::c_void,
+        cmdp: cdb.as_ptr(),
+        sbp: sense.as_mut_ptr(),
+        timeout: timeout.as_millis().min(u32::MAX as u128) as libc::c_uint,
+        flags: 0,
+        pack_id: 0,
+        usr_ptr: std::ptr::null_mut(),
+        status: 0,
+        masked_status: 0,
+        msg_status: 0,
+        sb_len_wr: 0,
+        host_status: 0,
+        driver_status: 0,
+        resid: 0,
+        duration: 0,
+        info: 0,
+    };
+
+    let ret = unsafe { libc::ioctl(device.as_raw_fd(), SG_IO, &mut hdr as *mut SgIoHdr) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    if hdr.status != 0 || hdr.host_status != 0 || hdr.driver_status != 0 {
+        return Err(io::Error::other(format!(
+            "ATA command 0x{:02X} failed (status {}, host {}, driver {})",
+            command, hdr.status, hdr.host_status, hdr.driver_status
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn identify(device: &File) -> io::Result<SecurityInfo> {
+    let mut buf = [0u8; SECTOR];
+    ata_command(
+        device,
+        ATA_IDENTIFY_DEVICE,
+        Transfer::PioIn,
+        &mut buf,
+        COMMAND_TIMEOUT,
+    )?;
+
+    let word = |n: usize| u16::from_le_bytes([buf[n * 2], buf[n * 2 + 1]]);
+
+    // word 128: סטטוס אבטחה, word 89: זמן מחיקה משוער ביחידות של 2 דקות
+    let security = word(128);
+    let time_word = word(89);
+    let units = if time_word & 0x8000 != 0 {
+        time_word & 0x7FFF
+    } else {
+        time_word & 0x00FF
+    };
+
+    Ok(SecurityInfo {
+        supported: security & 0x0001 != 0,
+        enabled: security & 0x0002 != 0,
+        locked: security & 0x0004 != 0,
+        frozen: security & 0x0008 != 0,
+        erase_time: (units != 0).then(|| Duration::from_secs(units as u64 * 120)),
+    })
+}
+
+fn password_sector(password: &[u8], control: u16) -> [u8; SECTOR] {
+    let mut buf = [0u8; SECTOR];
+    buf[0..2].copy_from_slice(&control.to_le_bytes());
+    let len = password.len().min(32);
+    buf[2..2 + len].copy_from_slice(&password[..len]);
+    buf
+}
+
+// סיסמת user זמנית - המחיקה עצמה מנטרלת אותה בסוף
+pub fn set_password(device: &File, password: &[u8]) -> io::Result<()> {
+    let mut buf = password_sector(password, 0);
+    ata_command(
+        device,
+        ATA_SECURITY_SET_PASSWORD,
+        Transfer::PioOut,
+        &mut buf,
+        COMMAND_TIMEOUT,
+    )
+}
+
+// מבטל את הסיסמה הזמנית כשהמחיקה נכשלה - אחרת הכונן ננעל בהדלקה הבאה
+pub fn disable_password(device: &File, password: &[u8]) -> io::Result<()> {
+    let mut buf = password_sector(password, 0);
+    ata_command(
+        device,
+        ATA_SECURITY_DISABLE_PASSWORD,
+        Transfer::PioOut,
+        &mut buf,
+        COMMAND_TIMEOUT,
+    )
+}
+
+// PREPARE חייב לבוא מיד לפני ERASE UNIT. הקריאה חוסמת עד סוף המחיקה
+pub fn erase_unit(device: &File, password: &[u8], timeout: Duration) -> io::Result<()> {
+    ata_command(
+        device,
+        ATA_SECURITY_ERASE_PREPARE,
+        Transfer::None,
+        &mut [],
+        COMMAND_TIMEOUT,
+    )?;
+
+    let mut buf = password_sector(password, 0);
+    ata_command(
+        device,
+        ATA_SECURITY_ERASE_UNIT,
+        Transfer::PioOut,
+        &mut buf,
+        timeout,
+    )
+}
@@ -0,0 +1,99 @@
+// כמה באפרים מוקצים ל-1GiB עם ה-pool מול באפר חדש לכל chunk (כמו לפני ה-pool).
+// binary נפרד כי ה-allocator הסופר תופס את כל התהליך: cargo bench --bench buffer_pool
+#[allow(dead_code)]
+#[path = "../aligned_buf.rs"]
+mod aligned_buf;
+#[allow(dead_code)]
+#[path = "../source.rs"]
+mod source;
+
+use source::ReadStats;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+// סופר רק הקצאות בגודל שאף קוד אחר לא מבקש, כך שה-threads של ה-reader לא מזיזים את המונה
+const COUNTED_CHUNK: usize = 8 * 1024 * 1024 + 4096; // BUFFER_SIZE + 4096
+static CHUNK_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == COUNTED_CHUNK {
+            CHUNK_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        System.alloc(layout)
+    }
+
+    // vec![0; n] מגיע לכאן ולא ל-alloc
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == COUNTED_CHUNK {
+            CHUNK_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let total: u64 = 1024 * 1024 * 1024;
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+
+    let before = CHUNK_ALLOCATIONS.load(Ordering::Relaxed);
+    let started = Instant::now();
+    let (data_rx, reader) = source::spawn_read_thread(
+        std::io::repeat(0x5A).take(total),
+        COUNTED_CHUNK,
+        false,
+        false,
+        cancel_flag,
+        Arc::new(ReadStats::default()),
+    );
+    let mut read = 0;
+    for chunk in data_rx {
+        read += std::hint::black_box(&chunk[..]).len() as u64;
+    }
+    reader.join().unwrap().unwrap();
+    let pooled = CHUNK_ALLOCATIONS.load(Ordering::Relaxed) - before;
+    let pooled_secs = started.elapsed().as_secs_f64();
+    assert_eq!(read, total);
+
+    let before = CHUNK_ALLOCATIONS.load(Ordering::Relaxed);
+    let started = Instant::now();
+    let mut source = std::io::repeat(0x5A).take(total);
+    let mut read = 0;
+    loop {
+        let mut buffer = vec![0u8; COUNTED_CHUNK];
+        let n = source.read(&mut buffer).unwrap();
+        if n == 0 {
+            break;
+        }
+        buffer.truncate(n);
+        read += std::hint::black_box(buffer).len() as u64;
+    }
+    let unpooled = CHUNK_ALLOCATIONS.load(Ordering::Relaxed) - before;
+    let unpooled_secs = started.elapsed().as_secs_f64();
+    assert_eq!(read, total);
+
+    eprintln!(
+        "  pool: {:>4} allocations, {:>8.1} MB/s",
+        pooled,
+        total as f64 / pooled_secs / 1_000_000.0
+    );
+    eprintln!(
+        "no pool: {:>4} allocations, {:>8.1} MB/s",
+        unpooled,
+        total as f64 / unpooled_secs / 1_000_000.0
+    );
+    assert_eq!(pooled, source::POOL_BUFFERS);
+    assert!(unpooled as u64 > total / COUNTED_CHUNK as u64);
+}
@@ -0,0 +1,201 @@
+// השוואות קצב: צריבה מקצה לקצה דרך BurnEngine על tmpfs, כדי שהדיסק לא יסתיר את ההבדל,
+// וגיבוב על באפר בזיכרון
+// cargo bench --bench throughput [שם], בלי שם רצים כולם.
+// clippy בודק את ה-benches עם cfg(test), אז גם ה-imports של הבדיקות בתוך המודולים לא בשימוש
+#[allow(dead_code)]
+#[path = "../aligned_buf.rs"]
+mod aligned_buf;
+#[allow(dead_code)]
+#[path = "../ata.rs"]
+mod ata;
+#[allow(dead_code, unused_imports)]
+#[path = "../burn_engine.rs"]
+mod burn_engine;
+#[allow(dead_code, unused_macros)]
+#[path = "../i18n.rs"]
+mod i18n;
+#[allow(dead_code)]
+#[path = "../image_info.rs"]
+mod image_info;
+#[allow(dead_code)]
+#[path = "../mount.rs"]
+mod mount;
+#[allow(dead_code)]
+#[path = "../multiboot.rs"]
+mod multiboot;
+#[allow(dead_code)]
+#[path = "../partitions.rs"]
+mod partitions;
+#[allow(dead_code)]
+#[path = "../persistence.rs"]
+mod persistence;
+#[allow(dead_code, unused_imports)]
+#[path = "../signature.rs"]
+mod signature;
+#[allow(dead_code)]
+#[path = "../source.rs"]
+mod source;
+#[allow(dead_code)]
+#[path = "../windows.rs"]
+mod windows;
+
+use burn_engine::{BurnBackend, BurnConfig, BurnEngine, BurnEvent};
+use sha2::digest::DynDigest;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const MB: u64 = 1024 * 1024;
+
+fn main() {
+    // cargo bench מוסיף --bench לשורת הפקודה
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let benches: [(&str, fn()); 3] = [
+        ("uring", uring_vs_threads),
+        ("mmap", mmap_vs_read),
+        ("hash", hash_algorithms),
+    ];
+    for (name, bench) in benches {
+        if filter.as_deref().is_none_or(|f| name.contains(f)) {
+            eprintln!("{}:", name);
+            bench();
+        }
+    }
+}
+
+// thread מול io_uring על אותה תמונה
+fn uring_vs_threads() {
+    let source = random_file("burn-bench-source.img", 512 * MB);
+    let target = Path::new("/dev/shm/burn-bench-target.img");
+    let engine = BurnEngine::new();
+
+    for use_io_uring in [false, true] {
+        let mode = if use_io_uring { "io_uring" } else { "threads" };
+        let speed = burn(
+            &engine,
+            BurnConfig {
+                iso_path: source.clone(),
+                device_path: target.to_path_buf(),
+                use_io_uring,
+                validate_iso: false,
+                ..Default::default()
+            },
+        );
+        match speed {
+            Some(mbps) => eprintln!("{:>10}: {:>8.1} MB/s", mode, mbps),
+            // למשל seccomp שחוסם io_uring_setup
+            None => eprintln!("{:>10}: unavailable here, skipped", mode),
+        }
+    }
+
+    let _ = std::fs::remove_file(&source);
+    let _ = std::fs::remove_file(target);
+}
+
+// העתקה לבאפר מול mmap על תמונה של 2GiB. צריך כ-4GiB פנויים ב-/dev/shm
+fn mmap_vs_read() {
+    let source = random_file("burn-mmap-source.img", 2048 * MB);
+    let target = Path::new("/dev/shm/burn-mmap-target.img");
+    let engine = BurnEngine::new();
+
+    for use_mmap in [false, true] {
+        let mode = if use_mmap { "mmap" } else { "read" };
+        let speed = burn(
+            &engine,
+            BurnConfig {
+                iso_path: source.clone(),
+                device_path: target.to_path_buf(),
+                use_mmap,
+                validate_iso: false,
+                ..Default::default()
+            },
+        );
+        if let Some(mbps) = speed {
+            eprintln!("{:>10}: {:>8.1} MB/s", mode, mbps);
+        }
+    }
+
+    let _ = std::fs::remove_file(&source);
+    let _ = std::fs::remove_file(target);
+}
+
+// קצב הגיבוב לבד, על באפר בזיכרון ובחתיכות של BUFFER_SIZE כמו ב-Hasher של המנוע
+fn hash_algorithms() {
+    let data = std::fs::read(random_file("burn-hash-source.img", 256 * MB)).unwrap();
+    let _ = std::fs::remove_file("/dev/shm/burn-hash-source.img");
+
+    let started = Instant::now();
+    let mut blake3 = blake3::Hasher::new();
+    for chunk in data.chunks(burn_engine::BUFFER_SIZE) {
+        blake3.update_rayon(chunk);
+    }
+    std::hint::black_box(blake3.finalize());
+    report("BLAKE3", data.len(), started);
+
+    let digests: [(&str, Box<dyn DynDigest>); 4] = [
+        ("SHA-256", Box::<sha2::Sha256>::default()),
+        ("SHA-512", Box::<sha2::Sha512>::default()),
+        ("MD5", Box::<md5::Md5>::default()),
+        ("SHA-1", Box::<sha1::Sha1>::default()),
+    ];
+    for (name, mut digest) in digests {
+        let started = Instant::now();
+        for chunk in data.chunks(burn_engine::BUFFER_SIZE) {
+            digest.update(chunk);
+        }
+        std::hint::black_box(digest.finalize());
+        report(name, data.len(), started);
+    }
+}
+
+fn report(name: &str, bytes: usize, started: Instant) {
+    let secs = started.elapsed().as_secs_f64();
+    eprintln!(
+        "{:>10}: {:>8.1} MB/s",
+        name,
+        bytes as f64 / secs / 1_000_000.0
+    );
+}
+
+// צריבה אחת עד Finished. None = המנוע נכשל, והשגיאה כבר הודפסה
+fn burn(engine: &BurnEngine, cfg: BurnConfig) -> Option<f64> {
+    // המנוע לא יוצר יעד שלא קיים, כמו התקן שנעלם
+    File::create(&cfg.device_path).unwrap();
+    let events = engine.events();
+    engine.start(cfg);
+    loop {
+        match events.recv().ok()? {
+            BurnEvent::Finished {
+                bytes,
+                elapsed_secs,
+                ..
+            } => return Some(bytes as f64 / elapsed_secs / 1_000_000.0),
+            BurnEvent::Error(e) => {
+                eprintln!("{}", e);
+                return None;
+            }
+            BurnEvent::Cancelled => return None,
+            _ => {}
+        }
+    }
+}
+
+// נכתב בחתיכות - לא מחזיקים את כל התמונה בזיכרון של התהליך
+fn random_file(name: &str, size: u64) -> PathBuf {
+    let path = Path::new("/dev/shm").join(name);
+    let mut file = File::create(&path).unwrap();
+    let mut state = 1u64;
+    let mut block = vec![0u8; MB as usize];
+    for _ in 0..size / MB {
+        for word in block.chunks_mut(8) {
+            // xorshift64 - מספיק אקראי למדידת קצב
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            word.copy_from_slice(&state.to_le_bytes());
+        }
+        file.write_all(&block).unwrap();
+    }
+    path
+}
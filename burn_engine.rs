@@ -1,251 +1,8934 @@
-use crossbeam_channel::{bounded, Receiver, Sender};
+use crate::aligned_buf::DIRECT_ALIGN;
+use crate::ata;
+use crate::image_info::{self, ImageSummary};
+use crate::mount::{self, Mount};
+use crate::multiboot;
+use crate::partitions::{self, NewPartition, PartitionInfo, PartitionScheme, PartitionType};
+use crate::persistence::{self, LiveFlavor};
+use crate::signature;
+use crate::source::{self, Chunk, ReadStats, ReaderHandle, CHANNEL_DEPTH};
+use crate::windows::{self, InstallImage};
+use crossbeam_channel::{bounded, unbounded, Receiver, RecvTimeoutError, Sender};
+use io_uring::{opcode, squeue, types, IoUring};
+use serde::{Deserialize, Serialize};
+use sha2::digest::DynDigest;
+use sha2::{Sha256, Sha512};
+use std::collections::VecDeque;
 use std::fs::{File, OpenOptions};
-use std::io::{Read, Write};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::{FileExt, FileTypeExt, MetadataExt, OpenOptionsExt};
 use std::os::unix::io::AsRawFd;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex,
 };
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-const BUFFER_SIZE: usize = 8 * 1024 * 1024; // 8MB
-const CHANNEL_DEPTH: usize = 4;
+pub(crate) const BUFFER_SIZE: usize = 8 * 1024 * 1024; // 8MB
 
-#[derive(Debug)]
+// ב-user_data של io_uring: אינדקס הבאפר, והביט הזה מסמן כתיבה
+const URING_WRITE: u64 = 1 << 63;
+
+// בבדיקת משטח כל סקטור של 512 בייט מקבל תבנית לפי ה-LBA שלו
+const SURFACE_SECTOR: u64 = 512;
+// בבדיקת קיבולת משווים בבלוקים של 1MB ועוצרים בראשון שנכשל
+const CAPACITY_BLOCK: usize = 1024 * 1024;
+// wipe_on_cancel מאפס את זה מתחילת התמונה
+const CANCEL_WIPE_BYTES: u64 = 1024 * 1024;
+
+// ioctls של block devices (linux/fs.h)
+const BLKGETSIZE64: libc::c_ulong = 0x80081272;
+const BLKDISCARD: libc::c_ulong = 0x1277;
+const BLKFLSBUF: libc::c_ulong = 0x1261;
+const BLKROGET: libc::c_ulong = 0x125E;
+
+const DEFAULT_SPEED_EMA_ALPHA: f64 = 0.3;
+const DEFAULT_WRITE_RETRIES: u32 = 3;
+// ההשהיה מוכפלת בכל ניסיון: 200ms, 400ms, 800ms...
+const WRITE_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+const DEFAULT_WRITE_TIMEOUT_SECS: u64 = 30;
+// כל החתימות שמצב בטוח מחפש נמצאות בטווח הזה
+const FILESYSTEM_PROBE_LEN: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct BurnConfig {
+    // כל תמונת דיסק גולמית: .iso, .img, .bin, .raw (וגם בגרסה דחוסה). נכתבת כמו שהיא
     pub iso_path: PathBuf,
     pub device_path: PathBuf,
-    pub verify: bool,
+    pub verify_mode: VerifyMode,
+    pub pre_discard: bool,
+    pub target_offset: u64,
+    pub wipe_on_cancel: bool,
+    // השוואת בייטים מול ה-ISO במקום hash - קורא את המקור פעמיים
+    pub byte_compare: bool,
+    pub eject_after: bool,
+    // משקל הדגימה החדשה בממוצע הנע של המהירות (0..1)
+    pub speed_ema_alpha: f64,
+    // בדיקת ISO 9660 לפני שנוגעים בהתקן
+    pub validate_iso: bool,
+    // תקרת מהירות כתיבה ב-MB/s (None = בלי הגבלה)
+    pub max_speed_mbps: Option<f64>,
+    // CRC32 לכל בלוק בזמן הכתיבה, ובבדיקה קוראים רק את ההתקן
+    pub fast_verify: bool,
+    // אפסים על כל ההתקן (מה-offset והלאה) לפני הכתיבה
+    pub wipe_before_burn: bool,
+    // בבדיקת בייטים: להמשיך אחרי אי-התאמה ולסכם את כל האזורים הפגומים
+    pub verify_full_scan: bool,
+    // כמה פעמים לנסות שוב chunk שנכשל ב-EIO וכו' לפני שמוותרים
+    pub write_retries: u32,
+    // קריאה וכתיבה דרך io_uring במקום שני threads. רק למקור לא דחוס
+    pub use_io_uring: bool,
+    // כתיבה ב-O_DIRECT, בלי page cache: ההתקדמות היא מה שהכונן קיבל ולא מה שנכנס לזיכרון.
+    // מערכת קבצים שלא תומכת (יעד שהוא קובץ) - ממשיכים כרגיל עם אזהרה
+    pub use_direct_io: bool,
+    // fdatasync כל כמה בייטים, כדי שאחרי נפילת חשמל לפחות ההתחלה כבר על המדיה.
+    // איטי יותר. resume_from_offset (פיצ'ר נפרד) ימשיך מה-Checkpoint האחרון
+    pub sync_interval_bytes: Option<u64>,
+    // ה-hash של הכתיבה והבדיקה. BLAKE3 מקבילי ומהיר בהרבה, SHA-2 להשוואה מול סכומים של הפצות
+    pub hash_algorithm: HashAlgorithm,
+    // המקור ממופה לזיכרון וה-chunks הם טווחים בתוכו, בלי העתקה לבאפר. רק למקור לא דחוס
+    pub use_mmap: bool,
+    // מחיצת persistence בגודל הזה (בבייטים) אחרי התמונה. רק לתמונות live של Ubuntu/Debian
+    pub persistence_bytes: Option<u64>,
+    // כל מה שנשאר אחרי התמונה (ואחרי ה-persistence) כמחיצת FAT32/exFAT שנראית גם ב-Windows
+    pub data_partition: bool,
+    // גודל ה-chunk שנקרא ונכתב בכל פעם. הבדיקה קוראת תמיד ב-BUFFER_SIZE
+    pub buffer_size: usize,
+    // כתיבה שלא חזרה אחרי כל כך הרבה שניות = הכונן נתקע. None = מחכים לנצח
+    pub write_timeout_secs: Option<u64>,
+    // אבחון לזיכרון או hub פגומים: CRC32 לכל chunk בקריאה, ובדיקה שלו לפני הכתיבה.
+    // עולה CPU, אז רק כשמבקשים
+    pub paranoid: bool,
+    // דורס גם כונן שיש עליו מערכת קבצים מוכרת, ומנתק קודם מחיצות מעוגנות שלו.
+    // רק אחרי שהמשתמש אישר במפורש
+    pub force_overwrite: bool,
+    // /dev/disk/by-id של הכונן מזמן הסריקה. USB reset יכול למספר אותו מחדש (sdb -> sdc),
+    // אז בודקים שהקישור עדיין מצביע לצומת שפתחנו - לפני הכתיבה וכל כמה שניות בזמנה
+    pub device_link: Option<PathBuf>,
+    // תמונת התקנה של Windows נכתבת כמו שהיא במקום העתקת הקבצים ל-FAT32.
+    // רק כשהמשתמש בחר את זה אחרי האזהרה
+    pub windows_raw: bool,
+    // DeviceToFile הופך את הכיוון: device_path נקרא ונשמר לקובץ, ו-iso_path לא בשימוש
+    pub direction: BurnDirection,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BurnDirection {
+    #[default]
+    IsoToDevice,
+    // גיבוי: אותו מסלול של CloneToImage, בלי דחיסה ועוצרים בשגיאת קריאה
+    DeviceToFile {
+        output_path: PathBuf,
+    },
+}
+
+impl Default for BurnConfig {
+    fn default() -> Self {
+        Self {
+            iso_path: PathBuf::new(),
+            device_path: PathBuf::new(),
+            verify_mode: VerifyMode::Off,
+            pre_discard: false,
+            target_offset: 0,
+            wipe_on_cancel: false,
+            byte_compare: false,
+            eject_after: false,
+            speed_ema_alpha: DEFAULT_SPEED_EMA_ALPHA,
+            validate_iso: true,
+            max_speed_mbps: None,
+            fast_verify: false,
+            wipe_before_burn: false,
+            verify_full_scan: false,
+            write_retries: DEFAULT_WRITE_RETRIES,
+            use_io_uring: false,
+            use_direct_io: false,
+            sync_interval_bytes: None,
+            hash_algorithm: HashAlgorithm::Blake3,
+            use_mmap: false,
+            persistence_bytes: None,
+            data_partition: false,
+            buffer_size: BUFFER_SIZE,
+            write_timeout_secs: Some(DEFAULT_WRITE_TIMEOUT_SECS),
+            force_overwrite: false,
+            paranoid: false,
+            device_link: None,
+            windows_raw: false,
+            direction: BurnDirection::IsoToDevice,
+        }
+    }
+}
+
+// הגדרות מוכנות למי שלא רוצה להבין גודל באפר ואלגוריתם בדיקה. הפרופיל קובע רק את
+// השדות שב-ConfigOverrides - כל השאר, וגם הם אחר כך, נשארים של המשתמש
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BurnProfile {
+    Fast,
+    #[default]
+    Balanced,
+    Paranoid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    pub buffer_size: usize,
+    pub verify_mode: VerifyMode,
+    pub byte_compare: bool,
+    pub hash_algorithm: HashAlgorithm,
+    pub sync_interval_bytes: Option<u64>,
+    pub pre_discard: bool,
+}
+
+impl BurnProfile {
+    pub fn into_config_overrides(self) -> ConfigOverrides {
+        const MB: usize = 1024 * 1024;
+        match self {
+            BurnProfile::Fast => ConfigOverrides {
+                buffer_size: 16 * MB,
+                verify_mode: VerifyMode::Off,
+                byte_compare: false,
+                hash_algorithm: HashAlgorithm::Blake3,
+                sync_interval_bytes: None,
+                pre_discard: false,
+            },
+            BurnProfile::Balanced => ConfigOverrides {
+                buffer_size: BUFFER_SIZE,
+                verify_mode: VerifyMode::Full,
+                byte_compare: true,
+                hash_algorithm: HashAlgorithm::Blake3,
+                sync_interval_bytes: None,
+                pre_discard: false,
+            },
+            BurnProfile::Paranoid => ConfigOverrides {
+                buffer_size: 4 * MB,
+                verify_mode: VerifyMode::Full,
+                byte_compare: false,
+                hash_algorithm: HashAlgorithm::Sha256,
+                sync_interval_bytes: Some(256 * MB as u64),
+                pre_discard: true,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyMode {
+    Full,
+    // ההתחלה, הסוף וחלונות אקראיים באמצע
+    Quick,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum HashAlgorithm {
+    Blake3,
+    Sha256,
+    Sha512,
+    // רק לקבצי checksum ישנים (MD5SUMS / SHA1SUMS)
+    Md5,
+    Sha1,
+}
+
+impl HashAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Blake3 => "BLAKE3",
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha512 => "SHA-512",
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Sha1 => "SHA-1",
+        }
+    }
+
+    // אפשר לייצר התנגשויות - התאמה מעידה על הורדה תקינה, לא על מקור אמין
+    pub fn is_weak(self) -> bool {
+        matches!(self, HashAlgorithm::Md5 | HashAlgorithm::Sha1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum VerifyMethod {
+    Hash(HashAlgorithm),
+    Crc32,
+    ByteCompare,
+    Sampled,
+}
+
+// ב-JSON: אובייקט שטוח עם "type" = שם ה-variant
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
 pub enum BurnEvent {
     Preparing,
+    Discarded {
+        elapsed_secs: f64,
+    },
     Progress {
         written: u64,
         total: u64,
-        speed_mbps: f64,
+        // ממוצע הקריאה מהמקור מתחילת הזרם. 0 כשאין מקור לקרוא ממנו (למשל secure erase)
+        read_speed_mbps: f64,
+        write_speed_mbps: f64,
+        // הערכה לפי מהירות הכתיבה הנוכחית
+        eta_secs: u64,
     },
     Verifying {
         checked: u64,
         total: u64,
     },
-    Finished,
+    VerifySummary {
+        method: VerifyMethod,
+        ok: bool,
+    },
+    // bytes = מה שנכתב (או נבדק, ב-VerifyOnly), מתחילת הפעולה
+    Finished {
+        bytes: u64,
+        elapsed_secs: f64,
+        verified: bool,
+    },
+    WipePass {
+        pass: u32,
+        passes: u32,
+    },
+    VerifySkipped {
+        reason: String,
+    },
+    // כל מה שלפני at_bytes (מתחילת התמונה) כבר נשמר על המדיה
+    Checkpoint {
+        at_bytes: u64,
+    },
+    // מחיצת persistence אחרי הכתיבה והבדיקה. size ב-Created הוא מה שנכנס בפועל
+    PersistenceStarted {
+        label: String,
+        size: u64,
+    },
+    PersistenceCreated {
+        partition: PathBuf,
+        label: String,
+        size: u64,
+    },
+    // מחיצת נתונים במקום שנשאר: קודם הטבלה, אחר כך mkfs
+    DataPartitionAdded {
+        partition: PathBuf,
+        size: u64,
+    },
+    DataPartitionFormatted {
+        partition: PathBuf,
+        filesystem: String,
+    },
+    // תמונת התקנה של Windows: מחיצת FAT32 והעתקת קבצים במקום כתיבה גולמית.
+    // split = ה-install image גדול מ-4GB ויחולק לקבצי .swm
+    WindowsInstaller {
+        image: String,
+        size: u64,
+        split: bool,
+    },
+    SplittingInstallImage,
+    // אחר כך Progress רגיל, על סך כל הקבצים
+    CopyingFiles {
+        files: u64,
+        total: u64,
+    },
+    // כונן multiboot: Prepared מסיים את ההכנה (בלי Finished), MenuUpdated אחרי כל
+    // ISO שנוסף - entries הם השמות בתפריט של GRUB
+    MultibootPrepared {
+        boot: PathBuf,
+        data: PathBuf,
+    },
+    MultibootMenuUpdated {
+        entries: Vec<String>,
+    },
+    // שחזור כונן: Wiping על הקצוות, טבלה חדשה, mkfs, ובסוף Finished
+    Partitioning {
+        scheme: PartitionScheme,
+    },
+    Formatting {
+        partition: PathBuf,
+        filesystem: String,
+        label: String,
+    },
+    // שורה מהפלט של כלי חיצוני (mkfs), ללוג
+    ToolOutput {
+        line: String,
+    },
+    Wiping {
+        wiped: u64,
+        total: u64,
+    },
+    Hashing {
+        done: u64,
+        total: u64,
+    },
+    ChecksumChecked {
+        algorithm: HashAlgorithm,
+        matches: bool,
+        digest: String,
+    },
+    // אחרון לכל צריבה, לפני JobEnded - גם להיסטוריה וגם לסיכום ב-UI
+    Report(BurnReport),
+    // ה-hash של הרקע אחרי בחירת תמונה. path - כדי שה-UI יזהה תוצאה של תמונה קודמת
+    ImageHashed {
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        digest: String,
+    },
+    SignatureVerified {
+        signer: String,
+    },
+    // לא קריטי - הפעולה ממשיכה
+    Warning {
+        message: String,
+    },
+    // טבלת המחיצות שבתוך התמונה. ריק = מערכת קבצים בלי טבלה
+    ImageInfo {
+        partitions: Vec<PartitionInfo>,
+    },
+    // סוף בדיקת משטח. ריק = כל הבלוקים נקראו בחזרה כמו שנכתבו
+    SurfaceReport {
+        bad_ranges: Vec<BadRange>,
+        total_bytes: u64,
+    },
+    // verified = כמה בייטים מההתחלה אפשר לסמוך עליהם. פחות מ-advertised = זיכרון מזויף או פגום.
+    // wraps_at = הכתובת שממנה הכתיבות חוזרות להתחלה, אם הכונן עוטף
+    CapacityReport {
+        advertised: u64,
+        verified: u64,
+        wraps_at: Option<u64>,
+    },
+    // שכפול לקובץ דחוס: read = בייטים גולמיים מההתקן, written = מה שנכתב לקובץ
+    CloneProgress {
+        read: u64,
+        total: u64,
+        written: u64,
+        speed_mbps: f64,
+        eta_secs: u64,
+    },
+    // כמו Progress, אבל של מדידת מהירות - ה-UI לא מבלבל אותה עם צריבה
+    BenchmarkProgress {
+        phase: BenchmarkPhase,
+        done: u64,
+        total: u64,
+        speed_mbps: f64,
+    },
+    // write = None כשהמשתמש לא ביקש בדיקת כתיבה
+    BenchmarkReport {
+        read: BenchmarkResult,
+        write: Option<BenchmarkResult>,
+    },
+    // אירוע של יעד אחד בצריבה לכמה התקנים (Progress, Verifying, Error...)
+    Target {
+        device: PathBuf,
+        event: Box<BurnEvent>,
+    },
+    // סוף צריבה לכמה התקנים - שורה לכל יעד, לפי הסדר שנבחרו
+    MultiFinished {
+        targets: Vec<TargetOutcome>,
+        elapsed_secs: f64,
+    },
+    // פקודה נכנסה לתור. position = כמה עבודות לפניה (0 = מתחילה עכשיו)
+    JobQueued {
+        id: u64,
+        label: String,
+        position: usize,
+    },
+    // כל האירועים עד ה-JobEnded עם אותו id שייכים לעבודה הזו
+    JobStarted {
+        id: u64,
+    },
+    JobEnded {
+        id: u64,
+        state: JobState,
+    },
+    QueueStatus {
+        jobs: Vec<JobInfo>,
+    },
+    Ejected,
+    // לא קריטי - הצריבה עצמה הצליחה
+    EjectFailed {
+        reason: String,
+    },
     Cancelled,
-    Error(String),
+    Error(BurnError),
 }
 
-pub enum BurnCommand {
-    Start(BurnConfig),
-    Cancel,
+// טווח סקטורים רצוף שנכשל, כולל שני הקצוות
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BadRange {
+    pub first_lba: u64,
+    pub last_lba: u64,
 }
 
-pub struct BurnEngine {
-    cmd_tx: Sender<BurnCommand>,
-    pub event_rx: Receiver<BurnEvent>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BenchmarkPhase {
+    Read,
+    Write,
 }
 
-impl BurnEngine {
-    pub fn new() -> Self {
-        let (cmd_tx, cmd_rx) = bounded::<BurnCommand>(2);
-        let (event_tx, event_rx) = bounded::<BurnEvent>(32);
+// MB/s. samples = מהירות בכל שנייה, לפי הסדר
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkResult {
+    pub bytes: u64,
+    pub elapsed_secs: f64,
+    pub min_mbps: f64,
+    pub avg_mbps: f64,
+    pub max_mbps: f64,
+    pub samples: Vec<f64>,
+}
 
-        thread::spawn(move || {
-            let cancel_flag = Arc::new(AtomicBool::new(false));
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum JobState {
+    Pending,
+    Active,
+    Done,
+    Failed,
+    Cancelled,
+}
 
-            while let Ok(cmd) = cmd_rx.recv() {
-                match cmd {
-                    BurnCommand::Start(cfg) => {
-                        cancel_flag.store(false, Ordering::Relaxed);
-                        run_burn(cfg, &event_tx, cancel_flag.clone());
-                    }
-                    BurnCommand::Cancel => {
-                        cancel_flag.store(true, Ordering::Relaxed);
-                    }
-                }
-            }
-        });
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: u64,
+    pub label: String,
+    pub state: JobState,
+}
 
-        Self { cmd_tx, event_rx }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BurnOutcome {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// שורה אחת בהיסטוריה לכל צריבה, גם שנכשלה או בוטלה: "מה שמתי על המקל הזה והאם נבדק"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnReport {
+    // שניות מאז epoch, מתחילת הצריבה
+    pub timestamp: u64,
+    pub image: PathBuf,
+    // רק אם ה-hash של הרקע הספיק להסתיים - לא קוראים את התמונה שוב בשבילו
+    pub image_sha256: Option<String>,
+    pub device: PathBuf,
+    pub device_model: Option<String>,
+    pub device_serial: Option<String>,
+    pub device_size: Option<u64>,
+    pub bytes_written: u64,
+    // 0 בהיסטוריה שנכתבה לפני השדה
+    #[serde(default)]
+    pub bytes_verified: u64,
+    pub avg_speed_mbps: f64,
+    // None = בלי בדיקה
+    pub verified: Option<bool>,
+    pub duration_secs: f64,
+    pub outcome: BurnOutcome,
+    pub error: Option<String>,
+}
+
+// צריבה אחת מההתחלה ועד הסוף, כפי שנראתה מהאירועים שלה. נשמרת במנוע אחרי שהיא נגמרת
+#[derive(Debug, Clone)]
+pub struct BurnSession {
+    pub config: BurnConfig,
+    pub start_time: Instant,
+    pub bytes_written: u64,
+    pub bytes_verified: u64,
+    // SHA-256 של התמונה שנכתבה, אם ה-hash של הרקע הספיק להסתיים
+    pub final_hash: Option<String>,
+    // None כל עוד הצריבה רצה
+    pub outcome: Option<BurnOutcome>,
+    pub verified: Option<bool>,
+    pub error: Option<String>,
+    timestamp: u64,
+    // הכונן נקרא לפני הצריבה - אחרי eject הוא כבר לא ב-sysfs
+    device_model: Option<String>,
+    device_serial: Option<String>,
+    device_size: Option<u64>,
+}
+
+impl BurnSession {
+    fn new(cfg: &BurnConfig) -> Self {
+        let (device_model, device_serial, device_size) = device_identity(&cfg.device_path);
+        Self {
+            config: cfg.clone(),
+            start_time: Instant::now(),
+            bytes_written: 0,
+            bytes_verified: 0,
+            final_hash: None,
+            outcome: None,
+            verified: None,
+            error: None,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            device_model,
+            device_serial,
+            device_size,
+        }
     }
 
-    pub fn start(&self, cfg: BurnConfig) {
-        let _ = self.cmd_tx.send(BurnCommand::Start(cfg));
+    fn observe(&mut self, event: &BurnEvent) {
+        match event {
+            BurnEvent::Progress { written, .. } => self.bytes_written = *written,
+            BurnEvent::Finished { bytes, .. } => self.bytes_written = *bytes,
+            BurnEvent::Verifying { checked, .. } => self.bytes_verified = *checked,
+            BurnEvent::VerifySummary { ok, .. } => self.verified = Some(*ok),
+            BurnEvent::Error(e) => self.error = Some(e.to_string()),
+            _ => {}
+        }
     }
 
-    pub fn cancel(&self) {
-        let _ = self.cmd_tx.send(BurnCommand::Cancel);
+    fn finish(&mut self, state: JobState, digests: &DigestCache) {
+        self.outcome = Some(match state {
+            JobState::Done => BurnOutcome::Completed,
+            JobState::Cancelled => BurnOutcome::Cancelled,
+            _ => BurnOutcome::Failed,
+        });
+        self.final_hash = digests.get(&self.config.iso_path, HashAlgorithm::Sha256);
     }
-}
 
-fn run_burn(cfg: BurnConfig, event_tx: &Sender<BurnEvent>, cancel_flag: Arc<AtomicBool>) {
-    let _ = event_tx.send(BurnEvent::Preparing);
+    pub fn duration(&self) -> Duration {
+        self.start_time.elapsed()
+    }
 
-    let total_size = match std::fs::metadata(&cfg.iso_path) {
-        Ok(m) => m.len(),
-        Err(e) => {
-            let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-            return;
+    // הרשומה שנשמרת בהיסטוריה. Instant ו-BurnConfig לא נכתבים לקובץ
+    pub fn report(&self) -> BurnReport {
+        let duration_secs = self.duration().as_secs_f64();
+        BurnReport {
+            timestamp: self.timestamp,
+            image: self.config.iso_path.clone(),
+            image_sha256: self.final_hash.clone(),
+            device: self.config.device_path.clone(),
+            device_model: self.device_model.clone(),
+            device_serial: self.device_serial.clone(),
+            device_size: self.device_size,
+            bytes_written: self.bytes_written,
+            bytes_verified: self.bytes_verified,
+            avg_speed_mbps: if duration_secs > 0.0 {
+                self.bytes_written as f64 / duration_secs / (1024.0 * 1024.0)
+            } else {
+                0.0
+            },
+            verified: self.verified,
+            duration_secs,
+            outcome: self.outcome.unwrap_or(BurnOutcome::Failed),
+            error: self.error.clone(),
         }
-    };
+    }
+}
 
-    let mut iso = match File::open(&cfg.iso_path) {
-        Ok(f) => f,
-        Err(e) => {
-            let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-            return;
-        }
+// דגם, מספר סידורי וגודל מ-/sys/block. קובץ יעד או התקן וירטואלי - מה שאין נשאר None
+fn device_identity(device: &Path) -> (Option<String>, Option<String>, Option<u64>) {
+    let node = std::fs::canonicalize(device).unwrap_or_else(|_| device.to_path_buf());
+    let Some(name) = node.file_name() else {
+        return (None, None, None);
     };
-
-    let mut device = match OpenOptions::new().write(true).open(&cfg.device_path) {
-        Ok(f) => f,
-        Err(e) => {
-            let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-            return;
-        }
+    let block = Path::new("/sys/block").join(name);
+    let read = |file: &str| {
+        std::fs::read_to_string(block.join(file))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
     };
+    let size = read("size")
+        .and_then(|sectors| sectors.parse::<u64>().ok())
+        .map(|sectors| sectors * 512);
+    (read("device/model"), read("device/serial"), size)
+}
 
-    // hint לקרנל
-    unsafe {
-        libc::posix_fadvise(iso.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
-    }
+// error = None - נכתב (ונבדק, אם ביקשו) בהצלחה
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetOutcome {
+    pub device: PathBuf,
+    pub bytes: u64,
+    pub avg_mbps: f64,
+    pub verified: bool,
+    pub error: Option<BurnError>,
+}
 
-    let (data_tx, data_rx) = bounded::<Vec<u8>>(CHANNEL_DEPTH);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ErrorKind {
+    Io,
+    PermissionDenied,
+    DeviceTooSmall,
+    VerificationFailed,
+    BadSignature,
+    InvalidIso,
+    // מתג נעילה פיזי, או התקן שהקרנל סימן לקריאה בלבד
+    WriteProtected,
+    // כתיבה שלא חזרה מהקרנל - כונן או חיבור USB תקועים
+    WriteTimeout,
+    // chunk השתנה בזיכרון בין הקריאה לכתיבה (paranoid)
+    BufferCorrupted,
+    // על ההתקן יש מערכת קבצים - אולי כונן גיבוי שנבחר בטעות
+    FilesystemDetected,
+    // הכונן שהמערכת רצה ממנו (/, /boot, swap, LVM וכו')
+    SystemDisk,
+    // הקישור ב-by-id כבר לא מצביע לצומת שאליו כותבים - הכונן מוספר מחדש
+    DeviceChanged,
+    // יעד שנעצר באמצע בלי שגיאה משלו. ביטול של עבודה שלמה נשאר BurnEvent::Cancelled
+    Cancelled,
+    // מחיצה של הכונן מעוגנת, ולא ביקשו לנתק אותה
+    Unmount,
+}
 
-    // Reader
-    let reader_cancel = cancel_flag.clone();
-    let reader = thread::spawn(move || loop {
-        if reader_cancel.load(Ordering::Relaxed) {
-            break;
+#[derive(Debug, Clone, Serialize)]
+pub struct BurnError {
+    pub kind: ErrorKind,
+    pub message: String,
+    // ה-kind נגזר ממנו כשהוא קיים, אז השניים לא יכולים לסתור. שדות שטוחים ב-JSON
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    detail: Option<ErrorDetail>,
+    // השגיאה של מערכת ההפעלה, ל-source(). Arc כי אירועים משוכפלים
+    #[serde(skip)]
+    source: Option<Arc<std::io::Error>>,
+}
+
+// מה שאפשר לפעול לפיו. variant אחד לכל ErrorKind שיש לו פרטים, באותו שם
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum ErrorDetail {
+    // המיקום של קריאה או כתיבה שנכשלו, ואיזו מהשתיים. בקריאת המקור - המיקום בזרם
+    Io { offset: u64, direction: IoDirection },
+    // ההתקן או הקובץ שלא נפתח - ה-UI מציע לנסות שוב עם הרשאות
+    PermissionDenied { path: PathBuf },
+    // כמה בייטים צריך וכמה יש
+    DeviceTooSmall { needed: u64, available: u64 },
+    // רק בבדיקה שהשווה בייטים
+    VerificationFailed { mismatch: VerifyMismatch },
+    FilesystemDetected { fs_type: String },
+    WriteTimeout { stalled_for_secs: u64 },
+    // נקודות העיגון שמחזיקות את הכונן
+    Unmount { mounts: Vec<PathBuf> },
+}
+
+impl ErrorDetail {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ErrorDetail::Io { .. } => ErrorKind::Io,
+            ErrorDetail::PermissionDenied { .. } => ErrorKind::PermissionDenied,
+            ErrorDetail::DeviceTooSmall { .. } => ErrorKind::DeviceTooSmall,
+            ErrorDetail::VerificationFailed { .. } => ErrorKind::VerificationFailed,
+            ErrorDetail::FilesystemDetected { .. } => ErrorKind::FilesystemDetected,
+            ErrorDetail::WriteTimeout { .. } => ErrorKind::WriteTimeout,
+            ErrorDetail::Unmount { .. } => ErrorKind::Unmount,
         }
+    }
+}
 
-        let mut buffer = vec![0u8; BUFFER_SIZE];
+// כתיבה נכשלה = הכונן או החיבור. קריאה נכשלה בבדיקה או בשכפול = סקטור פגום או מקור שבור
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IoDirection {
+    Read,
+    Write,
+}
 
-        let read_bytes = match iso.read(&mut buffer) {
-            Ok(0) => break,
-            Ok(n) => n,
-            Err(_) => break,
+// הבייט הראשון שלא תאם על ההתקן, ו-16 הבייטים שמתחילים בו משני הצדדים
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifyMismatch {
+    pub offset: u64,
+    pub expected: [u8; MISMATCH_BYTES],
+    pub actual: [u8; MISMATCH_BYTES],
+}
+
+const MISMATCH_BYTES: usize = 16;
+
+impl VerifyMismatch {
+    // pos = האינדקס הראשון שונה בתוך הבאפרים. בסוף הבאפר משלימים באפסים
+    fn at(expected: &[u8], actual: &[u8], pos: usize, offset: u64) -> Self {
+        let mut mismatch = Self {
+            offset,
+            expected: [0; MISMATCH_BYTES],
+            actual: [0; MISMATCH_BYTES],
         };
+        let end = (pos + MISMATCH_BYTES).min(expected.len()).min(actual.len());
+        mismatch.expected[..end - pos].copy_from_slice(&expected[pos..end]);
+        mismatch.actual[..end - pos].copy_from_slice(&actual[pos..end]);
+        mismatch
+    }
+}
 
-        buffer.truncate(read_bytes);
+impl BurnError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            detail: None,
+            source: None,
+        }
+    }
 
-        if data_tx.send(buffer).is_err() {
-            break;
+    fn detailed(detail: ErrorDetail, message: impl Into<String>) -> Self {
+        Self {
+            kind: detail.kind(),
+            detail: Some(detail),
+            ..Self::new(ErrorKind::Io, message)
         }
-    });
+    }
 
-    // Writer
-    let start_time = Instant::now();
-    let mut written: u64 = 0;
-    let mut last_progress = Instant::now();
+    pub fn detail(&self) -> Option<&ErrorDetail> {
+        self.detail.as_ref()
+    }
 
-    for chunk in data_rx {
-        if cancel_flag.load(Ordering::Relaxed) {
-            let _ = event_tx.send(BurnEvent::Cancelled);
-            return;
+    pub fn mismatch(&self) -> Option<&VerifyMismatch> {
+        match &self.detail {
+            Some(ErrorDetail::VerificationFailed { mismatch }) => Some(mismatch),
+            _ => None,
         }
+    }
 
-        if let Err(e) = device.write_all(&chunk) {
-            let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-            return;
+    pub fn fs_type(&self) -> Option<&str> {
+        match &self.detail {
+            Some(ErrorDetail::FilesystemDetected { fs_type }) => Some(fs_type),
+            _ => None,
         }
+    }
 
-        written += chunk.len() as u64;
+    // הנתיב נשמר רק כשאין הרשאה - שם יש מה לעשות איתו
+    fn opening(e: std::io::Error, path: &Path) -> Self {
+        let error = Self::from(e);
+        match error.kind {
+            ErrorKind::PermissionDenied => Self {
+                detail: Some(ErrorDetail::PermissionDenied {
+                    path: path.to_path_buf(),
+                }),
+                ..error
+            },
+            _ => error,
+        }
+    }
 
-        // עדכון כל ~100ms
-        if last_progress.elapsed() >= Duration::from_millis(100) {
-            let elapsed = start_time.elapsed().as_secs_f64();
-            let speed = written as f64 / elapsed / (1024.0 * 1024.0);
+    // ENOSPC או EROFS באמצע הכתיבה נשארים מהסוג שלהם, בלי המיקום
+    fn io_at(e: std::io::Error, offset: u64, direction: IoDirection) -> Self {
+        let error = Self::from(e);
+        match error.kind {
+            ErrorKind::Io => Self {
+                detail: Some(ErrorDetail::Io { offset, direction }),
+                ..error
+            },
+            _ => error,
+        }
+    }
 
-            let _ = event_tx.send(BurnEvent::Progress {
-                written,
-                total: total_size,
-                speed_mbps: speed,
-            });
+    fn too_small(needed: u64, available: u64, message: impl Into<String>) -> Self {
+        Self::detailed(ErrorDetail::DeviceTooSmall { needed, available }, message)
+    }
 
-            last_progress = Instant::now();
-        }
+    fn write_timeout(stalled_for_secs: u64) -> Self {
+        Self::detailed(
+            ErrorDetail::WriteTimeout { stalled_for_secs },
+            format!(
+                "The drive stopped responding: a write has not completed in {} seconds",
+                stalled_for_secs
+            ),
+        )
     }
 
-    let _ = reader.join();
+    fn system_disk(reason: &str) -> Self {
+        Self::new(
+            ErrorKind::SystemDisk,
+            format!("{} — refusing to overwrite the system disk", reason),
+        )
+    }
 
-    if let Err(e) = device.sync_all() {
-        let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-        return;
+    fn device_changed() -> Self {
+        Self::new(
+            ErrorKind::DeviceChanged,
+            "Device path changed during burn — aborting",
+        )
     }
 
-    if cfg.verify {
-        if !verify_image(&cfg, &event_tx, cancel_flag.clone()) {
-            return;
-        }
+    fn filesystem_detected(device: &Path, fs_type: &str) -> Self {
+        Self::detailed(
+            ErrorDetail::FilesystemDetected {
+                fs_type: fs_type.to_string(),
+            },
+            format!(
+                "{} holds a {} filesystem — refusing to overwrite it without confirmation",
+                device.display(),
+                fs_type
+            ),
+        )
+    }
+
+    fn verify_mismatch(message: impl Into<String>, mismatch: VerifyMismatch) -> Self {
+        Self::detailed(ErrorDetail::VerificationFailed { mismatch }, message)
+    }
+
+    // רמז לפתרון לפי סוג השגיאה, כבר מתורגם
+    pub fn hint(&self) -> &'static str {
+        let hint = match self.kind {
+            ErrorKind::PermissionDenied => " — run as root or add yourself to the disk group",
+            ErrorKind::DeviceTooSmall => " — pick a larger drive",
+            ErrorKind::VerificationFailed => " — the drive may be failing, try another one",
+            ErrorKind::BadSignature => " — do not trust this image",
+            ErrorKind::InvalidIso => " — the download may be corrupted",
+            ErrorKind::Unmount => " — close any open files on the drive and retry",
+            ErrorKind::WriteTimeout => " — replug the drive, try another port or a different drive",
+            ErrorKind::BufferCorrupted => " — test the RAM and avoid USB hubs",
+            ErrorKind::DeviceChanged => " — the drive was reset; replug it, rescan and burn again",
+            // ההודעה מהמנוע כבר אומרת לבדוק את המתג
+            ErrorKind::WriteProtected => return "",
+            // ה-UI שואל אם לדרוס
+            ErrorKind::FilesystemDetected => return "",
+            // ההודעה כבר אומרת מה מחזיק את הכונן
+            ErrorKind::SystemDisk => return "",
+            ErrorKind::Io | ErrorKind::Cancelled => return "",
+        };
+        crate::i18n::tr(hint)
     }
 
-    let _ = event_tx.send(BurnEvent::Finished);
+    // השורה לתווית הסטטוס: ההודעה והרמז
+    pub fn to_user_string(&self) -> String {
+        crate::i18n::fill(
+            crate::i18n::tr("Error: {}{}"),
+            &[&self.message, &self.hint()],
+        )
+    }
 }
 
-fn verify_image(
-    cfg: &BurnConfig,
-    event_tx: &Sender<BurnEvent>,
-    cancel_flag: Arc<AtomicBool>,
-) -> bool {
-    let mut iso = match File::open(&cfg.iso_path) {
-        Ok(f) => f,
-        Err(e) => {
-            let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-            return false;
-        }
-    };
+impl std::fmt::Display for BurnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
 
-    let mut device = match File::open(&cfg.device_path) {
-        Ok(f) => f,
-        Err(e) => {
-            let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-            return false;
+impl std::error::Error for BurnError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl From<std::io::Error> for BurnError {
+    fn from(e: std::io::Error) -> Self {
+        let kind = match e.kind() {
+            std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            _ if e.raw_os_error() == Some(libc::ENOSPC) => ErrorKind::DeviceTooSmall,
+            _ if e.raw_os_error() == Some(libc::EROFS) => ErrorKind::WriteProtected,
+            _ => ErrorKind::Io,
+        };
+        let message = e.to_string();
+        Self {
+            source: Some(Arc::new(e)),
+            ..Self::new(kind, message)
         }
-    };
+    }
+}
 
-    let total = std::fs::metadata(&cfg.iso_path)
-        .map(|m| m.len())
-        .unwrap_or(0);
-    let mut checked = 0u64;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WipePattern {
+    Zeros,
+    Ones,
+    Random,
+    // מעבר אקראי ואחריו מעבר אפסים
+    RandomThenZeros,
+}
 
-    let mut buf_iso = vec![0u8; BUFFER_SIZE];
-    let mut buf_dev = vec![0u8; BUFFER_SIZE];
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PassFill {
+    Byte(u8),
+    Random,
+}
 
-    loop {
-        if cancel_flag.load(Ordering::Relaxed) {
-            let _ = event_tx.send(BurnEvent::Cancelled);
-            return false;
+impl WipePattern {
+    fn passes(self) -> &'static [PassFill] {
+        match self {
+            WipePattern::Zeros => &[PassFill::Byte(0x00)],
+            WipePattern::Ones => &[PassFill::Byte(0xFF)],
+            WipePattern::Random => &[PassFill::Random],
+            WipePattern::RandomThenZeros => &[PassFill::Random, PassFill::Byte(0x00)],
         }
+    }
+}
 
-        let n1 = match iso.read(&mut buf_iso) {
-            Ok(0) => break,
-            Ok(n) => n,
-            Err(e) => {
-                let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-                return false;
-            }
-        };
+#[derive(Debug)]
+pub struct WipeConfig {
+    pub device_path: PathBuf,
+    pub pattern: WipePattern,
+    pub verify: bool,
+    // כמו בפירמוט: מחיצות מעוגנות מנותקות קודם. בלי זה - שגיאת Unmount
+    pub unmount: bool,
+}
 
-        let n2 = match device.read(&mut buf_dev[..n1]) {
-            Ok(n) => n,
-            Err(e) => {
-                let _ = event_tx.send(BurnEvent::Error(e.to_string()));
-                return false;
-            }
-        };
+// כונן חוזר לשימוש רגיל: מחיצה אחת על כולו, FAT32 או exFAT לפי הגודל
+#[derive(Debug, Clone)]
+pub struct RestoreConfig {
+    pub device_path: PathBuf,
+    pub scheme: PartitionScheme,
+    // ריק = VOLUME_LABEL. מותאם לחוקים של מערכת הקבצים
+    pub label: String,
+    // כמו בפירמוט: מחיצות מעוגנות מנותקות קודם. בלי זה - שגיאת Unmount
+    pub unmount: bool,
+}
 
-        if n1 != n2 || buf_iso[..n1] != buf_dev[..n2] {
-            let _ = event_tx.send(BurnEvent::Error("Verification failed".into()));
-            return false;
+// כלי הפירמוט: כמו שחזור, אבל מערכת הקבצים והפרמטרים לפי בחירת המשתמש
+#[derive(Debug, Clone)]
+pub struct FormatConfig {
+    pub device_path: PathBuf,
+    pub filesystem: Filesystem,
+    pub label: String,
+    // אפסים על כל הכונן לפני הטבלה, במקום רק על הקצוות
+    pub full: bool,
+    // בבייטים. None = מה שה-mkfs בוחר לפי הגודל
+    pub cluster_size: Option<u32>,
+    // מחיצות מעוגנות מנותקות קודם. בלי זה - שגיאת Unmount
+    pub unmount: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Filesystem {
+    Fat32,
+    Exfat,
+    Ntfs,
+    Ext4,
+}
+
+impl Filesystem {
+    pub fn name(self) -> &'static str {
+        match self {
+            Filesystem::Fat32 => "FAT32",
+            Filesystem::Exfat => "exFAT",
+            Filesystem::Ntfs => "NTFS",
+            Filesystem::Ext4 => "ext4",
         }
+    }
 
-        checked += n1 as u64;
+    fn tool(self) -> &'static str {
+        match self {
+            Filesystem::Fat32 => "mkfs.vfat",
+            Filesystem::Exfat => "mkfs.exfat",
+            Filesystem::Ntfs => "mkfs.ntfs",
+            Filesystem::Ext4 => "mkfs.ext4",
+        }
+    }
 
-        let _ = event_tx.send(BurnEvent::Verifying { checked, total });
+    // החבילה שמספקת את הכלי ברוב ההפצות
+    fn package(self) -> &'static str {
+        match self {
+            Filesystem::Fat32 => "dosfstools",
+            Filesystem::Exfat => "exfatprogs",
+            Filesystem::Ntfs => "ntfs-3g",
+            Filesystem::Ext4 => "e2fsprogs",
+        }
     }
 
-    true
+    fn partition_type(self) -> PartitionType {
+        match self {
+            Filesystem::Fat32 => partitions::FAT32_DATA,
+            Filesystem::Exfat => partitions::EXFAT_DATA,
+            Filesystem::Ntfs => partitions::NTFS_DATA,
+            Filesystem::Ext4 => partitions::LINUX_FILESYSTEM,
+        }
+    }
+
+    // NTFS עד 32 תווים, ext4 עד 16 בייט. FAT32 ו-exFAT כמו בשחזור
+    fn label(self, label: &str) -> String {
+        let chars = label.trim().chars().filter(|c| !c.is_control());
+        let fitted: String = match self {
+            Filesystem::Fat32 => return volume_label(label, false),
+            Filesystem::Exfat => return volume_label(label, true),
+            Filesystem::Ntfs => chars.take(32).collect(),
+            Filesystem::Ext4 => {
+                let mut fitted = String::new();
+                for c in chars {
+                    if fitted.len() + c.len_utf8() > 16 {
+                        break;
+                    }
+                    fitted.push(c);
+                }
+                fitted
+            }
+        };
+        match fitted.trim_end() {
+            "" => VOLUME_LABEL.into(),
+            fitted => fitted.into(),
+        }
+    }
+
+    // בלי הנתיב של המחיצה, שבא אחרון. mkfs.ntfs בלי -Q מאפס בעצמו את כל המחיצה -
+    // את זה עושה full, ועם Progress
+    fn mkfs_args(self, label: &str, cluster_size: Option<u32>) -> Result<Vec<String>, String> {
+        let mut args: Vec<String> = match self {
+            Filesystem::Fat32 => vec!["-F".into(), "32".into(), "-n".into(), label.into()],
+            Filesystem::Exfat | Filesystem::Ext4 => vec!["-L".into(), label.into()],
+            Filesystem::Ntfs => vec!["-Q".into(), "-L".into(), label.into()],
+        };
+        if let Some(size) = cluster_size {
+            // בלוק גדול מדף הזיכרון - הקרנל לא יעגן את מערכת הקבצים
+            if self == Filesystem::Ext4 && size > 4096 {
+                return Err(format!(
+                    "ext4 blocks larger than 4096 bytes cannot be mounted (asked for {})",
+                    size
+                ));
+            }
+            let (flag, value) = match self {
+                // סקטורים לקלאסטר
+                Filesystem::Fat32 => ("-s", size / 512),
+                Filesystem::Exfat | Filesystem::Ntfs => ("-c", size),
+                Filesystem::Ext4 => ("-b", size),
+            };
+            args.extend([flag.to_string(), value.to_string()]);
+        }
+        Ok(args)
+    }
+}
+
+// צריבה הפוכה: מההתקן לקובץ תמונה, או ישר להתקן אחר
+#[derive(Debug, Clone)]
+pub struct CloneConfig {
+    pub device_path: PathBuf,
+    // קובץ (.gz/.xz/.zst/.bz2 בסוף השם = דחיסה בזמן השכפול) או block device
+    pub target_path: PathBuf,
+    pub on_read_error: ReadErrorPolicy,
+    // None = ברירת המחדל של הפורמט
+    pub compression_level: Option<u32>,
+    // קריאה חוזרת של היעד מול hash של מה שנקרא מהמקור. לא ליעד דחוס
+    pub verify: bool,
+    // יעד שהוא התקן: מחיצות מעוגנות שלו מנותקות קודם. בלי זה - שגיאת Unmount
+    pub unmount: bool,
+}
+
+// אותה תמונה לכמה התקנים במקביל. המקור נקרא פעם אחת, ולכל התקן יש כותב משלו
+#[derive(Debug, Clone)]
+pub struct MultiBurnConfig {
+    pub iso_path: PathBuf,
+    pub device_paths: Vec<PathBuf>,
+    // hash בזמן הכתיבה, ואחריה קריאה חוזרת של כל התקן
+    pub verify: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadErrorPolicy {
+    Abort,
+    // כמו ddrescue בקטן: סקטור שלא נקרא נכתב כאפסים, והטווח מדווח בסוף
+    ZeroFill,
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkConfig {
+    pub device_path: PathBuf,
+    // נמדדים רק הבייטים הראשונים של ההתקן, עד הגודל שלו
+    pub max_bytes: u64,
+    // כותב נתונים אקראיים על max_bytes הראשונים - הורס את מה שהיה שם
+    pub write: bool,
+}
+
+pub enum BurnCommand {
+    Start(BurnConfig),
+    // בדיקה של התקן שכבר נצרב, בלי לכתוב
+    VerifyOnly(BurnConfig),
+    Wipe(WipeConfig),
+    SecureErase(PathBuf),
+    // כמו badblocks -w: כותב תבנית על כל ההתקן וקורא בחזרה
+    SurfaceTest(PathBuf),
+    // בודק אם הגודל שההתקן מצהיר עליו אמיתי. גם הוא הורס הכל
+    CapacityTest(PathBuf),
+    Benchmark(BenchmarkConfig),
+    CloneToImage(CloneConfig),
+    MultiBurn(MultiBurnConfig),
+    // טבלה חדשה עם מחיצת GRUB ומחיצת exFAT לקבצי ISO. הורס הכל.
+    // unmount כמו בפירמוט: מחיצות מעוגנות מנותקות קודם, בלי זה - שגיאת Unmount
+    PrepareMultiboot {
+        device_path: PathBuf,
+        unmount: bool,
+    },
+    // מעתיק ISO לכונן שכבר הוכן ומעדכן את התפריט
+    AddToMultiboot {
+        iso_path: PathBuf,
+        device_path: PathBuf,
+    },
+    Restore(RestoreConfig),
+    Format(FormatConfig),
+    VerifyChecksum {
+        iso_path: PathBuf,
+        expected: String,
+        algorithm: HashAlgorithm,
+    },
+    VerifySignature {
+        data_path: PathBuf,
+        signature_path: PathBuf,
+        key: signature::SigningKey,
+        fingerprint: Option<String>,
+    },
+    // SHA-256 של התמונה ברקע. מפנה את מקומו לכל עבודה אחרת שנכנסת לתור
+    HashImage(PathBuf),
+    // מצב התור כ-BurnEvent::QueueStatus
+    QueueStatus,
+    CancelJob(u64),
+}
+
+pub struct BurnEngine {
+    cmd_tx: Sender<(u64, BurnCommand)>,
+    next_job_id: AtomicU64,
+    cancel_flag: Arc<AtomicBool>,
+    last_session: Arc<Mutex<Option<BurnSession>>>,
+    // דרך iter_events או BurnBackend::events. הערוץ משותף: כל אירוע מגיע לצרכן אחד בלבד
+    event_rx: Receiver<BurnEvent>,
+}
+
+impl BurnEngine {
+    pub fn new() -> Self {
+        let (cmd_tx, cmd_rx) = unbounded::<(u64, BurnCommand)>();
+        let (event_tx, event_rx) = bounded::<BurnEvent>(32);
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+
+        // עבודה אחת רצה בכל רגע, בלי לקרוא פקודות - ביטול שלה עובר ישירות דרך הדגל.
+        // התור עצמו ב-thread נפרד, כך ש-QueueStatus ו-CancelJob נענים גם באמצע צריבה
+        let (job_tx, job_rx) = bounded::<(u64, BurnCommand)>(1);
+        let (done_tx, done_rx) = bounded::<(u64, JobState)>(1);
+        let worker_cancel = cancel_flag.clone();
+        let worker_events = event_tx.clone();
+        let last_session = Arc::new(Mutex::new(None));
+        let worker_session = last_session.clone();
+        thread::spawn(move || {
+            let digests = DigestCache::default();
+            for (id, cmd) in job_rx {
+                let _ = worker_events.send(BurnEvent::JobStarted { id });
+                let state = run_job(
+                    cmd,
+                    &worker_events,
+                    &worker_cancel,
+                    &digests,
+                    &worker_session,
+                );
+                let _ = worker_events.send(BurnEvent::JobEnded { id, state });
+                if done_tx.send((id, state)).is_err() {
+                    break;
+                }
+            }
+        });
+        let queue_cancel = cancel_flag.clone();
+        thread::spawn(move || run_queue(cmd_rx, job_tx, done_rx, &event_tx, &queue_cancel));
+
+        Self {
+            cmd_tx,
+            next_job_id: AtomicU64::new(1),
+            cancel_flag,
+            last_session,
+            event_rx,
+        }
+    }
+
+    // חוסם בין אירועים. לא נגמר לבד כל עוד המנוע חי - הצרכן יוצא כשראה את מה שחיכה לו
+    pub fn iter_events(&self) -> impl Iterator<Item = BurnEvent> + '_ {
+        self.event_rx.iter()
+    }
+}
+
+// הפקודה רצה מול ערוץ פנימי, כדי לדעת איך היא נגמרה בלי לשנות את כל הפונקציות
+fn run_job(
+    cmd: BurnCommand,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+    digests: &DigestCache,
+    last_session: &Mutex<Option<BurnSession>>,
+) -> JobState {
+    // רק צריבה של תמונה לכונן נרשמת. גיבוי, בדיקות ומחיקה לא
+    let mut session = match &cmd {
+        BurnCommand::Start(cfg) if cfg.direction == BurnDirection::IsoToDevice => {
+            Some(BurnSession::new(cfg))
+        }
+        _ => None,
+    };
+    // כל שגיאה נרשמת כאן עם שם העבודה, גם כשהיא הגיעה מ-open או ioctl עמוק בפנים
+    let span = tracing::info_span!("job", label = %job_label(&cmd));
+    let _entered = span.enter();
+    let (inner_tx, inner_rx) = bounded::<BurnEvent>(32);
+    thread::scope(|scope| {
+        let span = span.clone();
+        scope.spawn(move || {
+            let _entered = span.enter();
+            run_command(cmd, &inner_tx, cancel_flag, digests)
+        });
+        let mut state = JobState::Done;
+        for event in inner_rx {
+            match &event {
+                BurnEvent::Error(e) => {
+                    tracing::error!(kind = ?e.kind, "{}", e);
+                    state = JobState::Failed;
+                }
+                BurnEvent::Cancelled => {
+                    tracing::info!("cancelled");
+                    state = JobState::Cancelled;
+                }
+                BurnEvent::Warning { message } => tracing::warn!("{}", message),
+                BurnEvent::Finished {
+                    bytes,
+                    elapsed_secs,
+                    verified,
+                } => tracing::info!(bytes, elapsed_secs, verified, "finished"),
+                BurnEvent::VerifySummary { method, ok } => {
+                    tracing::info!(?method, ok, "verify summary")
+                }
+                _ => {}
+            }
+            if let Some(session) = session.as_mut() {
+                session.observe(&event);
+            }
+            let _ = event_tx.send(event);
+        }
+        if let Some(mut session) = session {
+            session.finish(state, digests);
+            let report = session.report();
+            // לפני Report, כדי שמי שראה את האירוע ימצא את הצריבה גם ב-last_session
+            *last_session.lock().unwrap() = Some(session);
+            let _ = event_tx.send(BurnEvent::Report(report));
+        }
+        state
+    })
+}
+
+fn run_command(
+    cmd: BurnCommand,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+    digests: &DigestCache,
+) {
+    match cmd {
+        BurnCommand::Start(cfg) => {
+            run_burn(cfg, event_tx, cancel_flag.clone());
+        }
+        BurnCommand::VerifyOnly(cfg) => {
+            run_verify_only(&cfg, event_tx, cancel_flag.clone());
+        }
+        BurnCommand::Wipe(cfg) => {
+            run_wipe(cfg, event_tx, cancel_flag.clone());
+        }
+        BurnCommand::SecureErase(device_path) => {
+            run_secure_erase(&device_path, event_tx);
+        }
+        BurnCommand::SurfaceTest(device_path) => {
+            run_surface_test(&device_path, event_tx, cancel_flag);
+        }
+        BurnCommand::CapacityTest(device_path) => {
+            run_capacity_test(&device_path, event_tx, cancel_flag);
+        }
+        BurnCommand::Benchmark(cfg) => {
+            run_benchmark(&cfg, event_tx, cancel_flag);
+        }
+        BurnCommand::CloneToImage(cfg) => {
+            run_clone(&cfg, event_tx, cancel_flag);
+        }
+        BurnCommand::MultiBurn(cfg) => {
+            run_multi_burn(&cfg, event_tx, cancel_flag);
+        }
+        BurnCommand::PrepareMultiboot {
+            device_path,
+            unmount,
+        } => {
+            run_prepare_multiboot(&device_path, unmount, event_tx);
+        }
+        BurnCommand::AddToMultiboot {
+            iso_path,
+            device_path,
+        } => {
+            run_add_to_multiboot(&iso_path, &device_path, event_tx, cancel_flag);
+        }
+        BurnCommand::Restore(cfg) => {
+            run_restore(&cfg, event_tx, cancel_flag);
+        }
+        BurnCommand::Format(cfg) => {
+            run_format(&cfg, event_tx, cancel_flag);
+        }
+        BurnCommand::VerifyChecksum {
+            iso_path,
+            expected,
+            algorithm,
+        } => {
+            run_checksum(
+                &iso_path,
+                &expected,
+                algorithm,
+                event_tx,
+                cancel_flag,
+                digests,
+            );
+        }
+        BurnCommand::VerifySignature {
+            data_path,
+            signature_path,
+            key,
+            fingerprint,
+        } => {
+            let _ = event_tx.send(
+                match signature::verify_detached(
+                    &data_path,
+                    &signature_path,
+                    &key,
+                    fingerprint.as_deref(),
+                ) {
+                    Ok(signer) => BurnEvent::SignatureVerified { signer },
+                    Err(message) => {
+                        BurnEvent::Error(BurnError::new(ErrorKind::BadSignature, message))
+                    }
+                },
+            );
+        }
+        BurnCommand::HashImage(path) => {
+            run_hash_image(&path, event_tx, cancel_flag, digests);
+        }
+        // מטופלות ב-run_queue ולא מגיעות לכאן
+        BurnCommand::QueueStatus | BurnCommand::CancelJob(_) => {}
+    }
+}
+
+fn run_queue(
+    cmd_rx: Receiver<(u64, BurnCommand)>,
+    job_tx: Sender<(u64, BurnCommand)>,
+    done_rx: Receiver<(u64, JobState)>,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) {
+    // כל העבודות לפי הסדר שנכנסו, כולל אלה שנגמרו
+    let mut jobs: Vec<JobInfo> = Vec::new();
+    let mut pending: VecDeque<(u64, BurnCommand)> = VecDeque::new();
+    let mut active: Option<u64> = None;
+    // העבודה שרצה היא HashImage - כל עבודה חדשה מבטלת אותה
+    let mut active_background = false;
+
+    loop {
+        crossbeam_channel::select! {
+            recv(cmd_rx) -> cmd => match cmd {
+                // המנוע נסגר. עבודה שרצה מסתיימת, והשאר לא מתחילות
+                Err(_) => return,
+                Ok((_, BurnCommand::QueueStatus)) => {
+                    let _ = event_tx.send(BurnEvent::QueueStatus { jobs: jobs.clone() });
+                }
+                Ok((_, BurnCommand::CancelJob(id))) => {
+                    if active == Some(id) {
+                        cancel_flag.store(true, Ordering::Relaxed);
+                    } else if let Some(index) = pending.iter().position(|(job, _)| *job == id) {
+                        pending.remove(index);
+                        set_job_state(&mut jobs, id, JobState::Cancelled);
+                        let _ = event_tx.send(BurnEvent::JobEnded {
+                            id,
+                            state: JobState::Cancelled,
+                        });
+                    }
+                }
+                Ok((id, cmd)) => {
+                    // לא מתחרים בצריבה על הקריאה, ותמונה חדשה מחליפה את ה-hash של הקודמת
+                    if active_background {
+                        cancel_flag.store(true, Ordering::Relaxed);
+                    }
+                    if matches!(cmd, BurnCommand::HashImage(_)) {
+                        pending.retain(|(job, queued)| {
+                            let superseded = matches!(queued, BurnCommand::HashImage(_));
+                            if superseded {
+                                set_job_state(&mut jobs, *job, JobState::Cancelled);
+                                let _ = event_tx.send(BurnEvent::JobEnded {
+                                    id: *job,
+                                    state: JobState::Cancelled,
+                                });
+                            }
+                            !superseded
+                        });
+                    }
+                    let label = job_label(&cmd);
+                    jobs.push(JobInfo {
+                        id,
+                        label: label.clone(),
+                        state: JobState::Pending,
+                    });
+                    pending.push_back((id, cmd));
+                    let _ = event_tx.send(BurnEvent::JobQueued {
+                        id,
+                        label,
+                        position: pending.len() - 1 + usize::from(active.is_some()),
+                    });
+                }
+            },
+            recv(done_rx) -> done => {
+                let Ok((id, state)) = done else {
+                    return;
+                };
+                active = None;
+                active_background = false;
+                set_job_state(&mut jobs, id, state);
+            }
+        }
+
+        if active.is_none() {
+            if let Some((id, cmd)) = pending.pop_front() {
+                // ביטול שנשאר מהעבודה הקודמת לא חל על הבאה
+                cancel_flag.store(false, Ordering::Relaxed);
+                active = Some(id);
+                active_background = matches!(cmd, BurnCommand::HashImage(_));
+                set_job_state(&mut jobs, id, JobState::Active);
+                if job_tx.send((id, cmd)).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn set_job_state(jobs: &mut [JobInfo], id: u64, state: JobState) {
+    if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+        job.state = state;
+    }
+}
+
+// שורה קצרה לרשימת העבודות ב-UI
+fn job_label(cmd: &BurnCommand) -> String {
+    let name = |path: &Path| {
+        path.file_name().map_or_else(
+            || path.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        )
+    };
+    match cmd {
+        BurnCommand::Start(cfg) => match &cfg.direction {
+            BurnDirection::IsoToDevice => format!(
+                "Burn {} → {}",
+                name(&cfg.iso_path),
+                cfg.device_path.display()
+            ),
+            BurnDirection::DeviceToFile { output_path } => format!(
+                "Back up {} → {}",
+                cfg.device_path.display(),
+                name(output_path)
+            ),
+        },
+        BurnCommand::VerifyOnly(cfg) => format!(
+            "Verify {} against {}",
+            cfg.device_path.display(),
+            name(&cfg.iso_path)
+        ),
+        BurnCommand::Wipe(cfg) => format!("Wipe {}", cfg.device_path.display()),
+        BurnCommand::SecureErase(device_path) => {
+            format!("Secure erase {}", device_path.display())
+        }
+        BurnCommand::SurfaceTest(device_path) => {
+            format!("Surface test {}", device_path.display())
+        }
+        BurnCommand::CapacityTest(device_path) => {
+            format!("Capacity test {}", device_path.display())
+        }
+        BurnCommand::Benchmark(cfg) => format!("Benchmark {}", cfg.device_path.display()),
+        BurnCommand::CloneToImage(cfg) => format!(
+            "Copy {} → {}",
+            cfg.device_path.display(),
+            cfg.target_path.display()
+        ),
+        BurnCommand::MultiBurn(cfg) => format!(
+            "Burn {} → {} drives",
+            name(&cfg.iso_path),
+            cfg.device_paths.len()
+        ),
+        BurnCommand::PrepareMultiboot { device_path, .. } => {
+            format!("Prepare multiboot {}", device_path.display())
+        }
+        BurnCommand::AddToMultiboot {
+            iso_path,
+            device_path,
+        } => format!("Add {} → {}", name(iso_path), device_path.display()),
+        BurnCommand::Restore(cfg) => format!("Restore {}", cfg.device_path.display()),
+        BurnCommand::Format(cfg) => format!(
+            "Format {} as {}",
+            cfg.device_path.display(),
+            cfg.filesystem.name()
+        ),
+        BurnCommand::VerifyChecksum { iso_path, .. } => format!("Checksum {}", name(iso_path)),
+        BurnCommand::VerifySignature { data_path, .. } => {
+            format!("Check signature of {}", name(data_path))
+        }
+        BurnCommand::HashImage(path) => format!("SHA-256 of {}", name(path)),
+        BurnCommand::QueueStatus | BurnCommand::CancelJob(_) => String::new(),
+    }
+}
+
+// מה שה-UI צריך מהמנוע - BurnEngine האמיתי, או מנוע מזויף בבדיקות
+pub trait BurnBackend {
+    // מחזיר את המספר של העבודה בתור (פקודות שמנהלות את התור לא נכנסות אליו)
+    fn submit(&self, cmd: BurnCommand) -> u64;
+    fn cancel(&self);
+    fn events(&self) -> Receiver<BurnEvent>;
+
+    // הצריבה האחרונה שהסתיימה (הצליחה, נכשלה או בוטלה). עבודות שאינן צריבה לא דורסות אותה
+    fn last_session(&self) -> Option<BurnSession> {
+        None
+    }
+
+    fn start(&self, cfg: BurnConfig) -> u64 {
+        self.submit(BurnCommand::Start(cfg))
+    }
+
+    fn verify_only(&self, cfg: BurnConfig) -> u64 {
+        self.submit(BurnCommand::VerifyOnly(cfg))
+    }
+
+    fn wipe(&self, cfg: WipeConfig) -> u64 {
+        self.submit(BurnCommand::Wipe(cfg))
+    }
+
+    fn surface_test(&self, device_path: PathBuf) -> u64 {
+        self.submit(BurnCommand::SurfaceTest(device_path))
+    }
+
+    fn capacity_test(&self, device_path: PathBuf) -> u64 {
+        self.submit(BurnCommand::CapacityTest(device_path))
+    }
+
+    fn benchmark(&self, cfg: BenchmarkConfig) -> u64 {
+        self.submit(BurnCommand::Benchmark(cfg))
+    }
+
+    fn clone_to_image(&self, cfg: CloneConfig) -> u64 {
+        self.submit(BurnCommand::CloneToImage(cfg))
+    }
+
+    fn multi_burn(&self, cfg: MultiBurnConfig) -> u64 {
+        self.submit(BurnCommand::MultiBurn(cfg))
+    }
+
+    fn secure_erase(&self, device_path: PathBuf) -> u64 {
+        self.submit(BurnCommand::SecureErase(device_path))
+    }
+
+    fn prepare_multiboot(&self, device_path: PathBuf, unmount: bool) -> u64 {
+        self.submit(BurnCommand::PrepareMultiboot {
+            device_path,
+            unmount,
+        })
+    }
+
+    fn add_to_multiboot(&self, iso_path: PathBuf, device_path: PathBuf) -> u64 {
+        self.submit(BurnCommand::AddToMultiboot {
+            iso_path,
+            device_path,
+        })
+    }
+
+    fn restore(&self, cfg: RestoreConfig) -> u64 {
+        self.submit(BurnCommand::Restore(cfg))
+    }
+
+    fn format(&self, cfg: FormatConfig) -> u64 {
+        self.submit(BurnCommand::Format(cfg))
+    }
+
+    fn verify_checksum(
+        &self,
+        iso_path: PathBuf,
+        expected: String,
+        algorithm: HashAlgorithm,
+    ) -> u64 {
+        self.submit(BurnCommand::VerifyChecksum {
+            iso_path,
+            expected,
+            algorithm,
+        })
+    }
+
+    fn verify_signature(
+        &self,
+        data_path: PathBuf,
+        signature_path: PathBuf,
+        key: signature::SigningKey,
+        fingerprint: Option<String>,
+    ) -> u64 {
+        self.submit(BurnCommand::VerifySignature {
+            data_path,
+            signature_path,
+            key,
+            fingerprint,
+        })
+    }
+
+    fn hash_image(&self, path: PathBuf) -> u64 {
+        self.submit(BurnCommand::HashImage(path))
+    }
+
+    fn queue_status(&self) {
+        self.submit(BurnCommand::QueueStatus);
+    }
+
+    // עבודה שמחכה יוצאת מהתור; עבודה שרצה מבוטלת כמו ב-cancel
+    fn cancel_job(&self, id: u64) {
+        self.submit(BurnCommand::CancelJob(id));
+    }
+}
+
+impl BurnBackend for BurnEngine {
+    fn submit(&self, cmd: BurnCommand) -> u64 {
+        let id = self.next_job_id.fetch_add(1, Ordering::Relaxed);
+        let _ = self.cmd_tx.send((id, cmd));
+        id
+    }
+
+    fn cancel(&self) {
+        self.cancel_flag.store(true, Ordering::Relaxed);
+    }
+
+    fn events(&self) -> Receiver<BurnEvent> {
+        self.event_rx.clone()
+    }
+
+    fn last_session(&self) -> Option<BurnSession> {
+        self.last_session.lock().unwrap().clone()
+    }
+}
+
+fn run_burn(cfg: BurnConfig, event_tx: &Sender<BurnEvent>, cancel_flag: Arc<AtomicBool>) {
+    let _span = tracing::info_span!(
+        "burn",
+        device = %cfg.device_path.display(),
+        image = %cfg.iso_path.display(),
+        offset = cfg.target_offset,
+        chunk_size = cfg.buffer_size,
+    )
+    .entered();
+    tracing::debug!(?cfg, "burn config");
+    // המקור והיעד מתחלפים. השכפול כבר קורא התקן (BLKGETSIZE64) וכותב לקובץ עם אותה התקדמות
+    if let BurnDirection::DeviceToFile { output_path } = &cfg.direction {
+        let backup = CloneConfig {
+            device_path: cfg.device_path.clone(),
+            target_path: output_path.clone(),
+            on_read_error: ReadErrorPolicy::Abort,
+            compression_level: None,
+            verify: cfg.verify_mode != VerifyMode::Off,
+            unmount: false,
+        };
+        run_clone(&backup, event_tx, &cancel_flag);
+        return;
+    }
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let burn_started = Instant::now();
+
+    let total_size = match std::fs::metadata(&cfg.iso_path) {
+        Ok(m) => m.len(),
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    // .img/.bin/.raw הן תמונות גולמיות בלי ISO 9660 - אין מה לבדוק
+    if cfg.validate_iso && is_iso_path(&cfg.iso_path) {
+        if let Err(reason) = validate_iso(&cfg.iso_path) {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+                ErrorKind::InvalidIso,
+                reason,
+            )));
+            return;
+        }
+    }
+
+    // לא עוצר את הצריבה - ה-UI כבר שאל את המשתמש
+    if let Ok(false) = looks_bootable(&cfg.iso_path) {
+        let _ = event_tx.send(BurnEvent::Warning {
+            message: "The image has no MBR boot signature or ISO 9660 header — it may not boot"
+                .into(),
+        });
+    }
+
+    if let Ok(head) = read_image_head(&cfg.iso_path, partitions::TABLE_HEAD_LEN) {
+        let _ = event_tx.send(BurnEvent::ImageInfo {
+            partitions: partitions::parse_partition_table(&head),
+        });
+    }
+
+    let is_block_device = std::fs::metadata(&cfg.device_path)
+        .map(|m| m.file_type().is_block_device())
+        .unwrap_or(false);
+
+    // לפני כל כתיבה, גם בהתקנת Windows
+    if let Err(e) = preflight_target(&cfg.device_path, cfg.force_overwrite) {
+        let _ = event_tx.send(BurnEvent::Error(e));
+        return;
+    }
+
+    // רק לכונן שלם. לקובץ יעד, עם offset או כשביקשו raw התמונה נכתבת כמו שהיא
+    let whole_device = cfg.target_offset == 0 && is_block_device;
+    if let Some(image) =
+        windows_installer(&cfg.iso_path).filter(|_| whole_device && !cfg.windows_raw)
+    {
+        let _ = event_tx.send(BurnEvent::WindowsInstaller {
+            image: image.name.clone(),
+            size: image.size,
+            split: image.needs_split(),
+        });
+        match burn_windows_installer(&cfg, &image, total_size, event_tx, &cancel_flag) {
+            Ok(Some(bytes)) => {
+                // הכונן מחזיק קבצים ולא את ה-ISO כמו שהוא - אין מה להשוות בייט-בייט
+                if cfg.verify_mode != VerifyMode::Off {
+                    let _ = event_tx.send(BurnEvent::VerifySkipped {
+                        reason: "Windows files were copied, not a raw image".into(),
+                    });
+                }
+                let _ = event_tx.send(BurnEvent::Finished {
+                    bytes,
+                    elapsed_secs: burn_started.elapsed().as_secs_f64(),
+                    verified: false,
+                });
+                if cfg.eject_after {
+                    let _ = event_tx.send(eject_device(&cfg.device_path));
+                }
+            }
+            Ok(None) => {
+                let _ = event_tx.send(BurnEvent::Cancelled);
+            }
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e));
+            }
+        }
+        return;
+    }
+
+    let iso_file = match File::open(&cfg.iso_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    // hint לקרנל
+    unsafe {
+        libc::posix_fadvise(iso_file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
+    }
+
+    // io_uring קורא את הקובץ עצמו לפי offset, אז תמונה דחוסה נשארת בנתיב הרגיל. גם
+    // paranoid - שם הבאפר עובר מהקריאה לכתיבה בלי thread באמצע, ואין CRC לבדוק.
+    // וגם O_DIRECT: הבאפרים של ה-ring לא מיושרים, וכתיבה קצרה שם ממשיכה מאמצע בלוק
+    let uring_source = if cfg.use_io_uring
+        && !cfg.paranoid
+        && !cfg.use_direct_io
+        && !is_compressed(&cfg.iso_path).unwrap_or(true)
+    {
+        iso_file.try_clone().ok()
+    } else {
+        None
+    };
+
+    // כפולה של גודל בלוק, גם אם הוגדר משהו עגול פחות
+    let chunk_size = cfg.buffer_size.max(1).div_ceil(DIRECT_ALIGN) * DIRECT_ALIGN;
+
+    // אותו תנאי למיפוי: בתמונה דחוסה אין מה למפות
+    let read_stats = Arc::new(ReadStats::default());
+    let mmap_source = if cfg.use_mmap
+        && uring_source.is_none()
+        && !is_compressed(&cfg.iso_path).unwrap_or(true)
+    {
+        match source::spawn_mmap_thread(
+            &iso_file,
+            total_size,
+            chunk_size,
+            cfg.paranoid,
+            cancel_flag.clone(),
+            read_stats.clone(),
+        ) {
+            Ok(stream) => Some(stream),
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let iso = match make_decompressor(&cfg.iso_path, iso_file) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    // יעד שהוא קובץ רגיל (שכפול תמונה, בדיקות) נוצר אם חסר. תחת /dev לא - שם שם
+    // שגוי צריך להיכשל ולא להפוך לקובץ
+    let mut device = match OpenOptions::new()
+        .write(true)
+        .create(!cfg.device_path.starts_with("/dev"))
+        .open(&cfg.device_path)
+    {
+        Ok(f) => f,
+        // המקל נשלף אחרי הסריקה (גם front-end בלי בדיקה משלו מקבל הודעה ברורה)
+        Err(e)
+            if e.kind() == std::io::ErrorKind::NotFound && cfg.device_path.starts_with("/dev") =>
+        {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+                ErrorKind::Io,
+                format!(
+                    "{} is no longer present — rescan",
+                    cfg.device_path.display()
+                ),
+            )));
+            return;
+        }
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::opening(e, &cfg.device_path)));
+            return;
+        }
+    };
+    let target_is_file = device.metadata().map(|m| m.is_file()).unwrap_or(false);
+
+    // בין הסריקה לכאן ה-sdX יכול היה לעבור לכונן אחר
+    let pinned_device = match &cfg.device_link {
+        Some(link) if !target_is_file => match PinnedDevice::new(link, &device) {
+            Some(pinned) => Some(pinned),
+            None => {
+                let _ = event_tx.send(BurnEvent::Error(BurnError::device_changed()));
+                return;
+            }
+        },
+        _ => None,
+    };
+
+    // open עם O_WRONLY מצליח גם על מקל נעול, והכתיבה נופלת עם EROFS רק אחרי שהמטמון מתמלא
+    if !target_is_file && is_read_only(&device) {
+        let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+            ErrorKind::WriteProtected,
+            "Device is write-protected — check the lock switch",
+        )));
+        return;
+    }
+
+    // הבדיקה כוללת את ה-offset - התמונה צריכה להיכנס אחריו.
+    // קובץ ריק פשוט גדל; קובץ שהוקצה מראש מחקה כונן בגודל שלו
+    let image_size = written_size(&cfg.iso_path, total_size);
+    match (image_size, device_size(&device)) {
+        (Some(image_size), Ok(capacity))
+            if !(target_is_file && capacity == 0)
+                && cfg.target_offset.saturating_add(image_size) > capacity =>
+        {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::too_small(
+                cfg.target_offset.saturating_add(image_size),
+                capacity,
+                format!(
+                    "Device too small: need {} bytes at offset {}, device has {}",
+                    image_size, cfg.target_offset, capacity
+                ),
+            )));
+            return;
+        }
+        (None, _) => {
+            let _ = event_tx.send(BurnEvent::Warning {
+                message: UNKNOWN_SIZE_WARNING.into(),
+            });
+        }
+        _ => {}
+    }
+
+    // ל-TRIM ולהוצאה אין משמעות בקובץ
+    if cfg.pre_discard && !target_is_file {
+        let started = Instant::now();
+        match discard_device(&device, cfg.target_offset) {
+            Ok(()) => {
+                let _ = event_tx.send(BurnEvent::Discarded {
+                    elapsed_secs: started.elapsed().as_secs_f64(),
+                });
+            }
+            // התקן שלא תומך ב-TRIM - ממשיכים כרגיל
+            Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => {
+                tracing::info!("device does not support discard");
+            }
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return;
+            }
+        }
+    }
+
+    if cfg.wipe_before_burn {
+        match zero_fill(&mut device, cfg.target_offset, event_tx, &cancel_flag) {
+            StreamEnd::Completed(_) => {}
+            StreamEnd::Failed => return,
+            StreamEnd::Cancelled => {
+                let _ = event_tx.send(BurnEvent::Cancelled);
+                return;
+            }
+        }
+    }
+
+    if let Err(e) = device.seek(SeekFrom::Start(cfg.target_offset)) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return;
+    }
+
+    // ה-hash מחושב על מה שנכתב בפועל, כך שגם מקור דחוס נבדק בלי לפתוח אותו שוב
+    // בבדיקה מדגמית ה-hash רק זורע את בחירת החלונות
+    let mut check = match cfg.verify_mode {
+        VerifyMode::Off => None,
+        VerifyMode::Quick => Some(InFlightCheck::Hash(Hasher::new(cfg.hash_algorithm))),
+        VerifyMode::Full if cfg.byte_compare => None,
+        VerifyMode::Full if cfg.fast_verify => Some(InFlightCheck::Crc32(ChunkCrcs::new())),
+        VerifyMode::Full => Some(InFlightCheck::Hash(Hasher::new(cfg.hash_algorithm))),
+    };
+
+    let mut options = WriteOptions {
+        speed_ema_alpha: cfg.speed_ema_alpha,
+        max_speed_mbps: cfg.max_speed_mbps.filter(|cap| *cap > 0.0),
+        write_retries: cfg.write_retries,
+        sync_interval_bytes: cfg.sync_interval_bytes.filter(|n| *n > 0),
+        chunk_size,
+        write_timeout: cfg
+            .write_timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs),
+        paranoid: cfg.paranoid,
+        pinned_device,
+        direct_io: false,
+    };
+
+    // רק ללולאת הכתיבה - המחיקה לפניה ו-zero_head בביטול כותבים מבאפרים רגילים.
+    // offset לא מיושר (כונן multiboot) נשאר בלי
+    if cfg.use_direct_io && cfg.target_offset.is_multiple_of(DIRECT_ALIGN as u64) {
+        match set_direct_io(&device, true) {
+            Ok(()) => options.direct_io = true,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Warning {
+                    message: format!(
+                        "Direct I/O unavailable ({}), writing through the page cache",
+                        e
+                    ),
+                });
+            }
+        }
+    }
+
+    // total = 0 בהתקדמות = גודל לא ידוע, וה-UI מהבהב
+    let progress_total = image_size.unwrap_or(0);
+    let end = match (uring_source, mmap_source) {
+        (Some(source), _) => write_stream_uring(
+            &source,
+            total_size,
+            &device,
+            cfg.target_offset,
+            &options,
+            check.as_mut(),
+            event_tx,
+            &cancel_flag,
+        ),
+        (None, Some((data_rx, reader))) => write_chunks(
+            data_rx,
+            Some(reader),
+            &read_stats,
+            total_size,
+            &mut device,
+            &options,
+            check.as_mut(),
+            event_tx,
+            &cancel_flag,
+        ),
+        (None, None) => write_stream(
+            iso,
+            progress_total,
+            &mut device,
+            &options,
+            check.as_mut(),
+            event_tx,
+            &cancel_flag,
+        ),
+    };
+    if options.direct_io {
+        let _ = set_direct_io(&device, false);
+    }
+
+    let written = match end {
+        StreamEnd::Completed(written) => written,
+        StreamEnd::Failed => return,
+        StreamEnd::Cancelled => {
+            // תמונה חלקית עלולה להיראות bootable - מוחקים את ההתחלה שלה
+            if cfg.wipe_on_cancel {
+                if let Err(e) = zero_head(&mut device, cfg.target_offset, event_tx) {
+                    let _ = event_tx.send(BurnEvent::Error(e.into()));
+                    return;
+                }
+                // מי שביקש TRIM לפני הצריבה מקבל גם את שאר התמונה החלקית. בלי TRIM
+                // לא נוגעים בשאר - אפסים על כל הכונן זה run_wipe ולא ביטול
+                if cfg.pre_discard && !target_is_file {
+                    match discard_device(&device, cfg.target_offset) {
+                        Ok(()) => {}
+                        Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => {}
+                        Err(e) => {
+                            let _ = event_tx.send(BurnEvent::Warning {
+                                message: format!("Could not discard the partial image: {}", e),
+                            });
+                        }
+                    }
+                }
+            }
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return;
+        }
+    };
+
+    if cfg.verify_mode != VerifyMode::Off {
+        let (method, verified) = match check {
+            Some(InFlightCheck::Hash(hasher)) if cfg.verify_mode == VerifyMode::Quick => {
+                let digest = hasher.finalize();
+                let seed = u64::from_le_bytes(digest[..8].try_into().unwrap());
+                (
+                    VerifyMethod::Sampled,
+                    verify_sampled(&cfg, written, seed, event_tx, &cancel_flag),
+                )
+            }
+            Some(InFlightCheck::Hash(hasher)) => (
+                VerifyMethod::Hash(cfg.hash_algorithm),
+                verify_digest(
+                    &hasher.finalize(),
+                    cfg.hash_algorithm,
+                    &cfg.device_path,
+                    cfg.target_offset,
+                    written,
+                    event_tx,
+                    &cancel_flag,
+                ),
+            ),
+            Some(InFlightCheck::Crc32(crcs)) => {
+                let table = crcs.finish();
+                tracing::info!(
+                    "CRC32 table ({} blocks): {}",
+                    table.len(),
+                    table
+                        .iter()
+                        .map(|crc| format!("{:08x}", crc))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+                (
+                    VerifyMethod::Crc32,
+                    verify_crc_table(
+                        &table,
+                        &cfg.device_path,
+                        cfg.target_offset,
+                        written,
+                        event_tx,
+                        &cancel_flag,
+                    ),
+                )
+            }
+            None => (
+                VerifyMethod::ByteCompare,
+                verify_image(&cfg, written, event_tx, cancel_flag.clone()),
+            ),
+        };
+        // אחרי ביטול כבר נשלח Cancelled, אין מה לסכם
+        if !cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::VerifySummary {
+                method,
+                ok: verified,
+            });
+        }
+        if !verified {
+            return;
+        }
+    }
+
+    // אחרי הבדיקה - המחיצה החדשה משנה את טבלת המחיצות שבתחילת התמונה
+    if let Some(size) = cfg.persistence_bytes.filter(|n| *n > 0) {
+        if target_is_file || cfg.target_offset != 0 {
+            let _ = event_tx.send(BurnEvent::Warning {
+                message: "Persistent storage needs a whole drive — skipped".into(),
+            });
+        } else if let Err(e) = create_persistence(&cfg, written, size, event_tx) {
+            let _ = event_tx.send(BurnEvent::Error(e));
+            return;
+        }
+    }
+
+    if cfg.data_partition {
+        if target_is_file || cfg.target_offset != 0 {
+            let _ = event_tx.send(BurnEvent::Warning {
+                message: "A data partition needs a whole drive — skipped".into(),
+            });
+        } else if let Err(e) = create_data_partition(&cfg, written, event_tx) {
+            let _ = event_tx.send(BurnEvent::Error(e));
+            return;
+        }
+    }
+
+    let _ = event_tx.send(BurnEvent::Finished {
+        bytes: written,
+        elapsed_secs: burn_started.elapsed().as_secs_f64(),
+        verified: cfg.verify_mode != VerifyMode::Off,
+    });
+
+    if cfg.eject_after && !target_is_file {
+        // udisks מסרב לכבות התקן שעדיין פתוח אצלנו
+        drop(device);
+        let _ = event_tx.send(eject_device(&cfg.device_path));
+    }
+}
+
+// הטבלה נכתבה עם התמונה, אז מוסיפים לה מחיצה ישירות על ההתקן, מבקשים מהקרנל לקרוא
+// אותה מחדש ומפרמטים את ה-node החדש
+fn create_persistence(
+    cfg: &BurnConfig,
+    image_len: u64,
+    size: u64,
+    event_tx: &Sender<BurnEvent>,
+) -> Result<(), BurnError> {
+    let Some(flavor) = live_flavor(&cfg.iso_path) else {
+        let _ = event_tx.send(BurnEvent::Warning {
+            message: "Not an Ubuntu/Debian live image — persistent storage skipped".into(),
+        });
+        return Ok(());
+    };
+    let label = flavor.label();
+    let _ = event_tx.send(BurnEvent::PersistenceStarted {
+        label: label.into(),
+        size,
+    });
+
+    let fail = |e: std::io::Error| {
+        BurnError::new(
+            ErrorKind::Io,
+            format!("Persistent storage setup failed: {}", e),
+        )
+    };
+    let (node, partition) = append_partition(
+        &cfg.device_path,
+        image_len,
+        size,
+        partitions::LINUX_FILESYSTEM,
+        label,
+    )
+    .map_err(fail)?;
+    persistence::format_ext4(&node, flavor).map_err(fail)?;
+
+    let _ = event_tx.send(BurnEvent::PersistenceCreated {
+        partition: node,
+        label: label.into(),
+        size: partition.size,
+    });
+    Ok(())
+}
+
+// FAT32 עד הגבול שבו Windows עוד מפרמט אותו בעצמו, מעליו exFAT
+const FAT32_MAX_PARTITION: u64 = 32 * 1024 * 1024 * 1024;
+const DATA_PARTITION_LABEL: &str = "DATA";
+
+fn create_data_partition(
+    cfg: &BurnConfig,
+    image_len: u64,
+    event_tx: &Sender<BurnEvent>,
+) -> Result<(), BurnError> {
+    let fail = |e: std::io::Error| {
+        BurnError::new(ErrorKind::Io, format!("Data partition setup failed: {}", e))
+    };
+    // הגודל המדויק ידוע רק אחרי היישור, אבל הסוג נכתב לטבלה כבר עכשיו
+    let capacity = File::open(&cfg.device_path)
+        .and_then(|device| device_size(&device))
+        .map_err(fail)?;
+    let exfat = capacity.saturating_sub(image_len) > FAT32_MAX_PARTITION;
+    let (kind, filesystem) = if exfat {
+        (partitions::EXFAT_DATA, "exFAT")
+    } else {
+        (partitions::FAT32_DATA, "FAT32")
+    };
+
+    let (node, partition) = append_partition(
+        &cfg.device_path,
+        image_len,
+        u64::MAX,
+        kind,
+        DATA_PARTITION_LABEL,
+    )
+    .map_err(fail)?;
+    let _ = event_tx.send(BurnEvent::DataPartitionAdded {
+        partition: node.clone(),
+        size: partition.size,
+    });
+
+    make_fat_filesystem(&node, exfat, DATA_PARTITION_LABEL).map_err(fail)?;
+
+    let _ = event_tx.send(BurnEvent::DataPartitionFormatted {
+        partition: node,
+        filesystem: filesystem.into(),
+    });
+    Ok(())
+}
+
+fn make_fat_filesystem(node: &Path, exfat: bool, label: &str) -> std::io::Result<()> {
+    let mut mkfs = if exfat {
+        let mut mkfs = std::process::Command::new("mkfs.exfat");
+        mkfs.args(["-L", label]);
+        mkfs
+    } else {
+        let mut mkfs = std::process::Command::new("mkfs.vfat");
+        mkfs.args(["-F", "32", "-n", label]);
+        mkfs
+    };
+    let output = mkfs.arg(node).output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "mkfs for {} failed: {}",
+            if exfat { "exFAT" } else { "FAT32" },
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+// טבלה חדשה על כל הכונן, ואז מחכים שהקרנל ייצור node לכל מחיצה
+fn write_fresh_table(
+    device: &File,
+    device_path: &Path,
+    scheme: PartitionScheme,
+    layout: &[(u64, PartitionType)],
+) -> std::io::Result<Vec<(PathBuf, NewPartition)>> {
+    let capacity = device_size(device)?;
+    let created = partitions::write_fresh_table(device, capacity, scheme, layout)?;
+    device.sync_all()?;
+    partitions::reread_partitions(device)?;
+
+    let mut nodes = Vec::new();
+    for partition in created {
+        let node = partitions::partition_path(device_path, partition.index);
+        partitions::wait_for_node(&node)?;
+        nodes.push((node, partition));
+    }
+    Ok(nodes)
+}
+
+// העתקת קבצים עם Progress רגיל על סך כל הבייטים (read 0 - אין מקור נפרד למדוד)
+struct CopyProgress<'a> {
+    copied: u64,
+    total: u64,
+    meter: SpeedMeter,
+    buf: Vec<u8>,
+    event_tx: &'a Sender<BurnEvent>,
+}
+
+impl<'a> CopyProgress<'a> {
+    fn new(total: u64, speed_ema_alpha: f64, event_tx: &'a Sender<BurnEvent>) -> Self {
+        Self {
+            copied: 0,
+            total,
+            meter: SpeedMeter::new(speed_ema_alpha),
+            buf: vec![0u8; BUFFER_SIZE],
+            event_tx,
+        }
+    }
+
+    // false = בוטל באמצע, והקובץ ביעד חלקי
+    fn copy(&mut self, from: &Path, to: &Path, cancel_flag: &AtomicBool) -> std::io::Result<bool> {
+        let mut input = File::open(from)?;
+        let mut output = File::create(to)?;
+        loop {
+            if cancel_flag.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+            let n = input.read(&mut self.buf)?;
+            if n == 0 {
+                return Ok(true);
+            }
+            output.write_all(&self.buf[..n])?;
+            self.copied += n as u64;
+
+            let speed = self.meter.update(self.copied);
+            let _ = self.event_tx.send(BurnEvent::Progress {
+                written: self.copied,
+                total: self.total,
+                read_speed_mbps: 0.0,
+                write_speed_mbps: speed,
+                eta_secs: eta_secs(self.total.saturating_sub(self.copied), speed),
+            });
+        }
+    }
+}
+
+// טבלה חדשה עם מחיצת FAT32 אחת, ה-ISO מותקן לקריאה והקבצים מועתקים אליה.
+// None = בוטל. הכונן נשאר עם מה שהספיק להיות מועתק
+fn burn_windows_installer(
+    cfg: &BurnConfig,
+    image: &InstallImage,
+    iso_size: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Result<Option<u64>, BurnError> {
+    let fail = |e: std::io::Error| {
+        BurnError::new(
+            ErrorKind::Io,
+            format!("Windows installer setup failed: {}", e),
+        )
+    };
+
+    // כונן המערכת ומחיצות מעוגנות כבר נבדקו ב-preflight_target של run_burn
+    let device = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&cfg.device_path)
+        .map_err(BurnError::from)?;
+    if is_read_only(&device) {
+        return Err(BurnError::new(
+            ErrorKind::WriteProtected,
+            "Device is write-protected — check the lock switch",
+        ));
+    }
+    // הקבצים תופסים קצת פחות מה-ISO עצמו
+    let capacity = device_size(&device).map_err(fail)?;
+    if iso_size > capacity {
+        return Err(BurnError::too_small(
+            iso_size,
+            capacity,
+            format!(
+                "Device too small: need {} bytes, device has {}",
+                iso_size, capacity
+            ),
+        ));
+    }
+    let (node, _) = write_fresh_table(
+        &device,
+        &cfg.device_path,
+        PartitionScheme::Mbr,
+        &[(u64::MAX, partitions::FAT32_DATA)],
+    )
+    .map_err(fail)?
+    .remove(0);
+    drop(device);
+
+    let label = read_image_head(&cfg.iso_path, ISO_PVD_OFFSET + ISO_SECTOR)
+        .map(|head| windows::fat_label(&head[ISO_PVD_OFFSET..]))
+        .unwrap_or_else(|_| windows::fat_label(&[]));
+    make_fat_filesystem(&node, false, &label).map_err(fail)?;
+
+    let source = Mount::new(&cfg.iso_path, "iso", "loop,ro").map_err(fail)?;
+    // flush כותב כל קובץ כשהוא נסגר, כך שההתקדמות קרובה למה שבאמת הגיע לכונן
+    let target = Mount::new(&node, "target", "flush").map_err(fail)?;
+
+    let mut files = windows::list_files(source.path()).map_err(fail)?;
+    let install = PathBuf::from(&image.name);
+    if image.needs_split() {
+        files.retain(|(path, _)| *path != install);
+    }
+    let total = files.iter().map(|(_, size)| size).sum::<u64>()
+        + if image.needs_split() { image.size } else { 0 };
+    let _ = event_tx.send(BurnEvent::CopyingFiles {
+        files: files.len() as u64,
+        total,
+    });
+
+    let mut progress = CopyProgress::new(total, cfg.speed_ema_alpha, event_tx);
+
+    // wimlib לא מדווח התקדמות שאפשר למפות, אז הפס קופץ בסוף החלוקה
+    if image.needs_split() {
+        let _ = event_tx.send(BurnEvent::SplittingInstallImage);
+        let parts = target.path().join(install.with_extension("swm"));
+        if let Some(parent) = parts.parent() {
+            std::fs::create_dir_all(parent).map_err(fail)?;
+        }
+        if !windows::split_wim(&source.path().join(&install), &parts, cancel_flag).map_err(fail)? {
+            return Ok(None);
+        }
+        progress.copied += image.size;
+    }
+
+    for (path, _) in &files {
+        let destination = target.path().join(path);
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent).map_err(fail)?;
+        }
+        if !progress
+            .copy(&source.path().join(path), &destination, cancel_flag)
+            .map_err(fail)?
+        {
+            return Ok(None);
+        }
+    }
+
+    // ה-umount של היעד מחכה לכל מה שעוד במטמון
+    target.unmount().map_err(fail)?;
+    source.unmount().map_err(fail)?;
+    Ok(Some(progress.copied))
+}
+
+// כונן שכבר הוכן: ב-FAT32 של המחיצה הראשונה יש התווית של multiboot, ויש מחיצה שנייה
+pub fn is_multiboot(device_path: &Path) -> bool {
+    let Ok(device) = File::open(device_path) else {
+        return false;
+    };
+    let mut head = vec![0u8; partitions::TABLE_HEAD_LEN];
+    if device.read_exact_at(&mut head, 0).is_err() {
+        return false;
+    }
+    let table = partitions::parse_partition_table(&head);
+    let Some(boot) = table.iter().find(|p| p.index == 1) else {
+        return false;
+    };
+    let mut sector = [0u8; 512];
+    table.iter().any(|p| p.index == 2)
+        && device.read_exact_at(&mut sector, boot.start).is_ok()
+        && multiboot::is_boot_sector(&sector)
+}
+
+fn run_prepare_multiboot(device_path: &Path, unmount: bool, event_tx: &Sender<BurnEvent>) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    match prepare_multiboot(device_path, unmount, event_tx) {
+        Ok((boot, data)) => {
+            let _ = event_tx.send(BurnEvent::MultibootPrepared { boot, data });
+        }
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e));
+        }
+    }
+}
+
+fn prepare_multiboot(
+    device_path: &Path,
+    unmount: bool,
+    event_tx: &Sender<BurnEvent>,
+) -> Result<(PathBuf, PathBuf), BurnError> {
+    let fail =
+        |e: std::io::Error| BurnError::new(ErrorKind::Io, format!("Multiboot setup failed: {}", e));
+
+    // הטבלה הישנה נמחקת, ומחיצה מעוגנת הייתה מכשילה את BLKRRPART רק אחרי זה
+    refuse_system_disk(device_path)?;
+    release_mounts(device_path, unmount)?;
+    let device = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(device_path)
+        .map_err(BurnError::from)?;
+    if is_read_only(&device) {
+        return Err(BurnError::new(
+            ErrorKind::WriteProtected,
+            "Device is write-protected — check the lock switch",
+        ));
+    }
+    let nodes = write_fresh_table(
+        &device,
+        device_path,
+        PartitionScheme::Mbr,
+        &[
+            (multiboot::BOOT_PARTITION_SIZE, partitions::FAT32_DATA),
+            (u64::MAX, partitions::EXFAT_DATA),
+        ],
+    )
+    .map_err(fail)?;
+    drop(device);
+    let (boot, data) = (nodes[0].0.clone(), nodes[1].0.clone());
+
+    make_fat_filesystem(&boot, false, multiboot::BOOT_LABEL).map_err(fail)?;
+    make_fat_filesystem(&data, true, multiboot::DATA_LABEL).map_err(fail)?;
+
+    let mount = Mount::new(&boot, "boot", "flush").map_err(fail)?;
+    if let Some(reason) = multiboot::install_grub(mount.path(), device_path).map_err(fail)? {
+        let _ = event_tx.send(BurnEvent::Warning {
+            message: format!(
+                "GRUB for legacy BIOS was not installed, the drive will boot on UEFI only: {}",
+                reason
+            ),
+        });
+    }
+    write_grub_config(mount.path(), &[]).map_err(fail)?;
+    mount.unmount().map_err(fail)?;
+    Ok((boot, data))
+}
+
+fn write_grub_config(boot: &Path, isos: &[String]) -> std::io::Result<()> {
+    let directory = multiboot::grub_directory(boot);
+    std::fs::create_dir_all(&directory)?;
+    std::fs::write(directory.join("grub.cfg"), multiboot::grub_config(isos))
+}
+
+fn run_add_to_multiboot(
+    iso_path: &Path,
+    device_path: &Path,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let started = Instant::now();
+    match add_to_multiboot(iso_path, device_path, event_tx, cancel_flag) {
+        Ok(Some(bytes)) => {
+            let _ = event_tx.send(BurnEvent::Finished {
+                bytes,
+                elapsed_secs: started.elapsed().as_secs_f64(),
+                verified: false,
+            });
+        }
+        Ok(None) => {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+        }
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e));
+        }
+    }
+}
+
+// None = בוטל. הקובץ החלקי נמחק, כך שהתפריט לא מקבל רשומה שלא תעלה
+fn add_to_multiboot(
+    iso_path: &Path,
+    device_path: &Path,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Result<Option<u64>, BurnError> {
+    let fail = |e: std::io::Error| {
+        BurnError::new(ErrorKind::Io, format!("Multiboot update failed: {}", e))
+    };
+    if !is_multiboot(device_path) {
+        return Err(BurnError::new(
+            ErrorKind::Io,
+            "The drive is not a multiboot drive — prepare it first",
+        ));
+    }
+    let name = iso_path
+        .file_name()
+        .ok_or_else(|| BurnError::new(ErrorKind::Io, "The image path has no file name"))?;
+    let size = std::fs::metadata(iso_path).map_err(BurnError::from)?.len();
+
+    let data =
+        Mount::new(&partitions::partition_path(device_path, 2), "isos", "flush").map_err(fail)?;
+    let destination = data.path().join(name);
+    let mut progress = CopyProgress::new(size, DEFAULT_SPEED_EMA_ALPHA, event_tx);
+    // ENOSPC כאן הוא "אין מקום ל-ISO הזה", לא כשל של הכונן
+    let copied = progress
+        .copy(iso_path, &destination, cancel_flag)
+        .map_err(BurnError::from);
+    if !matches!(copied, Ok(true)) {
+        let _ = std::fs::remove_file(&destination);
+        return copied.map(|_| None);
+    }
+    let isos = multiboot::list_isos(data.path()).map_err(fail)?;
+    data.unmount().map_err(fail)?;
+
+    let boot =
+        Mount::new(&partitions::partition_path(device_path, 1), "boot", "flush").map_err(fail)?;
+    write_grub_config(boot.path(), &isos).map_err(fail)?;
+    boot.unmount().map_err(fail)?;
+
+    let _ = event_tx.send(BurnEvent::MultibootMenuUpdated { entries: isos });
+    Ok(Some(progress.copied))
+}
+
+// MBR, GPT ראשי ומשני, ה-ISO 9660 וחתימות של mkfs - הכל יושב בקצוות
+const RESTORE_WIPE_BYTES: u64 = 8 * 1024 * 1024;
+const VOLUME_LABEL: &str = "USB";
+
+// FAT32 עד 11 תווי ASCII באותיות גדולות, exFAT עד 15. בלי התווים ש-FAT אוסר בתווית
+fn volume_label(label: &str, exfat: bool) -> String {
+    let label: String = label
+        .trim()
+        .chars()
+        .filter(|c| (exfat || c.is_ascii()) && !c.is_control())
+        .filter(|c| !"\"*+,./:;<=>?[\\]|".contains(*c))
+        .take(if exfat { 15 } else { 11 })
+        .collect();
+    let label = label.trim_end();
+    if label.is_empty() {
+        VOLUME_LABEL.into()
+    } else if exfat {
+        label.into()
+    } else {
+        label.to_ascii_uppercase()
+    }
+}
+
+fn run_restore(cfg: &RestoreConfig, event_tx: &Sender<BurnEvent>, cancel_flag: &Arc<AtomicBool>) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let started = Instant::now();
+    match restore_drive(cfg, event_tx, cancel_flag) {
+        Ok(Some(bytes)) => {
+            let _ = event_tx.send(BurnEvent::Finished {
+                bytes,
+                elapsed_secs: started.elapsed().as_secs_f64(),
+                verified: false,
+            });
+        }
+        Ok(None) => {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+        }
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e));
+        }
+    }
+}
+
+// None = בוטל באמצע המחיקה, לפני שנכתבה טבלה. מחזיר כמה בייטים נמחקו
+fn restore_drive(
+    cfg: &RestoreConfig,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Result<Option<u64>, BurnError> {
+    let fail =
+        |e: std::io::Error| BurnError::new(ErrorKind::Io, format!("Drive restore failed: {}", e));
+
+    refuse_system_disk(&cfg.device_path)?;
+    release_mounts(&cfg.device_path, cfg.unmount)?;
+    let device = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&cfg.device_path)
+        .map_err(BurnError::from)?;
+    if is_read_only(&device) {
+        return Err(BurnError::new(
+            ErrorKind::WriteProtected,
+            "Device is write-protected — check the lock switch",
+        ));
+    }
+    let capacity = device_size(&device).map_err(BurnError::from)?;
+    let Some(wiped) =
+        wipe_edges(&device, capacity, event_tx, cancel_flag).map_err(BurnError::from)?
+    else {
+        return Ok(None);
+    };
+
+    let _ = event_tx.send(BurnEvent::Partitioning { scheme: cfg.scheme });
+    let exfat = capacity > FAT32_MAX_PARTITION;
+    let (kind, filesystem) = if exfat {
+        (partitions::EXFAT_DATA, "exFAT")
+    } else {
+        (partitions::FAT32_DATA, "FAT32")
+    };
+    let (node, _) = write_fresh_table(&device, &cfg.device_path, cfg.scheme, &[(u64::MAX, kind)])
+        .map_err(fail)?
+        .remove(0);
+    drop(device);
+
+    let label = volume_label(&cfg.label, exfat);
+    let _ = event_tx.send(BurnEvent::Formatting {
+        partition: node.clone(),
+        filesystem: filesystem.into(),
+        label: label.clone(),
+    });
+    make_fat_filesystem(&node, exfat, &label).map_err(fail)?;
+    Ok(Some(wiped))
+}
+
+// אפסים על RESTORE_WIPE_BYTES בכל קצה, עם Wiping. None = בוטל
+fn wipe_edges(
+    device: &File,
+    capacity: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> std::io::Result<Option<u64>> {
+    let edge = RESTORE_WIPE_BYTES.min(capacity / 2);
+    let total = 2 * edge;
+    let zeros = vec![0u8; BUFFER_SIZE];
+    let mut wiped = 0u64;
+    for start in [0, capacity - edge] {
+        let mut offset = start;
+        while offset < start + edge {
+            if cancel_flag.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            let n = (start + edge - offset).min(BUFFER_SIZE as u64) as usize;
+            device.write_all_at(&zeros[..n], offset)?;
+            offset += n as u64;
+            wiped += n as u64;
+            let _ = event_tx.send(BurnEvent::Wiping { wiped, total });
+        }
+    }
+    device.sync_all()?;
+    Ok(Some(wiped))
+}
+
+// MBR סופר סקטורים ב-32 ביט. מעבר לזה הכלי עובר ל-GPT
+const MBR_MAX_BYTES: u64 = u32::MAX as u64 * 512;
+
+fn run_format(cfg: &FormatConfig, event_tx: &Sender<BurnEvent>, cancel_flag: &Arc<AtomicBool>) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let started = Instant::now();
+
+    // כל מה שאפשר לבדוק לפני שנוגעים בכונן
+    let label = cfg.filesystem.label(&cfg.label);
+    let prepared = mkfs_command(cfg, &label).and_then(|mkfs| {
+        // גם עם unmount: על כונן המערכת הניתוק היה מפיל עיגונים לפני שנכשל על /
+        refuse_system_disk(&cfg.device_path)?;
+        release_mounts(&cfg.device_path, cfg.unmount)?;
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&cfg.device_path)
+            .map_err(BurnError::from)?;
+        if is_read_only(&device) {
+            return Err(BurnError::new(
+                ErrorKind::WriteProtected,
+                "Device is write-protected — check the lock switch",
+            ));
+        }
+        let capacity = device_size(&device).map_err(BurnError::from)?;
+        Ok((mkfs, device, capacity))
+    });
+    let (mut mkfs, mut device, capacity) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e));
+            return;
+        }
+    };
+
+    // פירמוט מלא עובר בכותב הרגיל, עם Progress. מהיר - רק הקצוות, כמו בשחזור
+    let zeroed = if cfg.full {
+        match write_stream(
+            std::io::repeat(0).take(capacity),
+            capacity,
+            &mut device,
+            &WriteOptions::default(),
+            None,
+            event_tx,
+            cancel_flag,
+        ) {
+            StreamEnd::Completed(bytes) => bytes,
+            StreamEnd::Failed => return,
+            StreamEnd::Cancelled => {
+                let _ = event_tx.send(BurnEvent::Cancelled);
+                return;
+            }
+        }
+    } else {
+        match wipe_edges(&device, capacity, event_tx, cancel_flag) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => {
+                let _ = event_tx.send(BurnEvent::Cancelled);
+                return;
+            }
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return;
+            }
+        }
+    };
+
+    let fail = |e: std::io::Error| BurnError::new(ErrorKind::Io, format!("Format failed: {}", e));
+    let scheme = if capacity > MBR_MAX_BYTES {
+        PartitionScheme::Gpt
+    } else {
+        PartitionScheme::Mbr
+    };
+    let _ = event_tx.send(BurnEvent::Partitioning { scheme });
+    let formatted = write_fresh_table(
+        &device,
+        &cfg.device_path,
+        scheme,
+        &[(u64::MAX, cfg.filesystem.partition_type())],
+    )
+    .and_then(|mut created| {
+        let (node, _) = created.remove(0);
+        drop(device);
+        let _ = event_tx.send(BurnEvent::Formatting {
+            partition: node.clone(),
+            filesystem: cfg.filesystem.name().into(),
+            label,
+        });
+        run_streamed(mkfs.arg(&node), event_tx)
+    });
+
+    let _ = event_tx.send(match formatted {
+        Ok(()) => BurnEvent::Finished {
+            bytes: zeroed,
+            elapsed_secs: started.elapsed().as_secs_f64(),
+            verified: false,
+        },
+        Err(e) => BurnEvent::Error(fail(e)),
+    });
+}
+
+// הכלי חסר = שם החבילה להתקנה, לפני שמוחקים משהו
+fn mkfs_command(cfg: &FormatConfig, label: &str) -> Result<std::process::Command, BurnError> {
+    let tool = cfg.filesystem.tool();
+    let path = find_tool(tool).ok_or_else(|| {
+        BurnError::new(
+            ErrorKind::Io,
+            format!(
+                "{} was not found — install the {} package",
+                tool,
+                cfg.filesystem.package()
+            ),
+        )
+    })?;
+    let args = cfg
+        .filesystem
+        .mkfs_args(label, cfg.cluster_size)
+        .map_err(|reason| BurnError::new(ErrorKind::Io, reason))?;
+    let mut mkfs = std::process::Command::new(path);
+    mkfs.args(args);
+    Ok(mkfs)
+}
+
+// ה-mkfs יושבים ב-sbin, שלא תמיד ב-PATH של משתמש רגיל
+pub(crate) fn find_tool(tool: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    std::env::split_paths(&path)
+        .chain(["/usr/sbin", "/sbin"].map(PathBuf::from))
+        .map(|dir| dir.join(tool))
+        .find(|candidate| candidate.is_file())
+}
+
+// לפני צריבה: לא כונן המערכת, לא מחיצה מעוגנת ולא מערכת קבצים על ההתקן (מצב בטוח).
+// force_overwrite = המשתמש כבר הסכים לאבד מה שיש עליו: מנתקים ולא מחפשים מערכת קבצים
+fn preflight_target(path: &Path, force_overwrite: bool) -> Result<(), BurnError> {
+    refuse_system_disk(path)?;
+    release_mounts(path, force_overwrite)?;
+    let is_block_device = std::fs::metadata(path)
+        .map(|m| m.file_type().is_block_device())
+        .unwrap_or(false);
+    if !is_block_device || force_overwrite {
+        return Ok(());
+    }
+    // קריאה נפרדת - ההתקן נפתח לכתיבה בלבד
+    let found = File::open(path)
+        .ok()
+        .and_then(|device| filesystem_on_device(&device));
+    match found {
+        Some(fs_type) => Err(BurnError::filesystem_detected(path, fs_type)),
+        None => Ok(()),
+    }
+}
+
+// לכל פעולה שדורסת את הכונן כולו, לפני שפותחים אותו לכתיבה
+fn refuse_system_disk(device_path: &Path) -> Result<(), BurnError> {
+    match mount::system_use(device_path).map_err(BurnError::from)? {
+        Some(reason) => Err(BurnError::system_disk(&reason)),
+        None => Ok(()),
+    }
+}
+
+// מחיצה מעוגנת עוצרת את המחיקה, הפירמוט, השחזור, ה-multiboot או שכפול לכונן, אלא אם המשתמש אישר לנתק אותה
+fn release_mounts(device_path: &Path, unmount: bool) -> Result<(), BurnError> {
+    let mounted = mount::mounted_under(device_path).map_err(BurnError::from)?;
+    match mounted.first() {
+        None => Ok(()),
+        Some((node, target)) if !unmount => Err(BurnError::detailed(
+            ErrorDetail::Unmount {
+                mounts: mounted.iter().map(|(_, target)| target.clone()).collect(),
+            },
+            format!(
+                "{} is mounted at {} — unmount it first",
+                node.display(),
+                target.display()
+            ),
+        )),
+        Some(_) => mount::unmount_all(&mounted)
+            .map_err(|e| BurnError::new(ErrorKind::Unmount, e.to_string())),
+    }
+}
+
+// כל שורה של הכלי, משני הערוצים, נשלחת כ-ToolOutput. בכישלון השורה האחרונה
+// ב-stderr היא ההודעה
+fn run_streamed(
+    command: &mut std::process::Command,
+    event_tx: &Sender<BurnEvent>,
+) -> std::io::Result<()> {
+    use std::process::Stdio;
+
+    fn forward(output: impl Read, event_tx: &Sender<BurnEvent>) -> String {
+        let mut last = String::new();
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            last = line.to_string();
+            let _ = event_tx.send(BurnEvent::ToolOutput { line: last.clone() });
+        }
+        last
+    }
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stderr = child.stderr.take();
+    let stderr_tx = event_tx.clone();
+    let stderr_reader = thread::spawn(move || {
+        stderr
+            .map(|stderr| forward(stderr, &stderr_tx))
+            .unwrap_or_default()
+    });
+    if let Some(stdout) = child.stdout.take() {
+        forward(stdout, event_tx);
+    }
+    let status = child.wait()?;
+    let last_error = stderr_reader.join().unwrap_or_default();
+    if !status.success() {
+        let program = Path::new(command.get_program())
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        return Err(std::io::Error::other(format!(
+            "{} failed: {}",
+            program, last_error
+        )));
+    }
+    Ok(())
+}
+
+// מוסיף מחיצה לטבלה שנכתבה עם התמונה, מבקש מהקרנל לקרוא אותה מחדש ומחכה ל-node
+fn append_partition(
+    device_path: &Path,
+    image_len: u64,
+    size: u64,
+    kind: PartitionType,
+    label: &str,
+) -> std::io::Result<(PathBuf, NewPartition)> {
+    // הכותב פתוח לכתיבה בלבד, וכאן צריך גם לקרוא את הטבלה
+    let device = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(device_path)?;
+    let capacity = device_size(&device)?;
+    let partition = partitions::append_partition(&device, capacity, image_len, size, kind, label)?;
+    device.sync_all()?;
+    partitions::reread_partitions(&device)?;
+
+    let node = partitions::partition_path(device_path, partition.index);
+    partitions::wait_for_node(&node)?;
+    Ok((node, partition))
+}
+
+fn eject_device(device_path: &Path) -> BurnEvent {
+    let udisks_error = match udisks_eject(device_path) {
+        Ok(()) => return BurnEvent::Ejected,
+        Err(e) => e.to_string(),
+    };
+
+    // בלי udisks (למשל שרת בלי session bus) - הכלי הרגיל
+    match std::process::Command::new("eject")
+        .arg(device_path)
+        .output()
+    {
+        Ok(out) if out.status.success() => BurnEvent::Ejected,
+        Ok(out) => BurnEvent::EjectFailed {
+            reason: String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        },
+        Err(_) => BurnEvent::EjectFailed {
+            reason: udisks_error,
+        },
+    }
+}
+
+const UDISKS_BUS_NAME: &str = "org.freedesktop.UDisks2";
+
+// כמו "הוצאה בטוחה" בסייר הקבצים: מוצאים את ה-Block של הנתיב, ממנו את ה-Drive,
+// ואז Eject ו-PowerOff, כל אחד אם הכונן תומך בו
+fn udisks_eject(device_path: &Path) -> zbus::Result<()> {
+    use std::collections::HashMap;
+    use std::os::unix::ffi::OsStrExt;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::{OwnedObjectPath, Value};
+
+    let connection = Connection::system()?;
+    let manager = Proxy::new(
+        &connection,
+        UDISKS_BUS_NAME,
+        "/org/freedesktop/UDisks2/Manager",
+        "org.freedesktop.UDisks2.Manager",
+    )?;
+    let options: HashMap<&str, Value> = HashMap::new();
+    let blocks: Vec<OwnedObjectPath> = manager.call("GetBlockDevices", &(&options,))?;
+
+    // /dev/disk/by-id/... -> /dev/sdb, כמו ש-udisks מחזיק אותו
+    let wanted = std::fs::canonicalize(device_path).unwrap_or_else(|_| device_path.into());
+    for block_path in blocks {
+        let block = Proxy::new(
+            &connection,
+            UDISKS_BUS_NAME,
+            block_path,
+            "org.freedesktop.UDisks2.Block",
+        )?;
+        // מערך בייטים שמסתיים ב-NUL
+        let device: Vec<u8> = block.get_property("Device")?;
+        let device = device.strip_suffix(&[0]).unwrap_or(&device);
+        if Path::new(std::ffi::OsStr::from_bytes(device)) != wanted {
+            continue;
+        }
+
+        let drive_path: OwnedObjectPath = block.get_property("Drive")?;
+        // "/" = אין מאחוריו כונן פיזי, למשל loop
+        if drive_path.as_str() == "/" {
+            return Err(zbus::Error::Failure(format!(
+                "{} has no drive to eject",
+                device_path.display()
+            )));
+        }
+        let drive = Proxy::new(
+            &connection,
+            UDISKS_BUS_NAME,
+            drive_path,
+            "org.freedesktop.UDisks2.Drive",
+        )?;
+        if drive.get_property::<bool>("Ejectable")? {
+            drive.call_method("Eject", &(&options,))?;
+        }
+        if drive.get_property::<bool>("CanPowerOff")? {
+            drive.call_method("PowerOff", &(&options,))?;
+        }
+        return Ok(());
+    }
+
+    Err(zbus::Error::Failure(format!(
+        "{} is not known to udisks",
+        device_path.display()
+    )))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    None,
+    Gzip,
+    Xz,
+    Bzip2,
+    Zstd,
+}
+
+// סיומת + magic bytes לכל פורמט דחיסה
+const COMPRESSION_FORMATS: &[(&str, &[u8], Compression)] = &[
+    ("gz", &[0x1F, 0x8B], Compression::Gzip),
+    ("xz", &[0xFD, b'7', b'z', b'X', b'Z', 0x00], Compression::Xz),
+    ("bz2", b"BZh", Compression::Bzip2),
+    ("zst", &[0x28, 0xB5, 0x2F, 0xFD], Compression::Zstd),
+];
+
+// תמונות גולמיות: 1F 8B או BZh בתחילתן הם נתונים, לא כותרת של דחיסה
+const RAW_IMAGE_EXTENSIONS: [&str; 4] = ["iso", "img", "bin", "raw"];
+
+// ה-magic קובע. הסיומת רק כשאין magic מוכר - למשל קובץ יעד שעוד לא נכתב
+fn detect_compression(path: &Path, magic: &[u8]) -> Compression {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    if RAW_IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+        return Compression::None;
+    }
+
+    COMPRESSION_FORMATS
+        .iter()
+        .find(|(_, m, _)| magic.starts_with(m))
+        .or_else(|| COMPRESSION_FORMATS.iter().find(|(e, _, _)| *e == ext))
+        .map(|(_, _, c)| *c)
+        .unwrap_or(Compression::None)
+}
+
+// "x.iso" או "x.iso.xz". סיומת הדחיסה לא נחשבת
+fn is_iso_path(path: &Path) -> bool {
+    let ext = |p: &Path| {
+        p.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+    };
+
+    let mut path = path.to_path_buf();
+    if let Some(e) = ext(&path) {
+        if COMPRESSION_FORMATS.iter().any(|(c, _, _)| *c == e) {
+            path.set_extension("");
+        }
+    }
+    ext(&path).as_deref() == Some("iso")
+}
+
+const ISO_SECTOR: usize = 2048;
+// Primary Volume Descriptor בסקטור 16, Boot Record (El Torito) בסקטור 17
+const ISO_PVD_OFFSET: usize = 16 * ISO_SECTOR;
+const ISO_BOOT_RECORD_OFFSET: usize = 17 * ISO_SECTOR;
+
+// בודק רק את הכותרות - דרך ה-decompressor כך שגם .iso.xz נבדק
+fn validate_iso(path: &Path) -> Result<(), String> {
+    let header =
+        read_image_head(path, ISO_BOOT_RECORD_OFFSET + ISO_SECTOR).map_err(|e| e.to_string())?;
+    let filled = header.len();
+
+    if filled < ISO_PVD_OFFSET + ISO_SECTOR {
+        return Err(format!(
+            "Image is too small to be an ISO ({} bytes)",
+            filled
+        ));
+    }
+
+    let pvd = &header[ISO_PVD_OFFSET..ISO_PVD_OFFSET + ISO_SECTOR];
+    if pvd[0] != 1 || &pvd[1..6] != b"CD001" {
+        return Err("No ISO 9660 primary volume descriptor at sector 16".into());
+    }
+
+    // El Torito לא חובה, אבל אם יש Boot Record הוא צריך להיות תקין
+    if filled >= ISO_BOOT_RECORD_OFFSET + ISO_SECTOR {
+        let boot = &header[ISO_BOOT_RECORD_OFFSET..ISO_BOOT_RECORD_OFFSET + ISO_SECTOR];
+        if boot[0] == 0
+            && &boot[1..6] == b"CD001"
+            && !boot[7..].starts_with(b"EL TORITO SPECIFICATION")
+        {
+            return Err("Boot record at sector 17 is not a valid El Torito record".into());
+        }
+    }
+
+    Ok(())
+}
+
+// עד len בייטים מתחילת התמונה (אחרי פתיחת הדחיסה). קובץ קצר מחזיר פחות
+fn read_image_head(path: &Path, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut reader = make_decompressor(path, File::open(path)?)?;
+    let mut header = vec![0u8; len];
+    let mut filled = 0;
+    while filled < len {
+        match reader.read(&mut header[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    header.truncate(filled);
+    Ok(header)
+}
+
+// תיקיית השורש נמצאת בדרך כלל ב-MB הראשונים. לא פותחים gigabytes של xz בשבילה
+const LIVE_PROBE_LIMIT: usize = 64 * 1024 * 1024;
+
+// casper או live-boot לפי התיקייה בשורש ה-ISO. None לכל תמונה אחרת, וגם בשגיאת קריאה
+pub fn live_flavor(path: &Path) -> Option<LiveFlavor> {
+    let head = read_image_head(path, ISO_PVD_OFFSET + ISO_SECTOR).ok()?;
+    let pvd = head.get(ISO_PVD_OFFSET..ISO_PVD_OFFSET + ISO_SECTOR)?;
+    if pvd[0] != 1 || &pvd[1..6] != b"CD001" {
+        return None;
+    }
+    let (offset, len) = persistence::root_directory_extent(pvd)?;
+    if offset + len > LIVE_PROBE_LIMIT {
+        return None;
+    }
+    let head = read_image_head(path, offset + len).ok()?;
+    persistence::live_flavor(head.get(offset..)?)
+}
+
+// שם, גודל ואתחול לשורת המידע. None רק בשגיאת קריאה - תמונה שאינה ISO מקבלת גודל בלבד
+pub fn image_summary(path: &Path) -> Option<ImageSummary> {
+    let file_size = std::fs::metadata(path).ok()?.len();
+    let head = read_image_head(path, image_info::DESCRIPTORS_END).ok()?;
+    // הקטלוג בדרך כלל בסקטורים הראשונים, אבל לא פותחים gigabytes של xz בשבילו
+    let catalog = image_info::boot_catalog_sector(&head)
+        .map(|sector| sector as usize * ISO_SECTOR)
+        .filter(|offset| *offset < LIVE_PROBE_LIMIT)
+        .and_then(|offset| {
+            let head = read_image_head(path, offset + ISO_SECTOR).ok()?;
+            head.get(offset..).map(<[u8]>::to_vec)
+        });
+    Some(image_info::summarize(&head, catalog.as_deref(), file_size))
+}
+
+// None לכל תמונה שאינה תמונת התקנה של Windows, וגם בשגיאת קריאה
+pub fn windows_installer(path: &Path) -> Option<InstallImage> {
+    image_info::windows_installer(path).ok().flatten()
+}
+
+// הקישור היציב של כונן שלם ב-/dev/disk/by-id. usb-* קודם, כי wwn-* משותף לפעמים לכמה
+// כוננים מאחורי אותו גשר. None כשאין (כונן וירטואלי, קובץ)
+pub fn stable_device_link(device: &Path) -> Option<PathBuf> {
+    let node = std::fs::canonicalize(device).ok()?;
+    let mut links: Vec<PathBuf> = std::fs::read_dir("/dev/disk/by-id")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|link| std::fs::canonicalize(link).is_ok_and(|target| target == node))
+        .collect();
+    links.sort_by_key(|link| {
+        let name = link.file_name().unwrap_or_default().to_string_lossy();
+        (!name.starts_with("usb-"), name.into_owned())
+    });
+    links.into_iter().next()
+}
+
+// חתימת MBR או ISO 9660 - לא מבטיח שזה יעלה, רק שזה לא קובץ אקראי
+pub fn looks_bootable(path: &Path) -> std::io::Result<bool> {
+    let header = read_image_head(path, ISO_PVD_OFFSET + 6)?;
+    let mbr = header.get(510..512) == Some(&[0x55, 0xAA][..]);
+    let iso = header.get(ISO_PVD_OFFSET + 1..ISO_PVD_OFFSET + 6) == Some(&b"CD001"[..]);
+    Ok(mbr || iso)
+}
+
+const UNKNOWN_SIZE_WARNING: &str =
+    "The uncompressed image size is unknown — skipping the device capacity check";
+
+// מה שייכתב לכונן בפועל: בתמונה דחוסה file_size הוא גודל הקובץ הדחוס, והגודל הפתוח בא
+// מה-index של xz/zstd או מה-trailer של gzip. None = דחוס ולא ידוע
+fn written_size(path: &Path, file_size: u64) -> Option<u64> {
+    match source_compression(path) {
+        Ok(Compression::None) | Err(_) => Some(file_size),
+        Ok(compression) => uncompressed_size(path, compression),
+    }
+}
+
+// הגודל אחרי פתיחה, מהסוף של הקובץ ובלי לפתוח אותו. None = הפורמט לא שומר אותו
+fn uncompressed_size(path: &Path, compression: Compression) -> Option<u64> {
+    let file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    match compression {
+        Compression::Gzip => {
+            let mut isize = [0u8; 4];
+            file.read_exact_at(&mut isize, len.checked_sub(4)?).ok()?;
+            Some(gzip_size(u32::from_le_bytes(isize), len))
+        }
+        Compression::Xz => xz_size(&file, len),
+        Compression::Zstd => zstd_size(&file, len),
+        Compression::None | Compression::Bzip2 => None,
+    }
+}
+
+// ISIZE הוא הגודל mod 2^32. תמונת דיסק לא קטנה מהקובץ הדחוס שלה, אז לוקחים את
+// הערך הראשון שלא קטן ממנו. לא תמיד נכון (תמונה דלילה שנדחסה פי אלפים), ולכן הכותב
+// עובר ל-"לא ידוע" כשכתב יותר מזה
+fn gzip_size(isize: u32, compressed: u64) -> u64 {
+    const WRAP: u64 = 1 << 32;
+    let isize = isize as u64;
+    if isize >= compressed {
+        isize
+    } else {
+        isize + (compressed - isize).div_ceil(WRAP) * WRAP
+    }
+}
+
+// ב-xz כל stream נגמר ב-index עם הגודל הפתוח של כל block. הולכים מהסוף אחורה, כי
+// xz -T ו-pixz משרשרים כמה streams (עם ריפוד אפסים ביניהם)
+fn xz_size(file: &File, len: u64) -> Option<u64> {
+    const HEADER: u64 = 12;
+    const FOOTER: u64 = 12;
+    let mut end = len;
+    let mut total = 0u64;
+    while end > 0 {
+        let mut padding = [0u8; 4];
+        file.read_exact_at(&mut padding, end.checked_sub(4)?).ok()?;
+        if padding == [0; 4] {
+            end -= 4;
+            continue;
+        }
+        let mut footer = [0u8; FOOTER as usize];
+        file.read_exact_at(&mut footer, end.checked_sub(FOOTER)?)
+            .ok()?;
+        if &footer[10..12] != b"YZ" {
+            return None;
+        }
+        let index_size = (u32::from_le_bytes(footer[4..8].try_into().ok()?) as u64 + 1) * 4;
+        let index_start = (end - FOOTER).checked_sub(index_size)?;
+        let mut index = vec![0u8; index_size as usize];
+        file.read_exact_at(&mut index, index_start).ok()?;
+        let (blocks, uncompressed) = xz_index(&index)?;
+        end = index_start.checked_sub(blocks)?.checked_sub(HEADER)?;
+        total = total.checked_add(uncompressed)?;
+    }
+    Some(total)
+}
+
+// ב-zstd הגודל הפתוח נמצא ב-header של כל frame (אם המדחס ידע אותו מראש). zstd כותב frame
+// אחד, pzstd כמה, אז מדלגים על ה-blocks של כל frame כדי להגיע לבא אחריו
+fn zstd_size(file: &File, len: u64) -> Option<u64> {
+    const MAGIC: u32 = 0xFD2F_B528;
+    let mut at = 0u64;
+    let mut total = 0u64;
+    while at < len {
+        let mut head = [0u8; 18];
+        let n = file.read_at(&mut head, at).ok()?;
+        let head = head.get(..n)?;
+        let magic = u32::from_le_bytes(head.get(..4)?.try_into().ok()?);
+        // skippable frame: magic, אורך, ותוכן שלא נכתב לכונן
+        if magic & 0xFFFF_FFF0 == 0x184D_2A50 {
+            let size = u32::from_le_bytes(head.get(4..8)?.try_into().ok()?);
+            at = at.checked_add(8 + size as u64)?;
+            continue;
+        }
+        if magic != MAGIC {
+            return None;
+        }
+        total = total.checked_add(zstd::zstd_safe::get_frame_content_size(head).ok()??)?;
+        let descriptor = *head.get(4)?;
+        let single_segment = descriptor & 0x20 != 0;
+        let header_len = 1
+            + !single_segment as u64
+            + [0, 1, 2, 4][(descriptor & 0x03) as usize]
+            + match descriptor >> 6 {
+                0 => single_segment as u64,
+                1 => 2,
+                2 => 4,
+                _ => 8,
+            };
+        let mut block = at + 4 + header_len;
+        loop {
+            let mut block_header = [0u8; 4];
+            file.read_exact_at(&mut block_header[..3], block).ok()?;
+            let block_header = u32::from_le_bytes(block_header);
+            // raw ו-compressed שומרים את הגודל בקובץ, RLE רק בייט אחד
+            block += 3 + match (block_header >> 1) & 0x03 {
+                0 | 2 => (block_header >> 3) as u64,
+                1 => 1,
+                _ => return None,
+            };
+            if block_header & 1 != 0 {
+                break;
+            }
+        }
+        // checksum של 4 בייטים בסוף ה-frame
+        at = block + if descriptor & 0x04 != 0 { 4 } else { 0 };
+    }
+    Some(total)
+}
+
+// (גודל ה-blocks הדחוסים כולל ריפוד, סכום הגדלים הפתוחים)
+fn xz_index(index: &[u8]) -> Option<(u64, u64)> {
+    // מספר בשבעה ביטים לבייט, הנמוכים קודם. עד 9 בייטים
+    let varint = |at: &mut usize| -> Option<u64> {
+        let mut value = 0u64;
+        for shift in 0..9 {
+            let byte = *index.get(*at)?;
+            *at += 1;
+            value |= ((byte & 0x7F) as u64) << (shift * 7);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    };
+    if index.first() != Some(&0) {
+        return None;
+    }
+    let mut at = 1;
+    let records = varint(&mut at)?;
+    let (mut blocks, mut uncompressed) = (0u64, 0u64);
+    for _ in 0..records {
+        blocks = blocks.checked_add(varint(&mut at)?.div_ceil(4) * 4)?;
+        uncompressed = uncompressed.checked_add(varint(&mut at)?)?;
+    }
+    Some((blocks, uncompressed))
+}
+
+// תמונות דחוסות נפתחות תוך כדי קריאה. פורמט חדש = עוד שורה ב-match
+fn make_decompressor<R: Read + Send + 'static>(
+    path: &Path,
+    reader: R,
+) -> std::io::Result<Box<dyn Read + Send>> {
+    let mut reader = BufReader::with_capacity(BUFFER_SIZE, reader);
+    let compression = detect_compression(path, reader.fill_buf()?);
+
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        Compression::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+        Compression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+    })
+}
+
+fn run_wipe(cfg: WipeConfig, event_tx: &Sender<BurnEvent>, cancel_flag: Arc<AtomicBool>) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let wipe_started = Instant::now();
+
+    let released = refuse_system_disk(&cfg.device_path)
+        .and_then(|()| release_mounts(&cfg.device_path, cfg.unmount));
+    if let Err(e) = released {
+        let _ = event_tx.send(BurnEvent::Error(e));
+        return;
+    }
+
+    let mut device = match OpenOptions::new().write(true).open(&cfg.device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    let total_size = match device_size(&device) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    let passes = cfg.pattern.passes();
+    for (i, fill) in passes.iter().enumerate() {
+        let _ = event_tx.send(BurnEvent::WipePass {
+            pass: i as u32 + 1,
+            passes: passes.len() as u32,
+        });
+
+        if let Err(e) = device.seek(SeekFrom::Start(0)) {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+
+        let source = match pattern_source(*fill, total_size) {
+            Ok(s) => s,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return;
+            }
+        };
+
+        match write_stream(
+            source,
+            total_size,
+            &mut device,
+            &WriteOptions::default(),
+            None,
+            event_tx,
+            &cancel_flag,
+        ) {
+            StreamEnd::Completed(_) => {}
+            StreamEnd::Failed => return,
+            StreamEnd::Cancelled => {
+                let _ = event_tx.send(BurnEvent::Cancelled);
+                return;
+            }
+        }
+    }
+
+    // רק המעבר האחרון נשאר על ההתקן, ונתונים אקראיים אי אפשר לשחזר להשוואה
+    let mut verified = false;
+    if cfg.verify {
+        match passes.last() {
+            Some(PassFill::Byte(byte)) => {
+                let mut expected = std::io::repeat(*byte).take(total_size);
+                if !verify_stream(
+                    &mut expected,
+                    &cfg.device_path,
+                    0,
+                    total_size,
+                    false,
+                    event_tx,
+                    &cancel_flag,
+                ) {
+                    return;
+                }
+                verified = true;
+            }
+            _ => {
+                let _ = event_tx.send(BurnEvent::VerifySkipped {
+                    reason: "Random data cannot be read back and compared".into(),
+                });
+            }
+        }
+    }
+
+    let _ = event_tx.send(BurnEvent::Finished {
+        bytes: total_size * passes.len() as u64,
+        elapsed_secs: wipe_started.elapsed().as_secs_f64(),
+        verified,
+    });
+}
+
+fn run_surface_test(
+    device_path: &Path,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+
+    // שלב 1: כתיבה, עם Progress הרגיל
+    let Some((total_size, seed)) = write_test_pattern(device_path, event_tx, cancel_flag) else {
+        return;
+    };
+
+    // שלב 2: קריאה בחזרה מהמדיה עצמה, עם Verifying
+    let mut device = match open_uncached(device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::opening(e, device_path)));
+            return;
+        }
+    };
+
+    let mut bad_ranges: Vec<BadRange> = Vec::new();
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    let mut expected = vec![0u8; BUFFER_SIZE];
+    let mut checked = 0u64;
+
+    while checked < total_size {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return;
+        }
+
+        let want = (total_size - checked).min(BUFFER_SIZE as u64) as usize;
+        // בלוק שלא נקרא נחשב פגום כולו, וממשיכים מהבלוק הבא
+        let read = device
+            .seek(SeekFrom::Start(checked))
+            .and_then(|_| device.read_exact(&mut buf[..want]));
+        if read.is_err() {
+            mark_bad(&mut bad_ranges, checked, checked + want as u64);
+        } else {
+            fill_surface_pattern(&mut expected[..want], checked, seed);
+            for (i, (got, exp)) in buf[..want]
+                .chunks(SURFACE_SECTOR as usize)
+                .zip(expected[..want].chunks(SURFACE_SECTOR as usize))
+                .enumerate()
+            {
+                if got != exp {
+                    let start = checked + i as u64 * SURFACE_SECTOR;
+                    mark_bad(&mut bad_ranges, start, start + got.len() as u64);
+                }
+            }
+        }
+
+        checked += want as u64;
+        let _ = event_tx.send(BurnEvent::Verifying {
+            checked,
+            total: total_size,
+        });
+    }
+
+    let _ = event_tx.send(BurnEvent::SurfaceReport {
+        bad_ranges,
+        total_bytes: total_size,
+    });
+}
+
+// כמו f3/H2testw: זיכרון מזויף "עוטף" כתיבות מעבר לגודל האמיתי, אז ההתחלה מכילה
+// תבנית של LBA מאוחר יותר, והגודל האמיתי הוא המרחק עד שאותו תוכן חוזר
+fn run_capacity_test(
+    device_path: &Path,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+
+    let Some((advertised, seed)) = write_test_pattern(device_path, event_tx, cancel_flag) else {
+        return;
+    };
+
+    let mut device = match open_uncached(device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::opening(e, device_path)));
+            return;
+        }
+    };
+
+    let Some((verified, wraps_at)) =
+        read_back_capacity(&mut device, advertised, seed, event_tx, cancel_flag)
+    else {
+        return;
+    };
+
+    let _ = event_tx.send(BurnEvent::CapacityReport {
+        advertised,
+        verified,
+        wraps_at,
+    });
+}
+
+// מחזיר (קיבולת אמיתית, הכתובת שבה הכתיבות עוטפות להתחלה), או None בביטול
+fn read_back_capacity<R: Read>(
+    device: &mut R,
+    advertised: u64,
+    seed: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Option<(u64, Option<u64>)> {
+    let mut buf = vec![0u8; CAPACITY_BLOCK];
+    let mut expected = vec![0u8; CAPACITY_BLOCK];
+    let mut offset = 0u64;
+    // הסקטור הראשון שלא חזר כמו שנכתב, וה-LBA שהתבנית שלו נמצאה בו
+    let mut first_bad: Option<(u64, u64)> = None;
+
+    while offset < advertised {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return None;
+        }
+
+        let want = (advertised - offset).min(CAPACITY_BLOCK as u64) as usize;
+        if device.read_exact(&mut buf[..want]).is_err() {
+            break;
+        }
+        fill_surface_pattern(&mut expected[..want], offset, seed);
+        for (i, (got, exp)) in buf[..want]
+            .chunks(SURFACE_SECTOR as usize)
+            .zip(expected[..want].chunks(SURFACE_SECTOR as usize))
+            .enumerate()
+        {
+            let at = offset + i as u64 * SURFACE_SECTOR;
+            match first_bad {
+                None if got == exp => {}
+                // תוכן של LBA אחר = הכונן עוטף. כל דבר אחר - מכאן והלאה אין על מה לסמוך
+                None => match stored_lba(got, seed) {
+                    Some(lba) => first_bad = Some((at, lba)),
+                    None => return Some((at, None)),
+                },
+                // אותו תוכן חוזר בדיוק אחרי הגודל האמיתי
+                Some((start, lba)) if stored_lba(got, seed) == Some(lba) => {
+                    return Some((at - start, Some(at - start)));
+                }
+                Some(_) => {}
+            }
+        }
+
+        offset += want as u64;
+        let _ = event_tx.send(BurnEvent::Verifying {
+            checked: offset,
+            total: advertised,
+        });
+    }
+
+    Some(first_bad.map_or((offset, None), |(start, _)| (start, None)))
+}
+
+// ה-LBA שהתבנית שלו כתובה בסקטור, אם הסקטור הוא תבנית שלמה ולא זבל
+fn stored_lba(sector: &[u8], seed: u64) -> Option<u64> {
+    let lba = u64::from_le_bytes(sector.get(..8)?.try_into().ok()?) ^ seed;
+    let mut expected = [0u8; SURFACE_SECTOR as usize];
+    let expected = expected.get_mut(..sector.len())?;
+    fill_surface_pattern(expected, lba.checked_mul(SURFACE_SECTOR)?, seed);
+    (sector == &expected[..]).then_some(lba)
+}
+
+// אותו זוג reader/writer של הצריבה, רק שההתקן הוא המקור והקובץ הוא היעד
+fn run_clone(cfg: &CloneConfig, event_tx: &Sender<BurnEvent>, cancel_flag: &Arc<AtomicBool>) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let started = Instant::now();
+
+    let device = match File::open(&cfg.device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+    let source_size = match device_size(&device) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    // יעד שהוא התקן: לא יוצרים, לא מוחקים בכישלון, ומעתיקים רק כמה שנכנס
+    let target_is_device = std::fs::metadata(&cfg.target_path)
+        .map(|m| m.file_type().is_block_device())
+        .unwrap_or(false);
+    let mut image = if target_is_device {
+        match prepare_clone_target(cfg, &device, source_size) {
+            Ok(target) => target,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e));
+                return;
+            }
+        }
+    } else {
+        match File::create(&cfg.target_path) {
+            Ok(f) => f,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return;
+            }
+        }
+    };
+    let total_size = match device_size(&image) {
+        Ok(target_size) if target_is_device => {
+            let used = used_size(&device, source_size);
+            if target_size < used {
+                let _ = event_tx.send(BurnEvent::Warning {
+                    message: format!(
+                        "The destination ({} bytes) is smaller than the used part of the source ({} bytes) — the copy will be cut short",
+                        target_size, used
+                    ),
+                });
+            }
+            source_size.min(target_size)
+        }
+        _ => source_size,
+    };
+    // התמונה החלקית נמחקת בכישלון, אבל אף פעם לא צומת התקן
+    let discard_target = || {
+        if !target_is_device {
+            let _ = std::fs::remove_file(&cfg.target_path);
+        }
+    };
+
+    let bad_ranges = Arc::new(Mutex::new(Vec::new()));
+    let read_bytes = Arc::new(AtomicU64::new(0));
+    let source = CountingReader {
+        inner: RescueReader {
+            device,
+            position: 0,
+            total: total_size,
+            policy: cfg.on_read_error,
+            bad_ranges: bad_ranges.clone(),
+        },
+        count: read_bytes.clone(),
+    };
+
+    let compression = if target_is_device {
+        Compression::None
+    } else {
+        detect_compression(&cfg.target_path, &[])
+    };
+    let source = match make_compressor(compression, source, cfg.compression_level) {
+        Ok(s) => s,
+        Err(e) => {
+            discard_target();
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    let mut check = (cfg.verify && compression == Compression::None)
+        .then(|| InFlightCheck::Hash(Hasher::new(HashAlgorithm::Blake3)));
+
+    let end = if compression == Compression::None {
+        write_stream(
+            source,
+            total_size,
+            &mut image,
+            &WriteOptions::default(),
+            check.as_mut(),
+            event_tx,
+            cancel_flag,
+        )
+    } else {
+        // ה-Progress של write_stream סופר בייטים דחוסים, אז מחליפים אותו ב-CloneProgress
+        // שמראה גם כמה מההתקן כבר נקרא
+        let (inner_tx, inner_rx) = bounded::<BurnEvent>(32);
+        let image = &mut image;
+        thread::scope(|scope| {
+            let writer = scope.spawn(move || {
+                write_stream(
+                    source,
+                    total_size,
+                    image,
+                    &WriteOptions::default(),
+                    None,
+                    &inner_tx,
+                    cancel_flag,
+                )
+            });
+            let mut meter = SpeedMeter::new(DEFAULT_SPEED_EMA_ALPHA);
+            for event in inner_rx {
+                match event {
+                    BurnEvent::Progress { written, .. } => {
+                        let read = read_bytes.load(Ordering::Relaxed);
+                        let speed_mbps = meter.update(read);
+                        let _ = event_tx.send(BurnEvent::CloneProgress {
+                            read,
+                            total: total_size,
+                            written,
+                            speed_mbps,
+                            eta_secs: eta_secs(total_size.saturating_sub(read), speed_mbps),
+                        });
+                    }
+                    other => {
+                        let _ = event_tx.send(other);
+                    }
+                }
+            }
+            writer.join().unwrap_or(StreamEnd::Failed)
+        })
+    };
+
+    let written = match end {
+        StreamEnd::Completed(written) => written,
+        // תמונה חלקית לא שווה כלום, ועלולה להיצרב בטעות אחר כך
+        StreamEnd::Failed => {
+            discard_target();
+            return;
+        }
+        StreamEnd::Cancelled => {
+            discard_target();
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return;
+        }
+    };
+
+    let bad_ranges = std::mem::take(&mut *bad_ranges.lock().unwrap());
+    if !bad_ranges.is_empty() {
+        let bad_sectors: u64 = bad_ranges
+            .iter()
+            .map(|r| r.last_lba - r.first_lba + 1)
+            .sum();
+        for range in &bad_ranges {
+            tracing::warn!(
+                first_lba = range.first_lba,
+                last_lba = range.last_lba,
+                "unreadable sectors filled with zeros"
+            );
+        }
+        let _ = event_tx.send(BurnEvent::Warning {
+            message: format!(
+                "{} unreadable sector(s) in {} range(s) were filled with zeros",
+                bad_sectors,
+                bad_ranges.len()
+            ),
+        });
+    }
+
+    // ה-hash נלקח ממה שנקרא מהמקור, אז היעד נבדק מול המקור ולא מול עצמו
+    let verified = match check {
+        Some(InFlightCheck::Hash(hasher)) => {
+            drop(image);
+            let ok = verify_digest(
+                &hasher.finalize(),
+                HashAlgorithm::Blake3,
+                &cfg.target_path,
+                0,
+                written,
+                event_tx,
+                cancel_flag,
+            );
+            if !cancel_flag.load(Ordering::Relaxed) {
+                let _ = event_tx.send(BurnEvent::VerifySummary {
+                    method: VerifyMethod::Hash(HashAlgorithm::Blake3),
+                    ok,
+                });
+            }
+            if !ok {
+                return;
+            }
+            true
+        }
+        _ => false,
+    };
+
+    // הגודל הגולמי, גם כשהקובץ דחוס - זה מה שהמהירות הממוצעת צריכה
+    let _ = event_tx.send(BurnEvent::Finished {
+        bytes: read_bytes.load(Ordering::Relaxed),
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        verified,
+    });
+}
+
+// בדיקות לפני שכותבים על התקן יעד. אותו צומת (גם דרך symlink כמו /dev/disk/by-id)
+// היה דורס את המקור תוך כדי קריאה
+fn prepare_clone_target(
+    cfg: &CloneConfig,
+    source: &File,
+    source_size: u64,
+) -> Result<File, BurnError> {
+    let same_node = match (source.metadata(), std::fs::metadata(&cfg.target_path)) {
+        (Ok(s), Ok(t)) => s.rdev() == t.rdev(),
+        _ => false,
+    };
+    if same_node {
+        return Err(BurnError::new(
+            ErrorKind::Io,
+            "Source and destination are the same device",
+        ));
+    }
+    // היעד נדרס כולו, כמו בצריבה
+    refuse_system_disk(&cfg.target_path)?;
+    release_mounts(&cfg.target_path, cfg.unmount)?;
+
+    let target = OpenOptions::new()
+        .write(true)
+        .open(&cfg.target_path)
+        .map_err(|e| BurnError::opening(e, &cfg.target_path))?;
+    if is_read_only(&target) {
+        return Err(BurnError::new(
+            ErrorKind::WriteProtected,
+            "Device is write-protected — check the lock switch",
+        ));
+    }
+    if device_size(&target)? == 0 && source_size > 0 {
+        return Err(BurnError::new(
+            ErrorKind::DeviceTooSmall,
+            "Destination device reports a size of 0 (no media?)",
+        ));
+    }
+    Ok(target)
+}
+
+// סוף המחיצה האחרונה לפי הטבלה של המקור. בלי טבלה - כל ההתקן נחשב בשימוש
+fn used_size(source: &File, source_size: u64) -> u64 {
+    let mut head = vec![0u8; partitions::TABLE_HEAD_LEN];
+    if source.read_exact_at(&mut head, 0).is_err() {
+        return source_size;
+    }
+    partitions::parse_partition_table(&head)
+        .iter()
+        .map(|p| p.start + p.size)
+        .max()
+        .unwrap_or(source_size)
+        .min(source_size)
+}
+
+// ההפך של make_decompressor. הקצב נקבע בקריאה מההתקן, אז גם xz לא הופך לצוואר בקבוק
+// ברוב הכוננים - ובכל מקרה אפשר להוריד רמה
+fn make_compressor<R: Read + Send + 'static>(
+    compression: Compression,
+    reader: R,
+    level: Option<u32>,
+) -> std::io::Result<Box<dyn Read + Send>> {
+    let reader = BufReader::with_capacity(BUFFER_SIZE, reader);
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(flate2::read::GzEncoder::new(
+            reader,
+            flate2::Compression::new(level.unwrap_or(6).min(9)),
+        )),
+        Compression::Xz => Box::new(xz2::read::XzEncoder::new(reader, level.unwrap_or(6).min(9))),
+        Compression::Bzip2 => Box::new(bzip2::read::BzEncoder::new(
+            reader,
+            bzip2::Compression::new(level.unwrap_or(6).clamp(1, 9)),
+        )),
+        Compression::Zstd => Box::new(zstd::stream::read::Encoder::new(
+            reader,
+            level.unwrap_or(3).clamp(1, 22) as i32,
+        )?),
+    })
+}
+
+// סופר כמה בייטים עברו דרכו, בשביל התקדמות כשהצד השני של הצינור דחוס
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+// קורא את ההתקן לפי offset. כשבלוק נכשל, ב-ZeroFill עוברים עליו סקטור-סקטור
+// כדי לאבד רק את מה שבאמת לא נקרא
+struct RescueReader {
+    device: File,
+    position: u64,
+    total: u64,
+    policy: ReadErrorPolicy,
+    bad_ranges: Arc<Mutex<Vec<BadRange>>>,
+}
+
+// עובר את ה-thread הקורא בתוך io::Error, כדי שהשגיאה תגיע עם המיקום בהתקן
+#[derive(Debug)]
+struct DeviceReadError {
+    offset: u64,
+    source: std::io::Error,
+}
+
+impl std::fmt::Display for DeviceReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Read error at byte {}: {}", self.offset, self.source)
+    }
+}
+
+impl std::error::Error for DeviceReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl Read for RescueReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = (self.total - self.position).min(buf.len() as u64) as usize;
+        if n == 0 {
+            return Ok(0);
+        }
+        let buf = &mut buf[..n];
+        match self.device.read_exact_at(buf, self.position) {
+            Ok(()) => {}
+            Err(e) if self.policy == ReadErrorPolicy::Abort => {
+                return Err(std::io::Error::new(
+                    e.kind(),
+                    DeviceReadError {
+                        offset: self.position,
+                        source: e,
+                    },
+                ));
+            }
+            Err(_) => {
+                for (i, sector) in buf.chunks_mut(SURFACE_SECTOR as usize).enumerate() {
+                    let offset = self.position + i as u64 * SURFACE_SECTOR;
+                    if self.device.read_exact_at(sector, offset).is_err() {
+                        sector.fill(0);
+                        mark_bad(
+                            &mut self.bad_ranges.lock().unwrap(),
+                            offset,
+                            offset + sector.len() as u64,
+                        );
+                    }
+                }
+            }
+        }
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+fn run_benchmark(
+    cfg: &BenchmarkConfig,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+
+    // קריאה קודם: היא לא הורסת כלום, ואם היא נכשלת אין טעם לכתוב
+    let Some(read) = benchmark_read(cfg, event_tx, cancel_flag) else {
+        return;
+    };
+
+    let write = if cfg.write {
+        match benchmark_write(cfg, read.bytes, event_tx, cancel_flag) {
+            Some(result) => Some(result),
+            None => return,
+        }
+    } else {
+        None
+    };
+
+    let _ = event_tx.send(BurnEvent::BenchmarkReport { read, write });
+}
+
+fn benchmark_read(
+    cfg: &BenchmarkConfig,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &AtomicBool,
+) -> Option<BenchmarkResult> {
+    // O_DIRECT עוקף את ה-page cache, אחרת מודדים את הזיכרון ולא את הכונן.
+    // מערכת קבצים שלא תומכת בו (tmpfs) - לפחות מפנים את ה-cache
+    let device = match OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(&cfg.device_path)
+    {
+        Ok(f) => Ok(f),
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => open_uncached(&cfg.device_path),
+        Err(e) => Err(e),
+    };
+    let mut device = match device {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return None;
+        }
+    };
+
+    let size = match device_size(&device) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return None;
+        }
+    };
+    let total = size.min(cfg.max_bytes) / DIRECT_ALIGN as u64 * DIRECT_ALIGN as u64;
+    if let Err(e) = device.seek(SeekFrom::Start(0)) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return None;
+    }
+
+    // Vec לא מבטיח יישור, אז לוקחים חלון מיושר מתוך באפר קצת יותר גדול
+    let mut raw = vec![0u8; BUFFER_SIZE + DIRECT_ALIGN];
+    let offset = raw.as_ptr().align_offset(DIRECT_ALIGN);
+    let buf = &mut raw[offset..offset + BUFFER_SIZE];
+
+    let mut rates = RateSampler::new();
+    let mut done = 0u64;
+    let mut last_progress = Instant::now();
+    while done < total {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return None;
+        }
+
+        let want = (total - done).min(BUFFER_SIZE as u64) as usize;
+        if let Err(e) = device.read_exact(&mut buf[..want]) {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::io_at(
+                e,
+                done,
+                IoDirection::Read,
+            )));
+            return None;
+        }
+        done += want as u64;
+        rates.record(done);
+
+        if last_progress.elapsed() >= Duration::from_millis(100) {
+            let _ = event_tx.send(BurnEvent::BenchmarkProgress {
+                phase: BenchmarkPhase::Read,
+                done,
+                total,
+                speed_mbps: rates.current(),
+            });
+            last_progress = Instant::now();
+        }
+    }
+
+    Some(rates.finish(done))
+}
+
+// אותה לולאת כתיבה של הצריבה, כדי למדוד את מה שצריבה אמיתית תקבל.
+// ה-Progress שלה מתורגם ל-BenchmarkProgress בדרך החוצה
+fn benchmark_write(
+    cfg: &BenchmarkConfig,
+    total: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Option<BenchmarkResult> {
+    // הורס את תחילת הכונן - לא את זה שהמערכת רצה ממנו
+    if let Err(e) = refuse_system_disk(&cfg.device_path) {
+        let _ = event_tx.send(BurnEvent::Error(e));
+        return None;
+    }
+
+    let mut device = match OpenOptions::new().write(true).open(&cfg.device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return None;
+        }
+    };
+    // אקראי, כי יש בקרים שדוחסים או מדלגים על אפסים
+    let source = match pattern_source(PassFill::Random, total) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return None;
+        }
+    };
+
+    let (inner_tx, inner_rx) = bounded::<BurnEvent>(32);
+    let mut rates = RateSampler::new();
+    let device = &mut device;
+    let end = thread::scope(|scope| {
+        // ה-thread לוקח את inner_tx, אז הלולאה למטה נגמרת כש-write_stream מחזיר
+        let writer = scope.spawn(move || {
+            write_stream(
+                source,
+                total,
+                device,
+                &WriteOptions::default(),
+                None,
+                &inner_tx,
+                cancel_flag,
+            )
+        });
+        for event in inner_rx {
+            match event {
+                BurnEvent::Progress {
+                    written,
+                    total,
+                    write_speed_mbps,
+                    ..
+                } => {
+                    rates.record(written);
+                    let _ = event_tx.send(BurnEvent::BenchmarkProgress {
+                        phase: BenchmarkPhase::Write,
+                        done: written,
+                        total,
+                        speed_mbps: write_speed_mbps,
+                    });
+                }
+                other => {
+                    let _ = event_tx.send(other);
+                }
+            }
+        }
+        writer.join().unwrap_or(StreamEnd::Failed)
+    });
+
+    match end {
+        StreamEnd::Completed(written) => Some(rates.finish(written)),
+        StreamEnd::Failed => None,
+        StreamEnd::Cancelled => {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            None
+        }
+    }
+}
+
+// מהירות בכל שנייה שלמה, בשביל min/max והגרף בדוח
+struct RateSampler {
+    started: Instant,
+    last_time: Instant,
+    last_bytes: u64,
+    samples: Vec<f64>,
+}
+
+impl RateSampler {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            last_time: now,
+            last_bytes: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, done: u64) {
+        let dt = self.last_time.elapsed().as_secs_f64();
+        if dt >= 1.0 {
+            self.samples
+                .push((done - self.last_bytes) as f64 / dt / (1024.0 * 1024.0));
+            self.last_time = Instant::now();
+            self.last_bytes = done;
+        }
+    }
+
+    // הדגימה האחרונה, או הממוצע עד עכשיו לפני שעברה שנייה
+    fn current(&self) -> f64 {
+        match self.samples.last() {
+            Some(rate) => *rate,
+            None => {
+                let secs = self.started.elapsed().as_secs_f64();
+                if secs > 0.0 {
+                    self.last_bytes as f64 / secs / (1024.0 * 1024.0)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    fn finish(mut self, done: u64) -> BenchmarkResult {
+        let elapsed_secs = self.started.elapsed().as_secs_f64();
+        // השארית אחרי השנייה השלמה האחרונה - רק אם היא ארוכה מספיק להיות מדידה
+        let tail = self.last_time.elapsed().as_secs_f64();
+        if tail >= 0.25 && done > self.last_bytes {
+            self.samples
+                .push((done - self.last_bytes) as f64 / tail / (1024.0 * 1024.0));
+        }
+        let avg_mbps = if elapsed_secs > 0.0 {
+            done as f64 / elapsed_secs / (1024.0 * 1024.0)
+        } else {
+            0.0
+        };
+        // ריצה קצרה משנייה - הממוצע הוא הדגימה היחידה
+        if self.samples.is_empty() {
+            self.samples.push(avg_mbps);
+        }
+        BenchmarkResult {
+            bytes: done,
+            elapsed_secs,
+            min_mbps: self.samples.iter().copied().fold(f64::INFINITY, f64::min),
+            avg_mbps,
+            max_mbps: self.samples.iter().copied().fold(0.0, f64::max),
+            samples: self.samples,
+        }
+    }
+}
+
+// כותב את תבנית ה-LBA על כל ההתקן. מחזיר את הגודל וה-seed, או None אחרי Error/Cancelled
+fn write_test_pattern(
+    device_path: &Path,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Option<(u64, u64)> {
+    // בדיקה הורסת את כל הכונן - לא על זה שהמערכת רצה ממנו
+    if let Err(e) = refuse_system_disk(device_path) {
+        let _ = event_tx.send(BurnEvent::Error(e));
+        return None;
+    }
+
+    let mut device = match OpenOptions::new().write(true).open(device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return None;
+        }
+    };
+
+    let total_size = match device_size(&device) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return None;
+        }
+    };
+
+    if let Err(e) = device.seek(SeekFrom::Start(0)) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return None;
+    }
+
+    // seed חדש בכל ריצה, כדי שתוכן מריצה קודמת לא יעבור בטעות
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let seed = splitmix64(&mut seed);
+
+    let source = SurfacePattern {
+        seed,
+        position: 0,
+        total: total_size,
+    };
+    match write_stream(
+        source,
+        total_size,
+        &mut device,
+        &WriteOptions::default(),
+        None,
+        event_tx,
+        cancel_flag,
+    ) {
+        StreamEnd::Completed(_) => Some((total_size, seed)),
+        StreamEnd::Failed => None,
+        StreamEnd::Cancelled => {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            None
+        }
+    }
+}
+
+// מוסיף את הסקטורים של [start, end) ומאחד עם הטווח הקודם אם הם צמודים
+fn mark_bad(ranges: &mut Vec<BadRange>, start: u64, end: u64) {
+    let first_lba = start / SURFACE_SECTOR;
+    let last_lba = (end - 1) / SURFACE_SECTOR;
+    match ranges.last_mut() {
+        Some(prev) if prev.last_lba + 1 >= first_lba => prev.last_lba = last_lba,
+        _ => ranges.push(BadRange {
+            first_lba,
+            last_lba,
+        }),
+    }
+}
+
+// כל מילה של 64 ביט בסקטור = ה-LBA xor seed, כך שסקטור שנכתב למקום הלא נכון לא עובר
+fn fill_surface_pattern(buf: &mut [u8], offset: u64, seed: u64) {
+    for (i, byte) in buf.iter_mut().enumerate() {
+        let pos = offset + i as u64;
+        *byte = ((pos / SURFACE_SECTOR) ^ seed).to_le_bytes()[(pos % 8) as usize];
+    }
+}
+
+struct SurfacePattern {
+    seed: u64,
+    position: u64,
+    total: u64,
+}
+
+impl Read for SurfacePattern {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = (self.total - self.position).min(buf.len() as u64) as usize;
+        fill_surface_pattern(&mut buf[..n], self.position, self.seed);
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+// יעד אחד בצריבה מרובה, בין השלבים
+struct TargetRun {
+    path: PathBuf,
+    written: u64,
+    elapsed_secs: f64,
+    digest: Option<Vec<u8>>,
+    verified: bool,
+    error: Option<BurnError>,
+}
+
+fn run_multi_burn(
+    cfg: &MultiBurnConfig,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let started = Instant::now();
+
+    let total_size = match std::fs::metadata(&cfg.iso_path) {
+        Ok(m) => m.len(),
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+    let iso_file = match File::open(&cfg.iso_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+    unsafe {
+        libc::posix_fadvise(iso_file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
+    }
+    let iso = match make_decompressor(&cfg.iso_path, iso_file) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    let mut runs: Vec<TargetRun> = cfg
+        .device_paths
+        .iter()
+        .map(|path| TargetRun {
+            path: path.clone(),
+            written: 0,
+            elapsed_secs: 0.0,
+            digest: None,
+            verified: false,
+            error: None,
+        })
+        .collect();
+
+    let image_size = written_size(&cfg.iso_path, total_size);
+    if image_size.is_none() {
+        let _ = event_tx.send(BurnEvent::Warning {
+            message: UNKNOWN_SIZE_WARNING.into(),
+        });
+    }
+    // התקן שנופל בבדיקה המקדימה יוצא מהצריבה, והשאר ממשיכים בלעדיו
+    let mut devices = Vec::new();
+    for (index, run) in runs.iter_mut().enumerate() {
+        match open_multi_target(&run.path, image_size) {
+            Ok(device) => devices.push((index, device)),
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Target {
+                    device: run.path.clone(),
+                    event: Box::new(BurnEvent::Error(e.clone())),
+                });
+                run.error = Some(e);
+            }
+        }
+    }
+
+    // המקור נקרא פעם אחת, אז כל היעדים מדווחים את אותה מהירות קריאה
+    let read_stats = Arc::new(ReadStats::default());
+    let (data_rx, reader) = source::spawn_read_thread(
+        iso,
+        BUFFER_SIZE,
+        false,
+        false,
+        cancel_flag.clone(),
+        read_stats.clone(),
+    );
+    let (receivers, broadcaster) = source::spawn_broadcast(data_rx, reader, devices.len());
+
+    let writes: Vec<_> = thread::scope(|scope| {
+        let writers: Vec<_> = devices
+            .iter_mut()
+            .zip(receivers)
+            .map(|((index, device), data_rx)| {
+                let (inner_tx, errors) = tag_target_events(scope, &runs[*index].path, event_tx);
+                let index = *index;
+                let read_stats = &read_stats;
+                scope.spawn(move || {
+                    let writer_started = Instant::now();
+                    let mut check = cfg
+                        .verify
+                        .then(|| InFlightCheck::Hash(Hasher::new(HashAlgorithm::Blake3)));
+                    let end = write_chunks(
+                        data_rx,
+                        None,
+                        read_stats,
+                        total_size,
+                        device,
+                        &WriteOptions::default(),
+                        check.as_mut(),
+                        &inner_tx,
+                        cancel_flag,
+                    );
+                    drop(inner_tx);
+                    let digest = match check {
+                        Some(InFlightCheck::Hash(hasher)) => Some(hasher.finalize()),
+                        _ => None,
+                    };
+                    let error = errors.join().unwrap_or(None);
+                    (
+                        index,
+                        end,
+                        writer_started.elapsed().as_secs_f64(),
+                        digest,
+                        error,
+                    )
+                })
+            })
+            .collect();
+        writers
+            .into_iter()
+            .filter_map(|writer| writer.join().ok())
+            .collect()
+    });
+
+    // כל הכותבים קיבלו את אותה תמונה קטועה - זה לא כשל של התקן אחד
+    if let Ok(Err(e)) = broadcaster.join() {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return;
+    }
+
+    // גם בביטול כל יעד מקבל תוצאה: מה שהסתיים נשאר, מה שנקטע מסומן Cancelled
+    for (index, end, elapsed_secs, digest, error) in writes {
+        let run = &mut runs[index];
+        run.elapsed_secs = elapsed_secs;
+        match end {
+            StreamEnd::Completed(written) => {
+                run.written = written;
+                run.digest = digest;
+            }
+            // ה-Error כבר יצא עטוף ב-Target
+            StreamEnd::Failed => {
+                run.error = Some(error.unwrap_or_else(|| {
+                    BurnError::new(ErrorKind::Io, "Write stopped before the end of the image")
+                }))
+            }
+            StreamEnd::Cancelled => {
+                run.error = Some(error.unwrap_or_else(|| {
+                    BurnError::new(
+                        ErrorKind::Cancelled,
+                        "Cancelled before the end of the image",
+                    )
+                }))
+            }
+        }
+    }
+
+    // הבדיקה קוראת כל התקן בנפרד, אז גם היא במקביל
+    if cfg.verify && !cancel_flag.load(Ordering::Relaxed) {
+        let checks: Vec<_> = thread::scope(|scope| {
+            let checkers: Vec<_> = runs
+                .iter()
+                .enumerate()
+                .filter(|(_, run)| run.error.is_none())
+                .filter_map(|(index, run)| {
+                    Some((index, run.path.clone(), run.digest.clone()?, run.written))
+                })
+                .map(|(index, path, digest, written)| {
+                    let (inner_tx, errors) = tag_target_events(scope, &path, event_tx);
+                    scope.spawn(move || {
+                        let ok = verify_digest(
+                            &digest,
+                            HashAlgorithm::Blake3,
+                            &path,
+                            0,
+                            written,
+                            &inner_tx,
+                            cancel_flag,
+                        );
+                        drop(inner_tx);
+                        (index, ok, errors.join().unwrap_or(None))
+                    })
+                })
+                .collect();
+            checkers
+                .into_iter()
+                .filter_map(|checker| checker.join().ok())
+                .collect()
+        });
+
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        for (index, ok, error) in checks {
+            let run = &mut runs[index];
+            run.verified = ok;
+            if !ok {
+                run.error = Some(error.unwrap_or_else(|| {
+                    if cancelled {
+                        BurnError::new(
+                            ErrorKind::Cancelled,
+                            "Cancelled before verification finished",
+                        )
+                    } else {
+                        BurnError::new(ErrorKind::VerificationFailed, "Verification failed")
+                    }
+                }));
+            }
+        }
+    }
+
+    let targets = runs
+        .into_iter()
+        .map(|run| TargetOutcome {
+            avg_mbps: if run.elapsed_secs > 0.0 {
+                run.written as f64 / run.elapsed_secs / (1024.0 * 1024.0)
+            } else {
+                0.0
+            },
+            device: run.path,
+            bytes: run.written,
+            verified: run.verified,
+            error: run.error,
+        })
+        .collect();
+    let _ = event_tx.send(BurnEvent::MultiFinished {
+        targets,
+        elapsed_secs: started.elapsed().as_secs_f64(),
+    });
+}
+
+// אותן בדיקות מקדימות כמו בצריבה רגילה, בלי offset. image_size = None מדלג על בדיקת הגודל.
+// אין אישור לכל יעד, אז אין דריסה: יעד מעוגן או עם מערכת קבצים נצרב לבד
+fn open_multi_target(path: &Path, image_size: Option<u64>) -> Result<File, BurnError> {
+    preflight_target(path, false)?;
+
+    let device = OpenOptions::new()
+        .write(true)
+        .create(!path.starts_with("/dev"))
+        .open(path)?;
+    let target_is_file = device.metadata().map(|m| m.is_file()).unwrap_or(false);
+
+    if !target_is_file && is_read_only(&device) {
+        return Err(BurnError::new(
+            ErrorKind::WriteProtected,
+            "Device is write-protected — check the lock switch",
+        ));
+    }
+    if let (Some(image_size), Ok(capacity)) = (image_size, device_size(&device)) {
+        if !(target_is_file && capacity == 0) && image_size > capacity {
+            return Err(BurnError::too_small(
+                image_size,
+                capacity,
+                format!(
+                    "Device too small: need {} bytes, device has {}",
+                    image_size, capacity
+                ),
+            ));
+        }
+    }
+    Ok(device)
+}
+
+// ערוץ לאירועים של יעד אחד: כל אירוע יוצא ל-UI עטוף ב-Target. ה-handle מחזיר את
+// ה-Error האחרון שעבר בו, בשביל הסיכום
+fn tag_target_events<'scope>(
+    scope: &'scope thread::Scope<'scope, '_>,
+    device: &Path,
+    event_tx: &'scope Sender<BurnEvent>,
+) -> (
+    Sender<BurnEvent>,
+    thread::ScopedJoinHandle<'scope, Option<BurnError>>,
+) {
+    let (inner_tx, inner_rx) = bounded::<BurnEvent>(32);
+    let device = device.to_path_buf();
+    let forwarder = scope.spawn(move || {
+        let mut last_error = None;
+        for event in inner_rx {
+            if let BurnEvent::Error(e) = &event {
+                last_error = Some(e.clone());
+            }
+            let _ = event_tx.send(BurnEvent::Target {
+                device: device.clone(),
+                event: Box::new(event),
+            });
+        }
+        last_error
+    });
+    (inner_tx, forwarder)
+}
+
+fn run_verify_only(cfg: &BurnConfig, event_tx: &Sender<BurnEvent>, cancel_flag: Arc<AtomicBool>) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+    let started = Instant::now();
+
+    // בלי צריבה לפניה אין written - הגודל הפתוח, או 0 = לא ידוע
+    let total = std::fs::metadata(&cfg.iso_path)
+        .ok()
+        .and_then(|m| written_size(&cfg.iso_path, m.len()))
+        .unwrap_or(0);
+    if verify_image(cfg, total, event_tx, cancel_flag) {
+        let _ = event_tx.send(BurnEvent::Finished {
+            bytes: total,
+            elapsed_secs: started.elapsed().as_secs_f64(),
+            verified: true,
+        });
+    }
+}
+
+fn run_checksum(
+    iso_path: &Path,
+    expected: &str,
+    algorithm: HashAlgorithm,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+    digests: &DigestCache,
+) {
+    let digest = match digests.get(iso_path, algorithm) {
+        Some(digest) => digest,
+        None => {
+            let Some(digest) = hash_file(iso_path, algorithm, event_tx, cancel_flag) else {
+                return;
+            };
+            let digest = hex(&digest);
+            digests.put(iso_path, algorithm, &digest);
+            digest
+        }
+    };
+    let _ = event_tx.send(BurnEvent::ChecksumChecked {
+        algorithm,
+        matches: digest.eq_ignore_ascii_case(expected.trim()),
+        digest,
+    });
+}
+
+// ברקע, בזמן שהמשתמש בוחר כונן. ה-thread של העבודה מקבל עדיפות idle לדיסק ו-nice 19,
+// כדי שהממשק ושאר המערכת לא ירגישו אותו
+fn run_hash_image(
+    path: &Path,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+    digests: &DigestCache,
+) {
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+    // who = 0 ב-Linux הוא ה-thread הנוכחי, לא כל התהליך
+    unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE << 13,
+        );
+        libc::setpriority(libc::PRIO_PROCESS, 0, 19);
+    }
+
+    let algorithm = HashAlgorithm::Sha256;
+    let digest = match digests.get(path, algorithm) {
+        Some(digest) => digest,
+        None => {
+            let Some(digest) = hash_file(path, algorithm, event_tx, cancel_flag) else {
+                return;
+            };
+            let digest = hex(&digest);
+            digests.put(path, algorithm, &digest);
+            digest
+        }
+    };
+    let _ = event_tx.send(BurnEvent::ImageHashed {
+        path: path.to_path_buf(),
+        algorithm,
+        digest,
+    });
+}
+
+// ה-digest האחרון שחושב, כדי שבדיקת checksum אחרי ה-hash של הרקע לא תקרא את הקובץ שוב.
+// גודל וזמן שינוי שונים = קובץ אחר באותו נתיב
+#[derive(Default)]
+struct DigestCache(Mutex<Option<CachedDigest>>);
+
+struct CachedDigest {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+    algorithm: HashAlgorithm,
+    digest: String,
+}
+
+impl DigestCache {
+    fn get(&self, path: &Path, algorithm: HashAlgorithm) -> Option<String> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let cached = self.0.lock().ok()?;
+        cached
+            .as_ref()
+            .filter(|c| {
+                c.path == path
+                    && c.algorithm == algorithm
+                    && c.len == metadata.len()
+                    && c.modified == metadata.modified().ok()
+            })
+            .map(|c| c.digest.clone())
+    }
+
+    fn put(&self, path: &Path, algorithm: HashAlgorithm, digest: &str) {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return;
+        };
+        if let Ok(mut cached) = self.0.lock() {
+            *cached = Some(CachedDigest {
+                path: path.to_path_buf(),
+                len: metadata.len(),
+                modified: metadata.modified().ok(),
+                algorithm,
+                digest: digest.to_string(),
+            });
+        }
+    }
+}
+
+// אותה לולאת קריאה בחתיכות כמו בצריבה, רק עם hash במקום התקן
+fn hash_file(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Option<Vec<u8>> {
+    let mut file = match File::open(path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return None;
+        }
+    };
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
+    }
+
+    let mut hasher = Hasher::new(algorithm);
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    let mut done = 0u64;
+    let mut last_progress = Instant::now();
+
+    loop {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return None;
+        }
+
+        let n = match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return None;
+            }
+        };
+
+        hasher.update(&buf[..n]);
+        done += n as u64;
+
+        if last_progress.elapsed() >= Duration::from_millis(100) {
+            let _ = event_tx.send(BurnEvent::Hashing { done, total });
+            last_progress = Instant::now();
+        }
+    }
+
+    Some(hasher.finalize())
+}
+
+const SECURE_ERASE_PASSWORD: &[u8] = b"BurnStation";
+
+// ATA SECURITY ERASE UNIT - הכונן מוחק את עצמו. אין ביטול באמצע, והתקדמות לפי זמן בלבד
+fn run_secure_erase(device_path: &Path, event_tx: &Sender<BurnEvent>) {
+    let _ = event_tx.send(BurnEvent::Preparing);
+
+    // מוחק את כל הכונן בקושחה - לא את זה שהמערכת רצה ממנו
+    if let Err(e) = refuse_system_disk(device_path) {
+        let _ = event_tx.send(BurnEvent::Error(e));
+        return;
+    }
+
+    let device = match OpenOptions::new().read(true).write(true).open(device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+
+    let info = match ata::identify(&device) {
+        Ok(info) => info,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+                ErrorKind::Io,
+                format!(
+                    "Drive did not answer ATA IDENTIFY (not a SATA device?): {}",
+                    e
+                ),
+            )));
+            return;
+        }
+    };
+
+    if !info.supported {
+        let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+            ErrorKind::Io,
+            "Drive does not support ATA Secure Erase",
+        )));
+        return;
+    }
+    if info.frozen {
+        let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+            ErrorKind::Io,
+            "Drive security is frozen - replug the enclosure or suspend/resume and try again",
+        )));
+        return;
+    }
+    if info.enabled || info.locked {
+        let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+            ErrorKind::Io,
+            "Drive already has a security password set",
+        )));
+        return;
+    }
+
+    let total = device_size(&device).unwrap_or(0);
+    // בלי הערכה מהכונן מניחים שעתיים
+    let estimate = info.erase_time.unwrap_or(Duration::from_secs(2 * 60 * 60));
+
+    // ה-thread של המחיקה לוקח את ה-fd, וצריך עוד אחד כדי לבטל את הסיסמה אם היא נכשלת
+    let control = match device.try_clone() {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return;
+        }
+    };
+    if let Err(e) = ata::set_password(&device, SECURE_ERASE_PASSWORD) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return;
+    }
+
+    let (done_tx, done_rx) = bounded::<std::io::Result<()>>(1);
+    thread::spawn(move || {
+        let result = ata::erase_unit(&device, SECURE_ERASE_PASSWORD, estimate * 2);
+        let _ = done_tx.send(result);
+    });
+
+    let start_time = Instant::now();
+    let result = loop {
+        match done_rx.recv_timeout(Duration::from_millis(500)) {
+            Ok(result) => break result,
+            Err(RecvTimeoutError::Timeout) => {
+                let elapsed = start_time.elapsed().as_secs_f64();
+                let fraction = (elapsed / estimate.as_secs_f64()).min(0.99);
+                let _ = event_tx.send(BurnEvent::Progress {
+                    written: (total as f64 * fraction) as u64,
+                    total,
+                    read_speed_mbps: 0.0,
+                    write_speed_mbps: total as f64 / estimate.as_secs_f64() / (1024.0 * 1024.0),
+                    eta_secs: estimate.as_secs().saturating_sub(elapsed as u64),
+                });
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                break Err(std::io::Error::other(
+                    "Secure erase thread exited unexpectedly",
+                ))
+            }
+        }
+    };
+
+    match result {
+        // הקושחה לא מחזירה תוצאת בדיקה - רק שהפקודה הסתיימה
+        Ok(()) => {
+            let _ = event_tx.send(BurnEvent::Finished {
+                bytes: total,
+                elapsed_secs: start_time.elapsed().as_secs_f64(),
+                verified: false,
+            });
+        }
+        // הסיסמה עדיין פעילה. אם גם הביטול נכשל, המשתמש צריך אותה כדי לשחרר את הכונן
+        Err(e) => {
+            let error = match ata::disable_password(&control, SECURE_ERASE_PASSWORD) {
+                Ok(()) => e.into(),
+                Err(disable) => BurnError::new(
+                    ErrorKind::Io,
+                    format!(
+                        "Secure erase failed: {}. Removing its temporary password also failed ({}), \
+                         so the drive will lock at the next power cycle. Unlock it with: \
+                         hdparm --user-master u --security-disable {} {}",
+                        e,
+                        disable,
+                        String::from_utf8_lossy(SECURE_ERASE_PASSWORD),
+                        device_path.display()
+                    ),
+                ),
+            };
+            let _ = event_tx.send(BurnEvent::Error(error));
+        }
+    }
+}
+
+// מקור סינתטי במקום קובץ - ה-writer לא יודע את ההבדל
+fn pattern_source(fill: PassFill, total: u64) -> std::io::Result<Box<dyn Read + Send>> {
+    Ok(match fill {
+        PassFill::Byte(byte) => Box::new(std::io::repeat(byte).take(total)),
+        PassFill::Random => Box::new(File::open("/dev/urandom")?.take(total)),
+    })
+}
+
+// הגדרות של לולאת הכתיבה שלא תלויות בסוג הפעולה
+struct WriteOptions {
+    speed_ema_alpha: f64,
+    max_speed_mbps: Option<f64>,
+    write_retries: u32,
+    sync_interval_bytes: Option<u64>,
+    chunk_size: usize,
+    // רק בצריבה. ב-multi-burn הביטול משותף, וכונן תקוע אחד היה עוצר את כולם
+    write_timeout: Option<Duration>,
+    paranoid: bool,
+    // רק בצריבה, כשה-front-end מסר קישור by-id
+    pinned_device: Option<PinnedDevice>,
+    // ה-fd כבר ב-O_DIRECT: ה-reader ממלא באפרים מיושרים עד הסוף
+    direct_io: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            speed_ema_alpha: DEFAULT_SPEED_EMA_ALPHA,
+            max_speed_mbps: None,
+            write_retries: DEFAULT_WRITE_RETRIES,
+            sync_interval_bytes: None,
+            chunk_size: BUFFER_SIZE,
+            write_timeout: None,
+            paranoid: false,
+            pinned_device: None,
+            direct_io: false,
+        }
+    }
+}
+
+// הקישור ב-/dev/disk/by-id והצומת שאליו הצביע כשהכונן נפתח. קישור שנעלם או זז = הכונן
+// נעלם או מוספר מחדש, וכל כתיבה נוספת דרך ה-fd עלולה לנחות על משהו אחר
+#[derive(Clone)]
+struct PinnedDevice {
+    link: PathBuf,
+    rdev: u64,
+}
+
+impl PinnedDevice {
+    // None כשהקישור כבר עכשיו לא מצביע להתקן הפתוח
+    fn new(link: &Path, device: &File) -> Option<Self> {
+        let rdev = device.metadata().ok()?.rdev();
+        let pinned = Self {
+            link: link.to_path_buf(),
+            rdev,
+        };
+        (!pinned.moved()).then_some(pinned)
+    }
+
+    fn moved(&self) -> bool {
+        std::fs::metadata(&self.link).map_or(true, |m| m.rdev() != self.rdev)
+    }
+}
+
+// כתיבה שנתקעה בקרנל לא חוזרת, גם לא עם שגיאה. הכותב רושם מתי התחילה הכתיבה הנוכחית,
+// ו-thread נפרד מתעורר כל WATCHDOG_INTERVAL ובודק שהיא לא תקועה יותר מ-timeout.
+// כשכן - Error, ביטול, וה-StreamEnd של הכותב (אם יחזור) הופך ל-Failed. באותו סיבוב הוא
+// בודק גם שהכונן לא מוספר מחדש (PinnedDevice)
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+// אין כתיבה בטיסה - בקריאה, ב-sync או בהשהיה של תקרת המהירות אין מה למדוד
+const WATCHDOG_IDLE: u64 = u64::MAX;
+
+struct Watchdog {
+    started: Instant,
+    // ננו-שניות מ-started, או WATCHDOG_IDLE
+    write_began: Arc<AtomicU64>,
+    fired: Arc<AtomicBool>,
+    // כשה-Watchdog נזרק ה-thread מקבל Disconnected ויוצא
+    _stop: Option<Sender<()>>,
+}
+
+impl Watchdog {
+    fn start(
+        timeout: Option<Duration>,
+        pinned: Option<PinnedDevice>,
+        event_tx: &Sender<BurnEvent>,
+        cancel_flag: &Arc<AtomicBool>,
+    ) -> Self {
+        let mut watchdog = Self {
+            started: Instant::now(),
+            write_began: Arc::new(AtomicU64::new(WATCHDOG_IDLE)),
+            fired: Arc::new(AtomicBool::new(false)),
+            _stop: None,
+        };
+        if timeout.is_none() && pinned.is_none() {
+            return watchdog;
+        }
+
+        let (stop_tx, stop_rx) = bounded::<()>(0);
+        let started = watchdog.started;
+        let write_began = watchdog.write_began.clone();
+        let fired = watchdog.fired.clone();
+        let event_tx = event_tx.clone();
+        let cancel_flag = cancel_flag.clone();
+        thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(WATCHDOG_INTERVAL) {
+                let began = write_began.load(Ordering::Relaxed);
+                let now = started.elapsed().as_nanos() as u64;
+                let error = if pinned.as_ref().is_some_and(PinnedDevice::moved) {
+                    BurnError::device_changed()
+                } else if let Some(timeout) = timeout.filter(|timeout| {
+                    began != WATCHDOG_IDLE && now.saturating_sub(began) > timeout.as_nanos() as u64
+                }) {
+                    BurnError::write_timeout(timeout.as_secs())
+                } else {
+                    continue;
+                };
+                fired.store(true, Ordering::Relaxed);
+                cancel_flag.store(true, Ordering::Relaxed);
+                let _ = event_tx.send(BurnEvent::Error(error));
+                return;
+            }
+        });
+        watchdog._stop = Some(stop_tx);
+        watchdog
+    }
+
+    fn now(&self) -> u64 {
+        self.started.elapsed().as_nanos() as u64
+    }
+
+    // כתיבה מתחילה, או שכתיבה בטיסה התקדמה
+    fn beat(&self) {
+        self.write_began.store(self.now(), Ordering::Relaxed);
+    }
+
+    // כמו beat, אבל לא מאפס כתיבה שכבר נמדדת
+    fn arm(&self) {
+        let _ = self.write_began.compare_exchange(
+            WATCHDOG_IDLE,
+            self.now(),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+
+    fn idle(&self) {
+        self.write_began.store(WATCHDOG_IDLE, Ordering::Relaxed);
+    }
+
+    // ה-Error כבר נשלח, אז ביטול שנגרם ממנו הוא כשל ולא Cancelled
+    fn outcome(&self, end: StreamEnd) -> StreamEnd {
+        if self.fired.load(Ordering::Relaxed) {
+            StreamEnd::Failed
+        } else {
+            end
+        }
+    }
+}
+
+// מהירות לפי האינטרוול האחרון, מוחלקת בממוצע נע אקספוננציאלי
+struct SpeedMeter {
+    alpha: f64,
+    ema: Option<f64>,
+    last_bytes: u64,
+    last_time: Instant,
+}
+
+// 0 כשעוד אין מהירות למדוד
+fn eta_secs(remaining: u64, speed_mbps: f64) -> u64 {
+    if speed_mbps > 0.0 {
+        (remaining as f64 / (speed_mbps * 1024.0 * 1024.0)).round() as u64
+    } else {
+        0
+    }
+}
+
+impl SpeedMeter {
+    fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            ema: None,
+            last_bytes: 0,
+            last_time: Instant::now(),
+        }
+    }
+
+    fn update(&mut self, total_bytes: u64) -> f64 {
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_time).as_secs_f64();
+        if dt > 0.0 {
+            let sample = (total_bytes - self.last_bytes) as f64 / dt / (1024.0 * 1024.0);
+            self.ema = Some(match self.ema {
+                Some(prev) => self.alpha * sample + (1.0 - self.alpha) * prev,
+                None => sample,
+            });
+            self.last_bytes = total_bytes;
+            self.last_time = now;
+        }
+        self.ema.unwrap_or(0.0)
+    }
+}
+
+// BLAKE3 מפצל כל chunk של 8MB בין הליבות דרך rayon. כל השאר סדרתיים
+// ועוברים דרך אותו DynDigest
+enum Hasher {
+    Blake3(Box<blake3::Hasher>),
+    Digest(Box<dyn DynDigest + Send>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::Sha256 => Hasher::Digest(Box::<Sha256>::default()),
+            HashAlgorithm::Sha512 => Hasher::Digest(Box::<Sha512>::default()),
+            HashAlgorithm::Md5 => Hasher::Digest(Box::<md5::Md5>::default()),
+            HashAlgorithm::Sha1 => Hasher::Digest(Box::<sha1::Sha1>::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Blake3(hasher) => {
+                hasher.update_rayon(data);
+            }
+            Hasher::Digest(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            Hasher::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+            Hasher::Digest(hasher) => hasher.finalize().into_vec(),
+        }
+    }
+}
+
+enum InFlightCheck {
+    Hash(Hasher),
+    Crc32(ChunkCrcs),
+}
+
+impl InFlightCheck {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            InFlightCheck::Hash(hasher) => hasher.update(data),
+            InFlightCheck::Crc32(crcs) => crcs.update(data),
+        }
+    }
+}
+
+// CRC32 לכל בלוק של BUFFER_SIZE מתחילת התמונה, בלי קשר לגודל ה-chunks שה-reader מחזיר
+struct ChunkCrcs {
+    table: Vec<u32>,
+    current: crc32fast::Hasher,
+    filled: usize,
+}
+
+impl ChunkCrcs {
+    fn new() -> Self {
+        Self {
+            table: Vec::new(),
+            current: crc32fast::Hasher::new(),
+            filled: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = (BUFFER_SIZE - self.filled).min(data.len());
+            self.current.update(&data[..take]);
+            self.filled += take;
+            data = &data[take..];
+
+            if self.filled == BUFFER_SIZE {
+                let block = std::mem::replace(&mut self.current, crc32fast::Hasher::new());
+                self.table.push(block.finalize());
+                self.filled = 0;
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<u32> {
+        if self.filled > 0 {
+            self.table.push(self.current.finalize());
+        }
+        self.table
+    }
+}
+
+enum StreamEnd {
+    // כמה בייטים נכתבו
+    Completed(u64),
+    Cancelled,
+    // ה-Error כבר נשלח
+    Failed,
+}
+
+// צינור reader/writer משותף לצריבה ולמחיקה
+fn write_stream<R: Read + Send + 'static>(
+    source: R,
+    total_size: u64,
+    device: &mut File,
+    options: &WriteOptions,
+    check: Option<&mut InFlightCheck>,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> StreamEnd {
+    let read_stats = Arc::new(ReadStats::default());
+    let (data_rx, reader) = source::spawn_read_thread(
+        source,
+        options.chunk_size,
+        options.paranoid,
+        options.direct_io,
+        cancel_flag.clone(),
+        read_stats.clone(),
+    );
+    write_chunks(
+        data_rx,
+        Some(reader),
+        &read_stats,
+        total_size,
+        device,
+        options,
+        check,
+        event_tx,
+        cancel_flag,
+    )
+}
+
+// הצד הכותב: לא משנה לו אם ה-chunk הוא באפר שנקרא או טווח ב-mmap.
+// reader = None כשכמה כותבים חולקים reader אחד - מי שיצר אותם בודק אותו
+#[allow(clippy::too_many_arguments)]
+fn write_chunks(
+    data_rx: Receiver<Chunk>,
+    reader: Option<ReaderHandle>,
+    read_stats: &ReadStats,
+    total_size: u64,
+    device: &mut File,
+    options: &WriteOptions,
+    check: Option<&mut InFlightCheck>,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> StreamEnd {
+    let watchdog = Watchdog::start(
+        options.write_timeout,
+        options.pinned_device.clone(),
+        event_tx,
+        cancel_flag,
+    );
+    let end = write_chunks_watched(
+        data_rx,
+        reader,
+        read_stats,
+        total_size,
+        device,
+        options,
+        check,
+        &watchdog,
+        event_tx,
+        cancel_flag,
+    );
+    watchdog.outcome(end)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_chunks_watched(
+    data_rx: Receiver<Chunk>,
+    reader: Option<ReaderHandle>,
+    read_stats: &ReadStats,
+    total_size: u64,
+    device: &mut File,
+    options: &WriteOptions,
+    mut check: Option<&mut InFlightCheck>,
+    watchdog: &Watchdog,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> StreamEnd {
+    // המיקום ההתחלתי, כדי לחזור לתחילת ה-chunk אחרי כתיבה שנכשלה
+    let base = match device.stream_position() {
+        Ok(pos) => pos,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return StreamEnd::Failed;
+        }
+    };
+    let mut written: u64 = 0;
+    let mut last_progress = Instant::now();
+    let mut meter = SpeedMeter::new(options.speed_ema_alpha);
+    let started = Instant::now();
+
+    for chunk in data_rx {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return StreamEnd::Cancelled;
+        }
+
+        if let Some(expected) = chunk.crc {
+            let actual = crc32fast::hash(&chunk);
+            if actual != expected {
+                let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+                    ErrorKind::BufferCorrupted,
+                    format!(
+                        "Data at offset {} changed in memory between read and write \
+                         (CRC {:08X}, expected {:08X}) — nothing past it was written",
+                        base + written,
+                        actual,
+                        expected
+                    ),
+                )));
+                return StreamEnd::Failed;
+            }
+        }
+
+        if let Some(check) = check.as_mut() {
+            check.update(&chunk);
+        }
+
+        // הזנב של התמונה לא כפולה של בלוק - הוא עובר דרך ה-page cache וה-fsync בסוף
+        if options.direct_io && chunk.len() % DIRECT_ALIGN != 0 {
+            if let Err(e) = set_direct_io(device, false) {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return StreamEnd::Failed;
+            }
+        }
+
+        watchdog.beat();
+        let result = write_chunk(
+            device,
+            &chunk,
+            base + written,
+            options.write_retries,
+            event_tx,
+        );
+        watchdog.idle();
+        if let Err(e) = result {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::io_at(
+                e,
+                base + written,
+                IoDirection::Write,
+            )));
+            return StreamEnd::Failed;
+        }
+
+        written += chunk.len() as u64;
+
+        // הכתיבה סדרתית, אז אחרי fdatasync כל מה שלפני written כבר על המדיה
+        if let Some(interval) = options.sync_interval_bytes {
+            if written / interval > (written - chunk.len() as u64) / interval {
+                if let Err(e) = device.sync_data() {
+                    let _ = event_tx.send(BurnEvent::Error(e.into()));
+                    return StreamEnd::Failed;
+                }
+                let _ = event_tx.send(BurnEvent::Checkpoint { at_bytes: written });
+            }
+        }
+
+        if let Some(cap) = options.max_speed_mbps {
+            if !throttle(written, cap, started, cancel_flag) {
+                return StreamEnd::Cancelled;
+            }
+        }
+
+        // עדכון כל ~100ms
+        if last_progress.elapsed() >= Duration::from_millis(100) {
+            let speed = meter.update(written);
+            // הגודל הפתוח מה-trailer של gzip יכול לטעות (member אחרון, mod 4GiB). עברנו
+            // אותו = לא ידוע, במקום פס שעובר את ה-100%
+            let total = if written > total_size { 0 } else { total_size };
+
+            let _ = event_tx.send(BurnEvent::Progress {
+                written,
+                total,
+                read_speed_mbps: read_stats.speed_mbps(),
+                write_speed_mbps: speed,
+                eta_secs: eta_secs(total.saturating_sub(written), speed),
+            });
+
+            last_progress = Instant::now();
+        }
+    }
+
+    if let Some(Ok(Err(e))) = reader.map(|reader| reader.join()) {
+        // קריאה מההתקן בשכפול יודעת איפה נכשלה. קובץ תמונה - בלי מיקום
+        let device_offset = e
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<DeviceReadError>())
+            .map(|read| read.offset);
+        let error = match device_offset {
+            Some(offset) => BurnError::io_at(e, offset, IoDirection::Read),
+            None => e.into(),
+        };
+        let _ = event_tx.send(BurnEvent::Error(error));
+        return StreamEnd::Failed;
+    }
+
+    if cancel_flag.load(Ordering::Relaxed) {
+        return StreamEnd::Cancelled;
+    }
+
+    if let Err(e) = device.sync_all() {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return StreamEnd::Failed;
+    }
+
+    StreamEnd::Completed(written)
+}
+
+// מצב של באפר אחד בנתיב io_uring
+#[derive(Clone, Copy, Default)]
+struct UringSlot {
+    // המיקום בתמונה
+    offset: u64,
+    len: usize,
+    // כמה כבר נכתב (כתיבה קצרה נשלחת שוב מהנקודה הזו)
+    done: usize,
+    attempts: u32,
+}
+
+// קריאה אחת בטיסה בכל רגע, וכל באפר שהקריאה שלו חזרה נשלח מיד לכתיבה בזמן שהקריאה
+// הבאה כבר רצה - הקרנל מחפף בין השתיים בלי החלפות הקשר בין threads
+#[allow(clippy::too_many_arguments)]
+fn write_stream_uring(
+    source: &File,
+    total_size: u64,
+    device: &File,
+    device_offset: u64,
+    options: &WriteOptions,
+    mut check: Option<&mut InFlightCheck>,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> StreamEnd {
+    let mut ring = match IoUring::new(2 * CHANNEL_DEPTH as u32) {
+        Ok(ring) => ring,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return StreamEnd::Failed;
+        }
+    };
+
+    let source_fd = types::Fd(source.as_raw_fd());
+    let device_fd = types::Fd(device.as_raw_fd());
+    let mut buffers: Vec<Vec<u8>> = (0..CHANNEL_DEPTH)
+        .map(|_| vec![0u8; options.chunk_size])
+        .collect();
+    let mut slots = [UringSlot::default(); CHANNEL_DEPTH];
+    let mut free: Vec<usize> = (0..CHANNEL_DEPTH).collect();
+
+    let mut read_offset = 0u64;
+    let mut read_in_flight = false;
+    let mut source_ended = false;
+    let mut writes_in_flight = 0usize;
+    let mut written = 0u64;
+    let mut last_progress = Instant::now();
+    let mut meter = SpeedMeter::new(options.speed_ema_alpha);
+    let started = Instant::now();
+    // כאן אין thread קורא נפרד - הקריאות מה-ring נספרות באותו מבנה
+    let read_stats = ReadStats::default();
+    let mut next_checkpoint = options.sync_interval_bytes;
+    let watchdog = Watchdog::start(
+        options.write_timeout,
+        options.pinned_device.clone(),
+        event_tx,
+        cancel_flag,
+    );
+
+    // הקרנל עדיין משתמש בבאפרים - אסור לצאת לפני שכל הפעולות חזרו
+    let end = loop {
+        if cancel_flag.load(Ordering::Relaxed) {
+            break StreamEnd::Cancelled;
+        }
+
+        // כתיבות מסתיימות לא לפי הסדר - מפסיקים לקרוא עד שכולן חוזרות, ורק אז fdatasync
+        let barrier = next_checkpoint.is_some_and(|at| written >= at);
+        if barrier && !read_in_flight && writes_in_flight == 0 {
+            if let Err(e) = device.sync_data() {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                break StreamEnd::Failed;
+            }
+            let _ = event_tx.send(BurnEvent::Checkpoint { at_bytes: written });
+            next_checkpoint = options
+                .sync_interval_bytes
+                .map(|interval| (written / interval + 1) * interval);
+            continue;
+        }
+
+        if !barrier && !read_in_flight && !source_ended && read_offset < total_size {
+            if let Some(idx) = free.pop() {
+                let len = (total_size - read_offset).min(options.chunk_size as u64) as u32;
+                let entry = opcode::Read::new(source_fd, buffers[idx].as_mut_ptr(), len)
+                    .offset(read_offset)
+                    .build()
+                    .user_data(idx as u64);
+                if let Err(e) = push_sqe(&mut ring, &entry) {
+                    let _ = event_tx.send(BurnEvent::Error(e.into()));
+                    break StreamEnd::Failed;
+                }
+                slots[idx] = UringSlot {
+                    offset: read_offset,
+                    ..Default::default()
+                };
+                read_in_flight = true;
+            }
+        }
+
+        if !read_in_flight && writes_in_flight == 0 {
+            break StreamEnd::Completed(written);
+        }
+
+        if writes_in_flight > 0 {
+            watchdog.arm();
+        } else {
+            watchdog.idle();
+        }
+        match ring.submit_and_wait(1) {
+            Ok(_) => {}
+            Err(e) if e.raw_os_error() == Some(libc::EINTR) => continue,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                break StreamEnd::Failed;
+            }
+        }
+
+        let completions: Vec<(u64, i32)> = ring
+            .completion()
+            .map(|cqe| (cqe.user_data(), cqe.result()))
+            .collect();
+
+        // כל CQE שנאסף כבר יצא מהטבעת. גם אחרי כשל עוברים על כל האצווה ומורידים אותו
+        // מהמונים, אחרת ההמתנה בסוף מחכה לפעולה שכבר חזרה
+        let mut failed = false;
+        for (user_data, result) in completions {
+            let idx = (user_data & !URING_WRITE) as usize;
+            if user_data & URING_WRITE == 0 {
+                read_in_flight = false;
+            } else {
+                writes_in_flight -= 1;
+            }
+            if failed {
+                continue;
+            }
+
+            if user_data & URING_WRITE == 0 {
+                if result < 0 {
+                    let e = std::io::Error::from_raw_os_error(-result);
+                    let _ = event_tx.send(BurnEvent::Error(e.into()));
+                    failed = true;
+                    continue;
+                }
+                // קובץ שהתקצר באמצע - כמו ב-write_stream, כותבים את מה שיש
+                if result == 0 {
+                    source_ended = true;
+                    free.push(idx);
+                    continue;
+                }
+                let n = result as usize;
+                slots[idx].len = n;
+                read_offset += n as u64;
+                read_stats.record(read_offset, started);
+                // רק קריאה אחת בטיסה, אז ה-hash מקבל את הבאפרים לפי הסדר
+                if let Some(check) = check.as_mut() {
+                    check.update(&buffers[idx][..n]);
+                }
+            } else {
+                watchdog.beat();
+                if result < 0 {
+                    let e = std::io::Error::from_raw_os_error(-result);
+                    let slot = &mut slots[idx];
+                    if slot.attempts < options.write_retries && is_transient(&e) {
+                        slot.attempts += 1;
+                        let _ = event_tx.send(BurnEvent::Warning {
+                            message: format!(
+                                "Write error at byte {} ({}), retrying {}/{}",
+                                device_offset + slot.offset + slot.done as u64,
+                                e,
+                                slot.attempts,
+                                options.write_retries
+                            ),
+                        });
+                        thread::sleep(WRITE_RETRY_BACKOFF * 2u32.pow(slot.attempts - 1));
+                    } else {
+                        let at = device_offset + slot.offset + slot.done as u64;
+                        let _ = event_tx.send(BurnEvent::Error(BurnError::io_at(
+                            e,
+                            at,
+                            IoDirection::Write,
+                        )));
+                        failed = true;
+                        continue;
+                    }
+                } else {
+                    slots[idx].done += result as usize;
+                    if slots[idx].done >= slots[idx].len {
+                        written += slots[idx].len as u64;
+                        free.push(idx);
+                        continue;
+                    }
+                }
+            }
+
+            // כתיבה חדשה, ניסיון חוזר או המשך של כתיבה קצרה
+            let slot = slots[idx];
+            let entry = opcode::Write::new(
+                device_fd,
+                buffers[idx][slot.done..].as_ptr(),
+                (slot.len - slot.done) as u32,
+            )
+            .offset(device_offset + slot.offset + slot.done as u64)
+            .build()
+            .user_data(idx as u64 | URING_WRITE);
+            if let Err(e) = push_sqe(&mut ring, &entry) {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                failed = true;
+                continue;
+            }
+            writes_in_flight += 1;
+        }
+        if failed {
+            break StreamEnd::Failed;
+        }
+
+        if let Some(cap) = options.max_speed_mbps {
+            if !throttle(written, cap, started, cancel_flag) {
+                break StreamEnd::Cancelled;
+            }
+        }
+
+        if last_progress.elapsed() >= Duration::from_millis(100) {
+            let speed = meter.update(written);
+            let _ = event_tx.send(BurnEvent::Progress {
+                written,
+                total: total_size,
+                read_speed_mbps: read_stats.speed_mbps(),
+                write_speed_mbps: speed,
+                eta_secs: eta_secs(total_size.saturating_sub(written), speed),
+            });
+            last_progress = Instant::now();
+        }
+    };
+
+    let mut pending = read_in_flight as usize + writes_in_flight;
+    while pending > 0 {
+        if ring.submit_and_wait(1).is_err() {
+            // בלי אפשרות לחכות, דליפה עדיפה על באפר משוחרר שהקרנל עוד כותב אליו
+            std::mem::forget(buffers);
+            return StreamEnd::Failed;
+        }
+        pending -= ring.completion().count();
+    }
+
+    if let StreamEnd::Completed(_) = end {
+        if let Err(e) = device.sync_all() {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return StreamEnd::Failed;
+        }
+    }
+
+    watchdog.outcome(end)
+}
+
+fn push_sqe(ring: &mut IoUring, entry: &squeue::Entry) -> std::io::Result<()> {
+    // הטבעת בגודל כפול ממספר הבאפרים, אז תור מלא אומר באג ולא עומס
+    unsafe { ring.submission().push(entry) }
+        .map_err(|_| std::io::Error::other("io_uring submission queue is full"))
+}
+
+// בקרים מסוימים מחזירים EIO תחת עומס ומצליחים בניסיון הבא
+fn is_transient(e: &std::io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::EIO) | Some(libc::EAGAIN) | Some(libc::ETIMEDOUT)
+    )
+}
+
+fn write_chunk(
+    device: &mut File,
+    chunk: &[u8],
+    position: u64,
+    retries: u32,
+    event_tx: &Sender<BurnEvent>,
+) -> std::io::Result<()> {
+    let mut attempt = 0;
+    loop {
+        match device.write_all(chunk) {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < retries && is_transient(&e) => {
+                attempt += 1;
+                let _ = event_tx.send(BurnEvent::Warning {
+                    message: format!(
+                        "Write error at byte {} ({}), retrying {}/{}",
+                        position, e, attempt, retries
+                    ),
+                });
+                thread::sleep(WRITE_RETRY_BACKOFF * 2u32.pow(attempt - 1));
+                // write_all שנכשל באמצע כבר הזיז את המיקום
+                device.seek(SeekFrom::Start(position))?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// ישן עד שהממוצע מאז ההתחלה יורד לתקרה. פרוס למקטעים קצרים כדי שביטול יגיב מהר
+fn throttle(written: u64, cap_mbps: f64, started: Instant, cancel_flag: &AtomicBool) -> bool {
+    let target = Duration::from_secs_f64(written as f64 / (cap_mbps * 1024.0 * 1024.0));
+    loop {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return false;
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= target {
+            return true;
+        }
+        thread::sleep((target - elapsed).min(Duration::from_millis(50)));
+    }
+}
+
+// אפסים מה-offset ועד סוף ההתקן. קובץ רגיל (בלי BLKGETSIZE64) עד הגודל הנוכחי שלו
+fn zero_fill(
+    device: &mut File,
+    offset: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> StreamEnd {
+    let capacity = match device_size(device) {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return StreamEnd::Failed;
+        }
+    };
+
+    if let Err(e) = device.seek(SeekFrom::Start(offset)) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return StreamEnd::Failed;
+    }
+
+    let total = capacity.saturating_sub(offset);
+    let zeros = vec![0u8; BUFFER_SIZE];
+    let mut wiped = 0u64;
+    let mut last_progress = Instant::now();
+
+    while wiped < total {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return StreamEnd::Cancelled;
+        }
+
+        let n = (total - wiped).min(BUFFER_SIZE as u64) as usize;
+        if let Err(e) = device.write_all(&zeros[..n]) {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return StreamEnd::Failed;
+        }
+        wiped += n as u64;
+
+        if last_progress.elapsed() >= Duration::from_millis(100) || wiped == total {
+            let _ = event_tx.send(BurnEvent::Wiping { wiped, total });
+            last_progress = Instant::now();
+        }
+    }
+
+    if let Err(e) = device.sync_all() {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return StreamEnd::Failed;
+    }
+
+    StreamEnd::Completed(wiped)
+}
+
+// אפסים על ה-MB הראשון של התמונה: טבלת מחיצות ו-bootloader. Wiping לפני ואחרי,
+// כדי שהממשק יראה מה קורה עד ה-Cancelled
+fn zero_head(device: &mut File, offset: u64, event_tx: &Sender<BurnEvent>) -> std::io::Result<()> {
+    let total = CANCEL_WIPE_BYTES;
+    let _ = event_tx.send(BurnEvent::Wiping { wiped: 0, total });
+    device.seek(SeekFrom::Start(offset))?;
+    device.write_all(&vec![0u8; total as usize])?;
+    device.sync_all()?;
+    let _ = event_tx.send(BurnEvent::Wiping {
+        wiped: total,
+        total,
+    });
+    Ok(())
+}
+
+// קריאה חוזרת מיד אחרי הכתיבה מגיעה מה-page cache ולא מהמדיה - מרוקנים אותו קודם
+fn open_uncached(device_path: &Path) -> std::io::Result<File> {
+    let device = File::open(device_path)?;
+    let fd = device.as_raw_fd();
+
+    // BLKFLSBUF צריך CAP_SYS_ADMIN ועובד רק על block device (קובץ רגיל מחזיר ENOTTY).
+    // בלעדיו ה-fadvise שמתחת עדיין מפנה את הדפים הנקיים
+    if unsafe { libc::ioctl(fd, BLKFLSBUF, 0) } != 0 {
+        tracing::debug!(
+            device = %device_path.display(),
+            error = %std::io::Error::last_os_error(),
+            "BLKFLSBUF failed, relying on fadvise"
+        );
+    }
+
+    // הכתיבה כבר עשתה sync_all, אז אין דפים מלוכלכים שיישארו בזיכרון
+    let ret = unsafe { libc::posix_fadvise(fd, 0, 0, libc::POSIX_FADV_DONTNEED) };
+    if ret != 0 {
+        return Err(std::io::Error::from_raw_os_error(ret));
+    }
+
+    Ok(device)
+}
+
+// החתימות בתחילת ההתקן: מערכת קבצים על כל הכונן, בלי טבלת מחיצות. ext2/3 חולקות
+// את החתימה עם ext4
+fn existing_filesystem(head: &[u8]) -> Option<&'static str> {
+    let has = |offset: usize, magic: &[u8]| head.get(offset..offset + magic.len()) == Some(magic);
+    if has(1080, &[0x53, 0xEF]) {
+        Some("ext4")
+    } else if has(82, b"FAT32") {
+        Some("FAT32")
+    } else if has(3, b"NTFS") {
+        Some("NTFS")
+    } else if has(3, b"EXFAT") {
+        Some("exFAT")
+    } else {
+        None
+    }
+}
+
+// מערכת קבצים על כל הכונן, או בתחילת אחת המחיצות שבטבלה שלו
+fn filesystem_on_device(device: &File) -> Option<&'static str> {
+    let mut head = vec![0u8; partitions::TABLE_HEAD_LEN];
+    device.read_exact_at(&mut head, 0).ok()?;
+    existing_filesystem(&head).or_else(|| {
+        partitions::parse_partition_table(&head)
+            .iter()
+            .find_map(|partition| {
+                let mut start = vec![0u8; FILESYSTEM_PROBE_LEN];
+                device.read_exact_at(&mut start, partition.start).ok()?;
+                existing_filesystem(&start)
+            })
+    })
+}
+
+// O_DIRECT דרך fcntl על fd פתוח, כדי להדליק אותו רק לשלב הכתיבה ולכבות לזנב
+fn set_direct_io(device: &File, on: bool) -> std::io::Result<()> {
+    let fd = device.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let flags = if on {
+        flags | libc::O_DIRECT
+    } else {
+        flags & !libc::O_DIRECT
+    };
+    if unsafe { libc::fcntl(fd, libc::F_SETFL, flags) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// הדגל שהקרנל מחזיק להתקן (אותו אחד כמו /sys/block/<dev>/ro). ioctl שנכשל = לא ידוע, ממשיכים
+fn is_read_only(device: &File) -> bool {
+    let mut ro: libc::c_int = 0;
+    let ret = unsafe { libc::ioctl(device.as_raw_fd(), BLKROGET, &mut ro) };
+    if ret != 0 {
+        tracing::debug!(error = %std::io::Error::last_os_error(), "BLKROGET failed");
+    }
+    ret == 0 && ro != 0
+}
+
+// BLKGETSIZE64 רק ל-block device. קובץ רגיל - האורך שלו
+fn device_size(device: &File) -> std::io::Result<u64> {
+    let metadata = device.metadata()?;
+    if metadata.is_file() {
+        return Ok(metadata.len());
+    }
+    let mut size: u64 = 0;
+    let ret = unsafe { libc::ioctl(device.as_raw_fd(), BLKGETSIZE64, &mut size) };
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(size)
+}
+
+// TRIM מה-offset ועד סוף ההתקן, כדי לא למחוק מחיצות שקיימות לפניו
+fn discard_device(device: &File, offset: u64) -> std::io::Result<()> {
+    let size = device_size(device)?;
+    let range: [u64; 2] = [offset, size.saturating_sub(offset)];
+    let ret = unsafe { libc::ioctl(device.as_raw_fd(), BLKDISCARD, &range) };
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// קורא רק את ההתקן ומשווה ל-hash שחושב בזמן הכתיבה
+fn verify_digest(
+    expected: &[u8],
+    algorithm: HashAlgorithm,
+    device_path: &Path,
+    offset: u64,
+    len: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> bool {
+    let mut device = match open_uncached(device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::opening(e, device_path)));
+            return false;
+        }
+    };
+
+    if let Err(e) = device.seek(SeekFrom::Start(offset)) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return false;
+    }
+
+    let mut hasher = Hasher::new(algorithm);
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    let mut checked = 0u64;
+
+    while checked < len {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return false;
+        }
+
+        let want = (len - checked).min(BUFFER_SIZE as u64) as usize;
+        let n = match device.read(&mut buf[..want]) {
+            Ok(0) => {
+                let _ = event_tx.send(BurnEvent::Error(device_ended(checked, len)));
+                return false;
+            }
+            Ok(n) => n,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(BurnError::io_at(
+                    e,
+                    offset + checked,
+                    IoDirection::Read,
+                )));
+                return false;
+            }
+        };
+
+        hasher.update(&buf[..n]);
+        checked += n as u64;
+
+        let _ = event_tx.send(BurnEvent::Verifying {
+            checked,
+            total: len,
+        });
+    }
+
+    let actual = hasher.finalize();
+    if actual != expected {
+        let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+            ErrorKind::VerificationFailed,
+            format!(
+                "Verification failed: expected {} {}, device has {}",
+                algorithm.name(),
+                hex(expected),
+                hex(&actual)
+            ),
+        )));
+        return false;
+    }
+
+    true
+}
+
+// קורא את ההתקן בבלוקים באותו גודל כמו בכתיבה ומשווה לטבלה
+fn verify_crc_table(
+    table: &[u32],
+    device_path: &Path,
+    offset: u64,
+    len: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> bool {
+    let mut device = match open_uncached(device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::opening(e, device_path)));
+            return false;
+        }
+    };
+
+    if let Err(e) = device.seek(SeekFrom::Start(offset)) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return false;
+    }
+
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    let mut checked = 0u64;
+
+    for (block, expected) in table.iter().enumerate() {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return false;
+        }
+
+        let want = (len - checked).min(BUFFER_SIZE as u64) as usize;
+        if let Err(e) = device.read_exact(&mut buf[..want]) {
+            let error = if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                device_ended(checked, len)
+            } else {
+                BurnError::io_at(e, offset + checked, IoDirection::Read)
+            };
+            let _ = event_tx.send(BurnEvent::Error(error));
+            return false;
+        }
+
+        let actual = crc32fast::hash(&buf[..want]);
+        if actual != *expected {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::new(
+                ErrorKind::VerificationFailed,
+                format!(
+                    "Verification failed: block {} (bytes {}..{}) has CRC32 {:08x}, expected {:08x}",
+                    block,
+                    checked,
+                    checked + want as u64,
+                    actual,
+                    expected
+                ),
+            )));
+            return false;
+        }
+
+        checked += want as u64;
+        let _ = event_tx.send(BurnEvent::Verifying {
+            checked,
+            total: len,
+        });
+    }
+
+    true
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn mismatch_detail(
+    expected: &[u8],
+    actual: &[u8],
+    device_offset: u64,
+    chunk: u64,
+    diff_count: u64,
+) -> String {
+    format!(
+        "Verification failed at device byte {} (chunk {}, {} bytes differ in this chunk)\nexpected: {}\ndevice:   {}",
+        device_offset,
+        chunk,
+        diff_count,
+        hexdump(expected),
+        hexdump(actual)
+    )
+}
+
+// חלון הבייטים סביב אי-ההתאמה הראשונה שמוצג בשגיאה
+const MISMATCH_WINDOW: usize = 32;
+
+fn hexdump(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// total = מה שנכתב לכונן. בתמונה דחוסה זה לא גודל הקובץ
+fn verify_image(
+    cfg: &BurnConfig,
+    total: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: Arc<AtomicBool>,
+) -> bool {
+    let mut iso = match File::open(&cfg.iso_path).and_then(|f| make_decompressor(&cfg.iso_path, f))
+    {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return false;
+        }
+    };
+
+    verify_stream(
+        &mut iso,
+        &cfg.device_path,
+        cfg.target_offset,
+        total,
+        cfg.verify_full_scan,
+        event_tx,
+        &cancel_flag,
+    )
+}
+
+const QUICK_VERIFY_EDGE: u64 = 64 * 1024 * 1024;
+const QUICK_VERIFY_WINDOW: u64 = 8 * 1024 * 1024;
+const QUICK_VERIFY_SAMPLES: u64 = 16;
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// (start, len) ממוינים ובלי חפיפות. אותו seed = אותם חלונות, כך שהרצה חוזרת זהה
+fn sample_windows(total: u64, seed: u64) -> Vec<(u64, u64)> {
+    let middle_start = QUICK_VERIFY_EDGE;
+    let middle_end = total.saturating_sub(QUICK_VERIFY_EDGE);
+    // תמונה קטנה - הדגימה כמעט כל התמונה, אז בודקים הכל
+    if middle_end <= middle_start + QUICK_VERIFY_WINDOW * QUICK_VERIFY_SAMPLES {
+        return vec![(0, total)];
+    }
+
+    let mut windows = vec![(0, QUICK_VERIFY_EDGE), (middle_end, QUICK_VERIFY_EDGE)];
+    let span = middle_end - QUICK_VERIFY_WINDOW - middle_start;
+    let mut state = seed;
+    for _ in 0..QUICK_VERIFY_SAMPLES {
+        let start = middle_start + splitmix64(&mut state) % span;
+        windows.push((start & !4095, QUICK_VERIFY_WINDOW));
+    }
+    windows.sort();
+
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+    for (start, len) in windows {
+        if let Some(last) = merged.last_mut() {
+            if start <= last.0 + last.1 {
+                last.1 = last.1.max(start + len - last.0);
+                continue;
+            }
+        }
+        merged.push((start, len));
+    }
+    merged
+}
+
+fn is_compressed(path: &Path) -> std::io::Result<bool> {
+    Ok(source_compression(path)? != Compression::None)
+}
+
+fn source_compression(path: &Path) -> std::io::Result<Compression> {
+    let mut magic = [0u8; 8];
+    let n = File::open(path)?.read(&mut magic)?;
+    Ok(detect_compression(path, &magic[..n]))
+}
+
+// משווה רק את החלונות שנדגמו. מקור רגיל מדלגים בו ב-seek, מקור דחוס פותחים עד החלון
+fn verify_sampled(
+    cfg: &BurnConfig,
+    total: u64,
+    seed: u64,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> bool {
+    let windows = sample_windows(total, seed);
+    let sampled: u64 = windows.iter().map(|(_, len)| len).sum();
+
+    let opened = is_compressed(&cfg.iso_path).and_then(|compressed| {
+        let file = File::open(&cfg.iso_path)?;
+        Ok(if compressed {
+            (None, Some(make_decompressor(&cfg.iso_path, file)?))
+        } else {
+            (Some(file), None)
+        })
+    });
+    let (mut plain, mut stream) = match opened {
+        Ok(sources) => sources,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return false;
+        }
+    };
+
+    let mut device = match open_uncached(&cfg.device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return false;
+        }
+    };
+
+    let mut buf_iso = vec![0u8; BUFFER_SIZE];
+    let mut buf_dev = vec![0u8; BUFFER_SIZE];
+    let mut stream_pos = 0u64;
+    let mut checked = 0u64;
+
+    for (index, (start, len)) in windows.into_iter().enumerate() {
+        let source: std::io::Result<&mut dyn Read> = match (plain.as_mut(), stream.as_mut()) {
+            (Some(file), _) => file
+                .seek(SeekFrom::Start(start))
+                .map(|_| file as &mut dyn Read),
+            (None, Some(reader)) => std::io::copy(
+                &mut reader.by_ref().take(start - stream_pos),
+                &mut std::io::sink(),
+            )
+            .map(|_| reader as &mut dyn Read),
+            (None, None) => unreachable!(),
+        };
+        let source = match source {
+            Ok(source) => source,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return false;
+            }
+        };
+        if let Err(e) = device.seek(SeekFrom::Start(cfg.target_offset + start)) {
+            let _ = event_tx.send(BurnEvent::Error(e.into()));
+            return false;
+        }
+
+        let mut done = 0u64;
+        while done < len {
+            if cancel_flag.load(Ordering::Relaxed) {
+                let _ = event_tx.send(BurnEvent::Cancelled);
+                return false;
+            }
+
+            let n = (len - done).min(BUFFER_SIZE as u64) as usize;
+            if let Err(e) = source
+                .read_exact(&mut buf_iso[..n])
+                .and_then(|_| device.read_exact(&mut buf_dev[..n]))
+            {
+                let _ = event_tx.send(BurnEvent::Error(e.into()));
+                return false;
+            }
+
+            if let Some(pos) = (0..n).find(|&i| buf_iso[i] != buf_dev[i]) {
+                let diff_count = buf_iso[..n]
+                    .iter()
+                    .zip(&buf_dev[..n])
+                    .filter(|(a, b)| a != b)
+                    .count() as u64;
+                let from = pos.saturating_sub(MISMATCH_WINDOW / 2);
+                let to = (from + MISMATCH_WINDOW).min(n);
+                let device_offset = cfg.target_offset + start + done + pos as u64;
+                let _ = event_tx.send(BurnEvent::Error(BurnError::verify_mismatch(
+                    format!(
+                        "{}\n(quick verify, sampled window {})",
+                        mismatch_detail(
+                            &buf_iso[from..to],
+                            &buf_dev[from..to],
+                            device_offset,
+                            (start + done) / BUFFER_SIZE as u64,
+                            diff_count,
+                        ),
+                        index
+                    ),
+                    VerifyMismatch::at(&buf_iso[..n], &buf_dev[..n], pos, device_offset),
+                )));
+                return false;
+            }
+
+            done += n as u64;
+            checked += n as u64;
+            let _ = event_tx.send(BurnEvent::Verifying {
+                checked,
+                total: sampled,
+            });
+        }
+        stream_pos = start + len;
+    }
+
+    true
+}
+
+// משווה את מה שעל ההתקן מול מקור - קובץ ISO או תבנית סינתטית
+// הכונן נגמר לפני שכל התמונה נקראה ממנו בחזרה
+fn device_ended(at: u64, image_len: u64) -> BurnError {
+    BurnError::too_small(
+        image_len,
+        at,
+        format!(
+            "Device smaller than image: it ended after {} of {} bytes",
+            at, image_len
+        ),
+    )
+}
+
+fn verify_stream<R: Read>(
+    expected: &mut R,
+    device_path: &Path,
+    offset: u64,
+    total: u64,
+    full_scan: bool,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> bool {
+    let _span = tracing::info_span!(
+        "verify",
+        device = %device_path.display(),
+        offset,
+        total,
+        full_scan,
+    )
+    .entered();
+    let mut device = match open_uncached(device_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = event_tx.send(BurnEvent::Error(BurnError::opening(e, device_path)));
+            return false;
+        }
+    };
+
+    if let Err(e) = device.seek(SeekFrom::Start(offset)) {
+        let _ = event_tx.send(BurnEvent::Error(e.into()));
+        return false;
+    }
+
+    compare_stream(
+        expected,
+        &mut device,
+        offset,
+        total,
+        full_scan,
+        event_tx,
+        cancel_flag,
+    )
+}
+
+// ההשוואה עצמה, על כל Read - ההתקן כבר פתוח וממוקם ב-offset
+fn compare_stream<R: Read, D: Read>(
+    expected: &mut R,
+    device: &mut D,
+    offset: u64,
+    total: u64,
+    full_scan: bool,
+    event_tx: &Sender<BurnEvent>,
+    cancel_flag: &Arc<AtomicBool>,
+) -> bool {
+    let mut checked = 0u64;
+    let mut chunk = 0u64;
+
+    // פירוט של אי-ההתאמה הראשונה, וסיכום של כל השאר אם סורקים עד הסוף
+    let mut first_mismatch: Option<(String, VerifyMismatch)> = None;
+    let mut regions = 0u64;
+    let mut differing = 0u64;
+    let mut in_region = false;
+
+    let mut buf_iso = vec![0u8; BUFFER_SIZE];
+    let mut buf_dev = vec![0u8; BUFFER_SIZE];
+
+    loop {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = event_tx.send(BurnEvent::Cancelled);
+            return false;
+        }
+
+        // פענוח מחזיר חלקי באפר - ממלאים, כך שכל השוואה היא chunk שלם במקום הנכון
+        let n1 = match source::read_full(expected, &mut buf_iso) {
+            Ok(0) => break,
+            Ok(n) => n,
+            // המקור: המיקום בזרם, כמו בכתיבה
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(BurnError::io_at(
+                    e,
+                    checked,
+                    IoDirection::Read,
+                )));
+                return false;
+            }
+        };
+
+        // read() על התקן בלוקים יכול להחזיר פחות מ-n1 (ואז הבאפר היה מושווה עם זבל מהסבב
+        // הקודם). ממלאים עד n1; EOF לפני סוף התמונה = הכונן קטן ממנה, לא אי-התאמה
+        let filled = match source::read_full(device, &mut buf_dev[..n1]) {
+            Ok(n) => n,
+            Err(e) => {
+                let _ = event_tx.send(BurnEvent::Error(BurnError::io_at(
+                    e,
+                    offset + checked,
+                    IoDirection::Read,
+                )));
+                return false;
+            }
+        };
+        if filled < n1 {
+            let error = device_ended(checked + filled as u64, total);
+            let _ = event_tx.send(BurnEvent::Error(error));
+            return false;
+        }
+
+        let diff_count = buf_iso[..n1]
+            .iter()
+            .zip(&buf_dev[..n1])
+            .filter(|(a, b)| a != b)
+            .count() as u64;
+
+        if diff_count == 0 {
+            in_region = false;
+        } else {
+            if !in_region {
+                regions += 1;
+            }
+            in_region = true;
+            differing += diff_count;
+
+            if first_mismatch.is_none() {
+                let pos = (0..n1).find(|&i| buf_iso[i] != buf_dev[i]).unwrap_or(0);
+                let start = pos.saturating_sub(MISMATCH_WINDOW / 2);
+                let end = (start + MISMATCH_WINDOW).min(n1);
+                let device_offset = offset + checked + pos as u64;
+                first_mismatch = Some((
+                    mismatch_detail(
+                        &buf_iso[start..end],
+                        &buf_dev[start..end],
+                        device_offset,
+                        chunk,
+                        diff_count,
+                    ),
+                    VerifyMismatch::at(&buf_iso[..n1], &buf_dev[..n1], pos, device_offset),
+                ));
+            }
+
+            if !full_scan {
+                break;
+            }
+        }
+
+        checked += n1 as u64;
+        chunk += 1;
+
+        let _ = event_tx.send(BurnEvent::Verifying { checked, total });
+    }
+
+    if let Some((mut message, mismatch)) = first_mismatch {
+        if full_scan {
+            message.push_str(&format!(
+                "\n{} mismatched region(s), {} differing bytes in total",
+                regions, differing
+            ));
+        }
+        let _ = event_tx.send(BurnEvent::Error(BurnError::verify_mismatch(
+            message, mismatch,
+        )));
+        return false;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aligned_buf::AlignedBuffer;
+    use serde_json::Value;
+    use std::os::unix::ffi::OsStrExt;
+
+    fn is_str(v: &Value) -> bool {
+        v.is_string()
+    }
+
+    fn is_uint(v: &Value) -> bool {
+        v.is_u64()
+    }
+
+    fn is_num(v: &Value) -> bool {
+        v.is_number()
+    }
+
+    fn is_bool(v: &Value) -> bool {
+        v.is_boolean()
+    }
+
+    fn is_array(v: &Value) -> bool {
+        v.is_array()
+    }
+
+    fn is_object(v: &Value) -> bool {
+        v.is_object()
+    }
+
+    type FieldCheck = (&'static str, fn(&Value) -> bool);
+
+    // כל variant: ה-type הנכון, בדיוק השדות הצפויים, והטיפוס של כל שדה
+    #[test]
+    fn events_serialize_as_flat_json() {
+        let cases: Vec<(BurnEvent, &str, &[FieldCheck])> = vec![
+            (BurnEvent::Preparing, "Preparing", &[]),
+            (
+                BurnEvent::Discarded { elapsed_secs: 1.5 },
+                "Discarded",
+                &[("elapsed_secs", is_num)],
+            ),
+            (
+                BurnEvent::Progress {
+                    written: 1048576,
+                    total: 734003200,
+                    read_speed_mbps: 310.0,
+                    write_speed_mbps: 24.5,
+                    eta_secs: 28,
+                },
+                "Progress",
+                &[
+                    ("written", is_uint),
+                    ("total", is_uint),
+                    ("read_speed_mbps", is_num),
+                    ("write_speed_mbps", is_num),
+                    ("eta_secs", is_uint),
+                ],
+            ),
+            (
+                BurnEvent::Verifying {
+                    checked: 1,
+                    total: 2,
+                },
+                "Verifying",
+                &[("checked", is_uint), ("total", is_uint)],
+            ),
+            (
+                BurnEvent::VerifySummary {
+                    method: VerifyMethod::Hash(HashAlgorithm::Blake3),
+                    ok: true,
+                },
+                "VerifySummary",
+                &[("method", is_object), ("ok", is_bool)],
+            ),
+            (
+                BurnEvent::VerifySummary {
+                    method: VerifyMethod::Crc32,
+                    ok: false,
+                },
+                "VerifySummary",
+                &[("method", is_str), ("ok", is_bool)],
+            ),
+            (
+                BurnEvent::Finished {
+                    bytes: 4096,
+                    elapsed_secs: 1.5,
+                    verified: true,
+                },
+                "Finished",
+                &[
+                    ("bytes", is_uint),
+                    ("elapsed_secs", is_num),
+                    ("verified", is_bool),
+                ],
+            ),
+            (
+                BurnEvent::WipePass { pass: 1, passes: 2 },
+                "WipePass",
+                &[("pass", is_uint), ("passes", is_uint)],
+            ),
+            (
+                BurnEvent::VerifySkipped {
+                    reason: "random".into(),
+                },
+                "VerifySkipped",
+                &[("reason", is_str)],
+            ),
+            (
+                BurnEvent::Checkpoint {
+                    at_bytes: 536870912,
+                },
+                "Checkpoint",
+                &[("at_bytes", is_uint)],
+            ),
+            (
+                BurnEvent::PersistenceStarted {
+                    label: "casper-rw".into(),
+                    size: 4294967296,
+                },
+                "PersistenceStarted",
+                &[("label", is_str), ("size", is_uint)],
+            ),
+            (
+                BurnEvent::PersistenceCreated {
+                    partition: "/dev/sdb3".into(),
+                    label: "casper-rw".into(),
+                    size: 4294967296,
+                },
+                "PersistenceCreated",
+                &[("partition", is_str), ("label", is_str), ("size", is_uint)],
+            ),
+            (
+                BurnEvent::DataPartitionAdded {
+                    partition: "/dev/sdb3".into(),
+                    size: 61203283968,
+                },
+                "DataPartitionAdded",
+                &[("partition", is_str), ("size", is_uint)],
+            ),
+            (
+                BurnEvent::DataPartitionFormatted {
+                    partition: "/dev/sdb3".into(),
+                    filesystem: "exFAT".into(),
+                },
+                "DataPartitionFormatted",
+                &[("partition", is_str), ("filesystem", is_str)],
+            ),
+            (
+                BurnEvent::WindowsInstaller {
+                    image: "sources/install.wim".into(),
+                    size: 5 << 30,
+                    split: true,
+                },
+                "WindowsInstaller",
+                &[("image", is_str), ("size", is_uint), ("split", is_bool)],
+            ),
+            (
+                BurnEvent::SplittingInstallImage,
+                "SplittingInstallImage",
+                &[],
+            ),
+            (
+                BurnEvent::CopyingFiles {
+                    files: 900,
+                    total: 6 << 30,
+                },
+                "CopyingFiles",
+                &[("files", is_uint), ("total", is_uint)],
+            ),
+            (
+                BurnEvent::MultibootPrepared {
+                    boot: "/dev/sdb1".into(),
+                    data: "/dev/sdb2".into(),
+                },
+                "MultibootPrepared",
+                &[("boot", is_str), ("data", is_str)],
+            ),
+            (
+                BurnEvent::MultibootMenuUpdated {
+                    entries: vec!["debian.iso".into()],
+                },
+                "MultibootMenuUpdated",
+                &[("entries", is_array)],
+            ),
+            (
+                BurnEvent::Partitioning {
+                    scheme: PartitionScheme::Gpt,
+                },
+                "Partitioning",
+                &[("scheme", is_str)],
+            ),
+            (
+                BurnEvent::Formatting {
+                    partition: "/dev/sdb1".into(),
+                    filesystem: "exFAT".into(),
+                    label: "USB".into(),
+                },
+                "Formatting",
+                &[
+                    ("partition", is_str),
+                    ("filesystem", is_str),
+                    ("label", is_str),
+                ],
+            ),
+            (
+                BurnEvent::Wiping { wiped: 1, total: 2 },
+                "Wiping",
+                &[("wiped", is_uint), ("total", is_uint)],
+            ),
+            (
+                BurnEvent::Hashing { done: 1, total: 2 },
+                "Hashing",
+                &[("done", is_uint), ("total", is_uint)],
+            ),
+            (
+                BurnEvent::ChecksumChecked {
+                    algorithm: HashAlgorithm::Sha256,
+                    matches: true,
+                    digest: "ab".into(),
+                },
+                "ChecksumChecked",
+                &[
+                    ("algorithm", is_str),
+                    ("matches", is_bool),
+                    ("digest", is_str),
+                ],
+            ),
+            (
+                BurnEvent::ImageHashed {
+                    path: "/tmp/debian.iso".into(),
+                    algorithm: HashAlgorithm::Sha256,
+                    digest: "ab".into(),
+                },
+                "ImageHashed",
+                &[("path", is_str), ("algorithm", is_str), ("digest", is_str)],
+            ),
+            (
+                BurnEvent::Report(BurnReport {
+                    timestamp: 1700000000,
+                    image: "/tmp/debian.iso".into(),
+                    image_sha256: Some("ab".into()),
+                    device: "/dev/sdb".into(),
+                    device_model: Some("Cruzer Blade".into()),
+                    device_serial: Some("4C530001".into()),
+                    device_size: Some(16 << 30),
+                    bytes_written: 1048576,
+                    bytes_verified: 0,
+                    avg_speed_mbps: 24.5,
+                    verified: Some(false),
+                    duration_secs: 12.5,
+                    outcome: BurnOutcome::Failed,
+                    error: Some("Verification failed".into()),
+                }),
+                "Report",
+                &[
+                    ("timestamp", is_uint),
+                    ("image", is_str),
+                    ("image_sha256", is_str),
+                    ("device", is_str),
+                    ("device_model", is_str),
+                    ("device_serial", is_str),
+                    ("device_size", is_uint),
+                    ("bytes_written", is_uint),
+                    ("bytes_verified", is_uint),
+                    ("avg_speed_mbps", is_num),
+                    ("verified", is_bool),
+                    ("duration_secs", is_num),
+                    ("outcome", is_str),
+                    ("error", is_str),
+                ],
+            ),
+            (
+                BurnEvent::SignatureVerified {
+                    signer: "someone".into(),
+                },
+                "SignatureVerified",
+                &[("signer", is_str)],
+            ),
+            (
+                BurnEvent::Warning {
+                    message: "careful".into(),
+                },
+                "Warning",
+                &[("message", is_str)],
+            ),
+            (
+                BurnEvent::ImageInfo {
+                    partitions: vec![PartitionInfo {
+                        index: 1,
+                        kind: "EFI System".into(),
+                        start: 1048576,
+                        size: 4096,
+                    }],
+                },
+                "ImageInfo",
+                &[("partitions", is_array)],
+            ),
+            (
+                BurnEvent::SurfaceReport {
+                    bad_ranges: vec![BadRange {
+                        first_lba: 8,
+                        last_lba: 15,
+                    }],
+                    total_bytes: 4096,
+                },
+                "SurfaceReport",
+                &[("bad_ranges", is_array), ("total_bytes", is_uint)],
+            ),
+            (
+                BurnEvent::CapacityReport {
+                    advertised: 1 << 40,
+                    verified: 32 << 30,
+                    wraps_at: Some(32 << 30),
+                },
+                "CapacityReport",
+                &[
+                    ("advertised", is_uint),
+                    ("verified", is_uint),
+                    ("wraps_at", is_uint),
+                ],
+            ),
+            (
+                BurnEvent::CloneProgress {
+                    read: 4096,
+                    total: 8192,
+                    written: 100,
+                    speed_mbps: 50.0,
+                    eta_secs: 1,
+                },
+                "CloneProgress",
+                &[
+                    ("read", is_uint),
+                    ("total", is_uint),
+                    ("written", is_uint),
+                    ("speed_mbps", is_num),
+                    ("eta_secs", is_uint),
+                ],
+            ),
+            (
+                BurnEvent::BenchmarkProgress {
+                    phase: BenchmarkPhase::Read,
+                    done: 1,
+                    total: 2,
+                    speed_mbps: 30.5,
+                },
+                "BenchmarkProgress",
+                &[
+                    ("phase", is_str),
+                    ("done", is_uint),
+                    ("total", is_uint),
+                    ("speed_mbps", is_num),
+                ],
+            ),
+            (
+                BurnEvent::BenchmarkReport {
+                    read: BenchmarkResult {
+                        bytes: 1 << 30,
+                        elapsed_secs: 10.0,
+                        min_mbps: 90.0,
+                        avg_mbps: 102.4,
+                        max_mbps: 110.0,
+                        samples: vec![90.0, 110.0],
+                    },
+                    write: None,
+                },
+                "BenchmarkReport",
+                &[("read", is_object), ("write", Value::is_null)],
+            ),
+            (
+                BurnEvent::Target {
+                    device: "/dev/sdb".into(),
+                    event: Box::new(BurnEvent::Verifying {
+                        checked: 1,
+                        total: 2,
+                    }),
+                },
+                "Target",
+                &[("device", is_str), ("event", is_object)],
+            ),
+            (
+                BurnEvent::MultiFinished {
+                    targets: vec![TargetOutcome {
+                        device: "/dev/sdb".into(),
+                        bytes: 4096,
+                        avg_mbps: 20.0,
+                        verified: false,
+                        error: Some(BurnError::new(ErrorKind::Io, "gone")),
+                    }],
+                    elapsed_secs: 1.5,
+                },
+                "MultiFinished",
+                &[("targets", is_array), ("elapsed_secs", is_num)],
+            ),
+            (
+                BurnEvent::JobQueued {
+                    id: 3,
+                    label: "Burn a.iso → /dev/sdb".into(),
+                    position: 1,
+                },
+                "JobQueued",
+                &[("id", is_uint), ("label", is_str), ("position", is_uint)],
+            ),
+            (
+                BurnEvent::JobStarted { id: 3 },
+                "JobStarted",
+                &[("id", is_uint)],
+            ),
+            (
+                BurnEvent::JobEnded {
+                    id: 3,
+                    state: JobState::Cancelled,
+                },
+                "JobEnded",
+                &[("id", is_uint), ("state", is_str)],
+            ),
+            (
+                BurnEvent::QueueStatus {
+                    jobs: vec![JobInfo {
+                        id: 3,
+                        label: "Wipe /dev/sdc".into(),
+                        state: JobState::Pending,
+                    }],
+                },
+                "QueueStatus",
+                &[("jobs", is_array)],
+            ),
+            (BurnEvent::Ejected, "Ejected", &[]),
+            (
+                BurnEvent::EjectFailed {
+                    reason: "busy".into(),
+                },
+                "EjectFailed",
+                &[("reason", is_str)],
+            ),
+            (BurnEvent::Cancelled, "Cancelled", &[]),
+            (
+                BurnEvent::Error(BurnError::new(ErrorKind::DeviceTooSmall, "too small")),
+                "Error",
+                &[("kind", is_str), ("message", is_str)],
+            ),
+            (
+                BurnEvent::Error(BurnError::verify_mismatch(
+                    "bad block",
+                    VerifyMismatch::at(&[1; 4], &[2; 4], 0, 0x1F400000),
+                )),
+                "Error",
+                &[
+                    ("kind", is_str),
+                    ("message", is_str),
+                    ("mismatch", is_object),
+                ],
+            ),
+            (
+                BurnEvent::Error(BurnError::write_timeout(30)),
+                "Error",
+                &[
+                    ("kind", is_str),
+                    ("message", is_str),
+                    ("stalled_for_secs", is_uint),
+                ],
+            ),
+            (
+                BurnEvent::Error(BurnError::filesystem_detected(
+                    Path::new("/dev/sdb"),
+                    "NTFS",
+                )),
+                "Error",
+                &[("kind", is_str), ("message", is_str), ("fs_type", is_str)],
+            ),
+        ];
+
+        for (event, type_name, fields) in cases {
+            let json: Value = serde_json::to_value(&event).unwrap();
+            let object = json.as_object().unwrap();
+            assert_eq!(object["type"], type_name);
+            assert_eq!(object.len(), fields.len() + 1, "{}: {}", type_name, json);
+            for (key, check) in fields {
+                let value = object
+                    .get(*key)
+                    .unwrap_or_else(|| panic!("{}: missing {}", type_name, key));
+                assert!(check(value), "{}: {} has the wrong type", type_name, key);
+            }
+        }
+    }
+
+    #[test]
+    fn nested_values_serialize_by_name() {
+        let json = serde_json::to_value(BurnEvent::VerifySummary {
+            method: VerifyMethod::Hash(HashAlgorithm::Blake3),
+            ok: true,
+        })
+        .unwrap();
+        assert_eq!(json["method"]["Hash"], "Blake3");
+
+        let json = serde_json::to_value(BurnEvent::ImageInfo {
+            partitions: vec![PartitionInfo {
+                index: 2,
+                kind: "Linux".into(),
+                start: 512,
+                size: 1024,
+            }],
+        })
+        .unwrap();
+        let partition = &json["partitions"][0];
+        assert_eq!(partition["index"], 2);
+        assert_eq!(partition["kind"], "Linux");
+        assert_eq!(partition["start"], 512);
+        assert_eq!(partition["size"], 1024);
+
+        let json =
+            serde_json::to_value(BurnEvent::Error(BurnError::new(ErrorKind::Io, "eio"))).unwrap();
+        assert_eq!(json["kind"], "Io");
+        // פרטים שלא קיימים לשגיאה הזו לא מופיעים
+        assert!(json.get("offset").is_none() && json.get("mounts").is_none());
+
+        let error = BurnError::io_at(
+            std::io::Error::from_raw_os_error(libc::EIO),
+            4096,
+            IoDirection::Write,
+        );
+        let source = std::error::Error::source(&error).unwrap();
+        assert_eq!(
+            source
+                .downcast_ref::<std::io::Error>()
+                .unwrap()
+                .raw_os_error(),
+            Some(libc::EIO)
+        );
+        let json = serde_json::to_value(BurnEvent::Error(error)).unwrap();
+        assert_eq!(
+            (&json["offset"], &json["direction"]),
+            (&4096.into(), &"Write".into())
+        );
+        // ENOSPC באמצע הכתיבה נשאר "קטן מדי" - בלי פרטים של Io שיסתרו אותו
+        let error = BurnError::io_at(
+            std::io::Error::from_raw_os_error(libc::ENOSPC),
+            4096,
+            IoDirection::Write,
+        );
+        assert_eq!(
+            (error.kind, error.detail()),
+            (ErrorKind::DeviceTooSmall, None)
+        );
+        let json = serde_json::to_value(BurnEvent::Error(device_ended(10, 20))).unwrap();
+        assert_eq!(
+            (&json["needed"], &json["available"]),
+            (&20.into(), &10.into())
+        );
+    }
+
+    // תיקייה זמנית עם תמונת מקור אקראית ונתיב יעד שעוד לא קיים. נמחקת בסוף הבדיקה
+    struct TempBurn {
+        dir: PathBuf,
+        source: PathBuf,
+        target: PathBuf,
+        data: Vec<u8>,
+    }
+
+    impl TempBurn {
+        fn new(name: &str, len: usize) -> Self {
+            let mut state = len as u64;
+            Self::with_data(
+                name,
+                (0..len).map(|_| splitmix64(&mut state) as u8).collect(),
+            )
+        }
+
+        fn with_data(name: &str, data: Vec<u8>) -> Self {
+            let dir = std::env::temp_dir().join(format!("burn-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let source = dir.join("source.img");
+            std::fs::write(&source, &data).unwrap();
+            Self {
+                target: dir.join("target.img"),
+                source,
+                dir,
+                data,
+            }
+        }
+
+        fn config(&self) -> BurnConfig {
+            BurnConfig {
+                iso_path: self.source.clone(),
+                device_path: self.target.clone(),
+                verify_mode: VerifyMode::Full,
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Drop for TempBurn {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    // כל האירועים עד Finished/Error/Cancelled, כולל אותו
+    fn drain(events: &Receiver<BurnEvent>, mut on_event: impl FnMut(&BurnEvent)) -> Vec<BurnEvent> {
+        let mut seen = Vec::new();
+        loop {
+            let event = events.recv_timeout(Duration::from_secs(30)).unwrap();
+            on_event(&event);
+            let done = matches!(
+                event,
+                BurnEvent::Finished { .. }
+                    | BurnEvent::MultiFinished { .. }
+                    | BurnEvent::Error(_)
+                    | BurnEvent::Cancelled
+            );
+            seen.push(event);
+            if done {
+                return seen;
+            }
+        }
+    }
+
+    fn position(events: &[BurnEvent], name: &str, pred: impl Fn(&BurnEvent) -> bool) -> usize {
+        events
+            .iter()
+            .position(pred)
+            .unwrap_or_else(|| panic!("no {} in {:?}", name, events))
+    }
+
+    // צריבה מקצה לקצה בלי התקן אמיתי: קובץ מקור, קובץ יעד שעוד לא קיים, ובדיקה מלאה
+    #[test]
+    fn burns_to_a_regular_file() {
+        // לא כפולה של BUFFER_SIZE, כדי שגם הבלוק האחרון החלקי ייבדק
+        let burn = TempBurn::new("file-target", BUFFER_SIZE + 12345);
+        let engine = BurnEngine::new();
+        engine.start(burn.config());
+
+        let events = drain(&engine.events(), |_| {});
+        match events.last().unwrap() {
+            BurnEvent::Finished {
+                bytes, verified, ..
+            } => {
+                assert_eq!(*bytes, burn.data.len() as u64);
+                assert!(verified);
+            }
+            other => panic!("burn did not finish: {:?}", other),
+        }
+        let target = std::fs::read(&burn.target).unwrap();
+        assert!(target == burn.data, "target differs from source");
+    }
+
+    // .bin של בייטים אקראיים: בלי ISO 9660, ובכל זאת לא נעצר בבדיקת ה-ISO ונכתב כמו שהוא
+    #[test]
+    fn burns_a_bin_image_byte_for_byte() {
+        let burn = TempBurn::new("bin-source", BUFFER_SIZE + 4321);
+        let bin_path = burn.dir.join("firmware.bin");
+        std::fs::rename(&burn.source, &bin_path).unwrap();
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            iso_path: bin_path,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |_| {});
+        assert!(
+            matches!(events.last().unwrap(), BurnEvent::Finished { bytes, verified: true, .. } if *bytes == burn.data.len() as u64),
+            "{:?}",
+            events.last()
+        );
+        assert!(std::fs::read(&burn.target).unwrap() == burn.data);
+    }
+
+    // כמה מדפי הקובץ יושבים עכשיו ב-page cache. המיפוי לא נוגע בדפים, אז הוא לא מכניס אותם
+    fn resident_pages(path: &Path) -> usize {
+        let file = File::open(path).unwrap();
+        let map = unsafe { memmap2::Mmap::map(&file).unwrap() };
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let mut residency = vec![0u8; map.len().div_ceil(page)];
+        let ret = unsafe {
+            libc::mincore(
+                map.as_ptr() as *mut libc::c_void,
+                map.len(),
+                residency.as_mut_ptr(),
+            )
+        };
+        assert_eq!(ret, 0, "{}", std::io::Error::last_os_error());
+        residency.iter().filter(|&&page| page & 1 != 0).count()
+    }
+
+    // על קובץ רגיל BLKFLSBUF נכשל ו-open_uncached ממשיך עם fadvise בלבד - ואחריו אף דף של
+    // היעד לא נשאר ב-cache, אז הבדיקה קוראת מהדיסק. ובדיקה חוזרת רואה שינוי ביעד
+    #[test]
+    fn verify_sees_a_target_modified_after_the_burn() {
+        let burn = TempBurn::new("uncached", BUFFER_SIZE + 12345);
+        let engine = BurnEngine::new();
+        engine.start(burn.config());
+        let events = drain(&engine.events(), |_| {});
+        assert!(
+            matches!(
+                events.last().unwrap(),
+                BurnEvent::Finished { verified: true, .. }
+            ),
+            "{:?}",
+            events.last()
+        );
+
+        let target = File::open(&burn.target).unwrap();
+        assert_ne!(unsafe { libc::ioctl(target.as_raw_fd(), BLKFLSBUF, 0) }, 0);
+        assert_eq!(
+            std::io::Error::last_os_error().raw_os_error(),
+            Some(libc::ENOTTY)
+        );
+        // tmpfs הוא ה-page cache עצמו - אין לאן לפנות את הדפים
+        let mut fs: libc::statfs = unsafe { std::mem::zeroed() };
+        let dir = std::ffi::CString::new(burn.dir.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::statfs(dir.as_ptr(), &mut fs) }, 0);
+        if fs.f_type != libc::TMPFS_MAGIC {
+            assert!(std::fs::read(&burn.target).unwrap() == burn.data);
+            assert!(resident_pages(&burn.target) > 0);
+            drop(open_uncached(&burn.target).unwrap());
+            assert_eq!(resident_pages(&burn.target), 0);
+        }
+        let mut cached = Vec::new();
+        open_uncached(&burn.target)
+            .unwrap()
+            .read_to_end(&mut cached)
+            .unwrap();
+        assert!(cached == burn.data);
+
+        let offset = BUFFER_SIZE as u64 + 100;
+        File::options()
+            .write(true)
+            .open(&burn.target)
+            .unwrap()
+            .write_all_at(&[!burn.data[offset as usize]], offset)
+            .unwrap();
+        engine.verify_only(BurnConfig {
+            byte_compare: true,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |_| {});
+        match events.last().unwrap() {
+            BurnEvent::Error(e) => {
+                assert_eq!(e.kind, ErrorKind::VerificationFailed);
+                assert_eq!(e.mismatch().map(|m| m.offset), Some(offset));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    // כונן "משומש" מלא ב-0xFF: קודם כולו אפסים (עם Wiping), ורק אחר כך התמונה
+    #[test]
+    fn wipe_before_burn_zero_fills_then_writes() {
+        let burn = TempBurn::new("wipe-before", 1024 * 1024 + 123);
+        let device_len = 4 * 1024 * 1024;
+        std::fs::write(&burn.target, vec![0xFFu8; device_len]).unwrap();
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            wipe_before_burn: true,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |_| {});
+        assert!(
+            matches!(
+                events.last().unwrap(),
+                BurnEvent::Finished { verified: true, .. }
+            ),
+            "{:?}",
+            events.last()
+        );
+
+        let last_wipe = events
+            .iter()
+            .rposition(|e| matches!(e, BurnEvent::Wiping { .. }))
+            .expect("no Wiping event");
+        // צריבה קטנה לא מספיקה לשלוח Progress; הבדיקה באה אחרי הכתיבה
+        let verifying = position(&events, "Verifying", |e| {
+            matches!(e, BurnEvent::Verifying { .. })
+        });
+        assert!(last_wipe < verifying, "{:?}", events);
+        assert!(matches!(
+            events[last_wipe],
+            BurnEvent::Wiping { wiped, total } if wiped == total && total == device_len as u64
+        ));
+
+        // אם המחיקה הייתה אחרי הכתיבה, גם ההתחלה הייתה אפסים
+        let device = std::fs::read(&burn.target).unwrap();
+        assert_eq!(device.len(), device_len);
+        assert!(device[..burn.data.len()] == burn.data[..]);
+        assert!(device[burn.data.len()..].iter().all(|&b| b == 0));
+    }
+
+    // ביטול באמצע המחיקה: התמונה לא מתחילה להיכתב
+    #[test]
+    fn cancelled_wipe_never_starts_the_write() {
+        let burn = TempBurn::new("wipe-cancel", 1024 * 1024);
+        // יעד דליל של 1GB - המחיקה שלו לוקחת מספיק זמן כדי לבטל באמצע
+        File::create(&burn.target)
+            .unwrap()
+            .set_len(1024 * 1024 * 1024)
+            .unwrap();
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            wipe_before_burn: true,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |event| {
+            if let BurnEvent::Wiping { wiped, total } = event {
+                assert!(wiped < total, "wipe finished before it could be cancelled");
+                engine.cancel();
+            }
+        });
+        assert!(
+            matches!(events.last().unwrap(), BurnEvent::Cancelled),
+            "{:?}",
+            events
+        );
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, BurnEvent::Progress { .. })));
+        // רק אפסים בהתחלה - אף בייט של התמונה
+        let mut head = vec![0xAAu8; burn.data.len()];
+        File::open(&burn.target)
+            .unwrap()
+            .read_exact(&mut head)
+            .unwrap();
+        assert!(head.iter().all(|&b| b == 0));
+    }
+
+    // הגדרות הפרופיל כמו שה-UI ממלא אותן: chunks של 4MB עם checkpoints עדיין נבדקים מול SHA-256
+    #[test]
+    fn paranoid_profile_burns_in_smaller_chunks() {
+        let burn = TempBurn::new("profile", BUFFER_SIZE + 12345);
+        let overrides = BurnProfile::Paranoid.into_config_overrides();
+        assert_eq!(overrides.buffer_size, 4 * 1024 * 1024);
+        assert_eq!(overrides.hash_algorithm, HashAlgorithm::Sha256);
+        assert!(overrides.pre_discard);
+        let cfg = BurnConfig {
+            buffer_size: overrides.buffer_size,
+            verify_mode: overrides.verify_mode,
+            byte_compare: overrides.byte_compare,
+            hash_algorithm: overrides.hash_algorithm,
+            pre_discard: overrides.pre_discard,
+            sync_interval_bytes: Some(2 * 1024 * 1024),
+            eject_after: true,
+            ..burn.config()
+        };
+
+        let engine = BurnEngine::new();
+        engine.start(cfg);
+        let events = drain(&engine.events(), |_| {});
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, BurnEvent::Checkpoint { .. })));
+        assert!(
+            matches!(
+                events.last().unwrap(),
+                BurnEvent::Finished { verified: true, .. }
+            ),
+            "{:?}",
+            events.last()
+        );
+        let target = std::fs::read(&burn.target).unwrap();
+        assert!(target == burn.data, "target differs from source");
+        assert_eq!(
+            BurnProfile::default().into_config_overrides().buffer_size,
+            BUFFER_SIZE
+        );
+    }
+
+    // read() שמחזיר לכל היותר 4KB בכל פעם, כמו פענוח
+    struct Trickle<R>(R);
+
+    impl<R: Read> Read for Trickle<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(4096);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    // מקור שמחזיר קריאות קצרות עדיין יוצא ב-chunks מלאים ומיושרים, חוץ מהאחרון
+    #[test]
+    fn direct_io_reader_hands_out_aligned_full_chunks() {
+        let buffer = AlignedBuffer::new(BUFFER_SIZE, DIRECT_ALIGN);
+        assert_eq!(buffer.as_ptr() as usize % 4096, 0);
+        assert!(buffer.iter().all(|b| *b == 0));
+
+        let total = 3 * BUFFER_SIZE + 1000;
+        let (data_rx, reader) = source::spawn_read_thread(
+            Trickle(std::io::repeat(0x5A).take(total as u64)),
+            BUFFER_SIZE,
+            false,
+            true,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(ReadStats::default()),
+        );
+        let lens: Vec<usize> = data_rx
+            .iter()
+            .map(|chunk| {
+                assert_eq!(chunk.as_ptr() as usize % 4096, 0);
+                chunk.len()
+            })
+            .collect();
+        reader.join().unwrap().unwrap();
+        assert_eq!(lens, [BUFFER_SIZE, BUFFER_SIZE, BUFFER_SIZE, 1000]);
+    }
+
+    // O_DIRECT לכל ה-chunks המיושרים והזנב בלעדיו. יעד שלא תומך - אזהרה וצריבה רגילה
+    #[test]
+    fn direct_io_burn_writes_the_unaligned_tail() {
+        let burn = TempBurn::new("direct-io", 2 * BUFFER_SIZE + 12345);
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            use_direct_io: true,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |_| {});
+        assert!(
+            matches!(
+                events.last().unwrap(),
+                BurnEvent::Finished { verified: true, .. }
+            ),
+            "{:?}",
+            events.last()
+        );
+        let target = std::fs::read(&burn.target).unwrap();
+        assert!(target == burn.data, "target differs from source");
+    }
+
+    // בלוק רע באמצע הבאפר השני: ה-offset על ההתקן והבייטים משני הצדדים מגיעים בשגיאה
+    #[test]
+    fn verify_error_reports_the_first_mismatch() {
+        let burn = TempBurn::new("verify-mismatch", BUFFER_SIZE * 2);
+        let bad = BUFFER_SIZE + 4096;
+        let mut device = burn.data.clone();
+        for byte in &mut device[bad..bad + 512] {
+            *byte = !*byte;
+        }
+        std::fs::write(&burn.target, &device).unwrap();
+
+        let (event_tx, events) = unbounded();
+        let ok = verify_stream(
+            &mut burn.data.as_slice(),
+            &burn.target,
+            0,
+            burn.data.len() as u64,
+            true,
+            &event_tx,
+            &Arc::new(AtomicBool::new(false)),
+        );
+        assert!(!ok);
+
+        let error = events
+            .try_iter()
+            .find_map(|event| match event {
+                BurnEvent::Error(e) => Some(e),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(error.kind, ErrorKind::VerificationFailed);
+        let mismatch = error.mismatch().unwrap();
+        assert_eq!(mismatch.offset, bad as u64);
+        assert_eq!(mismatch.expected, burn.data[bad..bad + MISMATCH_BYTES]);
+        assert_eq!(mismatch.actual, device[bad..bad + MISMATCH_BYTES]);
+    }
+
+    // מקור בקריאות קצרות מול כונן שנגמר באמצע התמונה: "קטן מדי" ולא אי-התאמה
+    #[test]
+    fn verify_reports_a_device_shorter_than_the_image() {
+        let burn = TempBurn::new("verify-short", BUFFER_SIZE * 2);
+        let short = BUFFER_SIZE + 12345;
+        std::fs::write(&burn.target, &burn.data[..short]).unwrap();
+
+        let (event_tx, events) = unbounded();
+        let ok = verify_stream(
+            &mut Trickle(burn.data.as_slice()),
+            &burn.target,
+            0,
+            burn.data.len() as u64,
+            false,
+            &event_tx,
+            &Arc::new(AtomicBool::new(false)),
+        );
+        assert!(!ok);
+
+        let events: Vec<_> = events.try_iter().collect();
+        // ה-chunk הראשון שלם למרות הקריאות הקצרות, והשני כבר חורג מהכונן
+        assert!(matches!(
+            events[0],
+            BurnEvent::Verifying { checked, .. } if checked == BUFFER_SIZE as u64
+        ));
+        match events.last().unwrap() {
+            BurnEvent::Error(e) => {
+                assert_eq!(e.kind, ErrorKind::DeviceTooSmall);
+                assert!(
+                    e.message.starts_with("Device smaller than image"),
+                    "{}",
+                    e.message
+                );
+                assert!(e.message.contains(&format!("after {} of", short)));
+                assert_eq!(
+                    e.detail(),
+                    Some(&ErrorDetail::DeviceTooSmall {
+                        needed: burn.data.len() as u64,
+                        available: short as u64
+                    })
+                );
+                assert_eq!(
+                    e.to_user_string(),
+                    format!("Error: {} — pick a larger drive", e)
+                );
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    // כונן שמחזיר את הנתונים ב-4KB בכל read(): כל chunk מושווה שלם, והאימות עובר
+    #[test]
+    fn verify_accepts_matching_data_in_small_reads() {
+        let data: Vec<u8> = (0..2 * BUFFER_SIZE + 777)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let (event_tx, events) = unbounded();
+        let ok = compare_stream(
+            &mut data.as_slice(),
+            &mut Trickle(data.as_slice()),
+            0,
+            data.len() as u64,
+            true,
+            &event_tx,
+            &Arc::new(AtomicBool::new(false)),
+        );
+        let events: Vec<_> = events.try_iter().collect();
+        assert!(ok, "{:?}", events.last());
+        assert!(matches!(
+            events.last().unwrap(),
+            BurnEvent::Verifying { checked, .. } if *checked == data.len() as u64
+        ));
+    }
+
+    // ISO קטן עם תיקייה אחת בשורש ומחיצה 1 שמכסה אותו, כמו ISO היברידי
+    fn live_image(dir_name: &[u8]) -> Vec<u8> {
+        let mut image = vec![0u8; 20 * ISO_SECTOR];
+        image[510..512].copy_from_slice(&[0x55, 0xAA]);
+        let mbr = &mut image[446..462];
+        mbr[4] = 0x83;
+        mbr[12..16].copy_from_slice(&(20 * ISO_SECTOR as u32 / 512).to_le_bytes());
+
+        let pvd = &mut image[ISO_PVD_OFFSET..ISO_PVD_OFFSET + ISO_SECTOR];
+        pvd[0] = 1;
+        pvd[1..6].copy_from_slice(b"CD001");
+        pvd[156 + 2..156 + 6].copy_from_slice(&18u32.to_le_bytes());
+        pvd[156 + 10..156 + 14].copy_from_slice(&(ISO_SECTOR as u32).to_le_bytes());
+
+        // "." ואחריו התיקייה
+        let root = &mut image[18 * ISO_SECTOR..19 * ISO_SECTOR];
+        root[0] = 34;
+        root[25] = 0x02;
+        root[32] = 1;
+        let record = &mut root[34..];
+        record[0] = 33 + dir_name.len() as u8;
+        record[25] = 0x02;
+        record[32] = dir_name.len() as u8;
+        record[33..33 + dir_name.len()].copy_from_slice(dir_name);
+        image
+    }
+
+    #[test]
+    fn new_partition_follows_the_image() {
+        const MB: u64 = 1024 * 1024;
+        let burn = TempBurn::with_data("persistence", live_image(b"CASPER"));
+        assert_eq!(live_flavor(&burn.source), Some(LiveFlavor::Casper));
+        std::fs::write(&burn.source, live_image(b"LIVE")).unwrap();
+        assert_eq!(live_flavor(&burn.source), Some(LiveFlavor::LiveBoot));
+        std::fs::write(&burn.source, live_image(b"ISOLINUX")).unwrap();
+        assert_eq!(live_flavor(&burn.source), None);
+
+        // MBR: הרשומה הפנויה הבאה, מיושרת ל-MB אחרי התמונה
+        let image = live_image(b"CASPER");
+        std::fs::write(&burn.target, &image).unwrap();
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&burn.target)
+            .unwrap();
+        device.set_len(256 * MB).unwrap();
+        let added = partitions::append_partition(
+            &device,
+            256 * MB,
+            image.len() as u64,
+            128 * MB,
+            partitions::LINUX_FILESYSTEM,
+            "casper-rw",
+        )
+        .unwrap();
+        assert_eq!((added.index, added.start, added.size), (2, MB, 128 * MB));
+        let mut head = vec![0u8; partitions::TABLE_HEAD_LEN];
+        device.read_exact_at(&mut head, 0).unwrap();
+        let table = partitions::parse_partition_table(&head);
+        assert_eq!(table.len(), 2);
+        assert_eq!((table[1].kind.as_str(), table[1].start), ("Linux", MB));
+
+        // GPT: מחיצת נתונים על כל השאר נעצרת לפני ה-GPT המשני, שעובר לסוף ההתקן
+        let mut gpt = vec![0u8; 2 * MB as usize];
+        gpt[510..512].copy_from_slice(&[0x55, 0xAA]);
+        gpt[446 + 4] = 0xEE;
+        gpt[446 + 8..446 + 12].copy_from_slice(&1u32.to_le_bytes());
+        let header = &mut gpt[512..1024];
+        header[0..8].copy_from_slice(b"EFI PART");
+        header[12..16].copy_from_slice(&92u32.to_le_bytes());
+        header[72..80].copy_from_slice(&2u64.to_le_bytes());
+        header[80..84].copy_from_slice(&128u32.to_le_bytes());
+        header[84..88].copy_from_slice(&128u32.to_le_bytes());
+        let entry = &mut gpt[1024..1152];
+        entry[0] = 0x28;
+        entry[32..40].copy_from_slice(&2048u64.to_le_bytes());
+        entry[40..48].copy_from_slice(&4095u64.to_le_bytes());
+        device.set_len(0).unwrap();
+        device.write_all_at(&gpt, 0).unwrap();
+        device.set_len(256 * MB).unwrap();
+        let added = partitions::append_partition(
+            &device,
+            256 * MB,
+            gpt.len() as u64,
+            u64::MAX,
+            partitions::FAT32_DATA,
+            "DATA",
+        )
+        .unwrap();
+        let last_lba = 256 * MB / 512 - 1;
+        assert_eq!((added.index, added.start), (2, 2 * MB));
+        assert_eq!(added.start + added.size, (last_lba - 32) * 512);
+
+        let mut backup = [0u8; 92];
+        device.read_exact_at(&mut backup, last_lba * 512).unwrap();
+        assert_eq!(&backup[0..8], b"EFI PART");
+        let crc = u32::from_le_bytes(backup[16..20].try_into().unwrap());
+        backup[16..20].fill(0);
+        assert_eq!(crc, crc32fast::hash(&backup));
+        device.read_exact_at(&mut head, 0).unwrap();
+        let table = partitions::parse_partition_table(&head);
+        assert_eq!(table[1].kind, "Basic data");
+    }
+
+    // UDF כמו ב-ISO של מיקרוסופט: שורש בתוך extent עם bootmgr, sources מוטמע
+    // ב-File Entry, ו-install.wim שרק הגודל שלו נקרא
+    fn windows_image(wim_size: u64) -> Vec<u8> {
+        const PARTITION: usize = 270;
+        let mut image = vec![0u8; 280 * ISO_SECTOR];
+        let sector = |n: usize| n * ISO_SECTOR;
+        let tag = |image: &mut Vec<u8>, at: usize, id: u16| {
+            image[at..at + 2].copy_from_slice(&id.to_le_bytes());
+        };
+
+        let pvd = sector(16);
+        image[pvd] = 1;
+        image[pvd + 1..pvd + 6].copy_from_slice(b"CD001");
+        image[pvd + 40..pvd + 72].fill(b' ');
+        image[pvd + 40..pvd + 53].copy_from_slice(b"CCCOMA_X64FRE");
+
+        tag(&mut image, sector(256), 2);
+        image[sector(256) + 16..sector(256) + 20].copy_from_slice(&(3 * 2048u32).to_le_bytes());
+        image[sector(256) + 20..sector(256) + 24].copy_from_slice(&257u32.to_le_bytes());
+        tag(&mut image, sector(257), 5);
+        image[sector(257) + 188..sector(257) + 192]
+            .copy_from_slice(&(PARTITION as u32).to_le_bytes());
+        tag(&mut image, sector(258), 6);
+        tag(&mut image, sector(259), 8);
+        // File Set Descriptor ב-LBN 0, השורש ב-LBN 1
+        tag(&mut image, sector(PARTITION), 256);
+        image[sector(PARTITION) + 404..sector(PARTITION) + 408]
+            .copy_from_slice(&1u32.to_le_bytes());
+
+        let fid = |name: &str, icb: u32, characteristics: u8| {
+            let mut fid = vec![0u8; (38 + 1 + name.len() + 3) & !3];
+            fid[0..2].copy_from_slice(&257u16.to_le_bytes());
+            fid[18] = characteristics;
+            fid[19] = name.len() as u8 + 1;
+            fid[24..28].copy_from_slice(&icb.to_le_bytes());
+            fid[38] = 8;
+            fid[39..39 + name.len()].copy_from_slice(name.as_bytes());
+            fid
+        };
+        let file_entry = |image: &mut Vec<u8>, lbn: usize, flags: u16, size: u64, ads: &[u8]| {
+            let at = sector(PARTITION + lbn);
+            image[at..at + 2].copy_from_slice(&261u16.to_le_bytes());
+            image[at + 34..at + 36].copy_from_slice(&flags.to_le_bytes());
+            image[at + 56..at + 64].copy_from_slice(&size.to_le_bytes());
+            image[at + 172..at + 176].copy_from_slice(&(ads.len() as u32).to_le_bytes());
+            image[at + 176..at + 176 + ads.len()].copy_from_slice(ads);
+        };
+
+        let mut root = fid("", 1, 0x0A);
+        root.truncate(40);
+        root[19] = 0;
+        root.extend(fid("README.TXT", 5, 0));
+        root.extend(fid("bootmgr", 6, 0));
+        root.extend(fid("sources", 2, 0x02));
+        let mut short_ad = (root.len() as u32).to_le_bytes().to_vec();
+        short_ad.extend(4u32.to_le_bytes());
+        file_entry(&mut image, 1, 0, root.len() as u64, &short_ad);
+        let at = sector(PARTITION + 4);
+        image[at..at + root.len()].copy_from_slice(&root);
+
+        let sources = fid("install.wim", 3, 0);
+        file_entry(&mut image, 2, 3, sources.len() as u64, &sources);
+        file_entry(&mut image, 3, 0, wim_size, &[]);
+        file_entry(&mut image, 6, 0, 400 * 1024, &[]);
+        image
+    }
+
+    #[test]
+    fn windows_installer_is_found_in_the_udf_tree() {
+        const MB: u64 = 1024 * 1024;
+        let burn = TempBurn::with_data("windows", windows_image(5000 * MB));
+        let image = windows_installer(&burn.source).unwrap();
+        assert_eq!(image.name, "sources/install.wim");
+        assert!(image.needs_split());
+        std::fs::write(&burn.source, windows_image(3000 * MB)).unwrap();
+        assert!(!windows_installer(&burn.source).unwrap().needs_split());
+        std::fs::write(&burn.source, live_image(b"CASPER")).unwrap();
+        assert_eq!(windows_installer(&burn.source), None);
+        // sources/install.wim בלי bootmgr בשורש - ארכיון, לא מדיית התקנה
+        let mut archive = windows_image(MB);
+        let at = archive
+            .windows(7)
+            .position(|name| name == b"bootmgr")
+            .unwrap();
+        archive[at..at + 7].copy_from_slice(b"license");
+        std::fs::write(&burn.source, archive).unwrap();
+        assert_eq!(windows_installer(&burn.source), None);
+
+        let head = windows_image(MB);
+        assert_eq!(windows::fat_label(&head[ISO_PVD_OFFSET..]), "CCCOMA_X64F");
+        assert_eq!(windows::fat_label(&[]), "WINDOWS");
+
+        // כונן עם GPT ישן: MBR חדש עם מחיצה אחת bootable, וה-GPT המשני נמחק
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&burn.target)
+            .unwrap();
+        device.set_len(256 * MB).unwrap();
+        device.write_all_at(b"EFI PART", 256 * MB - 512).unwrap();
+        let added = partitions::write_fresh_table(
+            &device,
+            256 * MB,
+            PartitionScheme::Mbr,
+            &[(u64::MAX, partitions::FAT32_DATA)],
+        )
+        .unwrap();
+        assert_eq!(
+            (added[0].index, added[0].start, added[0].size),
+            (1, MB, 255 * MB)
+        );
+        let mut head = vec![0u8; partitions::TABLE_HEAD_LEN];
+        device.read_exact_at(&mut head, 0).unwrap();
+        let table = partitions::parse_partition_table(&head);
+        assert_eq!(table.len(), 1);
+        assert_eq!(head[446], 0x80);
+        let mut tail = [0u8; 8];
+        device.read_exact_at(&mut tail, 256 * MB - 512).unwrap();
+        assert_eq!(tail, [0; 8]);
+    }
+
+    #[test]
+    fn multiboot_menu_lists_every_iso() {
+        const MB: u64 = 1024 * 1024;
+        let burn = TempBurn::with_data("multiboot", Vec::new());
+        for name in ["ubuntu.iso", "Fedora.ISO", "notes.txt"] {
+            std::fs::write(burn.dir.join(name), b"").unwrap();
+        }
+        std::fs::create_dir(burn.dir.join("folder.iso")).unwrap();
+        let isos = multiboot::list_isos(&burn.dir).unwrap();
+        assert_eq!(isos, ["Fedora.ISO", "ubuntu.iso"]);
+
+        let config = multiboot::grub_config(&[r#"my "$HOME".iso"#.into(), "debian.iso".into()]);
+        assert_eq!(config.matches("menuentry ").count(), 2);
+        assert!(config.contains(r#"set iso_path="/my \"\$HOME\".iso""#));
+        assert!(config.contains("--label ISOS"));
+        assert_eq!(multiboot::grub_config(&[]).matches("menuentry ").count(), 0);
+
+        // מחיצת GRUB בגודל קבוע, ומחיצת ה-ISO על כל השאר
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&burn.target)
+            .unwrap();
+        device.set_len(1024 * MB).unwrap();
+        let created = partitions::write_fresh_table(
+            &device,
+            1024 * MB,
+            PartitionScheme::Mbr,
+            &[
+                (multiboot::BOOT_PARTITION_SIZE, partitions::FAT32_DATA),
+                (u64::MAX, partitions::EXFAT_DATA),
+            ],
+        )
+        .unwrap();
+        let layout: Vec<_> = created.iter().map(|p| (p.index, p.start, p.size)).collect();
+        assert_eq!(layout, [(1, MB, 256 * MB), (2, 257 * MB, 767 * MB)]);
+
+        // מזוהה רק אחרי mkfs עם התווית
+        assert!(!is_multiboot(&burn.target));
+        let mut sector = [0u8; 512];
+        sector[71..82].copy_from_slice(b"MULTIBOOT  ");
+        sector[82..90].copy_from_slice(b"FAT32   ");
+        device.write_all_at(&sector, MB).unwrap();
+        assert!(is_multiboot(&burn.target));
+    }
+
+    #[test]
+    fn restore_writes_a_gpt_spanning_the_drive() {
+        const MB: u64 = 1024 * 1024;
+        let burn = TempBurn::with_data("restore", live_image(b"CASPER"));
+        std::fs::copy(&burn.source, &burn.target).unwrap();
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&burn.target)
+            .unwrap();
+        device.set_len(512 * MB).unwrap();
+        let created = partitions::write_fresh_table(
+            &device,
+            512 * MB,
+            PartitionScheme::Gpt,
+            &[(u64::MAX, partitions::EXFAT_DATA)],
+        )
+        .unwrap();
+        let last_lba = 512 * MB / 512 - 1;
+        assert_eq!((created[0].index, created[0].start), (1, MB));
+        assert_eq!(created[0].start + created[0].size, (last_lba - 32) * 512);
+
+        let mut head = vec![0u8; partitions::TABLE_HEAD_LEN];
+        device.read_exact_at(&mut head, 0).unwrap();
+        let table = partitions::parse_partition_table(&head);
+        assert_eq!(table.len(), 1);
+        assert_eq!(table[0].kind, "Basic data");
+        // רשומה מגינה אחת על כל הדיסק, בלי דגל bootable
+        assert_eq!((head[446], head[446 + 4]), (0, 0xEE));
+        assert_eq!(
+            u32::from_le_bytes(head[446 + 12..446 + 16].try_into().unwrap()),
+            last_lba as u32
+        );
+        for lba in [1, last_lba] {
+            let mut header = [0u8; 92];
+            device.read_exact_at(&mut header, lba * 512).unwrap();
+            assert_eq!(&header[0..8], b"EFI PART");
+            let crc = u32::from_le_bytes(header[16..20].try_into().unwrap());
+            header[16..20].fill(0);
+            assert_eq!(crc, crc32fast::hash(&header));
+        }
+
+        assert_eq!(volume_label(" my stick ", false), "MY STICK");
+        assert_eq!(volume_label("backup/2026: photos", true), "backup2026 phot");
+        assert_eq!(volume_label("שלום", false), VOLUME_LABEL);
+        assert_eq!(volume_label("שלום", true), "שלום");
+        assert_eq!(volume_label("", true), VOLUME_LABEL);
+    }
+
+    #[test]
+    fn paranoid_writer_stops_at_a_chunk_that_changed_in_memory() {
+        let burn = TempBurn::with_data("paranoid", vec![0x5A; 4 * BUFFER_SIZE]);
+        let (event_tx, event_rx) = unbounded();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let read_stats = Arc::new(ReadStats::default());
+        let (data_rx, reader) = source::spawn_read_thread(
+            File::open(&burn.source).unwrap(),
+            BUFFER_SIZE,
+            true,
+            false,
+            cancel_flag.clone(),
+            read_stats.clone(),
+        );
+
+        // ביט שהתהפך ב-chunk השלישי אחרי שה-reader חישב את ה-CRC
+        let (tampered_tx, tampered_rx) = bounded(CHANNEL_DEPTH);
+        let tamper = thread::spawn(move || {
+            for (i, mut chunk) in data_rx.into_iter().enumerate() {
+                assert!(chunk.crc.is_some());
+                if i == 2 {
+                    chunk.crc = chunk.crc.map(|crc| crc ^ 1);
+                }
+                if tampered_tx.send(chunk).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut target = File::create(&burn.target).unwrap();
+        let end = write_chunks(
+            tampered_rx,
+            Some(reader),
+            &read_stats,
+            4 * BUFFER_SIZE as u64,
+            &mut target,
+            &WriteOptions::default(),
+            None,
+            &event_tx,
+            &cancel_flag,
+        );
+        tamper.join().unwrap();
+        assert!(matches!(end, StreamEnd::Failed));
+        let error = event_rx
+            .try_iter()
+            .find_map(|event| match event {
+                BurnEvent::Error(e) => Some(e),
+                _ => None,
+            })
+            .expect("an Error event");
+        assert_eq!(error.kind, ErrorKind::BufferCorrupted);
+        assert!(error.message.contains(&(2 * BUFFER_SIZE).to_string()));
+        // שני ה-chunks שלפני נכתבו, הפגום לא
+        assert_eq!(
+            std::fs::metadata(&burn.target).unwrap().len(),
+            2 * BUFFER_SIZE as u64
+        );
+    }
+
+    #[test]
+    fn format_fits_labels_and_cluster_sizes_to_each_mkfs() {
+        assert_eq!(Filesystem::Fat32.label("my stick"), "MY STICK");
+        assert_eq!(Filesystem::Ntfs.label("  Backup drive  "), "Backup drive");
+        // 16 בייט - 8 אותיות עבריות
+        assert_eq!(Filesystem::Ext4.label("גיבוי של התמונות"), "גיבוי של");
+        assert_eq!(Filesystem::Ext4.label(""), VOLUME_LABEL);
+
+        assert_eq!(
+            Filesystem::Fat32.mkfs_args("USB", Some(32768)).unwrap(),
+            ["-F", "32", "-n", "USB", "-s", "64"]
+        );
+        assert_eq!(
+            Filesystem::Ntfs.mkfs_args("USB", Some(4096)).unwrap(),
+            ["-Q", "-L", "USB", "-c", "4096"]
+        );
+        assert_eq!(
+            Filesystem::Exfat.mkfs_args("USB", None).unwrap(),
+            ["-L", "USB"]
+        );
+        assert!(Filesystem::Ext4.mkfs_args("USB", Some(65536)).is_err());
+
+        let mounts = "/dev/sdb1 /media/me/My\\040Stick vfat rw 0 0\n\
+                      /dev/sdbc1 /mnt/other ext4 rw 0 0\n\
+                      /dev/nvme0n1p2 / ext4 rw 0 0\n";
+        assert_eq!(
+            mount::parse_mounts(mounts, Path::new("/dev/sdb")),
+            [(
+                PathBuf::from("/dev/sdb1"),
+                PathBuf::from("/media/me/My Stick")
+            )]
+        );
+        assert!(mount::parse_mounts(mounts, Path::new("/dev/nvme0n1")).len() == 1);
+        assert!(mount::parse_mounts(mounts, Path::new("/dev/nvme0")).is_empty());
+    }
+
+    #[test]
+    fn destructive_tests_refuse_the_system_disk() {
+        let mounts = "/dev/nvme0n1p2 / btrfs rw 0 0\n\
+                      /dev/nvme0n1p1 /boot/efi vfat rw 0 0\n\
+                      /dev/sdb1 /media/me/STICK vfat rw 0 0\n\
+                      /dev/sdc1 /home/me/backup ext4 rw 0 0\n";
+        let swaps = "Filename\t\t\t\tType\t\tSize\tUsed\tPriority\n\
+                     /dev/sdd2                               partition\t8388604\t0\t-2\n";
+        let reason = |device: &str| mount::system_use_in(mounts, swaps, Path::new(device));
+
+        assert_eq!(
+            reason("/dev/nvme0n1").as_deref(),
+            Some("/dev/nvme0n1p2 is mounted at /")
+        );
+        assert_eq!(
+            reason("/dev/sdd").as_deref(),
+            Some("/dev/sdd2 is in use as swap")
+        );
+        // מקל מעוגן, או כונן גיבוי מתחת ל-home - לא המערכת עצמה
+        assert_eq!(reason("/dev/sdb"), None);
+        assert_eq!(reason("/dev/sdc"), None);
+    }
+
+    #[test]
+    fn safe_mode_recognizes_whole_drive_filesystems() {
+        let mut head = vec![0u8; FILESYSTEM_PROBE_LEN];
+        assert_eq!(existing_filesystem(&head), None);
+        head[1080..1082].copy_from_slice(&[0x53, 0xEF]);
+        assert_eq!(existing_filesystem(&head), Some("ext4"));
+
+        for (offset, magic, name) in [
+            (82, &b"FAT32   "[..], "FAT32"),
+            (3, &b"NTFS    "[..], "NTFS"),
+            (3, &b"EXFAT   "[..], "exFAT"),
+        ] {
+            let mut head = vec![0u8; FILESYSTEM_PROBE_LEN];
+            head[offset..offset + magic.len()].copy_from_slice(magic);
+            assert_eq!(existing_filesystem(&head), Some(name));
+        }
+
+        // ISO שנצרב קודם, או כונן עם טבלת מחיצות - ההתחלה לבדה לא נחסמת
+        let mut head = vec![0u8; FILESYSTEM_PROBE_LEN];
+        head[510..512].copy_from_slice(&[0x55, 0xAA]);
+        assert_eq!(existing_filesystem(&head), None);
+        assert_eq!(existing_filesystem(&head[..100]), None);
+    }
+
+    #[test]
+    fn filesystem_probe_looks_inside_partitions() {
+        const MB: u64 = 1024 * 1024;
+        let burn = TempBurn::with_data("fs-partitions", Vec::new());
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&burn.target)
+            .unwrap();
+        device.set_len(256 * MB).unwrap();
+        let created = partitions::write_fresh_table(
+            &device,
+            256 * MB,
+            PartitionScheme::Gpt,
+            &[
+                (128 * MB, partitions::FAT32_DATA),
+                (u64::MAX, partitions::LINUX_FILESYSTEM),
+            ],
+        )
+        .unwrap();
+
+        // טבלה עם מחיצות ריקות - כמו ISO שנצרב קודם
+        assert_eq!(filesystem_on_device(&device), None);
+
+        // מערכת קבצים במחיצה השנייה, לא בתחילת הכונן
+        device
+            .write_all_at(&[0x53, 0xEF], created[1].start + 1080)
+            .unwrap();
+        assert_eq!(filesystem_on_device(&device), Some("ext4"));
+        device
+            .write_all_at(&[0; 2], created[1].start + 1080)
+            .unwrap();
+        device.write_all_at(b"NTFS", created[0].start + 3).unwrap();
+        assert_eq!(filesystem_on_device(&device), Some("NTFS"));
+    }
+
+    // זיכרון מזויף: קובץ קטן שכל כתובת בו היא המיקום modulo הגודל האמיתי
+    struct WrappingStick {
+        file: File,
+        real: u64,
+        position: u64,
+    }
+
+    impl Read for WrappingStick {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let at = self.position % self.real;
+            let n = (self.real - at).min(buf.len() as u64) as usize;
+            self.file.read_exact_at(&mut buf[..n], at)?;
+            self.position += n as u64;
+            Ok(n)
+        }
+    }
+
+    impl Write for WrappingStick {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let at = self.position % self.real;
+            let n = (self.real - at).min(buf.len() as u64) as usize;
+            self.file.write_all_at(&buf[..n], at)?;
+            self.position += n as u64;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn capacity_test_finds_where_a_fake_stick_wraps() {
+        let path = std::env::temp_dir().join(format!("burn-wrap-{}", std::process::id()));
+        let (event_tx, _event_rx) = unbounded();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let seed = 0x5eed_1234_abcd_0042;
+        let advertised = 8 * CAPACITY_BLOCK as u64;
+
+        // גודל אמיתי שלא מתיישר לבלוק, ואחד שגדול מחצי המוצהר
+        for real in [
+            3 * CAPACITY_BLOCK as u64 + 5 * SURFACE_SECTOR,
+            5 * CAPACITY_BLOCK as u64,
+        ] {
+            let file = File::create(&path).unwrap();
+            file.set_len(real).unwrap();
+            let mut stick = WrappingStick {
+                file: OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .open(&path)
+                    .unwrap(),
+                real,
+                position: 0,
+            };
+            let mut pattern = SurfacePattern {
+                seed,
+                position: 0,
+                total: advertised,
+            };
+            std::io::copy(&mut pattern, &mut stick).unwrap();
+
+            // ההתחלה נדרסה בתבנית של LBA מאוחר יותר - לא "0 בייטים תקינים"
+            stick.position = 0;
+            assert_eq!(
+                read_back_capacity(&mut stick, advertised, seed, &event_tx, &cancel_flag),
+                Some((real, Some(real)))
+            );
+        }
+
+        // כונן אמיתי: הכל חוזר ואין עטיפה
+        let file = File::create(&path).unwrap();
+        file.set_len(advertised).unwrap();
+        let mut stick = WrappingStick {
+            file: OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&path)
+                .unwrap(),
+            real: advertised,
+            position: 0,
+        };
+        let mut pattern = SurfacePattern {
+            seed,
+            position: 0,
+            total: advertised,
+        };
+        std::io::copy(&mut pattern, &mut stick).unwrap();
+        stick.position = 0;
+        assert_eq!(
+            read_back_capacity(&mut stick, advertised, seed, &event_tx, &cancel_flag),
+            Some((advertised, None))
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn watchdog_fires_on_a_hung_write() {
+        let (event_tx, event_rx) = unbounded();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let watchdog = Watchdog::start(
+            Some(Duration::from_secs(DEFAULT_WRITE_TIMEOUT_SECS)),
+            None,
+            &event_tx,
+            &cancel_flag,
+        );
+
+        // כתיבה שחזרה לא נמדדת יותר
+        watchdog.beat();
+        watchdog.idle();
+        // והכתיבה הבאה תקועה 35 שניות
+        watchdog.beat();
+        thread::sleep(Duration::from_secs(35));
+        match event_rx.recv_timeout(WATCHDOG_INTERVAL) {
+            Ok(BurnEvent::Error(e)) => {
+                assert_eq!(e.kind, ErrorKind::WriteTimeout);
+                assert_eq!(
+                    e.detail(),
+                    Some(&ErrorDetail::WriteTimeout {
+                        stalled_for_secs: DEFAULT_WRITE_TIMEOUT_SECS
+                    })
+                );
+            }
+            other => panic!("{:?}", other),
+        }
+        assert!(cancel_flag.load(Ordering::Relaxed));
+        assert!(matches!(
+            watchdog.outcome(StreamEnd::Cancelled),
+            StreamEnd::Failed
+        ));
+    }
+
+    #[test]
+    fn watchdog_aborts_when_the_device_is_renumbered() {
+        // שני התקני תו עם צמתים שונים במקום sdb ו-sdc
+        let dir = std::env::temp_dir().join(format!("burn-renumber-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let link = dir.join("usb-Stick_0123-0:0");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink("/dev/null", &link).unwrap();
+        let device = OpenOptions::new().write(true).open("/dev/null").unwrap();
+        let other = OpenOptions::new().write(true).open("/dev/zero").unwrap();
+        assert!(PinnedDevice::new(&link, &other).is_none());
+        let pinned = PinnedDevice::new(&link, &device).unwrap();
+
+        let (event_tx, event_rx) = unbounded();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let watchdog = Watchdog::start(None, Some(pinned), &event_tx, &cancel_flag);
+        assert!(event_rx
+            .recv_timeout(WATCHDOG_INTERVAL + Duration::from_secs(1))
+            .is_err());
+
+        std::fs::remove_file(&link).unwrap();
+        std::os::unix::fs::symlink("/dev/zero", &link).unwrap();
+        match event_rx.recv_timeout(WATCHDOG_INTERVAL * 2) {
+            Ok(BurnEvent::Error(e)) => assert_eq!(e.kind, ErrorKind::DeviceChanged),
+            other => panic!("{:?}", other),
+        }
+        assert!(cancel_flag.load(Ordering::Relaxed));
+        assert!(matches!(
+            watchdog.outcome(StreamEnd::Cancelled),
+            StreamEnd::Failed
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn burn_events_arrive_in_order() {
+        // התקרה מאריכה את הכתיבה מעבר ל-100ms, כדי שיהיה לפחות Progress אחד
+        let burn = TempBurn::new("event-order", 16 * 1024 * 1024);
+        let engine = BurnEngine::new();
+        let id = engine.start(BurnConfig {
+            max_speed_mbps: Some(40.0),
+            ..burn.config()
+        });
+
+        let events = drain(&engine.events(), |_| {});
+        assert!(
+            matches!(events[0], BurnEvent::JobQueued { id: queued, position: 0, .. } if queued == id),
+            "{:?}",
+            events
+        );
+        assert!(matches!(events[1], BurnEvent::JobStarted { id: started } if started == id));
+        assert!(matches!(events[2], BurnEvent::Preparing), "{:?}", events);
+        let progress = position(&events, "Progress", |e| {
+            matches!(e, BurnEvent::Progress { .. })
+        });
+        let summary = position(&events, "VerifySummary", |e| {
+            matches!(e, BurnEvent::VerifySummary { .. })
+        });
+        assert!(progress < summary);
+        assert!(matches!(
+            events[summary],
+            BurnEvent::VerifySummary { ok: true, .. }
+        ));
+        assert!(matches!(
+            events.last().unwrap(),
+            BurnEvent::Finished { verified: true, .. }
+        ));
+        // ה-written לא יורד לאורך הכתיבה
+        let written: Vec<u64> = events
+            .iter()
+            .filter_map(|e| match e {
+                BurnEvent::Progress { written, .. } => Some(*written),
+                _ => None,
+            })
+            .collect();
+        assert!(written.windows(2).all(|w| w[0] <= w[1]), "{:?}", written);
+    }
+
+    // יעד אחד שלא נפתח לא עוצר את השניים האחרים
+    #[test]
+    fn multi_burn_survives_a_failed_target() {
+        let burn = TempBurn::new("multi", BUFFER_SIZE * 2 + 12345);
+        let good = [burn.dir.join("a.img"), burn.dir.join("b.img")];
+        let bad = burn.dir.join("missing").join("c.img");
+        let engine = BurnEngine::new();
+        engine.multi_burn(MultiBurnConfig {
+            iso_path: burn.source.clone(),
+            device_paths: vec![good[0].clone(), bad.clone(), good[1].clone()],
+            verify: true,
+        });
+
+        let events = drain(&engine.events(), |_| {});
+        position(&events, "Target error", |e| {
+            matches!(e, BurnEvent::Target { device, event }
+                if *device == bad && matches!(**event, BurnEvent::Error(_)))
+        });
+        let BurnEvent::MultiFinished { targets, .. } = events.last().unwrap() else {
+            panic!("multi burn did not finish: {:?}", events.last());
+        };
+        let devices: Vec<&PathBuf> = targets.iter().map(|t| &t.device).collect();
+        assert_eq!(devices, [&good[0], &bad, &good[1]]);
+        assert!(targets[1].error.is_some());
+        for target in [&targets[0], &targets[2]] {
+            assert!(target.error.is_none(), "{:?}", target);
+            assert!(target.verified);
+            assert_eq!(target.bytes, burn.data.len() as u64);
+        }
+        for path in &good {
+            assert!(std::fs::read(path).unwrap() == burn.data);
+        }
+    }
+
+    // ביטול לא מדלג על הסיכום: כל יעד מקבל תוצאה משלו
+    #[test]
+    fn cancelled_multi_burn_still_reports_every_target() {
+        let burn = TempBurn::new("multi-cancel", BUFFER_SIZE * 4);
+        let targets = [burn.dir.join("a.img"), burn.dir.join("b.img")];
+        let (event_tx, event_rx) = unbounded();
+        run_multi_burn(
+            &MultiBurnConfig {
+                iso_path: burn.source.clone(),
+                device_paths: targets.to_vec(),
+                verify: true,
+            },
+            &event_tx,
+            &Arc::new(AtomicBool::new(true)),
+        );
+
+        let events = drain(&event_rx, |_| {});
+        let BurnEvent::MultiFinished {
+            targets: outcomes, ..
+        } = events.last().unwrap()
+        else {
+            panic!("multi burn did not finish: {:?}", events.last());
+        };
+        assert_eq!(outcomes.len(), targets.len());
+        for outcome in outcomes {
+            assert!(!outcome.verified);
+            assert_eq!(
+                outcome.error.as_ref().map(|e| e.kind),
+                Some(ErrorKind::Cancelled),
+                "{:?}",
+                outcome
+            );
+        }
+    }
+
+    #[test]
+    fn queued_jobs_run_one_after_another() {
+        let burn = TempBurn::new("queue", 4 * 1024 * 1024);
+        let engine = BurnEngine::new();
+        let first = engine.start(burn.config());
+        let second = engine.start(burn.config());
+        let third = engine.start(burn.config());
+        // השלישית עוד מחכה, אז היא פשוט יוצאת מהתור
+        engine.cancel_job(third);
+
+        let events = engine.events();
+        let mut seen = Vec::new();
+        loop {
+            let event = events.recv_timeout(Duration::from_secs(30)).unwrap();
+            let done = matches!(event, BurnEvent::JobEnded { id, .. } if id == second);
+            seen.push(event);
+            if done {
+                break;
+            }
+        }
+
+        let positions: Vec<(u64, usize)> = seen
+            .iter()
+            .filter_map(|e| match e {
+                BurnEvent::JobQueued { id, position, .. } => Some((*id, *position)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(positions, [(first, 0), (second, 1), (third, 2)]);
+        let first_ended = position(
+            &seen,
+            "first JobEnded",
+            |e| matches!(e, BurnEvent::JobEnded { id, state: JobState::Done } if *id == first),
+        );
+        let second_started = position(
+            &seen,
+            "second JobStarted",
+            |e| matches!(e, BurnEvent::JobStarted { id } if *id == second),
+        );
+        assert!(first_ended < second_started);
+        assert!(!seen
+            .iter()
+            .any(|e| matches!(e, BurnEvent::JobStarted { id } if *id == third)));
+
+        engine.queue_status();
+        let states = loop {
+            if let BurnEvent::QueueStatus { jobs } =
+                events.recv_timeout(Duration::from_secs(30)).unwrap()
+            {
+                break jobs.into_iter().map(|job| job.state).collect::<Vec<_>>();
+            }
+        };
+        assert_eq!(
+            states,
+            [JobState::Done, JobState::Done, JobState::Cancelled]
+        );
+    }
+
+    // hash הרקע מפנה את מקומו לצריבה, ובדיקת checksum אחריו לא קוראת את הקובץ שוב
+    #[test]
+    fn background_hash_yields_to_a_burn_and_is_reused() {
+        let burn = TempBurn::new("background-hash", 32 * 1024 * 1024);
+        let engine = BurnEngine::new();
+        let events = engine.events();
+        let until_ended = |job: u64| {
+            let mut seen = Vec::new();
+            loop {
+                let event = events.recv_timeout(Duration::from_secs(30)).unwrap();
+                let done = matches!(event, BurnEvent::JobEnded { id, .. } if id == job);
+                seen.push(event);
+                if done {
+                    return seen;
+                }
+            }
+        };
+
+        let hash = engine.hash_image(burn.source.clone());
+        let start = engine.start(burn.config());
+        let seen = until_ended(start);
+        assert!(seen.iter().any(
+            |e| matches!(e, BurnEvent::JobEnded { id, state: JobState::Cancelled } if *id == hash)
+        ));
+        assert!(seen.iter().any(
+            |e| matches!(e, BurnEvent::JobEnded { id, state: JobState::Done } if *id == start)
+        ));
+        assert!(!seen
+            .iter()
+            .any(|e| matches!(e, BurnEvent::ImageHashed { .. })));
+
+        let hash = engine.hash_image(burn.source.clone());
+        let digest = until_ended(hash)
+            .into_iter()
+            .find_map(|e| match e {
+                BurnEvent::ImageHashed { path, digest, .. } if path == burn.source => Some(digest),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(digest, hex(&<Sha256 as sha2::Digest>::digest(&burn.data)));
+
+        // תוכן אחר באותו גודל ובאותו זמן שינוי: רק ה-digest השמור עוד מתאים לו
+        let modified = std::fs::metadata(&burn.source).unwrap().modified().unwrap();
+        std::fs::write(&burn.source, vec![0u8; burn.data.len()]).unwrap();
+        File::options()
+            .write(true)
+            .open(&burn.source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let check = engine.verify_checksum(burn.source.clone(), digest, HashAlgorithm::Sha256);
+        assert!(until_ended(check)
+            .iter()
+            .any(|e| matches!(e, BurnEvent::ChecksumChecked { matches: true, .. })));
+    }
+
+    #[test]
+    fn cancel_stops_the_burn() {
+        let burn = TempBurn::new("cancel", 64 * 1024 * 1024);
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            max_speed_mbps: Some(20.0),
+            ..burn.config()
+        });
+
+        let events = drain(&engine.events(), |event| {
+            if matches!(event, BurnEvent::Progress { .. }) {
+                engine.cancel();
+            }
+        });
+        assert!(
+            matches!(events.last().unwrap(), BurnEvent::Cancelled),
+            "{:?}",
+            events
+        );
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, BurnEvent::VerifySummary { .. })));
+        let written = std::fs::metadata(&burn.target).unwrap().len();
+        assert!(written < burn.data.len() as u64, "wrote {} bytes", written);
+    }
+
+    // ביטול עם wipe_on_cancel: ה-MB הראשון אפסים, עם Wiping עד ה-Cancelled, והשאר כמו שנכתב
+    #[test]
+    fn wipe_on_cancel_zeroes_the_partial_image_head() {
+        let burn = TempBurn::new("wipe-on-cancel", 64 * 1024 * 1024);
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            max_speed_mbps: Some(20.0),
+            wipe_on_cancel: true,
+            ..burn.config()
+        });
+
+        let events = drain(&engine.events(), |event| {
+            if matches!(event, BurnEvent::Progress { .. }) {
+                engine.cancel();
+            }
+        });
+        let total = CANCEL_WIPE_BYTES;
+        assert!(
+            matches!(
+                &events[events.len() - 3..],
+                [
+                    BurnEvent::Wiping { wiped: 0, .. },
+                    BurnEvent::Wiping { wiped, total: t },
+                    BurnEvent::Cancelled,
+                ] if *wiped == total && *t == total
+            ),
+            "{:?}",
+            events
+        );
+
+        let target = std::fs::read(&burn.target).unwrap();
+        assert!(target.len() < burn.data.len());
+        let head = total as usize;
+        assert!(target[..head].iter().all(|&b| b == 0));
+        if target.len() > head {
+            assert!(target[head..] == burn.data[head..target.len()]);
+        }
+    }
+
+    // התקן כמעט ריק -> קובץ דחוס קטן -> צריבה בחזרה עם בדיקה -> אותם בייטים בדיוק
+    #[test]
+    fn compressed_clone_round_trips() {
+        let mut data = vec![0u8; 32 * 1024 * 1024];
+        let mut state = 11;
+        for byte in &mut data[10 * 1024 * 1024..12 * 1024 * 1024] {
+            *byte = splitmix64(&mut state) as u8;
+        }
+        let burn = TempBurn::with_data("compressed-clone", data);
+
+        for ext in ["gz", "xz", "zst", "bz2"] {
+            let image_path = burn.dir.join(format!("clone.img.{}", ext));
+            let engine = BurnEngine::new();
+            engine.clone_to_image(CloneConfig {
+                device_path: burn.source.clone(),
+                target_path: image_path.clone(),
+                on_read_error: ReadErrorPolicy::Abort,
+                compression_level: Some(1),
+                verify: false,
+                unmount: false,
+            });
+            let events = drain(&engine.events(), |_| {});
+            assert!(
+                matches!(events.last().unwrap(), BurnEvent::Finished { bytes, .. } if *bytes == burn.data.len() as u64),
+                "{}: {:?}",
+                ext,
+                events.last()
+            );
+            // 2MB אקראיים לא נדחסים, 30MB האפסים כמעט נעלמים
+            let compressed = std::fs::metadata(&image_path).unwrap().len();
+            assert!(
+                compressed < 3 * 1024 * 1024,
+                "{}: {} bytes",
+                ext,
+                compressed
+            );
+
+            let _ = std::fs::remove_file(&burn.target);
+            engine.start(BurnConfig {
+                iso_path: image_path,
+                ..burn.config()
+            });
+            let events = drain(&engine.events(), |_| {});
+            assert!(
+                matches!(
+                    events.last().unwrap(),
+                    BurnEvent::Finished { verified: true, .. }
+                ),
+                "{}: {:?}",
+                ext,
+                events.last()
+            );
+            let target = std::fs::read(&burn.target).unwrap();
+            assert!(target == burn.data, "{}: round trip changed the data", ext);
+        }
+    }
+
+    // Start עם DeviceToFile: הקובץ "ההתקן" נקרא ונשמר ל-.img, וההתקדמות היא מתוך גודל ההתקן
+    #[test]
+    fn backup_direction_reads_the_device_into_a_file() {
+        let burn = TempBurn::new("backup", 3 * 1024 * 1024 + 512);
+        let output_path = burn.dir.join("backup.img");
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            device_path: burn.source.clone(),
+            direction: BurnDirection::DeviceToFile {
+                output_path: output_path.clone(),
+            },
+            ..Default::default()
+        });
+        let events = drain(&engine.events(), |event| {
+            if let BurnEvent::Progress { total, .. } = event {
+                assert_eq!(*total, burn.data.len() as u64);
+            }
+        });
+        assert!(
+            matches!(events.last().unwrap(), BurnEvent::Finished { bytes, .. } if *bytes == burn.data.len() as u64),
+            "{:?}",
+            events.last()
+        );
+        assert!(std::fs::read(&output_path).unwrap() == burn.data);
+    }
+
+    // אחרי Finished מגיע דוח אחד לצריבה, ולא לגיבוי או לבדיקה
+    #[test]
+    fn burn_ends_with_a_report() {
+        let burn = TempBurn::new("report", 2 * 1024 * 1024);
+        let engine = BurnEngine::new();
+        engine.start(burn.config());
+        drain(&engine.events(), |_| {});
+        let report = loop {
+            match engine
+                .events()
+                .recv_timeout(Duration::from_secs(30))
+                .unwrap()
+            {
+                BurnEvent::Report(report) => break report,
+                BurnEvent::JobEnded { .. } => panic!("JobEnded before Report"),
+                _ => {}
+            }
+        };
+        assert_eq!(report.outcome, BurnOutcome::Completed);
+        assert_eq!(report.image, burn.source);
+        assert_eq!(report.bytes_written, burn.data.len() as u64);
+        assert_eq!(report.verified, Some(true));
+        assert_eq!(report.bytes_verified, burn.data.len() as u64);
+        assert!(report.error.is_none());
+        // יעד שהוא קובץ רגיל - אין לו רשומה ב-/sys/block
+        assert!(report.device_model.is_none());
+
+        let session = engine.last_session().unwrap();
+        assert_eq!(session.outcome, Some(BurnOutcome::Completed));
+        assert_eq!(session.bytes_verified, burn.data.len() as u64);
+        assert_eq!(session.config.device_path, burn.target);
+        // הרשומה מה-session היא אותה רשומה שנשלחה, חוץ מהזמן שעבר מאז
+        assert_eq!(session.report().timestamp, report.timestamp);
+        assert_eq!(session.report().verified, report.verified);
+    }
+
+    // .iso.gz נפתח לפי הסיומת, נצרב ונבדק. total בהתקדמות ובהשוואת הבייטים הוא הגודל
+    // הפתוח מה-trailer
+    #[test]
+    fn gzip_source_burns_with_its_uncompressed_total() {
+        let burn = TempBurn::with_data("gzip-source", vec![0u8; 10 * 1024 * 1024]);
+        let gz_path = burn.dir.join("zeros.iso.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&gz_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(&burn.data).unwrap();
+        encoder.finish().unwrap();
+
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            iso_path: gz_path,
+            // אפסים ולא ISO 9660 - רק הדחיסה נבדקת כאן
+            validate_iso: false,
+            byte_compare: true,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |event| {
+            if let BurnEvent::Progress { total, .. } | BurnEvent::Verifying { total, .. } = event {
+                assert_eq!(*total, burn.data.len() as u64);
+            }
+        });
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, BurnEvent::Verifying { .. })));
+        assert!(
+            matches!(events.last().unwrap(), BurnEvent::Finished { bytes, verified: true, .. } if *bytes == burn.data.len() as u64),
+            "{:?}",
+            events.last()
+        );
+        assert!(std::fs::read(&burn.target).unwrap() == burn.data);
+    }
+
+    // .iso נבדק לפני שנוגעים בכונן: PVD תקין נצרב, קובץ קטוע או לא-ISO נעצר עם InvalidIso
+    #[test]
+    fn iso_headers_are_validated_before_the_device_is_opened() {
+        let mut iso = vec![0u8; 4 * 1024 * 1024];
+        iso[ISO_PVD_OFFSET] = 1;
+        iso[ISO_PVD_OFFSET + 1..ISO_PVD_OFFSET + 6].copy_from_slice(b"CD001");
+        let burn = TempBurn::with_data("validate-iso", iso);
+        let iso_path = burn.dir.join("image.iso");
+        let run = |data: &[u8]| {
+            std::fs::write(&iso_path, data).unwrap();
+            let _ = std::fs::remove_file(&burn.target);
+            let engine = BurnEngine::new();
+            engine.start(BurnConfig {
+                iso_path: iso_path.clone(),
+                ..burn.config()
+            });
+            drain(&engine.events(), |_| {})
+        };
+        let rejected = |events: Vec<BurnEvent>, reason: &str| {
+            match events.last().unwrap() {
+                BurnEvent::Error(e) => {
+                    assert_eq!(e.kind, ErrorKind::InvalidIso);
+                    assert_eq!(e.message, reason);
+                }
+                other => panic!("{}: {:?}", reason, other),
+            }
+            assert!(!burn.target.exists(), "{}", reason);
+        };
+
+        let events = run(&burn.data);
+        assert!(
+            matches!(
+                events.last().unwrap(),
+                BurnEvent::Finished { verified: true, .. }
+            ),
+            "{:?}",
+            events.last()
+        );
+        assert!(std::fs::read(&burn.target).unwrap() == burn.data);
+
+        // ההורדה נקטעה באמצע ה-PVD
+        rejected(
+            run(&burn.data[..ISO_PVD_OFFSET + 100]),
+            &format!(
+                "Image is too small to be an ISO ({} bytes)",
+                ISO_PVD_OFFSET + 100
+            ),
+        );
+        // תמונה גולמית ששמה .iso
+        rejected(
+            run(&TempBurn::new("validate-raw", 64 * 1024).data),
+            "No ISO 9660 primary volume descriptor at sector 16",
+        );
+        // Boot Record בסקטור 17 שאינו El Torito
+        let mut bad_boot = burn.data.clone();
+        bad_boot[ISO_BOOT_RECORD_OFFSET + 1..ISO_BOOT_RECORD_OFFSET + 6].copy_from_slice(b"CD001");
+        bad_boot[ISO_BOOT_RECORD_OFFSET + 7..ISO_BOOT_RECORD_OFFSET + 14]
+            .copy_from_slice(b"GARBAGE");
+        rejected(
+            run(&bad_boot),
+            "Boot record at sector 17 is not a valid El Torito record",
+        );
+    }
+
+    // 10MB אפסים נדחסים לכמה KB - הכונן נמדד מול הגודל הפתוח, לא מול הקובץ
+    #[test]
+    fn compressed_source_is_checked_against_its_uncompressed_size() {
+        let burn = TempBurn::with_data("compressed-capacity", vec![0u8; 10 * 1024 * 1024]);
+        let gz_path = burn.dir.join("zeros.img.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&gz_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(&burn.data).unwrap();
+        encoder.finish().unwrap();
+        let small = 4 * 1024 * 1024;
+        File::create(&burn.target).unwrap().set_len(small).unwrap();
+
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            iso_path: gz_path,
+            validate_iso: false,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |_| {});
+        match events.last().unwrap() {
+            BurnEvent::Error(e) => assert_eq!(
+                e.detail(),
+                Some(&ErrorDetail::DeviceTooSmall {
+                    needed: burn.data.len() as u64,
+                    available: small
+                })
+            ),
+            other => panic!("{:?}", other),
+        }
+
+        // bzip2 לא שומר את הגודל: אזהרה, והצריבה ממשיכה כרגיל
+        let bz2_path = burn.dir.join("zeros.img.bz2");
+        let mut encoder = bzip2::write::BzEncoder::new(
+            File::create(&bz2_path).unwrap(),
+            bzip2::Compression::fast(),
+        );
+        encoder.write_all(&burn.data).unwrap();
+        encoder.finish().unwrap();
+        File::create(&burn.target).unwrap();
+        let engine = BurnEngine::new();
+        engine.start(BurnConfig {
+            iso_path: bz2_path,
+            validate_iso: false,
+            ..burn.config()
+        });
+        let events = drain(&engine.events(), |_| {});
+        assert!(events.iter().any(
+            |event| matches!(event, BurnEvent::Warning { message } if message == UNKNOWN_SIZE_WARNING)
+        ));
+        assert!(
+            matches!(events.last().unwrap(), BurnEvent::Finished { bytes, .. } if *bytes == burn.data.len() as u64),
+            "{:?}",
+            events.last()
+        );
+    }
+
+    #[test]
+    fn uncompressed_size_comes_from_the_trailer_or_index() {
+        const GIB: u64 = 1 << 30;
+        assert_eq!(gzip_size(700 << 20, 300 << 20), 700 << 20);
+        // 5GiB שנדחסו ל-2GiB: ISIZE הוא רק 1GiB
+        assert_eq!(gzip_size(GIB as u32, 2 * GIB), 5 * GIB);
+
+        // שני streams עם ריפוד ביניהם, כמו אחרי cat של שני קבצי xz
+        let burn = TempBurn::new("xz-size", 3 * 1024 * 1024);
+        let xz = |data: &[u8]| {
+            let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 1);
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        };
+        let mut joined = xz(&burn.data);
+        joined.extend([0u8; 8]);
+        joined.extend(xz(&burn.data[..12345]));
+        let xz_path = burn.dir.join("joined.img.xz");
+        std::fs::write(&xz_path, &joined).unwrap();
+        assert_eq!(
+            uncompressed_size(&xz_path, Compression::Xz),
+            Some(burn.data.len() as u64 + 12345)
+        );
+        // zstd: frame אחד עם checksum, ועוד frame אחרי skippable frame, כמו ש-pzstd כותב
+        let mut zst = zstd::stream::Encoder::new(Vec::new(), 1).unwrap();
+        zst.include_checksum(true).unwrap();
+        zst.set_pledged_src_size(Some(burn.data.len() as u64))
+            .unwrap();
+        zst.write_all(&burn.data).unwrap();
+        let mut joined = zst.finish().unwrap();
+        joined.extend(0x184D_2A50u32.to_le_bytes());
+        joined.extend(4u32.to_le_bytes());
+        joined.extend([0xAA; 4]);
+        joined.extend(zstd::bulk::compress(&burn.data[..12345], 1).unwrap());
+        let zst_path = burn.dir.join("joined.img.zst");
+        std::fs::write(&zst_path, &joined).unwrap();
+        assert_eq!(
+            uncompressed_size(&zst_path, Compression::Zstd),
+            Some(burn.data.len() as u64 + 12345)
+        );
+        // stream בלי גודל ידוע מראש
+        let mut zst = zstd::stream::Encoder::new(Vec::new(), 1).unwrap();
+        zst.write_all(&burn.data).unwrap();
+        std::fs::write(&zst_path, zst.finish().unwrap()).unwrap();
+        assert_eq!(uncompressed_size(&zst_path, Compression::Zstd), None);
+        // לא xz בכלל: אין "YZ" בסוף
+        assert_eq!(uncompressed_size(&burn.source, Compression::Xz), None);
+        assert_eq!(uncompressed_size(&burn.source, Compression::Zstd), None);
+    }
+
+    // ה-magic גובר על הסיומת, והסיומת נשארת רק כשאין magic. תמונה גולמית לא נפתחת
+    // גם אם היא מתחילה כמו gzip
+    #[test]
+    fn compression_follows_the_magic_bytes_before_the_extension() {
+        let xz_magic = [0xFD, b'7', b'z', b'X', b'Z', 0x00];
+        let detect = |name: &str, magic: &[u8]| detect_compression(Path::new(name), magic);
+        assert_eq!(detect("image.gz", &xz_magic), Compression::Xz);
+        assert_eq!(detect("image", b"BZh91AY"), Compression::Bzip2);
+        assert_eq!(detect("backup.img.zst", &[]), Compression::Zstd);
+        assert_eq!(detect("image.gz", b"plain"), Compression::Gzip);
+        for raw in ["disk.img", "fw.BIN", "dump.raw", "live.iso"] {
+            assert_eq!(
+                detect(raw, &[0x1F, 0x8B, 0x08]),
+                Compression::None,
+                "{}",
+                raw
+            );
+            assert_eq!(detect(raw, b"BZh9"), Compression::None, "{}", raw);
+        }
+    }
+
+    // יעד לקריאה בלבד: כל כתיבה חוזרת מיד עם EBADF, באותה אצווה עם הקריאה הבאה. ה-CQEs
+    // שאחרי הכשל כבר יצאו מהטבעת - אם הם לא יורדים מהמונים, ההמתנה בסוף לא נגמרת
+    #[test]
+    fn uring_write_error_mid_batch_fails_instead_of_hanging() {
+        if IoUring::new(2).is_err() {
+            eprintln!("io_uring unavailable here, skipped");
+            return;
+        }
+        let burn = TempBurn::new("uring-fail", 4 * BUFFER_SIZE);
+        std::fs::write(&burn.target, vec![0u8; burn.data.len()]).unwrap();
+        let source = File::open(&burn.source).unwrap();
+        let device = File::open(&burn.target).unwrap();
+        let total = burn.data.len() as u64;
+
+        let (event_tx, events) = unbounded();
+        let (end_tx, end_rx) = bounded(1);
+        thread::spawn(move || {
+            let end = write_stream_uring(
+                &source,
+                total,
+                &device,
+                0,
+                &WriteOptions::default(),
+                None,
+                &event_tx,
+                &Arc::new(AtomicBool::new(false)),
+            );
+            let _ = end_tx.send(end);
+        });
+        let end = end_rx
+            .recv_timeout(Duration::from_secs(10))
+            .expect("write_stream_uring hung after a failed write");
+        assert!(matches!(end, StreamEnd::Failed));
+        assert!(events.try_iter().any(|event| matches!(
+            event,
+            BurnEvent::Error(BurnError {
+                kind: ErrorKind::Io,
+                ..
+            })
+        )));
+    }
 }
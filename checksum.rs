@@ -0,0 +1,92 @@
+use crate::burn_engine::HashAlgorithm;
+use std::path::{Path, PathBuf};
+
+// שמות נפוצים של קבצי checksum שהפצות מפרסמות ליד ה-ISO
+const CHECKSUM_FILE_NAMES: &[&str] = &[
+    "SHA256SUMS",
+    "sha256sum.txt",
+    "SHA256SUMS.txt",
+    "SHA512SUMS",
+    "sha512sum.txt",
+    "SHA512SUMS.txt",
+    // הפצות ישנות וכלי קושחה מפרסמים רק את אלה
+    "SHA1SUMS",
+    "sha1sum.txt",
+    "MD5SUMS",
+    "md5sum.txt",
+];
+
+pub fn find_checksum_file(iso: &Path) -> Option<PathBuf> {
+    for ext in [".sha256", ".sha512", ".sha1", ".md5"] {
+        let mut sidecar = iso.as_os_str().to_owned();
+        sidecar.push(ext);
+        let sidecar = PathBuf::from(sidecar);
+        if sidecar.is_file() {
+            return Some(sidecar);
+        }
+    }
+
+    let dir = iso.parent()?;
+    CHECKSUM_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|p| p.is_file())
+}
+
+// האלגוריתם לפי אורך ה-hash, כמו בקבצי *SUMS שאין בהם שם אלגוריתם
+pub fn digest_algorithm(digest: &str) -> Option<HashAlgorithm> {
+    if !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    match digest.len() {
+        32 => Some(HashAlgorithm::Md5),
+        40 => Some(HashAlgorithm::Sha1),
+        64 => Some(HashAlgorithm::Sha256),
+        128 => Some(HashAlgorithm::Sha512),
+        _ => None,
+    }
+}
+
+fn is_digest_hex(s: &str) -> bool {
+    digest_algorithm(s).is_some()
+}
+
+// תומך ב-"hash  filename", "hash *filename", "SHA256 (filename) = hash" (וכן SHA512/SHA1/MD5) וב-hash בודד
+pub fn parse_checksum_file(content: &str, file_name: &str) -> Option<String> {
+    let mut bare = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = ["SHA256 (", "SHA512 (", "SHA1 (", "MD5 ("]
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+        {
+            if let Some((name, hash)) = rest.split_once(") = ") {
+                if name == file_name && is_digest_hex(hash.trim()) {
+                    return Some(hash.trim().to_ascii_lowercase());
+                }
+            }
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let Some(hash) = parts.next().filter(|h| is_digest_hex(h)) else {
+            continue;
+        };
+        match parts.next() {
+            Some(name) => {
+                let name = name.trim_start_matches('*').trim_start_matches("./");
+                if name == file_name {
+                    return Some(hash.to_ascii_lowercase());
+                }
+            }
+            None => bare = Some(hash.to_ascii_lowercase()),
+        }
+    }
+
+    bare
+}
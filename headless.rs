@@ -0,0 +1,364 @@
+use crate::burn_engine::{
+    self, BurnBackend, BurnConfig, BurnDirection, BurnEngine, BurnEvent, HashAlgorithm,
+    VerifyMethod, VerifyMode,
+};
+use clap::{Parser, ValueEnum};
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+// מצב שורת פקודה בלי GTK - שורה אחת לכל אירוע, קלה לפענוח מסקריפט
+#[derive(Parser, Debug)]
+#[command(name = "gtkBURN", version, about = "Burn a disk image without the GUI")]
+pub struct HeadlessArgs {
+    #[arg(long)]
+    pub headless: bool,
+    #[arg(long, required_unless_present = "backup_to")]
+    pub iso: Option<PathBuf>,
+    // גיבוי במקום צריבה: --device נקרא ונשמר לקובץ הזה
+    #[arg(long, value_name = "FILE")]
+    pub backup_to: Option<PathBuf>,
+    #[arg(long)]
+    pub device: PathBuf,
+    #[arg(long, value_enum, default_value_t = VerifyArg::Full)]
+    pub verify: VerifyArg,
+    // אירוע אחד לשורה כאובייקט JSON במקום הטקסט
+    #[arg(long)]
+    pub json: bool,
+    #[arg(long, value_enum, default_value_t = HashArg::Blake3)]
+    pub hash_algorithm: HashArg,
+    // fdatasync כל N MB: פחות המתנה בסוף הצריבה ופחות אובדן בנפילת חשמל. 0 = רק בסוף
+    #[arg(long, value_name = "MB", default_value_t = 0)]
+    pub sync_every_mb: u64,
+    // דורס גם כונן שיש עליו מערכת קבצים (ext4, FAT32, NTFS, exFAT), ומנתק מחיצות מעוגנות
+    #[arg(long)]
+    pub force: bool,
+    // CRC32 לכל chunk בין הקריאה לכתיבה - לאבחון זיכרון או hub פגומים
+    #[arg(long)]
+    pub paranoid: bool,
+    // כתיבה ב-O_DIRECT, עוקפת את ה-page cache
+    #[arg(long)]
+    pub direct_io: bool,
+    // TRIM לכל הכונן לפני הכתיבה
+    #[arg(long)]
+    pub discard: bool,
+    // אפסים על כל הכונן לפני התמונה
+    #[arg(long)]
+    pub wipe_before: bool,
+    // בביטול מוחקים את ההתחלה של התמונה החלקית
+    #[arg(long)]
+    pub wipe_on_cancel: bool,
+    // בהשוואת בייטים ממשיכים אחרי אי-התאמה ומסכמים את כל האזורים
+    #[arg(long)]
+    pub full_scan: bool,
+    // כמו --hash-algorithm bytes/crc32, אבל אפשר לשלב עם אלגוריתם ל-checksum
+    #[arg(long)]
+    pub byte_compare: bool,
+    #[arg(long)]
+    pub fast_verify: bool,
+    #[arg(long)]
+    pub eject: bool,
+    #[arg(long, value_name = "KB", default_value_t = burn_engine::BUFFER_SIZE / 1024)]
+    pub buffer_kb: usize,
+    #[arg(long, value_name = "MB/s")]
+    pub max_speed: Option<f64>,
+    // מחיצת persistence בגודל הזה אחרי תמונת live
+    #[arg(long, value_name = "MB")]
+    pub persistence_mb: Option<u64>,
+    // מה שנשאר אחרי התמונה כמחיצת FAT32/exFAT
+    #[arg(long)]
+    pub data_partition: bool,
+    // תמונת Windows נכתבת כמו שהיא, בלי העתקת הקבצים
+    #[arg(long)]
+    pub windows_raw: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum VerifyArg {
+    Full,
+    Quick,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HashArg {
+    Blake3,
+    Sha256,
+    Sha512,
+    Crc32,
+    // השוואה מלאה מול ה-ISO
+    Bytes,
+}
+
+// נבדק לפני ש-GTK מפרסר את argv
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == "--headless")
+}
+
+// אותה צריבה דרך pkexec, במצב headless עם JSON. None = אין pkexec, או שיש בהגדרות משהו
+// שאין לו דגל - צריבה "דומה" על הכונן הלא נכון עדיף שלא תקרה
+pub fn elevated_command(config: &BurnConfig) -> Option<std::process::Command> {
+    let args = elevated_args(config)?;
+    let mut command = std::process::Command::new(burn_engine::find_tool("pkexec")?);
+    command.arg(std::env::current_exe().ok()?).args(args);
+    Some(command)
+}
+
+// הדגלים שמשחזרים את config. עוברים שוב דרך clap ו-config_from, ואם יוצא משהו אחר -
+// None. הקישור by-id נקבע מחדש בתהליך עצמו
+fn elevated_args(config: &BurnConfig) -> Option<Vec<OsString>> {
+    let mut args: Vec<OsString> = vec!["--headless".into(), "--json".into()];
+    if !config.iso_path.as_os_str().is_empty() {
+        args.extend(["--iso".into(), config.iso_path.clone().into()]);
+    }
+    if let BurnDirection::DeviceToFile { output_path } = &config.direction {
+        args.extend(["--backup-to".into(), output_path.clone().into()]);
+    }
+    args.extend(["--device".into(), config.device_path.clone().into()]);
+    let verify = match config.verify_mode {
+        VerifyMode::Full => "full",
+        VerifyMode::Quick => "quick",
+        VerifyMode::Off => "off",
+    };
+    let hash = match config.hash_algorithm {
+        HashAlgorithm::Sha256 => "sha256",
+        HashAlgorithm::Sha512 => "sha512",
+        _ => "blake3",
+    };
+    args.push(format!("--verify={}", verify).into());
+    args.push(format!("--hash-algorithm={}", hash).into());
+    args.push(format!("--buffer-kb={}", config.buffer_size / 1024).into());
+    if let Some(bytes) = config.sync_interval_bytes {
+        args.push(format!("--sync-every-mb={}", bytes / (1024 * 1024)).into());
+    }
+    if let Some(cap) = config.max_speed_mbps {
+        args.push(format!("--max-speed={}", cap).into());
+    }
+    if let Some(bytes) = config.persistence_bytes {
+        args.push(format!("--persistence-mb={}", bytes / (1024 * 1024)).into());
+    }
+    for (flag, on) in [
+        ("--force", config.force_overwrite),
+        ("--paranoid", config.paranoid),
+        ("--direct-io", config.use_direct_io),
+        ("--discard", config.pre_discard),
+        ("--wipe-before", config.wipe_before_burn),
+        ("--wipe-on-cancel", config.wipe_on_cancel),
+        ("--full-scan", config.verify_full_scan),
+        ("--byte-compare", config.byte_compare),
+        ("--fast-verify", config.fast_verify),
+        ("--eject", config.eject_after),
+        ("--data-partition", config.data_partition),
+        ("--windows-raw", config.windows_raw),
+    ] {
+        if on {
+            args.push(flag.into());
+        }
+    }
+
+    let parsed =
+        HeadlessArgs::try_parse_from(std::iter::once("gtkBURN".into()).chain(args.clone())).ok()?;
+    let rebuilt = BurnConfig {
+        device_link: config.device_link.clone(),
+        ..config_from(parsed)
+    };
+    (rebuilt == *config).then_some(args)
+}
+
+fn config_from(args: HeadlessArgs) -> BurnConfig {
+    BurnConfig {
+        iso_path: args.iso.unwrap_or_default(),
+        device_path: args.device,
+        direction: match args.backup_to {
+            Some(output_path) => BurnDirection::DeviceToFile { output_path },
+            None => BurnDirection::IsoToDevice,
+        },
+        verify_mode: match args.verify {
+            VerifyArg::Full => VerifyMode::Full,
+            VerifyArg::Quick => VerifyMode::Quick,
+            VerifyArg::Off => VerifyMode::Off,
+        },
+        fast_verify: args.fast_verify || matches!(args.hash_algorithm, HashArg::Crc32),
+        byte_compare: args.byte_compare || matches!(args.hash_algorithm, HashArg::Bytes),
+        hash_algorithm: match args.hash_algorithm {
+            HashArg::Sha256 => HashAlgorithm::Sha256,
+            HashArg::Sha512 => HashAlgorithm::Sha512,
+            _ => HashAlgorithm::Blake3,
+        },
+        sync_interval_bytes: Some(args.sync_every_mb * 1024 * 1024).filter(|n| *n > 0),
+        force_overwrite: args.force,
+        paranoid: args.paranoid,
+        use_direct_io: args.direct_io,
+        pre_discard: args.discard,
+        wipe_before_burn: args.wipe_before,
+        wipe_on_cancel: args.wipe_on_cancel,
+        verify_full_scan: args.full_scan,
+        eject_after: args.eject,
+        buffer_size: args.buffer_kb * 1024,
+        max_speed_mbps: args.max_speed,
+        persistence_bytes: args.persistence_mb.map(|mb| mb * 1024 * 1024),
+        data_partition: args.data_partition,
+        windows_raw: args.windows_raw,
+        ..Default::default()
+    }
+}
+
+pub fn run() -> bool {
+    let args = HeadlessArgs::parse();
+    let json = args.json;
+    // /dev/sdX שנתנו עכשיו - מכאן והלאה הצריבה נעולה על הכונן הזה
+    let device_link = burn_engine::stable_device_link(&args.device);
+
+    let engine = BurnEngine::new();
+    engine.start(BurnConfig {
+        device_link,
+        ..config_from(args)
+    });
+
+    for event in engine.iter_events() {
+        if json {
+            match serde_json::to_string(&event) {
+                Ok(line) => println!("{}", line),
+                Err(e) => eprintln!("Failed to serialize event: {}", e),
+            }
+        } else {
+            println!("{}", format_event(&event));
+        }
+        match event {
+            BurnEvent::Finished { .. } => return true,
+            BurnEvent::Cancelled | BurnEvent::Error(_) => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn format_event(event: &BurnEvent) -> String {
+    match event {
+        BurnEvent::Preparing => "PREPARING".to_string(),
+        // speed= נשאר מהירות הכתיבה, כמו לפני שהקריאה דווחה בנפרד
+        BurnEvent::Progress {
+            written,
+            total,
+            read_speed_mbps,
+            write_speed_mbps,
+            eta_secs,
+        } => format!(
+            "PROGRESS written={} total={} speed={:.1} read_speed={:.1} eta={}",
+            written, total, write_speed_mbps, read_speed_mbps, eta_secs
+        ),
+        BurnEvent::Verifying { checked, total } => {
+            format!("VERIFYING checked={} total={}", checked, total)
+        }
+        BurnEvent::Wiping { wiped, total } => format!("WIPING wiped={} total={}", wiped, total),
+        BurnEvent::VerifySummary { method, ok } => {
+            let method = match method {
+                VerifyMethod::Hash(algorithm) => algorithm.name(),
+                VerifyMethod::Crc32 => "CRC32",
+                VerifyMethod::ByteCompare => "bytes",
+                VerifyMethod::Sampled => "sampled",
+            };
+            format!("VERIFY_SUMMARY method={} ok={}", method, ok)
+        }
+        BurnEvent::Warning { message } => format!("WARNING message={:?}", message),
+        BurnEvent::Checkpoint { at_bytes } => format!("CHECKPOINT at={}", at_bytes),
+        BurnEvent::Finished {
+            bytes,
+            elapsed_secs,
+            verified,
+        } => format!(
+            "FINISHED bytes={} elapsed={:.1} verified={}",
+            bytes, elapsed_secs, verified
+        ),
+        BurnEvent::Cancelled => "CANCELLED".to_string(),
+        BurnEvent::Error(e) => match e.mismatch() {
+            Some(mismatch) => format!(
+                "ERROR kind={:?} offset=0x{:X} message={:?}",
+                e.kind, mismatch.offset, e.message
+            ),
+            None => format!("ERROR kind={:?} message={:?}", e.kind, e.message),
+        },
+        other => format!("EVENT {:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gui_config() -> BurnConfig {
+        BurnConfig {
+            iso_path: "/tmp/ubuntu.iso".into(),
+            device_path: "/dev/sdb".into(),
+            verify_mode: VerifyMode::Quick,
+            pre_discard: true,
+            wipe_on_cancel: true,
+            byte_compare: true,
+            hash_algorithm: HashAlgorithm::Sha256,
+            eject_after: true,
+            sync_interval_bytes: Some(64 * 1024 * 1024),
+            buffer_size: 4 * 1024 * 1024,
+            max_speed_mbps: Some(12.5),
+            persistence_bytes: Some(4 * 1024 * 1024 * 1024),
+            data_partition: true,
+            device_link: Some("/dev/disk/by-id/usb-Kingston_DataTraveler-0:0".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn elevated_args_round_trip_gui_config() {
+        let config = gui_config();
+        let args = elevated_args(&config).expect("every option has a flag");
+        let parsed =
+            HeadlessArgs::try_parse_from(std::iter::once(OsString::from("gtkBURN")).chain(args))
+                .unwrap();
+        assert!(parsed.headless && parsed.json);
+        assert_eq!(
+            BurnConfig {
+                device_link: config.device_link.clone(),
+                ..config_from(parsed)
+            },
+            config
+        );
+    }
+
+    // הכפתור BACKUP DEVICE: בלי תמונה, רק הכונן וקובץ היעד
+    #[test]
+    fn backup_needs_no_image() {
+        let parsed = HeadlessArgs::try_parse_from([
+            "gtkBURN",
+            "--headless",
+            "--device",
+            "/dev/sdb",
+            "--backup-to",
+            "/tmp/backup.img",
+        ])
+        .unwrap();
+        let config = config_from(parsed);
+        assert_eq!(
+            config.direction,
+            BurnDirection::DeviceToFile {
+                output_path: "/tmp/backup.img".into()
+            }
+        );
+        assert!(elevated_args(&config).is_some());
+        assert!(
+            HeadlessArgs::try_parse_from(["gtkBURN", "--headless", "--device", "/dev/sdb"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn elevated_args_refuse_options_without_flags() {
+        let config = BurnConfig {
+            target_offset: 1024 * 1024,
+            ..gui_config()
+        };
+        assert!(elevated_args(&config).is_none());
+        let config = BurnConfig {
+            hash_algorithm: HashAlgorithm::Md5,
+            ..gui_config()
+        };
+        assert!(elevated_args(&config).is_none());
+    }
+}
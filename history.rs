@@ -0,0 +1,182 @@
+use crate::burn_engine::{BurnOutcome, BurnReport};
+use serde::Deserialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// הפורמט הישן: מערך JSON אחד, רק צריבות שהצליחו
+#[derive(Debug, Deserialize)]
+struct LegacyRecord {
+    iso_path: PathBuf,
+    device: String,
+    timestamp: u64,
+    duration_secs: f64,
+    verified: bool,
+}
+
+impl From<LegacyRecord> for BurnReport {
+    fn from(record: LegacyRecord) -> Self {
+        Self {
+            timestamp: record.timestamp,
+            image: record.iso_path,
+            image_sha256: None,
+            device: PathBuf::from(record.device),
+            device_model: None,
+            device_serial: None,
+            device_size: None,
+            bytes_written: 0,
+            bytes_verified: 0,
+            avg_speed_mbps: 0.0,
+            verified: record.verified.then_some(true),
+            duration_secs: record.duration_secs,
+            outcome: BurnOutcome::Completed,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BurnHistory {
+    path: PathBuf,
+    pub records: Vec<BurnReport>,
+}
+
+impl BurnHistory {
+    // ~/.local/share/burnstation/history.jsonl - שורת JSON לכל צריבה
+    pub fn default_path() -> PathBuf {
+        Self::data_dir().join("history.jsonl")
+    }
+
+    fn data_dir() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("burnstation")
+    }
+
+    // ההיסטוריה מלפני ה-jsonl נקראת ראשונה. לא כותבים אותה מחדש - היא נשארת כמו שהיא
+    pub fn load() -> Self {
+        let mut history = Self::load_from(Self::default_path());
+        let legacy = load_legacy(&Self::data_dir().join("history.json"));
+        history.records.splice(0..0, legacy);
+        history
+    }
+
+    // קובץ חסר = היסטוריה ריקה. שורה פגומה (נפילה באמצע כתיבה) מדלגים עליה, לא על כל הקובץ
+    pub fn load_from(path: PathBuf) -> Self {
+        let records = std::fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Self { path, records }
+    }
+
+    // רק מוסיפים שורה בסוף - רשומה קיימת לא נכתבת שוב
+    pub fn append(&mut self, report: BurnReport) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut line = serde_json::to_vec(&report)?;
+        line.push(b'\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&line)?;
+        self.records.push(report);
+        Ok(())
+    }
+}
+
+fn load_legacy(path: &Path) -> Vec<BurnReport> {
+    std::fs::read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice::<Vec<LegacyRecord>>(&data).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(BurnReport::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("history-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn report(timestamp: u64, outcome: BurnOutcome) -> BurnReport {
+        BurnReport {
+            timestamp,
+            image: PathBuf::from("/home/user/ubuntu-24.04.iso"),
+            image_sha256: Some("ab".repeat(32)),
+            device: PathBuf::from("/dev/sdb"),
+            device_model: Some("DataTraveler 3.0".into()),
+            device_serial: None,
+            device_size: Some(32 * 1024 * 1024 * 1024),
+            bytes_written: 6 * 1024 * 1024 * 1024,
+            bytes_verified: 6 * 1024 * 1024 * 1024,
+            avg_speed_mbps: 41.5,
+            verified: Some(true),
+            duration_secs: 148.25,
+            outcome,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn appended_reports_load_back_in_order() {
+        let dir = temp_dir("round-trip");
+        // התיקייה עוד לא קיימת - append יוצר אותה
+        let path = dir.join("nested").join("history.jsonl");
+        let mut history = BurnHistory::load_from(path.clone());
+        assert!(history.records.is_empty());
+
+        let failed = BurnReport {
+            verified: None,
+            error: Some("Device is write-protected".into()),
+            ..report(1_700_000_100, BurnOutcome::Failed)
+        };
+        history
+            .append(report(1_700_000_000, BurnOutcome::Completed))
+            .unwrap();
+        history.append(failed.clone()).unwrap();
+
+        let loaded = BurnHistory::load_from(path);
+        assert_eq!(loaded.records, history.records);
+        assert_eq!(loaded.records[1], failed);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file_is_empty_and_corrupt_lines_are_skipped() {
+        let dir = temp_dir("corrupt");
+        assert!(BurnHistory::load_from(dir.join("history.jsonl"))
+            .records
+            .is_empty());
+
+        // שורה שנחתכה באמצע כתיבה, ושורה שהיא בכלל לא רשומה
+        let good = report(1_700_000_000, BurnOutcome::Cancelled);
+        let mut text = serde_json::to_string(&good).unwrap();
+        text.push('\n');
+        text.push_str("{\"timestamp\":1700000200,\"image\":\"/ho\n");
+        text.push_str("not json\n");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.jsonl");
+        std::fs::write(&path, text).unwrap();
+        assert_eq!(BurnHistory::load_from(path).records, vec![good]);
+
+        // הפורמט הישן: מערך שלם או כלום
+        let legacy = dir.join("history.json");
+        std::fs::write(
+            &legacy,
+            "[{\"iso_path\":\"/a.iso\",\"device\":\"/dev/sdc\",",
+        )
+        .unwrap();
+        assert!(load_legacy(&legacy).is_empty());
+        assert!(load_legacy(&dir.join("absent.json")).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
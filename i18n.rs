@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// המחרוזת באנגלית היא המפתח (כמו ב-gettext). שפה בלי טבלה נשארת באנגלית
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Hebrew,
+}
+
+// {} ממולא לפי הסדר ע"י tr! - פורמט מיוחד ({:.1}) מכינים מראש עם format!
+const HEBREW: &[(&str, &str)] = &[
+    ("Burn Station Pro 2026", "תחנת צריבה Pro 2026"),
+    ("About Burn Station", "אודות תחנת הצריבה"),
+    ("History", "היסטוריה"),
+    ("Keyboard Shortcuts", "קיצורי מקלדת"),
+    ("Burning", "צריבה"),
+    ("General", "כללי"),
+    ("Select image", "בחירת תמונה"),
+    ("Scan devices", "סריקת התקנים"),
+    ("Start burning", "התחלת צריבה"),
+    ("Cancel", "ביטול"),
+    ("Theme: system", "ערכת נושא: מערכת"),
+    ("Theme: dark", "ערכת נושא: כהה"),
+    ("Theme: light", "ערכת נושא: בהירה"),
+    ("Burn History", "היסטוריית צריבות"),
+    ("No image selected", "לא נבחרה תמונה"),
+    ("SELECT IMAGE", "בחירת תמונה"),
+    ("VERIFY CHECKSUM", "בדיקת CHECKSUM"),
+    ("VERIFY SIGNATURE", "בדיקת חתימה"),
+    (
+        "Expected signing key fingerprint (optional)",
+        "טביעת האצבע של מפתח החתימה (לא חובה)",
+    ),
+    ("SCAN DEVICES", "סריקת התקנים"),
+    ("Show only removable devices", "הצג רק התקנים נשלפים"),
+    ("TRIM device first (faster on SSDs)", "TRIM להתקן לפני הצריבה (מהיר יותר ב-SSD)"),
+    ("Erase partial image if cancelled", "מחק תמונה חלקית אם בוטל"),
+    ("Zero-fill the whole drive before burning", "מלא את כל הכונן באפסים לפני הצריבה"),
+    ("Verify: full read-back", "בדיקה: קריאה מלאה"),
+    ("Verify: quick (sampled)", "בדיקה: מהירה (מדגמית)"),
+    ("Verify: off", "בדיקה: כבויה"),
+    (
+        "Paranoid verify (re-read the ISO byte by byte)",
+        "בדיקה פרנואידית (קריאה חוזרת של ה-ISO בייט אחר בייט)",
+    ),
+    (
+        "Keep scanning after a mismatch (full report)",
+        "המשך לסרוק אחרי אי-התאמה (דוח מלא)",
+    ),
+    ("Hash: BLAKE3 (multi-core)", "Hash: BLAKE3 (מרובה ליבות)"),
+    ("Fast verify (CRC32 per block)", "בדיקה מהירה (CRC32 לכל בלוק)"),
+    ("Safely eject when done", "הוצא בבטחה בסיום"),
+    (
+        "Sync every {} MB (slower, survives power loss)",
+        "סנכרון כל {} MB (איטי יותר, שורד נפילת חשמל)",
+    ),
+    ("Checkpoint at {}", "נקודת שמירה ב-{}"),
+    ("Max write speed (MB/s, 0 = unlimited)", "מהירות כתיבה מרבית (MB/s, 0 = ללא הגבלה)"),
+    ("Ready to Create Magic.", "מוכן ליצור קסמים."),
+    ("START BURNING", "התחל צריבה"),
+    ("VERIFY ONLY", "בדיקה בלבד"),
+    ("CREATE IMAGE FROM DRIVE", "יצירת תמונה מהכונן"),
+    ("COPY SOURCE DRIVE ONTO SELECTED DRIVE", "העתקת כונן המקור לכונן שנבחר"),
+    ("Source drive:", "כונן מקור:"),
+    (
+        "Source and destination must be different drives.",
+        "המקור והיעד חייבים להיות כוננים שונים.",
+    ),
+    ("Compression level (0 = default)", "רמת דחיסה (0 = ברירת מחדל)"),
+    ("{} MB/s | {}% | {} read → {} written", "{} MB/s | {}% | נקראו {} ← נכתבו {}"),
+    ("On read error: stop", "בשגיאת קריאה: לעצור"),
+    (
+        "On read error: fill with zeros and continue",
+        "בשגיאת קריאה: למלא באפסים ולהמשיך",
+    ),
+    ("Zeros (1 pass)", "אפסים (מעבר אחד)"),
+    ("0xFF (1 pass)", "0xFF (מעבר אחד)"),
+    ("Random data (1 pass)", "נתונים אקראיים (מעבר אחד)"),
+    ("Random, then zeros (2 passes)", "אקראי ואז אפסים (2 מעברים)"),
+    ("WIPE DRIVE", "מחיקת כונן"),
+    ("ATA SECURE ERASE", "מחיקה מאובטחת ATA"),
+    ("SURFACE TEST", "בדיקת משטח"),
+    ("FAKE CAPACITY TEST (erases drive)", "בדיקת נפח מזויף (מוחק את הכונן)"),
+    ("Usable capacity: {} of {} advertised", "נפח שמיש: {} מתוך {} מוצהרים"),
+    (
+        "This drive is not the size it claims! Only the first {} can hold data.",
+        "הכונן הזה לא בגודל שהוא מצהיר! רק {} הראשונים יכולים להחזיק מידע.",
+    ),
+    (
+        "This drive is not the size it claims! Writes past {} wrap around and overwrite the start.",
+        "הכונן הזה לא בגודל שהוא מצהיר! כתיבה מעבר ל-{} חוזרת להתחלה ודורסת אותה.",
+    ),
+    (
+        "Surface test passed: every block read back correctly.",
+        "בדיקת המשטח עברה: כל הבלוקים נקראו בחזרה כמו שנכתבו.",
+    ),
+    ("Surface test found {} bad range(s).", "בדיקת המשטח מצאה {} טווחים פגומים."),
+    ("Surface test report", "דוח בדיקת משטח"),
+    ("BENCHMARK", "מדידת מהירות"),
+    (
+        "Also benchmark writes (erases the first 1 GB)",
+        "מדוד גם כתיבה (מוחק את ה-1 GB הראשון)",
+    ),
+    ("Benchmark: reading… {}% | {} MB/s", "מדידת מהירות: קריאה… {}% | {} MB/s"),
+    ("Benchmark: writing… {}% | {} MB/s", "מדידת מהירות: כתיבה… {}% | {} MB/s"),
+    ("Benchmark done: read {} MB/s", "מדידת המהירות הסתיימה: קריאה {} MB/s"),
+    ("Benchmark results", "תוצאות מדידת מהירות"),
+    ("Read", "קריאה"),
+    ("Write", "כתיבה"),
+    (
+        "{}: {} in {} — min {} / avg {} / max {} MB/s",
+        "{}: {} תוך {} — מינימום {} / ממוצע {} / מקסימום {} MB/s",
+    ),
+    ("Copy", "העתק"),
+    ("Tested {} ({} sectors).", "נבדקו {} ({} סקטורים)."),
+    ("No bad blocks found.", "לא נמצאו בלוקים פגומים."),
+    ("Good sectors: {}", "סקטורים תקינים: {}"),
+    ("Bad sectors: {}", "סקטורים פגומים: {}"),
+    ("…and {} more", "…ועוד {}"),
+    ("CANCEL", "ביטול"),
+    ("Done in {} — {} at avg {} MB/s{}", "הסתיים תוך {} — {} בממוצע {} MB/s{}"),
+    (", verified OK", ", נבדק ותקין"),
+    ("✘ SIGNATURE", "✘ חתימה"),
+    (
+        " — run as root or add yourself to the disk group",
+        " — הרץ כ-root או הוסף את המשתמש לקבוצת disk",
+    ),
+    (" — pick a larger drive", " — בחר כונן גדול יותר"),
+    (
+        " — the drive may be failing, try another one",
+        " — ייתכן שהכונן כושל, נסה כונן אחר",
+    ),
+    (" — do not trust this image", " — אין לסמוך על התמונה הזו"),
+    (" — the download may be corrupted", " — ייתכן שההורדה פגומה"),
+    (
+        " — close any open files on the drive and retry",
+        " — סגור קבצים פתוחים בכונן ונסה שוב",
+    ),
+    ("Error: {}{}", "שגיאה: {}{}"),
+    ("Wiping… pass {}/{}", "מוחק… מעבר {}/{}"),
+    ("byte compare", "השוואת בייטים"),
+    ("sampled", "מדגמית"),
+    ("passed", "עברה"),
+    ("FAILED", "נכשלה"),
+    ("Verify {} ({})", "בדיקה {} ({})"),
+    ("Single filesystem, no partition table", "מערכת קבצים אחת, ללא טבלת מחיצות"),
+    ("Type", "סוג"),
+    ("Start", "התחלה"),
+    ("Size", "גודל"),
+    ("Verify skipped: {}", "הבדיקה דולגה: {}"),
+    ("Wiping… {}%", "מוחק… {}%"),
+    ("Verifying… {}%", "בודק… {}%"),
+    ("Hashing ISO… {}%", "מחשב hash ל-ISO… {}%"),
+    ("✔ {} OK", "✔ {} תקין"),
+    ("Checksum matches.", "ה-checksum תואם."),
+    (
+        "Checksum matches {} — note: {} is not collision-resistant.",
+        "ה-checksum תואם ל-{} — שים לב: {} אינו עמיד בפני התנגשויות.",
+    ),
+    ("✘ {} MISMATCH", "✘ {} לא תואם"),
+    ("Checksum mismatch! Image hash is {}", "ה-checksum לא תואם! ה-hash של התמונה הוא {}"),
+    ("✔ SIGNED", "✔ חתום"),
+    ("Good signature from {}", "חתימה תקינה של {}"),
+    ("Device ejected safely—remove now!", "הכונן הוצא בבטחה — אפשר לנתק אותו!"),
+    (
+        "Success! Drive is ready, but eject failed ({}).",
+        "הצלחה! הכונן מוכן, אבל ההוצאה נכשלה ({}).",
+    ),
+    ("Cancelled.", "בוטל."),
+    ("Device trimmed in {}s", "בוצע TRIM להתקן תוך {} שניות"),
+    ("Disk images (*.iso, *.img, *.bin, *.raw)", "תמונות דיסק (*.iso, *.img, *.bin, *.raw)"),
+    ("Compressed images", "תמונות דחוסות"),
+    ("Select an image first.", "בחר תמונה קודם."),
+    ("Select checksum file", "בחירת קובץ checksum"),
+    ("No checksum for {} in {}", "אין checksum עבור {} בקובץ {}"),
+    ("Select signature file", "בחירת קובץ חתימה"),
+    ("Signatures", "חתימות"),
+    (
+        "The image failed checksum or signature verification. Burn it anyway?",
+        "התמונה נכשלה בבדיקת checksum או חתימה. לצרוב בכל זאת?",
+    ),
+    (
+        "The selected file has no MBR or ISO 9660 signature and may not boot. Burn it anyway?",
+        "לקובץ שנבחר אין חתימת MBR או ISO 9660 וייתכן שלא יעלה. לצרוב בכל זאת?",
+    ),
+    (
+        "All data on {} will be overwritten. Continue?",
+        "כל המידע ב-{} יידרס. להמשיך?",
+    ),
+    (
+        "The drive firmware will permanently erase every block on {}.\nThis cannot be cancelled once started. Continue?",
+        "הקושחה של הכונן תמחק לצמיתות כל בלוק ב-{}.\nאי אפשר לבטל אחרי ההתחלה. להמשיך?",
+    ),
+    ("No drives detected - Click SCAN", "לא נמצאו כוננים - לחץ על סריקה"),
+    ("BURN TO ALL CHECKED DRIVES", "צרוב לכל הכוננים המסומנים"),
+    ("Burn the same image to several drives:", "צריבת אותה תמונה לכמה כוננים:"),
+    ("Check at least one drive.", "יש לסמן לפחות כונן אחד."),
+    ("{}: verifying… {}%", "{}: בודק… {}%"),
+    ("{}: error: {}", "{}: שגיאה: {}"),
+    ("{}: ✔ {} at avg {} MB/s{}", "{}: ✔ {} בממוצע {} MB/s{}"),
+    ("{} of {} drives done in {}", "{} מתוך {} כוננים הסתיימו תוך {}"),
+    ("Waiting ({} ahead): {}", "ממתין ({} לפניו): {}"),
+    ("Running: {}", "רץ: {}"),
+    ("Cancelled: {}", "בוטל: {}"),
+    ("Cancel this job", "ביטול העבודה הזו"),
+    ("Desktop notification when a burn ends", "התראה בשולחן העבודה כשצריבה מסתיימת"),
+    ("Burn complete", "הצריבה הושלמה"),
+    ("ISO successfully written to {}", "התמונה נכתבה בהצלחה ל-{}"),
+    ("Burn failed", "הצריבה נכשלה"),
+    ("Open Burn Station", "פתח את Burn Station"),
+    ("That is not a disk image.", "זה לא קובץ תמונה של דיסק."),
+    ("Persistent storage (GB, 0 = off)", "אחסון קבוע (GB, 0 = כבוי)"),
+    ("Creating persistent storage \"{}\" ({})…", "יוצר אחסון קבוע \"{}\" ({})…"),
+    ("Persistent storage ready: {} on {}", "האחסון הקבוע מוכן: {} ב-{}"),
+    ("Use remaining space as data partition", "להשתמש במקום שנשאר כמחיצת נתונים"),
+    ("Formatting data partition {} ({})…", "מפרמט את מחיצת הנתונים {} ({})…"),
+    ("Data partition ready: {} ({})", "מחיצת הנתונים מוכנה: {} ({})"),
+    ("Selected device is no longer present — rescan", "ההתקן שנבחר כבר לא מחובר — סרוק מחדש"),
+    ("Read {} MB/s | Write {} MB/s", "קריאה {} MB/s | כתיבה {} MB/s"),
+    ("Verify failed at offset {} — possible bad block", "הבדיקה נכשלה ב-offset {} — ייתכן בלוק פגום"),
+    ("Windows installer — files will be copied to a FAT32 partition", "מתקין של Windows — הקבצים יועתקו למחיצת FAT32"),
+    ("Windows installer — files will be copied to a FAT32 partition, install.wim split in parts", "מתקין של Windows — הקבצים יועתקו למחיצת FAT32, ו-install.wim יחולק לחלקים"),
+    ("Partitioning and formatting FAT32…", "יוצר מחיצה ומפרמט FAT32…"),
+    ("Splitting install.wim for FAT32…", "מחלק את install.wim בשביל FAT32…"),
+    ("Copying {} files ({})…", "מעתיק {} קבצים ({})…"),
+    ("Burn Profile", "פרופיל צריבה"),
+    ("Fast", "מהיר"),
+    ("Balanced", "מאוזן"),
+    ("Paranoid", "פרנואידי"),
+    ("Burn Station — {} ({})", "תחנת צריבה — {} ({})"),
+    ("Burn Station — {}", "תחנת צריבה — {}"),
+    ("PREPARE MULTIBOOT STICK", "הכנת כונן MULTIBOOT"),
+    ("ADD ISO TO STICK", "הוסף ISO לכונן"),
+    ("Multiboot stick ready — select an ISO and press ADD ISO TO STICK", "כונן ה-multiboot מוכן — בחר ISO ולחץ על הוסף ISO לכונן"),
+    ("Boot menu updated: {} images", "תפריט האתחול עודכן: {} תמונות"),
+    ("RESTORE DRIVE", "שחזור כונן"),
+    ("Volume label (optional)", "תווית כונן (לא חובה)"),
+    ("MBR (boots everywhere)", "MBR (עולה בכל מחשב)"),
+    ("GPT (drives over 2 TB)", "GPT (כוננים מעל 2TB)"),
+    ("Writing a new MBR partition table…", "כותב טבלת מחיצות MBR חדשה…"),
+    ("Writing a new GPT partition table…", "כותב טבלת מחיצות GPT חדשה…"),
+    ("Formatting {} \"{}\"…", "מפרמט {} \"{}\"…"),
+    (
+        " — replug the drive, try another port or a different drive",
+        " — חבר את הכונן מחדש, נסה יציאה אחרת או כונן אחר",
+    ),
+    (
+        "{} already has a {} filesystem — everything on it will be erased. Burn anyway?",
+        "ב-{} כבר יש מערכת קבצים {} — כל מה שעליה יימחק. לצרוב בכל זאת?",
+    ),
+    ("Format Drive…", "פירמוט כונן…"),
+    ("Format Drive", "פירמוט כונן"),
+    ("Format", "פרמט"),
+    ("Cluster size: default", "גודל קלאסטר: ברירת מחדל"),
+    ("Cluster size: {}", "גודל קלאסטר: {}"),
+    (
+        "Full format (write zeros over the whole drive)",
+        "פירמוט מלא (כתיבת אפסים על כל הכונן)",
+    ),
+    (
+        "{} is mounted at {}. Unmount it and format?",
+        "{} מעוגן ב-{}. לנתק אותו ולפרמט?",
+    ),
+    (
+        "{} is mounted at {}. Unmount it and burn?",
+        "{} מעוגן ב-{}. לנתק אותו ולצרוב?",
+    ),
+    (
+        "{} is mounted at {}. Unmount it and set up multiboot?",
+        "{} מעוגן ב-{}. לנתק אותו ולהכין כונן multiboot?",
+    ),
+    (
+        "{} is mounted at {}. Unmount it and copy onto it?",
+        "{} מעוגן ב-{}. לנתק אותו ולהעתיק אליו?",
+    ),
+    (
+        "{} is mounted at {}. Unmount it and wipe it?",
+        "{} מעוגן ב-{}. לנתק אותו ולמחוק אותו?",
+    ),
+    (
+        "{} is mounted at {}. Unmount it and restore?",
+        "{} מעוגן ב-{}. לנתק אותו ולשחזר?",
+    ),
+    (
+        "{} — this is the system disk and cannot be erased",
+        "{} — זה כונן המערכת ואי אפשר למחוק אותו",
+    ),
+    (
+        "Check each block in memory before writing (diagnostic)",
+        "בדוק כל בלוק בזיכרון לפני הכתיבה (אבחון)",
+    ),
+    (" — test the RAM and avoid USB hubs", " — בדוק את הזיכרון והימנע מ-hub של USB"),
+    ("BIOS+UEFI bootable", "ניתן לאתחול ב-BIOS וב-UEFI"),
+    ("BIOS bootable", "ניתן לאתחול ב-BIOS"),
+    ("UEFI bootable", "ניתן לאתחול ב-UEFI"),
+    ("not bootable", "לא ניתן לאתחול"),
+    ("Technical Log", "יומן טכני"),
+    ("Copy Log", "העתק יומן"),
+    (
+        " — the drive was reset; replug it, rescan and burn again",
+        " — הכונן אותחל; חבר אותו מחדש, סרוק וצרוב שוב",
+    ),
+    (
+        "This image may not be bootable when written raw — consider extraction mode.",
+        "ייתכן שהתמונה לא תעלה כשהיא נכתבת כמו שהיא — שקול מצב חילוץ.",
+    ),
+    ("This is a Windows installer image", "זו תמונת התקנה של Windows"),
+    (
+        "Written raw, a Windows ISO does not boot on most PCs. Copying its files to a FAT32 partition makes the drive bootable on UEFI machines; a large install.wim is split automatically.",
+        "ISO של Windows שנכתב כמו שהוא לא עולה ברוב המחשבים. העתקת הקבצים למחיצת FAT32 הופכת את הכונן לבר-אתחול במחשבי UEFI; install.wim גדול יחולק אוטומטית.",
+    ),
+    ("Write raw anyway", "כתוב כמו שהוא בכל זאת"),
+    ("Copy files to FAT32 (recommended)", "העתק קבצים ל-FAT32 (מומלץ)"),
+    (
+        "Windows installer — will be written raw and may not boot on most PCs",
+        "מתקין של Windows — ייכתב כמו שהוא וייתכן שלא יעלה ברוב המחשבים",
+    ),
+    ("Kiosk device {} was not found", "כונן העמדה {} לא נמצא"),
+    ("Burning will start automatically", "הצריבה תתחיל אוטומטית"),
+    ("Starting in {} s…", "מתחיל בעוד {} שניות…"),
+    ("Click to copy", "לחץ להעתקה"),
+    ("SHA-256: computing…", "SHA-256: מחשב…"),
+    ("SHA-256: computing… {}%", "SHA-256: מחשב… {}%"),
+    ("SHA-256: {}", "SHA-256: {}"),
+    ("Copy Report", "העתק דוח"),
+    ("Date: {}", "תאריך: {}"),
+    ("Image: {}", "תמונה: {}"),
+    ("Drive: {}", "כונן: {}"),
+    ("Result: {}", "תוצאה: {}"),
+    ("completed", "הושלמה"),
+    ("failed", "נכשלה"),
+    ("cancelled", "בוטלה"),
+    ("Written: {} in {} at avg {} MB/s", "נכתבו {} ב-{}, בממוצע {} MB/s"),
+    ("Verify: {}", "בדיקה: {}"),
+    ("passed", "עברה"),
+    ("not run", "לא בוצעה"),
+    ("Error: {}", "שגיאה: {}"),
+    ("Burn summary", "סיכום צריבה"),
+    ("Last Burn Summary", "סיכום הצריבה האחרונה"),
+    ("No burn has finished since the app started.", "אף צריבה לא הסתיימה מאז שהתוכנה נפתחה."),
+    ("Open Log File", "פתח את קובץ הלוג"),
+    ("Cannot open {}", "לא ניתן לפתוח את {}"),
+    ("Details are in the log: {}", "הפרטים בלוג: {}"),
+    ("No permission to write to {}", "אין הרשאה לכתוב ל-{}"),
+    ("Retry with elevated privileges", "לנסות שוב עם הרשאות מנהל"),
+    ("Burning with elevated privileges… {}%", "צורב עם הרשאות מנהל… {}%"),
+    ("Burn finished with elevated privileges", "הצריבה עם הרשאות מנהל הסתיימה"),
+    ("The elevated burn did not run", "הצריבה עם הרשאות מנהל לא רצה"),
+    (
+        "Signing key (paste it, or leave empty to pick a keyring)",
+        "מפתח חתימה (להדביק, או להשאיר ריק ולבחור keyring)",
+    ),
+    ("Select the signing keyring", "בחירת keyring של מפתח החתימה"),
+    ("OpenPGP keys", "מפתחות OpenPGP"),
+    ("BACKUP DEVICE", "גיבוי הכונן"),
+];
+
+pub fn language() -> Language {
+    static LANGUAGE: OnceLock<Language> = OnceLock::new();
+    *LANGUAGE.get_or_init(|| {
+        // אותו סדר עדיפות כמו ב-gettext
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        // iw הוא הקוד הישן לעברית
+        if locale.starts_with("he") || locale.starts_with("iw") {
+            Language::Hebrew
+        } else {
+            Language::English
+        }
+    })
+}
+
+pub fn is_rtl() -> bool {
+    language() == Language::Hebrew
+}
+
+fn table() -> &'static HashMap<&'static str, &'static str> {
+    static TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| match language() {
+        Language::English => HashMap::new(),
+        Language::Hebrew => HEBREW.iter().copied().collect(),
+    })
+}
+
+pub fn tr(text: &'static str) -> &'static str {
+    table().get(text).copied().unwrap_or(text)
+}
+
+pub fn fill(template: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// tr!("text") מחזיר &'static str, tr!("text {}", arg) מחזיר String
+macro_rules! tr {
+    ($text:literal) => {
+        $crate::i18n::tr($text)
+    };
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::tr($text),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
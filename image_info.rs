@@ -0,0 +1,348 @@
+// שורת המידע שמופיעה מיד אחרי בחירת תמונה: שם הדיסק, גודל ואיך הוא עולה.
+// רק פענוח של כותרות - את הקריאה מהקובץ (גם דרך הדחיסה) עושה burn_engine::image_summary
+use crate::partitions::u32_at;
+use crate::windows::{InstallImage, Udf};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+pub const ISO_SECTOR: usize = 2048;
+const PVD_OFFSET: usize = 16 * ISO_SECTOR;
+// סדרת ה-volume descriptors מתחילה בסקטור 16 ונגמרת ב-terminator. 16 סקטורים מספיקים לכל ISO מוכר
+pub const DESCRIPTORS_END: usize = 32 * ISO_SECTOR;
+const DESCRIPTOR_BOOT_RECORD: u8 = 0;
+const DESCRIPTOR_PRIMARY: u8 = 1;
+const DESCRIPTOR_TERMINATOR: u8 = 255;
+
+// El Torito: מזהה הפלטפורמה בכותרת של כל קבוצת רשומות בקטלוג
+const PLATFORM_X86: u8 = 0x00;
+const PLATFORM_EFI: u8 = 0xEF;
+const CATALOG_ENTRY: usize = 32;
+const ENTRY_BOOTABLE: u8 = 0x88;
+const SECTION_HEADER: u8 = 0x90;
+const SECTION_HEADER_LAST: u8 = 0x91;
+
+// סוגי מחיצה ב-MBR של isohybrid שמצביעים על ESP
+const MBR_TYPE_EFI: u8 = 0xEF;
+const MBR_TYPE_GPT_PROTECTIVE: u8 = 0xEE;
+const GPT_HEADER_OFFSET: usize = 512;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Boot {
+    pub bios: bool,
+    pub uefi: bool,
+}
+
+impl Boot {
+    fn or(self, other: Boot) -> Boot {
+        Boot {
+            bios: self.bios || other.bios,
+            uefi: self.uefi || other.uefi,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsoVolume {
+    pub volume_id: String,
+    pub application_id: String,
+    pub sectors: u32,
+    pub block_size: u16,
+}
+
+impl IsoVolume {
+    pub fn size(&self) -> u64 {
+        self.sectors as u64 * self.block_size as u64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSummary {
+    // None = לא ISO 9660 (img גולמי וכד') - מציגים רק גודל
+    pub iso: Option<IsoVolume>,
+    pub size: u64,
+    pub boot: Boot,
+    // MBR עם מחיצה או GPT בתחילת הקובץ - מה ש-dd צריך כדי שהמקל יעלה
+    pub hybrid: bool,
+}
+
+impl ImageSummary {
+    // ISO שלא נועד להיכתב כמו שהוא: בלי טבלת מחיצות ובלי רשומת EFI ב-El Torito.
+    // תמונה גולמית מתחילה בטבלה משלה (או בכלום, וזה בסדר) אז לא נבדקת
+    pub fn may_not_boot_raw(&self) -> bool {
+        // בלי טבלה, uefi יכול להגיע רק מהקטלוג
+        self.iso.is_some() && !self.hybrid && !self.boot.uefi
+    }
+}
+
+// head = תחילת התמונה עד DESCRIPTORS_END, catalog = הסקטור שעליו מצביע ה-Boot Record
+pub fn summarize(head: &[u8], catalog: Option<&[u8]>, file_size: u64) -> ImageSummary {
+    let Some(volume) = primary_volume(head) else {
+        return ImageSummary {
+            iso: None,
+            size: file_size,
+            boot: Boot::default(),
+            hybrid: partition_table(head),
+        };
+    };
+    let boot = catalog.map(catalog_platforms).unwrap_or_default();
+    ImageSummary {
+        // בתמונה דחוסה גודל הקובץ קטן מהדיסק עצמו
+        size: volume.size().max(file_size),
+        iso: Some(volume),
+        boot: boot.or(isohybrid(head)),
+        hybrid: partition_table(head),
+    }
+}
+
+fn descriptor(head: &[u8], kind: u8) -> Option<&[u8]> {
+    head.get(PVD_OFFSET..)?
+        .chunks_exact(ISO_SECTOR)
+        .filter(|sector| &sector[1..6] == b"CD001")
+        .take_while(|sector| sector[0] != DESCRIPTOR_TERMINATOR)
+        .find(|sector| sector[0] == kind)
+}
+
+// שדות טקסט ב-PVD מרופדים ברווחים
+fn text(field: &[u8]) -> String {
+    String::from_utf8_lossy(field)
+        .trim_matches(|c: char| c == ' ' || c == '\0')
+        .to_string()
+}
+
+pub fn primary_volume(head: &[u8]) -> Option<IsoVolume> {
+    let pvd = descriptor(head, DESCRIPTOR_PRIMARY)?;
+    let block_size = u16::from_le_bytes([pvd[128], pvd[129]]);
+    Some(IsoVolume {
+        volume_id: text(&pvd[40..72]),
+        application_id: text(&pvd[574..702]),
+        sectors: u32_at(pvd, 80),
+        // 0 לא חוקי, אבל יש כלים שכותבים אותו
+        block_size: if block_size == 0 {
+            ISO_SECTOR as u16
+        } else {
+            block_size
+        },
+    })
+}
+
+// מספר הסקטור של קטלוג האתחול, אם יש Boot Record של El Torito
+pub fn boot_catalog_sector(head: &[u8]) -> Option<u32> {
+    let record = descriptor(head, DESCRIPTOR_BOOT_RECORD)?;
+    record[7..]
+        .starts_with(b"EL TORITO SPECIFICATION")
+        .then(|| u32_at(record, 0x47))
+}
+
+// רשומת ה-validation נותנת את הפלטפורמה של רשומת ברירת המחדל, ואחריה קבוצות
+// עם כותרת משלהן - שם בדרך כלל יושבת רשומת ה-EFI
+pub fn catalog_platforms(catalog: &[u8]) -> Boot {
+    let mut boot = Boot::default();
+    let mut mark = |platform: u8| match platform {
+        PLATFORM_X86 => boot.bios = true,
+        PLATFORM_EFI => boot.uefi = true,
+        _ => {}
+    };
+    let entry = |index: usize| catalog.get(index * CATALOG_ENTRY..(index + 1) * CATALOG_ENTRY);
+
+    let Some(validation) = entry(0) else {
+        return boot;
+    };
+    if validation[0] != 0x01 || validation[30..32] != [0x55, 0xAA] {
+        return boot;
+    }
+    if entry(1).is_some_and(|default| default[0] == ENTRY_BOOTABLE) {
+        mark(validation[1]);
+    }
+
+    let mut index = 2;
+    while let Some(header) = entry(index) {
+        if header[0] != SECTION_HEADER && header[0] != SECTION_HEADER_LAST {
+            break;
+        }
+        let count = u16::from_le_bytes([header[2], header[3]]) as usize;
+        if (1..=count).any(|i| entry(index + i).is_some_and(|e| e[0] == ENTRY_BOOTABLE)) {
+            mark(header[1]);
+        }
+        if header[0] == SECTION_HEADER_LAST {
+            break;
+        }
+        index += 1 + count;
+    }
+    boot
+}
+
+// isohybrid: קוד אתחול ב-MBR עם מחיצה שמכסה את ה-ISO, כך שגם מקל USB עולה ב-BIOS.
+// מחיצת EFI (או GPT מוגן) = ESP שהקושחה מוצאת בלי El Torito
+pub fn isohybrid(head: &[u8]) -> Boot {
+    if head.get(510..512) != Some(&[0x55, 0xAA][..]) {
+        return Boot::default();
+    }
+    let types: Vec<u8> = (0..4).map(|i| head[446 + i * 16 + 4]).collect();
+    Boot {
+        bios: types.iter().any(|t| *t != 0),
+        uefi: types
+            .iter()
+            .any(|t| *t == MBR_TYPE_EFI || *t == MBR_TYPE_GPT_PROTECTIVE),
+    }
+}
+
+// חתימה לבד לא מספיקה - ISO בלי isohybrid מתחיל באפסים, אבל יש כלים שכותבים 55AA בלי מחיצות
+fn partition_table(head: &[u8]) -> bool {
+    let gpt = head.get(GPT_HEADER_OFFSET..GPT_HEADER_OFFSET + 8) == Some(&b"EFI PART"[..]);
+    gpt || isohybrid(head).bios
+}
+
+// תמונת התקנה של Windows: bootmgr בשורש ו-sources/install.wim (בתמונות חדשות
+// install.esd). ב-ISO 9660 של מיקרוסופט יש רק README, אז מחפשים בעץ ה-UDF בלי mount
+pub fn windows_installer(path: &Path) -> io::Result<Option<InstallImage>> {
+    let iso = File::open(path)?;
+    let Some((udf, root)) = Udf::open(&iso)? else {
+        return Ok(None);
+    };
+    if udf.lookup(&root, "bootmgr", false)?.is_none() {
+        return Ok(None);
+    }
+    let Some((sources_name, sources)) = udf.lookup(&root, "sources", true)? else {
+        return Ok(None);
+    };
+    for name in ["install.wim", "install.esd"] {
+        if let Some((found, entry)) = udf.lookup(&sources, name, false)? {
+            return Ok(Some(InstallImage {
+                name: format!("{}/{}", sources_name, found),
+                size: entry.size,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sector(n: usize) -> std::ops::Range<usize> {
+        n * ISO_SECTOR..(n + 1) * ISO_SECTOR
+    }
+
+    fn descriptor_at(head: &mut [u8], n: usize, kind: u8) {
+        let d = &mut head[sector(n)];
+        d[0] = kind;
+        d[1..6].copy_from_slice(b"CD001");
+        d[6] = 1;
+    }
+
+    // כותרות כמו של Ubuntu 24.04: PVD, Boot Record שמצביע לסקטור 20, terminator
+    fn ubuntu_head() -> Vec<u8> {
+        let mut head = vec![0u8; DESCRIPTORS_END];
+        descriptor_at(&mut head, 16, DESCRIPTOR_PRIMARY);
+        let pvd = &mut head[sector(16)];
+        pvd[40..72].fill(b' ');
+        pvd[40..64].copy_from_slice(b"Ubuntu 24.04.1 LTS amd64");
+        pvd[574..702].fill(b' ');
+        pvd[574..581].copy_from_slice(b"XORRISO");
+        pvd[80..84].copy_from_slice(&3_033_454u32.to_le_bytes());
+        pvd[128..130].copy_from_slice(&2048u16.to_le_bytes());
+
+        descriptor_at(&mut head, 17, DESCRIPTOR_BOOT_RECORD);
+        let record = &mut head[sector(17)];
+        record[7..30].copy_from_slice(b"EL TORITO SPECIFICATION");
+        record[0x47..0x4B].copy_from_slice(&20u32.to_le_bytes());
+
+        descriptor_at(&mut head, 18, DESCRIPTOR_TERMINATOR);
+        head
+    }
+
+    // validation (x86) + ברירת מחדל שניתנת לאתחול + קבוצה אחרונה של EFI
+    fn hybrid_catalog() -> Vec<u8> {
+        let mut catalog = vec![0u8; ISO_SECTOR];
+        catalog[0] = 0x01;
+        catalog[1] = PLATFORM_X86;
+        catalog[30..32].copy_from_slice(&[0x55, 0xAA]);
+        catalog[32] = ENTRY_BOOTABLE;
+        catalog[64] = SECTION_HEADER_LAST;
+        catalog[65] = PLATFORM_EFI;
+        catalog[66..68].copy_from_slice(&1u16.to_le_bytes());
+        catalog[96] = ENTRY_BOOTABLE;
+        catalog
+    }
+
+    #[test]
+    fn iso_summary_reads_label_size_and_boot_platforms() {
+        let head = ubuntu_head();
+        assert_eq!(boot_catalog_sector(&head), Some(20));
+
+        let summary = summarize(&head, Some(&hybrid_catalog()), 1234);
+        let volume = summary.iso.as_ref().unwrap();
+        assert_eq!(volume.volume_id, "Ubuntu 24.04.1 LTS amd64");
+        assert_eq!(volume.application_id, "XORRISO");
+        assert_eq!(volume.sectors, 3_033_454);
+        assert_eq!(summary.size, 3_033_454 * 2048);
+        assert_eq!(
+            summary.boot,
+            Boot {
+                bios: true,
+                uefi: true
+            }
+        );
+
+        // בלי קטלוג: רק ה-MBR של isohybrid קובע
+        let mut head = head;
+        head[510..512].copy_from_slice(&[0x55, 0xAA]);
+        head[446 + 4] = 0x17;
+        assert_eq!(
+            summarize(&head, None, 0).boot,
+            Boot {
+                bios: true,
+                uefi: false
+            }
+        );
+        head[446 + 16 + 4] = MBR_TYPE_EFI;
+        assert!(summarize(&head, None, 0).boot.uefi);
+
+        // קטלוג עם מפתח שבור לא נחשב
+        let mut catalog = hybrid_catalog();
+        catalog[31] = 0;
+        assert_eq!(catalog_platforms(&catalog), Boot::default());
+    }
+
+    #[test]
+    fn bios_only_iso_without_a_partition_table_may_not_boot_raw() {
+        // ISO ישן של CD: El Torito ל-BIOS בלבד, בלי isohybrid
+        let head = ubuntu_head();
+        let mut bios_only = hybrid_catalog();
+        bios_only[64..].fill(0);
+        let summary = summarize(&head, Some(&bios_only), 0);
+        assert!(summary.boot.bios && !summary.hybrid);
+        assert!(summary.may_not_boot_raw());
+
+        // רשומת EFI בקטלוג מספיקה
+        assert!(!summarize(&head, Some(&hybrid_catalog()), 0).may_not_boot_raw());
+
+        // וגם isohybrid עם MBR או GPT
+        let mut mbr = head.clone();
+        mbr[510..512].copy_from_slice(&[0x55, 0xAA]);
+        mbr[446 + 4] = 0x17;
+        assert!(!summarize(&mbr, Some(&bios_only), 0).may_not_boot_raw());
+        let mut gpt = head;
+        gpt[512..520].copy_from_slice(b"EFI PART");
+        assert!(!summarize(&gpt, Some(&bios_only), 0).may_not_boot_raw());
+    }
+
+    #[test]
+    fn raw_images_fall_back_to_file_size() {
+        // MBR של תמונת Raspberry Pi - אין CD001, אז גם לא מנחשים אתחול
+        let mut head = vec![0u8; DESCRIPTORS_END];
+        head[510..512].copy_from_slice(&[0x55, 0xAA]);
+        head[446 + 4] = 0x0C;
+        let summary = summarize(&head, None, 2_000_000_000);
+        assert_eq!(summary.iso, None);
+        assert_eq!(summary.size, 2_000_000_000);
+        assert_eq!(summary.boot, Boot::default());
+        assert!(!summary.may_not_boot_raw());
+
+        // קובץ קצר מהכותרות
+        assert_eq!(summarize(&[0u8; 100], None, 100).iso, None);
+        assert_eq!(boot_catalog_sector(&[0u8; 100]), None);
+    }
+}
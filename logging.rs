@@ -0,0 +1,75 @@
+// לוג לקובץ יומי ול-stderr. דיווח על באג מגיע עם הקובץ מהיום שבו זה קרה
+use std::path::PathBuf;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+
+const LOG_PREFIX: &str = "burnstation";
+const LOG_SUFFIX: &str = "log";
+// שבוע אחורה - מספיק לדיווח על צריבה שנכשלה אתמול
+const KEEP_LOG_FILES: usize = 7;
+
+// ~/.local/state/burnstation/logs
+pub fn log_dir() -> PathBuf {
+    dirs::state_dir()
+        .or_else(dirs::data_dir)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("burnstation")
+        .join("logs")
+}
+
+// burnstation.2026-10-16.log - התאריך בשם, אז הגדול ביותר הוא של היום
+pub fn current_log_file() -> Option<PathBuf> {
+    std::fs::read_dir(log_dir())
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_PREFIX) && name.ends_with(LOG_SUFFIX))
+        })
+        .max()
+}
+
+// stderr = false בממשק הטקסט - שם כל שורה על stderr הורסת את המסך. את ה-guard מחזיקים
+// עד היציאה, אחרת השורות האחרונות לא מגיעות לקובץ
+pub fn init(stderr: bool) -> Option<WorkerGuard> {
+    let dir = log_dir();
+    let appender = std::fs::create_dir_all(&dir)
+        .map_err(|e| e.to_string())
+        .and_then(|()| {
+            Builder::new()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_PREFIX)
+                .filename_suffix(LOG_SUFFIX)
+                .max_log_files(KEEP_LOG_FILES)
+                .build(&dir)
+                .map_err(|e| e.to_string())
+        });
+    // בלי קובץ (תיקיית בית לקריאה בלבד) ממשיכים עם stderr בלבד
+    let (file_layer, guard) = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer)
+                .with_filter(LevelFilter::DEBUG);
+            (Some(layer), Some(guard))
+        }
+        Err(e) => {
+            eprintln!("Cannot write the log under {}: {}", dir.display(), e);
+            (None, None)
+        }
+    };
+    let stderr_layer = stderr.then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(LevelFilter::INFO)
+    });
+    let _ = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(stderr_layer)
+        .try_init();
+    guard
+}
@@ -1,43 +1,346 @@
 use gtk4::prelude::*;
 use gtk4::{
-    Application, ApplicationWindow, Box, Button, ComboBoxText, Label, Orientation, ProgressBar,
+    Application, ApplicationWindow, Box, Button, CheckButton, ComboBoxText, HeaderBar, Label,
+    MenuButton, Orientation, ProgressBar, SpinButton,
 };
-use std::cell::RefCell;
-use std::path::PathBuf;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::Arc;
 
+#[cfg(feature = "adwaita")]
+use libadwaita as adw;
+#[cfg(feature = "adwaita")]
+use adw::prelude::AdwApplicationWindowExt;
+
+mod aligned_buf;
+mod ata;
 mod burn_engine;
-use burn_engine::{BurnConfig, BurnEngine, BurnEvent};
+mod checksum;
+mod headless;
+mod history;
+mod logging;
+mod image_info;
+#[macro_use]
+mod i18n;
+#[cfg(test)]
+mod mock_engine;
+mod mount;
+mod multiboot;
+mod partitions;
+mod persistence;
+mod signature;
+mod source;
+#[cfg(feature = "tui")]
+mod tui;
+mod windows;
+use history::BurnHistory;
+use burn_engine::{
+    BadRange, BenchmarkConfig, BenchmarkPhase, BenchmarkResult, BurnBackend, BurnConfig,
+    BurnDirection, BurnProfile, BurnReport, CloneConfig, ReadErrorPolicy, BurnEngine, BurnEvent, ErrorDetail, ErrorKind, Filesystem,
+    FormatConfig, HashAlgorithm, JobState, MultiBurnConfig, RestoreConfig, BurnOutcome, VerifyMethod,
+    VerifyMode, WipeConfig, WipePattern,
+};
+use partitions::PartitionScheme;
+
+// 1. עיצוב קרבי (CSS) - הצבעים מוגדרים בפלטה נפרדת, כהה או בהירה
+#[cfg(not(feature = "adwaita"))]
+const APP_CSS: &str = r#"
+        window { background-color: @bs_bg; color: @bs_fg; }
+        .refresh-button { background-color: @bs_surface; color: @bs_accent; font-weight: bold; border-radius: 8px; }
+        button.suggested-action { background-color: @bs_danger; font-weight: bold; }
+        progressbar progress { background-color: @bs_accent; border-radius: 25px; }
+        label { font-family: 'Assistant', sans-serif; font-size: 14px; }
+        .checksum-ok { color: @bs_ok; font-weight: bold; }
+        .checksum-bad { color: @bs_danger; font-weight: bold; }
+        .warning-text { color: @bs_warning; font-size: 12px; }
+        .warning-banner { background-color: @bs_warning; color: @bs_bg; padding: 6px 12px; border-radius: 6px; }
+    "#;
+
+// Catppuccin Mocha - הפס הכתום והרקע הכהה
+#[cfg(not(feature = "adwaita"))]
+const DARK_PALETTE: &str = r#"
+        @define-color bs_bg #1e1e2e;
+        @define-color bs_fg #cdd6f4;
+        @define-color bs_surface #313244;
+        @define-color bs_accent #fab387;
+        @define-color bs_danger #f38ba8;
+        @define-color bs_ok #a6e3a1;
+        @define-color bs_warning #f9e2af;
+    "#;
+
+// Catppuccin Latte - לסביבה מוארת
+#[cfg(not(feature = "adwaita"))]
+const LIGHT_PALETTE: &str = r#"
+        @define-color bs_bg #eff1f5;
+        @define-color bs_fg #4c4f69;
+        @define-color bs_surface #ccd0da;
+        @define-color bs_accent #fe640b;
+        @define-color bs_danger #d20f39;
+        @define-color bs_ok #40a02b;
+        @define-color bs_warning #df8e1d;
+    "#;
+
+// עם Adwaita הרקע והצבעים מגיעים מערכת הנושא, נשארים רק ההדגשות
+#[cfg(feature = "adwaita")]
+const APP_CSS: &str = r#"
+        .refresh-button { color: @accent_color; font-weight: bold; border-radius: 8px; }
+        button.suggested-action { font-weight: bold; }
+        progressbar progress { background-color: @accent_color; border-radius: 25px; }
+        label { font-family: 'Assistant', sans-serif; font-size: 14px; }
+        .warning-text { color: @warning_color; font-size: 12px; }
+        .warning-banner { background-color: @warning_bg_color; color: @warning_fg_color; padding: 6px 12px; border-radius: 6px; }
+    "#;
+
+const APP_ID: &str = "com.shay.icedburn.pro";
+const CHECKPOINT_INTERVAL: u64 = 512 * 1024 * 1024;
+const BENCHMARK_BYTES: u64 = 1024 * 1024 * 1024;
+// כמה שניות יש למפעיל בעמדת kiosk לבטל לפני שהצריבה מתחילה לבד
+const KIOSK_COUNTDOWN_SECS: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Theme {
+    // לפי ה-portal של המערכת (org.freedesktop.appearance color-scheme)
+    System,
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BurnFilter {
+    RemovableOnly,
+    All,
+}
+
+// עמדת הכנה: תמונה וכונן קבועים מהסביבה, בלי לבנות גרסה לכל פריסה
+#[derive(Debug, Default, PartialEq)]
+struct KioskPreset {
+    iso: Option<PathBuf>,
+    device: Option<PathBuf>,
+    // ICEDBURN_AUTOSTART=1, ורק כששני האחרים קיימים
+    autostart: bool,
+}
+
+impl KioskPreset {
+    fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let path = |name| var(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+        let iso = path("ICEDBURN_ISO");
+        let device = path("ICEDBURN_DEVICE");
+        let autostart = iso.is_some()
+            && device.is_some()
+            && var("ICEDBURN_AUTOSTART").as_deref() == Some("1");
+        Self {
+            iso,
+            device,
+            autostart,
+        }
+    }
+}
+
+// צריבה שהתחילה מה-UI - להתראות, ולצריבה חוזרת אחרי אישור
+struct ActiveBurn {
+    device: String,
+    // לצריבה חוזרת אחרי שהמשתמש אישר לדרוס מערכת קבצים
+    config: BurnConfig,
+}
 
 fn main() -> gtk4::glib::ExitCode {
+    // ב-TUI שורות על stderr היו נכתבות על המסך - שם רק לקובץ
+    #[cfg(feature = "tui")]
+    let _log = logging::init(!tui::requested());
+    #[cfg(not(feature = "tui"))]
+    let _log = logging::init(true);
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "starting");
+
+    // בלי שרת תצוגה: לא נוגעים ב-GTK בכלל
+    if headless::requested() {
+        return if headless::run() {
+            gtk4::glib::ExitCode::SUCCESS
+        } else {
+            gtk4::glib::ExitCode::FAILURE
+        };
+    }
+    #[cfg(feature = "tui")]
+    if tui::requested() {
+        return if tui::run() {
+            gtk4::glib::ExitCode::SUCCESS
+        } else {
+            gtk4::glib::ExitCode::FAILURE
+        };
+    }
+
+    #[cfg(feature = "adwaita")]
+    let app = adw::Application::builder()
+        .application_id(APP_ID)
+        .build();
+    #[cfg(not(feature = "adwaita"))]
     let app = Application::builder()
-        .application_id("com.shay.icedburn.pro")
+        .application_id(APP_ID)
         .build();
-    app.connect_activate(build_ui);
+    app.connect_startup(|app| add_focus_action(app.upcast_ref()));
+    app.connect_activate(|app| build_ui(app.upcast_ref()));
     app.run()
 }
 
+// ההתראה יכולה להפעיל רק action של האפליקציה, אז ה-id של החלון מגיע כפרמטר
+fn add_focus_action(app: &Application) {
+    let action = gtk4::gio::SimpleAction::new("focus-window", Some(gtk4::glib::VariantTy::UINT32));
+    let app_c = app.downgrade();
+    action.connect_activate(move |_, id| {
+        let window = app_c
+            .upgrade()
+            .zip(id.and_then(|id| id.get::<u32>()))
+            .and_then(|(app, id)| app.window_by_id(id));
+        if let Some(window) = window {
+            window.present();
+        }
+    });
+    app.add_action(&action);
+}
+
 fn build_ui(app: &Application) {
-    // 1. עיצוב קרבי (CSS) - הפס הכתום והרקע הכהה
+    build_window(app, Arc::new(BurnEngine::new())).present();
+}
+
+// המנוע מוזרק מבחוץ כדי שבדיקות יוכלו להריץ את ה-UI מול MockBurnEngine
+fn build_window(app: &Application, engine: Arc<dyn BurnBackend>) -> ApplicationWindow {
     let provider = gtk4::CssProvider::new();
-    provider.load_from_data(r#"
-        window { background-color: #1e1e2e; color: #cdd6f4; }
-        .refresh-button { background-color: #313244; color: #fab387; font-weight: bold; border-radius: 8px; }
-        button.suggested-action { background-color: #f38ba8; font-weight: bold; }
-        progressbar progress { background-color: #fab387; border-radius: 25px; }
-        label { font-family: 'Assistant', sans-serif; font-size: 14px; }
-    "#);
+    let display = gtk4::gdk::Display::default().expect("Display error");
     gtk4::style_context_add_provider_for_display(
-        &gtk4::gdk::Display::default().expect("Display error"),
+        &display,
         &provider,
         gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
     );
 
+    // עברית: כל הווידג'טים מימין לשמאל
+    if i18n::is_rtl() {
+        gtk4::Widget::set_default_direction(gtk4::TextDirection::Rtl);
+    }
+
+    #[cfg(feature = "adwaita")]
+    let window: ApplicationWindow = adw::ApplicationWindow::builder()
+        .application(app)
+        .title(window_title(None))
+        .default_width(600)
+        .build()
+        .upcast();
+    #[cfg(not(feature = "adwaita"))]
     let window = ApplicationWindow::builder()
         .application(app)
-        .title("Burn Station Pro 2026")
+        .title(window_title(None))
         .default_width(600)
         .build();
+
+    // תפריט ההמבורגר בכותרת
+    let menu = gtk4::gio::Menu::new();
+    menu.append(Some(tr!("Format Drive…")), Some("win.format"));
+    menu.append(Some(tr!("Keyboard Shortcuts")), Some("win.shortcuts"));
+    menu.append(Some(tr!("Last Burn Summary")), Some("win.last-burn"));
+    menu.append(Some(tr!("Open Log File")), Some("win.open-log"));
+    menu.append(Some(tr!("About Burn Station")), Some("win.about"));
+    let menu_btn = MenuButton::builder()
+        .icon_name("open-menu-symbolic")
+        .menu_model(&menu)
+        .build();
+    let header = HeaderBar::new();
+    header.pack_end(&menu_btn);
+    let history_btn = Button::with_label(tr!("History"));
+    header.pack_start(&history_btn);
+    let theme_combo = ComboBoxText::new();
+    theme_combo.append(Some("system"), tr!("Theme: system"));
+    theme_combo.append(Some("dark"), tr!("Theme: dark"));
+    theme_combo.append(Some("light"), tr!("Theme: light"));
+    theme_combo.set_active_id(Some("system"));
+    if let Some(settings) = app_settings() {
+        settings.bind("theme", &theme_combo, "active-id").build();
+    }
+    header.pack_end(&theme_combo);
+
+    // החלפה בזמן ריצה: טוענים את ה-CSS מחדש ומוסיפים שוב את ה-provider
+    apply_theme(&display, &provider, theme_from_id(theme_combo.active_id().as_deref()));
+    let display_c = display.clone();
+    let provider_c = provider.clone();
+    theme_combo.connect_changed(move |combo| {
+        apply_theme(&display_c, &provider_c, theme_from_id(combo.active_id().as_deref()));
+    });
+    // מצב "מערכת" עוקב גם אחרי שינוי של ההעדפה בזמן ריצה
+    if let Some(gtk_settings) = gtk4::Settings::default() {
+        let theme_combo_c = theme_combo.clone();
+        gtk_settings.connect_gtk_application_prefer_dark_theme_notify(move |_| {
+            let theme = theme_from_id(theme_combo_c.active_id().as_deref());
+            if theme == Theme::System {
+                apply_theme(&display, &provider, theme);
+            }
+        });
+    }
+
+    let about_action = gtk4::gio::SimpleAction::new("about", None);
+    let window_c = window.clone();
+    about_action.connect_activate(move |_, _| {
+        // הגרסה נמשכת מ-Cargo.toml כדי שלא תברח
+        gtk4::AboutDialog::builder()
+            .transient_for(&window_c)
+            .modal(true)
+            .program_name("Burn Station Pro")
+            .version(env!("CARGO_PKG_VERSION"))
+            .license_type(gtk4::License::MitX11)
+            .authors(["Shay"])
+            .website("https://github.com/56tytt/GTK-USB-FLASH")
+            .logo_icon_name("drive-removable-media")
+            .build()
+            .present();
+    });
+    window.add_action(&about_action);
+
+    let last_burn_action = gtk4::gio::SimpleAction::new("last-burn", None);
+    let window_c = window.clone();
+    let engine_c = engine.clone();
+    last_burn_action.connect_activate(move |_, _| match engine_c.last_session() {
+        Some(session) => show_burn_report(&window_c, &session.report()),
+        None => {
+            let dialog = gtk4::MessageDialog::new(
+                Some(&window_c),
+                gtk4::DialogFlags::MODAL,
+                gtk4::MessageType::Info,
+                gtk4::ButtonsType::Close,
+                tr!("No burn has finished since the app started."),
+            );
+            dialog.connect_response(|dialog, _| dialog.close());
+            dialog.present();
+        }
+    });
+    window.add_action(&last_burn_action);
+
+    // הקובץ של היום, בעורך ברירת המחדל. אם עוד לא נכתב - התיקייה
+    let open_log_action = gtk4::gio::SimpleAction::new("open-log", None);
+    let window_c = window.clone();
+    open_log_action.connect_activate(move |_, _| {
+        let path = logging::current_log_file().unwrap_or_else(logging::log_dir);
+        let uri = gtk4::gio::File::for_path(&path).uri();
+        if let Err(e) =
+            gtk4::gio::AppInfo::launch_default_for_uri(&uri, None::<&gtk4::gio::AppLaunchContext>)
+        {
+            tracing::error!(path = %path.display(), "Cannot open the log: {}", e);
+            let dialog = gtk4::MessageDialog::new(
+                Some(&window_c),
+                gtk4::DialogFlags::MODAL,
+                gtk4::MessageType::Error,
+                gtk4::ButtonsType::Close,
+                &tr!("Cannot open {}", path.display()),
+            );
+            dialog.set_secondary_text(Some(&e.to_string()));
+            dialog.connect_response(|dialog, _| dialog.close());
+            dialog.present();
+        }
+    });
+    window.add_action(&open_log_action);
+
     
     let vbox = Box::new(Orientation::Vertical, 15);
     vbox.set_margin_top(30);
@@ -47,146 +350,3070 @@ fn build_ui(app: &Application) {
 
 
     // 2. רכיבי הממשק
-    let iso_label = Label::new(Some("No ISO selected"));
-    let iso_btn = Button::with_label("SELECT ISO");
+    let iso_label = Label::new(Some(tr!("No image selected")));
+    let iso_btn = Button::with_label(tr!("SELECT IMAGE"));
+    let checksum_label = Label::new(None);
+    // שם, גודל ואתחול של התמונה שנבחרה - לפני שמתחילים לצרוב
+    let image_info_label = Label::new(None);
+    image_info_label.set_visible(false);
+    // SHA-256 שמחושב ברקע אחרי הבחירה, להשוואה מול האתר. לחיצה מעתיקה
+    let image_digest_label = Label::new(None);
+    image_digest_label.set_selectable(true);
+    image_digest_label.set_wrap(true);
+    image_digest_label.set_wrap_mode(gtk4::pango::WrapMode::Char);
+    image_digest_label.add_css_class("monospace");
+    image_digest_label.set_tooltip_text(Some(tr!("Click to copy")));
+    image_digest_label.set_visible(false);
+    let digest_click = gtk4::GestureClick::new();
+    digest_click.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let image_digest_c = image_digest_label.clone();
+    digest_click.connect_released(move |_, _, _, _| {
+        if let Some(digest) = image_digest_c.text().split_whitespace().last() {
+            image_digest_c.clipboard().set_text(digest);
+        }
+    });
+    image_digest_label.add_controller(digest_click);
+    // ISO שלא נועד ל-dd. לא עוצר כלום - רק מסביר מראש למה המקל אולי לא יעלה
+    let raw_boot_warning = Label::new(Some(tr!(
+        "This image may not be bootable when written raw — consider extraction mode."
+    )));
+    raw_boot_warning.set_wrap(true);
+    raw_boot_warning.add_css_class("warning-banner");
+    raw_boot_warning.set_visible(false);
+    // טבלת המחיצות של התמונה, מתמלאת כשהמנוע שולח ImageInfo
+    let partitions_grid = gtk4::Grid::builder()
+        .column_spacing(16)
+        .halign(gtk4::Align::Center)
+        .build();
+    let checksum_btn = Button::with_label(tr!("VERIFY CHECKSUM"));
+    let signature_label = Label::new(None);
+    let signature_btn = Button::with_label(tr!("VERIFY SIGNATURE"));
+    let fingerprint_entry = gtk4::Entry::builder()
+        .placeholder_text(tr!("Expected signing key fingerprint (optional)"))
+        .build();
+    // המפתח הציבורי של ההפצה כ-armored. ריק = בוחרים קובץ keyring בלחיצה
+    let signing_key_view = gtk4::TextView::builder().monospace(true).build();
+    let signing_key_scroll = gtk4::ScrolledWindow::builder()
+        .child(&signing_key_view)
+        .min_content_height(80)
+        .build();
+    let signing_key_expander = gtk4::Expander::builder()
+        .label(tr!("Signing key (paste it, or leave empty to pick a keyring)"))
+        .child(&signing_key_scroll)
+        .build();
     let drive_combo = ComboBoxText::new();
-    let scan_btn = Button::with_label("SCAN DEVICES");
+    let scan_btn = Button::with_label(tr!("SCAN DEVICES"));
     scan_btn.add_css_class("refresh-button");
+    // כונן שנשאר עם מחיצה של 4MB אחרי ISO - חוזר למחיצה אחת רגילה
+    let restore_btn = Button::with_label(tr!("RESTORE DRIVE"));
+    restore_btn.add_css_class("destructive-action");
+    let restore_label_entry = gtk4::Entry::builder()
+        .placeholder_text(tr!("Volume label (optional)"))
+        .build();
+    let restore_scheme_combo = ComboBoxText::new();
+    restore_scheme_combo.append(Some("mbr"), tr!("MBR (boots everywhere)"));
+    restore_scheme_combo.append(Some("gpt"), tr!("GPT (drives over 2 TB)"));
+    restore_scheme_combo.set_active(Some(0));
+    let removable_check = CheckButton::with_label(tr!("Show only removable devices"));
+    removable_check.set_active(true);
+    // ההעדפה נשמרת ב-GSettings (אם הסכמה מותקנת)
+    if let Some(settings) = app_settings() {
+        settings
+            .bind("removable-only", &removable_check, "active")
+            .build();
+    }
+    let trim_check = CheckButton::with_label(tr!("TRIM device first (faster on SSDs)"));
+    let wipe_on_cancel_check = CheckButton::with_label(tr!("Erase partial image if cancelled"));
+    let wipe_before_check = CheckButton::with_label(tr!("Zero-fill the whole drive before burning"));
+    let verify_mode_combo = ComboBoxText::new();
+    verify_mode_combo.append(Some("full"), tr!("Verify: full read-back"));
+    verify_mode_combo.append(Some("quick"), tr!("Verify: quick (sampled)"));
+    verify_mode_combo.append(Some("off"), tr!("Verify: off"));
+    verify_mode_combo.set_active(Some(0));
+    // SHA-2 רק כשרוצים להשוות ידנית מול hash שההפצה פרסמה
+    let hash_combo = ComboBoxText::new();
+    hash_combo.append(Some("blake3"), tr!("Hash: BLAKE3 (multi-core)"));
+    hash_combo.append(Some("sha256"), tr!("Hash: SHA-256"));
+    hash_combo.append(Some("sha512"), tr!("Hash: SHA-512"));
+    hash_combo.set_active(Some(0));
+    let byte_compare_check = CheckButton::with_label(tr!("Paranoid verify (re-read the ISO byte by byte)"));
+    let full_scan_check = CheckButton::with_label(tr!("Keep scanning after a mismatch (full report)"));
+    let fast_verify_check = CheckButton::with_label(tr!("Fast verify (CRC32 per block)"));
+    // אבחון לזיכרון או hub פגומים - לא חלק מאף פרופיל
+    let paranoid_check = CheckButton::with_label(tr!("Check each block in memory before writing (diagnostic)"));
+    let eject_check = CheckButton::with_label(tr!("Safely eject when done"));
+    let notify_check = CheckButton::with_label(tr!("Desktop notification when a burn ends"));
+    notify_check.set_active(true);
+    if let Some(settings) = app_settings() {
+        settings
+            .bind("desktop-notifications", &notify_check, "active")
+            .build();
+    }
+    let checkpoint_check = CheckButton::with_label(&tr!(
+        "Sync every {} MB (slower, survives power loss)",
+        CHECKPOINT_INTERVAL / (1024 * 1024)
+    ));
+    // 0 = בלי הגבלה
+    let speed_limit_spin = SpinButton::with_range(0.0, 1000.0, 1.0);
+    speed_limit_spin.set_value(0.0);
+    // גודל מחיצת persistence ב-GB, 0 = בלי. מוצג רק לתמונת live של Ubuntu/Debian
+    let persistence_scale = gtk4::Scale::with_range(Orientation::Horizontal, 0.0, 64.0, 1.0);
+    persistence_scale.set_digits(0);
+    persistence_scale.set_hexpand(true);
+    let data_partition_check = CheckButton::with_label(tr!("Use remaining space as data partition"));
+    // תמונת Windows לא נכתבת כמו שהיא - מודיעים לפני הצריבה
+    let windows_label = Label::new(None);
+    windows_label.set_wrap(true);
+    windows_label.set_visible(false);
+
+    // פרופיל ממלא את האפשרויות שלמטה, ואפשר לשנות כל אחת מהן אחריו
+    let profile_combo = ComboBoxText::new();
+    profile_combo.append(Some("fast"), tr!("Fast"));
+    profile_combo.append(Some("balanced"), tr!("Balanced"));
+    profile_combo.append(Some("paranoid"), tr!("Paranoid"));
+    let profile = Rc::new(Cell::new(BurnProfile::default()));
+    let profile_c = profile.clone();
+    let verify_mode_c = verify_mode_combo.clone();
+    let hash_c = hash_combo.clone();
+    let byte_compare_c = byte_compare_check.clone();
+    let checkpoint_c = checkpoint_check.clone();
+    let trim_c = trim_check.clone();
+    profile_combo.connect_changed(move |combo| {
+        let selected = match combo.active_id().as_deref() {
+            Some("fast") => BurnProfile::Fast,
+            Some("paranoid") => BurnProfile::Paranoid,
+            _ => BurnProfile::Balanced,
+        };
+        profile_c.set(selected);
+        let overrides = selected.into_config_overrides();
+        verify_mode_c.set_active_id(Some(match overrides.verify_mode {
+            VerifyMode::Full => "full",
+            VerifyMode::Quick => "quick",
+            VerifyMode::Off => "off",
+        }));
+        hash_c.set_active_id(Some(match overrides.hash_algorithm {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+            _ => "blake3",
+        }));
+        byte_compare_c.set_active(overrides.byte_compare);
+        checkpoint_c.set_active(overrides.sync_interval_bytes.is_some());
+        let interval = overrides.sync_interval_bytes.unwrap_or(CHECKPOINT_INTERVAL);
+        checkpoint_c.set_label(Some(&tr!(
+            "Sync every {} MB (slower, survives power loss)",
+            interval / (1024 * 1024)
+        )));
+        trim_c.set_active(overrides.pre_discard);
+    });
+    profile_combo.set_active_id(Some("balanced"));
     let progress_bar = ProgressBar::new();
-    let status_label = Label::new(Some("Ready to Create Magic."));
-    let start_btn = Button::with_label("START BURNING");
+    progress_bar.set_widget_name("progress");
+    // עבודות שמחכות, רצות ונגמרו - שורה לכל אחת, עם ביטול משלה
+    let jobs_list = Box::new(Orientation::Vertical, 2);
+    let status_label = Label::new(Some(tr!("Ready to Create Magic.")));
+    status_label.set_widget_name("status");
+    // אזהרות לא עוצרות את הצריבה ולא דורסות את הסטטוס
+    let warnings_list = Box::new(Orientation::Vertical, 4);
+    let warnings_scroll = gtk4::ScrolledWindow::builder()
+        .child(&warnings_list)
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .max_content_height(80)
+        .propagate_natural_height(true)
+        .visible(false)
+        .build();
+    // כל אירוע של המנוע כמו שהוא, עם שעה - לדיווחי באגים ולמי שרוצה לראות מה קורה
+    let log_view = gtk4::TextView::builder()
+        .editable(false)
+        .cursor_visible(false)
+        .monospace(true)
+        .build();
+    let log_scroll = gtk4::ScrolledWindow::builder()
+        .child(&log_view)
+        .min_content_height(160)
+        .build();
+    let copy_log_btn = Button::with_label(tr!("Copy Log"));
+    copy_log_btn.set_halign(gtk4::Align::Start);
+    let log_view_c = log_view.clone();
+    copy_log_btn.connect_clicked(move |btn| {
+        let buffer = log_view_c.buffer();
+        let (start, end) = buffer.bounds();
+        btn.clipboard().set_text(&buffer.text(&start, &end, false));
+    });
+    let log_box = Box::new(Orientation::Vertical, 6);
+    log_box.append(&log_scroll);
+    log_box.append(&copy_log_btn);
+    let log_expander = gtk4::Expander::builder()
+        .label(tr!("Technical Log"))
+        .child(&log_box)
+        .build();
+    let start_btn = Button::with_label(tr!("START BURNING"));
     start_btn.add_css_class("suggested-action");
+    start_btn.set_widget_name("start");
+    let verify_only_btn = Button::with_label(tr!("VERIFY ONLY"));
+    verify_only_btn.set_sensitive(false);
+    verify_only_btn.set_widget_name("verify-only");
+    let clone_btn = Button::with_label(tr!("CREATE IMAGE FROM DRIVE"));
+    // אותו קובץ בלי דחיסה ובלי לדלג על שגיאות - העתק מדויק של הכונן
+    let backup_btn = Button::with_label(tr!("BACKUP DEVICE"));
+    // מקור להעתקה ישירה; היעד הוא הכונן שנבחר למעלה
+    let copy_source_combo = ComboBoxText::new();
+    let copy_btn = Button::with_label(tr!("COPY SOURCE DRIVE ONTO SELECTED DRIVE"));
+    copy_btn.add_css_class("destructive-action");
+    // אותה תמונה לכמה כוננים בבת אחת - CheckButton לכל כונן, ושורת התקדמות לכל יעד
+    let multi_targets_list = Box::new(Orientation::Vertical, 2);
+    let multi_burn_btn = Button::with_label(tr!("BURN TO ALL CHECKED DRIVES"));
+    multi_burn_btn.add_css_class("destructive-action");
+    let multi_rows_box = Box::new(Orientation::Vertical, 4);
+    let multi_rows = Rc::new(RefCell::new(HashMap::<PathBuf, (ProgressBar, Label)>::new()));
+    let read_error_combo = ComboBoxText::new();
+    read_error_combo.append(Some("abort"), tr!("On read error: stop"));
+    read_error_combo.append(Some("zero-fill"), tr!("On read error: fill with zeros and continue"));
+    read_error_combo.set_active(Some(0));
+    // רק לשם קובץ שנגמר ב-.gz/.xz/.zst/.bz2
+    let compression_level_spin = SpinButton::with_range(0.0, 22.0, 1.0);
+    compression_level_spin.set_value(0.0);
+    let wipe_pattern_combo = ComboBoxText::new();
+    wipe_pattern_combo.append(Some("zeros"), tr!("Zeros (1 pass)"));
+    wipe_pattern_combo.append(Some("ones"), tr!("0xFF (1 pass)"));
+    wipe_pattern_combo.append(Some("random"), tr!("Random data (1 pass)"));
+    wipe_pattern_combo.append(Some("random-zeros"), tr!("Random, then zeros (2 passes)"));
+    wipe_pattern_combo.set_active(Some(0));
+    let wipe_btn = Button::with_label(tr!("WIPE DRIVE"));
+    wipe_btn.add_css_class("destructive-action");
+    let secure_erase_btn = Button::with_label(tr!("ATA SECURE ERASE"));
+    secure_erase_btn.add_css_class("destructive-action");
+    let surface_test_btn = Button::with_label(tr!("SURFACE TEST"));
+    surface_test_btn.add_css_class("destructive-action");
+    let capacity_test_btn = Button::with_label(tr!("FAKE CAPACITY TEST (erases drive)"));
+    capacity_test_btn.add_css_class("destructive-action");
+    let multiboot_btn = Button::with_label(tr!("PREPARE MULTIBOOT STICK"));
+    multiboot_btn.add_css_class("destructive-action");
+    let benchmark_btn = Button::with_label(tr!("BENCHMARK"));
+    let benchmark_write_check =
+        CheckButton::with_label(tr!("Also benchmark writes (erases the first 1 GB)"));
+    let cancel_btn = Button::with_label(tr!("CANCEL"));
+    cancel_btn.set_widget_name("cancel");
+
+    // הפעולות הראשיות הן actions של החלון, והכפתורים רק מפעילים אותן - כך הקיצור, הכפתור
+    // והרגישות שלו תמיד מסכימים. של החלון ולא של האפליקציה: בכל חלון מנוע וכונן משלו
+    let select_image_action = button_action(&window, "select-image", &iso_btn, "<Control>o");
+    let scan_action = button_action(&window, "scan", &scan_btn, "F5");
+    let start_action = button_action(&window, "start", &start_btn, "<Control>Return");
+    let cancel_action = button_action(&window, "cancel", &cancel_btn, "Escape");
 
     // חיבור כפתור ה-SCAN לפונקציית הסריקה
     let drive_combo_clone = drive_combo.clone();
-    scan_btn.connect_clicked(move |_| {
-        update_device_list(&drive_combo_clone);
+    let copy_source_c = copy_source_combo.clone();
+    let multi_targets_c = multi_targets_list.clone();
+    let removable_c = removable_check.clone();
+    scan_action.connect_activate(move |_, _| {
+        update_device_list(&drive_combo_clone, current_filter(&removable_c));
+        update_device_list(&copy_source_c, current_filter(&removable_c));
+        fill_target_checks(&multi_targets_c, &scan_devices(current_filter(&removable_c)));
+    });
+
+    let drive_combo_clone = drive_combo.clone();
+    let copy_source_c = copy_source_combo.clone();
+    let multi_targets_c = multi_targets_list.clone();
+    removable_check.connect_toggled(move |check| {
+        update_device_list(&drive_combo_clone, current_filter(check));
+        update_device_list(&copy_source_c, current_filter(check));
+        fill_target_checks(&multi_targets_c, &scan_devices(current_filter(check)));
     });
 
     // סריקה ראשונית אוטומטית כשהתוכנה נדלקת
-    update_device_list(&drive_combo);
+    let devices = scan_devices(current_filter(&removable_check));
+    fill_device_combo(&drive_combo, &devices);
+    fill_device_combo(&copy_source_combo, &devices);
+    fill_target_checks(&multi_targets_list, &devices);
+
+    // גיבוי בלבד - סריקה כל 30 שניות תופסת התקנים שאף אחד לא דיווח עליהם (למשל loop
+    // שנוצר עכשיו). ה-combo מתעדכן רק כשרשימת ההתקנים השתנתה, כדי לא לאפס אותו באמצע צריבה
+    let drive_combo_clone = drive_combo.clone();
+    let copy_source_c = copy_source_combo.clone();
+    let multi_targets_c = multi_targets_list.clone();
+    let removable_c = removable_check.clone();
+    let mut device_snapshot: Vec<String> =
+        devices.into_iter().map(|(dev_path, _)| dev_path).collect();
+    let refresh_timer = gtk4::glib::timeout_add_seconds_local(30, move || {
+        let devices = scan_devices(current_filter(&removable_c));
+        let names: Vec<String> = devices.iter().map(|(dev_path, _)| dev_path.clone()).collect();
+        if names != device_snapshot {
+            fill_device_combo(&drive_combo_clone, &devices);
+            fill_device_combo(&copy_source_c, &devices);
+            fill_target_checks(&multi_targets_c, &devices);
+            device_snapshot = names;
+        }
+        gtk4::glib::ControlFlow::Continue
+    });
+    let refresh_timer = RefCell::new(Some(refresh_timer));
+    window.connect_close_request(move |_| {
+        if let Some(timer) = refresh_timer.borrow_mut().take() {
+            timer.remove();
+        }
+        gtk4::glib::Propagation::Proceed
+    });
 
     // סידור על המסך
     vbox.append(&iso_btn);
-    vbox.append(&iso_label);
-    vbox.append(&scan_btn);
+    let iso_row = Box::new(Orientation::Horizontal, 10);
+    iso_row.set_halign(gtk4::Align::Center);
+    iso_row.append(&iso_label);
+    iso_row.append(&checksum_label);
+    iso_row.append(&signature_label);
+    vbox.append(&iso_row);
+    vbox.append(&image_info_label);
+    vbox.append(&image_digest_label);
+    vbox.append(&raw_boot_warning);
+    vbox.append(&partitions_grid);
+    vbox.append(&checksum_btn);
+    vbox.append(&fingerprint_entry);
+    vbox.append(&signing_key_expander);
+    vbox.append(&signature_btn);
+    let scan_row = Box::new(Orientation::Horizontal, 10);
+    scan_row.append(&scan_btn);
+    scan_row.append(&restore_btn);
+    scan_row.append(&restore_label_entry);
+    scan_row.append(&restore_scheme_combo);
+    vbox.append(&scan_row);
+    vbox.append(&removable_check);
     vbox.append(&drive_combo);
+    let profile_row = Box::new(Orientation::Horizontal, 10);
+    profile_row.append(&Label::new(Some(tr!("Burn Profile"))));
+    profile_row.append(&profile_combo);
+    vbox.append(&profile_row);
+    vbox.append(&trim_check);
+    vbox.append(&wipe_on_cancel_check);
+    vbox.append(&wipe_before_check);
+    vbox.append(&verify_mode_combo);
+    vbox.append(&hash_combo);
+    vbox.append(&byte_compare_check);
+    vbox.append(&full_scan_check);
+    vbox.append(&fast_verify_check);
+    vbox.append(&paranoid_check);
+    vbox.append(&eject_check);
+    vbox.append(&notify_check);
+    vbox.append(&checkpoint_check);
+    let speed_limit_row = Box::new(Orientation::Horizontal, 10);
+    speed_limit_row.append(&Label::new(Some(tr!("Max write speed (MB/s, 0 = unlimited)"))));
+    speed_limit_row.append(&speed_limit_spin);
+    vbox.append(&speed_limit_row);
+    let persistence_row = Box::new(Orientation::Horizontal, 10);
+    persistence_row.append(&Label::new(Some(tr!("Persistent storage (GB, 0 = off)"))));
+    persistence_row.append(&persistence_scale);
+    persistence_row.set_visible(false);
+    vbox.append(&persistence_row);
+    vbox.append(&data_partition_check);
+    vbox.append(&windows_label);
     vbox.append(&progress_bar);
+    vbox.append(&jobs_list);
     vbox.append(&status_label);
+    vbox.append(&warnings_scroll);
     vbox.append(&start_btn);
-    window.set_child(Some(&vbox));
+    vbox.append(&verify_only_btn);
+    vbox.append(&read_error_combo);
+    let compression_level_row = Box::new(Orientation::Horizontal, 10);
+    compression_level_row.append(&Label::new(Some(tr!("Compression level (0 = default)"))));
+    compression_level_row.append(&compression_level_spin);
+    vbox.append(&compression_level_row);
+    vbox.append(&clone_btn);
+    vbox.append(&backup_btn);
+    let copy_source_row = Box::new(Orientation::Horizontal, 10);
+    copy_source_row.append(&Label::new(Some(tr!("Source drive:"))));
+    copy_source_row.append(&copy_source_combo);
+    vbox.append(&copy_source_row);
+    vbox.append(&copy_btn);
+    vbox.append(&Label::new(Some(tr!("Burn the same image to several drives:"))));
+    vbox.append(&multi_targets_list);
+    vbox.append(&multi_burn_btn);
+    vbox.append(&multi_rows_box);
+    vbox.append(&wipe_pattern_combo);
+    vbox.append(&wipe_btn);
+    vbox.append(&secure_erase_btn);
+    vbox.append(&surface_test_btn);
+    vbox.append(&capacity_test_btn);
+    vbox.append(&multiboot_btn);
+    vbox.append(&benchmark_write_check);
+    vbox.append(&benchmark_btn);
+    vbox.append(&cancel_btn);
+    vbox.append(&log_expander);
+    set_window_content(&window, &header, &vbox);
 
     // 3. חיבור המנוע והעברת הודעות (The Bridge)
-    let engine = Arc::new(BurnEngine::new());
     let (sender, receiver) = gtk4::glib::MainContext::channel::<BurnEvent>(gtk4::glib::Priority::DEFAULT);
     
     // --- התיקון הקריטי: הגישור ---
     // פותחים חוט ברקע שלוקח מהמנוע ודוחף ל-UI בזמן אמת
-    let event_rx = engine.event_rx.clone();
-    std::thread::spawn(move || {
-        while let Ok(event) = event_rx.recv() {
-            if sender.send(event).is_err() {
-                break; // החלון נסגר, מפסיקים להעביר
-            }
+    let event_rx = engine.events();
+    // המנוע חי כל עוד התהליך חי, אז recv לבד לא היה נגמר. כשהחלון נהרס זורקים את
+    // stop_tx, ה-select מתעורר, וה-sender של glib נזרק יחד עם ה-thread
+    let (stop_tx, stop_rx) = crossbeam_channel::bounded::<()>(0);
+    let bridge = std::thread::spawn(move || loop {
+        crossbeam_channel::select! {
+            recv(event_rx) -> event => match event {
+                Ok(event) => {
+                    if sender.send(event).is_err() {
+                        break; // ה-main context כבר פורק
+                    }
+                }
+                Err(_) => break,
+            },
+            recv(stop_rx) -> _ => break,
+        }
+    });
+    let bridge = RefCell::new(Some((stop_tx, bridge)));
+    window.connect_destroy(move |_| {
+        if let Some((stop_tx, bridge)) = bridge.borrow_mut().take() {
+            drop(stop_tx);
+            // לא נחסם: ה-send של glib לא מחכה, וה-select יוצא מיד
+            let _ = bridge.join();
         }
     });
     let iso_path = Arc::new(RefCell::new(None::<PathBuf>));
+    // ה-hash של הרקע לתמונה הנוכחית - האירועים שלו הולכים לשורת ה-digest ולא לפס ההתקדמות
+    let hash_job = Rc::new(Cell::new(None::<u64>));
+    let history = Rc::new(RefCell::new(BurnHistory::load()));
+    let active_burns = Rc::new(RefCell::new(HashMap::<u64, ActiveBurn>::new()));
+    // None = לא נבדק, Some(false) = לא תואם
+    let checksum_ok = Rc::new(Cell::new(None::<bool>));
+    let signature_ok = Rc::new(Cell::new(None::<bool>));
+    // כונן multiboot שכבר הוכן: START מעתיק אליו את ה-ISO במקום לצרוב
+    let multiboot_mode = Rc::new(Cell::new(false));
+    // תמונת Windows שהמשתמש בחר לכתוב כמו שהיא, בלי העתקה ל-FAT32
+    let windows_raw = Rc::new(Cell::new(false));
+    let multiboot_mode_c = multiboot_mode.clone();
+    let drive_c = drive_combo.clone();
+    let start_btn_c = start_btn.clone();
+    let refresh_multiboot = Rc::new(move || {
+        let multiboot = drive_c
+            .active_id()
+            .is_some_and(|dev| burn_engine::is_multiboot(Path::new(dev.as_str())));
+        multiboot_mode_c.set(multiboot);
+        start_btn_c.set_label(if multiboot { tr!("ADD ISO TO STICK") } else { tr!("START BURNING") });
+    });
     // עדכון ה-UI כשהמנוע שולח הודעה
     let progress_clone = progress_bar.clone();
     let status_clone = status_label.clone();
+    let history_c = history.clone();
+    let active_burns_c = active_burns.clone();
+    let jobs_list_c = jobs_list.clone();
+    let mut job_rows = HashMap::<u64, (Label, Button, String)>::new();
+    let engine_jobs = engine.clone();
+    let checksum_label_c = checksum_label.clone();
+    let checksum_ok_c = checksum_ok.clone();
+    let signature_label_c = signature_label.clone();
+    let signature_ok_c = signature_ok.clone();
+    let image_digest_c = image_digest_label.clone();
+    let hash_job_c = hash_job.clone();
+    let iso_path_c = iso_path.clone();
+    let partitions_grid_c = partitions_grid.clone();
+    let warnings_list_c = warnings_list.clone();
+    let warnings_scroll_c = warnings_scroll.clone();
+    let multi_rows_c = multi_rows.clone();
+    let notify_check_c = notify_check.clone();
+    let refresh_multiboot_c = refresh_multiboot.clone();
+    let app_c = app.clone();
+    // הצריבה שרצה עכשיו, כדי לדעת לאיזה כונן ההתראה מתייחסת
+    let mut current_job = None::<u64>;
+    let window_c = window.clone();
     receiver.attach(None, move |event| {
+        append_log(&log_view, &event);
         match event {
+            BurnEvent::Preparing => {
+                // אזהרות מהפעולה הקודמת
+                while let Some(child) = warnings_list_c.first_child() {
+                    warnings_list_c.remove(&child);
+                }
+                warnings_scroll_c.set_visible(false);
+                progress_clone.set_show_text(false);
+            }
             BurnEvent::Progress {
                 written,
                 total,
+                read_speed_mbps,
+                write_speed_mbps,
+                eta_secs,
+            } => {
+                // total = 0: מקור דחוס שהגודל הפתוח שלו לא ידוע - מהבהבים במקום אחוזים
+                let fraction = written as f64 / total.max(1) as f64;
+                if total == 0 {
+                    progress_clone.pulse();
+                } else {
+                    progress_clone.set_fraction(fraction);
+                }
+                const MB: f64 = 1024.0 * 1024.0;
+                // קריאה מהירה בהרבה מהכתיבה = הכונן הוא צוואר הבקבוק. 0 = אין מקור (secure erase)
+                let mut text = if read_speed_mbps > 0.0 {
+                    tr!(
+                        "Read {} MB/s | Write {} MB/s",
+                        format!("{:.1}", read_speed_mbps),
+                        format!("{:.1}", write_speed_mbps)
+                    )
+                } else {
+                    format!("{:.1} MB/s", write_speed_mbps)
+                };
+                if total == 0 {
+                    text.push_str(&format!(" | {:.1} MB", written as f64 / MB));
+                } else {
+                    text.push_str(&format!(
+                        " | {:.1} / {:.1} MB | {:.1}%",
+                        written as f64 / MB,
+                        total as f64 / MB,
+                        fraction * 100.0
+                    ));
+                }
+                // 0 = עוד אין מהירות להעריך לפיה
+                if eta_secs > 0 {
+                    text.push_str(" | ");
+                    text.push_str(&format_duration(eta_secs as f64));
+                }
+                status_clone.set_text(&text);
+            }
+            BurnEvent::Finished {
+                bytes,
+                elapsed_secs,
+                verified,
+            } => {
+                let avg_mbps = if elapsed_secs > 0.0 {
+                    bytes as f64 / elapsed_secs / (1024.0 * 1024.0)
+                } else {
+                    0.0
+                };
+                status_clone.set_text(&tr!(
+                    "Done in {} — {} at avg {} MB/s{}",
+                    format_duration(elapsed_secs),
+                    format_bytes(bytes),
+                    format!("{:.1}", avg_mbps),
+                    if verified { tr!(", verified OK") } else { "" }
+                ));
+                progress_clone.set_fraction(1.0);
+                let device = current_job
+                    .and_then(|id| active_burns_c.borrow().get(&id).map(|b| b.device.clone()));
+                if let Some(device) = device {
+                    if notify_check_c.is_active() && !window_c.is_active() {
+                        notify_desktop(&app_c, &window_c, tr!("Burn complete"), &tr!("ISO successfully written to {}", device));
+                    }
+                }
+                // צריבה רגילה דורסת כונן multiboot
+                refresh_multiboot_c();
+            }
+            BurnEvent::JobQueued {
+                id,
+                label,
+                position,
+            } => {
+                let row = Box::new(Orientation::Horizontal, 6);
+                let text = Label::new(Some(&tr!("Waiting ({} ahead): {}", position, label)));
+                text.set_xalign(0.0);
+                text.set_hexpand(true);
+                let cancel = Button::with_label("✕");
+                cancel.set_tooltip_text(Some(tr!("Cancel this job")));
+                let engine_c = engine_jobs.clone();
+                cancel.connect_clicked(move |_| {
+                    engine_c.cancel_job(id);
+                });
+                row.append(&text);
+                row.append(&cancel);
+                jobs_list_c.append(&row);
+                job_rows.insert(id, (text, cancel, label));
+            }
+            BurnEvent::JobStarted { id } => {
+                current_job = Some(id);
+                // פירוט כשל בדיקה קודם לא שייך לעבודה הזו
+                status_clone.set_tooltip_text(None);
+                if let Some((text, _, label)) = job_rows.get(&id) {
+                    text.set_text(&tr!("Running: {}", label));
+                }
+                // ה-"N ahead" של מי שמחכה התיישן - מבקשים את התור מחדש
+                engine_jobs.queue_status();
+            }
+            BurnEvent::QueueStatus { jobs } => {
+                let mut ahead = 0;
+                for job in jobs {
+                    match job.state {
+                        JobState::Pending => {
+                            if let Some((text, _, label)) = job_rows.get(&job.id) {
+                                text.set_text(&tr!("Waiting ({} ahead): {}", ahead, label));
+                            }
+                            ahead += 1;
+                        }
+                        JobState::Active => ahead += 1,
+                        _ => {}
+                    }
+                }
+            }
+            BurnEvent::JobEnded { id, state } => {
+                if let Some((text, cancel, label)) = job_rows.get(&id) {
+                    text.set_text(&match state {
+                        JobState::Done => format!("✔ {}", label),
+                        JobState::Failed => format!("✘ {}", label),
+                        _ => tr!("Cancelled: {}", label),
+                    });
+                    cancel.set_visible(false);
+                }
+                // ה-hash של הרקע פינה מקום לצריבה או נכשל - אין digest להראות
+                if Some(id) == hash_job_c.get() && state != JobState::Done {
+                    image_digest_c.set_visible(false);
+                }
+                active_burns_c.borrow_mut().remove(&id);
+            }
+            BurnEvent::Report(report) => {
+                // כשלא נכתב כלום (סירוב לפני הכתיבה) השגיאה כבר בשורת הסטטוס ואולי בדיאלוג
+                if report.bytes_written > 0 || report.outcome == BurnOutcome::Completed {
+                    show_burn_report(&window_c, &report);
+                }
+                if let Err(e) = history_c.borrow_mut().append(report) {
+                    tracing::error!("Failed to save burn history: {}", e);
+                }
+            }
+            BurnEvent::Error(e) => {
+                if e.kind == ErrorKind::BadSignature {
+                    signature_ok_c.set(Some(false));
+                    signature_label_c.set_text(tr!("✘ SIGNATURE"));
+                    signature_label_c.remove_css_class("checksum-ok");
+                    signature_label_c.add_css_class("checksum-bad");
+                }
+                match e.mismatch() {
+                    // הפירוט עם הבייטים ארוך מדי לשורת הסטטוס - הוא ב-tooltip
+                    Some(mismatch) => {
+                        status_clone.set_text(&tr!(
+                            "Verify failed at offset {} — possible bad block",
+                            format!("0x{:X}", mismatch.offset)
+                        ));
+                        status_clone.set_tooltip_text(Some(&e.message));
+                    }
+                    None => status_clone.set_text(&e.to_user_string()),
+                }
+                // מצב בטוח: הצריבה לא התחילה. אחרי אישור היא רצה שוב, הפעם דורסת
+                let refused = current_job
+                    .and_then(|id| active_burns_c.borrow().get(&id).map(|b| b.config.clone()));
+                // מחיצה מעוגנת - אותו אישור, והדריסה מנתקת אותה קודם
+                let overwrite = refused.clone().and_then(|config| {
+                    let device = config.device_path.display().to_string();
+                    let text = match (e.fs_type(), e.detail()) {
+                        (Some(fs_type), _) => tr!(
+                            "{} already has a {} filesystem — everything on it will be erased. Burn anyway?",
+                            device,
+                            fs_type
+                        ),
+                        (_, Some(ErrorDetail::Unmount { mounts })) => tr!(
+                            "{} is mounted at {}. Unmount it and burn?",
+                            device,
+                            mounts.first()?.display()
+                        ),
+                        _ => return None,
+                    };
+                    Some((text, config))
+                });
+                if let Some((text, config)) = overwrite {
+                    let engine_c = engine_jobs.clone();
+                    let active_burns_c = active_burns_c.clone();
+                    confirm_destructive(&window_c, &text, move || {
+                        let config = BurnConfig {
+                            force_overwrite: true,
+                            ..config.clone()
+                        };
+                        let burn = ActiveBurn {
+                            device: config.device_path.display().to_string(),
+                            config: config.clone(),
+                        };
+                        let id = engine_c.start(config);
+                        active_burns_c.borrow_mut().insert(id, burn);
+                    });
+                }
+                // אין הרשאה לכונן: אותה צריבה שוב, דרך pkexec
+                if let (Some(ErrorDetail::PermissionDenied { path }), Some(config)) = (e.detail(), refused) {
+                    offer_elevated_retry(&window_c, &status_clone, &progress_clone, path, config);
+                }
+                // רק על צריבה - בדיקות ו-benchmark לא מקפיצים התראה
+                let is_burn = current_job.is_some_and(|id| active_burns_c.borrow().contains_key(&id));
+                if is_burn && notify_check_c.is_active() && !window_c.is_active() {
+                    notify_desktop(&app_c, &window_c, tr!("Burn failed"), &e.to_string());
+                }
+            }
+            BurnEvent::Verifying { checked, total } => {
+                // הפס מתחיל מחדש מ-0 אחרי שלב הכתיבה
+                let fraction = checked as f64 / total.max(1) as f64;
+                progress_clone.set_fraction(fraction);
+                status_clone.set_text(&tr!("Verifying… {}%", (fraction * 100.0) as u64));
+            }
+            BurnEvent::WipePass { pass, passes } => {
+                progress_clone.set_fraction(0.0);
+                status_clone.set_text(&tr!("Wiping… pass {}/{}", pass, passes));
+            }
+            BurnEvent::VerifySummary { method, ok } => {
+                let method = match method {
+                    VerifyMethod::Hash(algorithm) => algorithm.name(),
+                    VerifyMethod::Crc32 => "CRC32",
+                    VerifyMethod::ByteCompare => tr!("byte compare"),
+                    VerifyMethod::Sampled => tr!("sampled"),
+                };
+                let result = if ok { tr!("passed") } else { tr!("FAILED") };
+                status_clone.set_text(&tr!("Verify {} ({})", result, method));
+            }
+            BurnEvent::Warning { message } => {
+                let label = Label::new(Some(&format!("⚠ {}", message)));
+                label.add_css_class("warning-text");
+                label.set_wrap(true);
+                label.set_xalign(0.0);
+                warnings_list_c.append(&label);
+                warnings_scroll_c.set_visible(true);
+            }
+            BurnEvent::ImageInfo { partitions } => {
+                while let Some(child) = partitions_grid_c.first_child() {
+                    partitions_grid_c.remove(&child);
+                }
+                if partitions.is_empty() {
+                    let label = Label::new(Some(tr!("Single filesystem, no partition table")));
+                    partitions_grid_c.attach(&label, 0, 0, 1, 1);
+                } else {
+                    for (col, title) in ["#", tr!("Type"), tr!("Start"), tr!("Size")].iter().enumerate() {
+                        let label = Label::new(Some(*title));
+                        label.add_css_class("heading");
+                        partitions_grid_c.attach(&label, col as i32, 0, 1, 1);
+                    }
+                    for (row, p) in partitions.iter().enumerate() {
+                        let cells = [
+                            p.index.to_string(),
+                            p.kind.clone(),
+                            format_bytes(p.start),
+                            format_bytes(p.size),
+                        ];
+                        for (col, text) in cells.iter().enumerate() {
+                            let label = Label::new(Some(text.as_str()));
+                            label.set_xalign(0.0);
+                            partitions_grid_c.attach(&label, col as i32, row as i32 + 1, 1, 1);
+                        }
+                    }
+                }
+            }
+            BurnEvent::VerifySkipped { reason } => {
+                status_clone.set_text(&tr!("Verify skipped: {}", reason));
+            }
+            BurnEvent::Wiping { wiped, total } => {
+                let fraction = wiped as f64 / total.max(1) as f64;
+                progress_clone.set_fraction(fraction);
+                status_clone.set_text(&tr!("Wiping… {}%", (fraction * 100.0) as u64));
+            }
+            BurnEvent::Hashing { done, total } => {
+                let fraction = done as f64 / total.max(1) as f64;
+                if current_job.is_some() && current_job == hash_job_c.get() {
+                    image_digest_c.set_text(&tr!("SHA-256: computing… {}%", (fraction * 100.0) as u64));
+                } else {
+                    progress_clone.set_fraction(fraction);
+                    status_clone.set_text(&tr!("Hashing ISO… {}%", (fraction * 100.0) as u64));
+                }
+            }
+            BurnEvent::ImageHashed { path, digest, .. } => {
+                // תוצאה של תמונה שכבר הוחלפה
+                if iso_path_c.borrow().as_deref() == Some(path.as_path()) {
+                    image_digest_c.set_text(&tr!("SHA-256: {}", digest));
+                }
+            }
+            BurnEvent::ChecksumChecked {
+                algorithm,
+                matches,
+                digest,
+            } => {
+                checksum_ok_c.set(Some(matches));
+                checksum_label_c.remove_css_class("checksum-ok");
+                checksum_label_c.remove_css_class("checksum-bad");
+                if matches {
+                    checksum_label_c.set_text(&tr!("✔ {} OK", algorithm.name()));
+                    checksum_label_c.add_css_class("checksum-ok");
+                    if algorithm.is_weak() {
+                        // MD5/SHA-1 מוכיחים רק שההורדה שלמה, לא שהיא אמיתית
+                        status_clone.set_text(&tr!(
+                            "Checksum matches {} — note: {} is not collision-resistant.",
+                            algorithm.name(),
+                            algorithm.name()
+                        ));
+                    } else {
+                        status_clone.set_text(tr!("Checksum matches."));
+                    }
+                } else {
+                    checksum_label_c.set_text(&tr!("✘ {} MISMATCH", algorithm.name()));
+                    checksum_label_c.add_css_class("checksum-bad");
+                    status_clone.set_text(&tr!("Checksum mismatch! Image hash is {}", digest));
+                }
+                progress_clone.set_fraction(1.0);
+            }
+            BurnEvent::SignatureVerified { signer } => {
+                signature_ok_c.set(Some(true));
+                signature_label_c.set_text(tr!("✔ SIGNED"));
+                signature_label_c.remove_css_class("checksum-bad");
+                signature_label_c.add_css_class("checksum-ok");
+                status_clone.set_text(&tr!("Good signature from {}", signer));
+            }
+            BurnEvent::Checkpoint { at_bytes } => {
+                // הסטטוס מתעדכן כל 100ms, אז הטקסט יושב על פס ההתקדמות
+                progress_clone.set_text(Some(&tr!("Checkpoint at {}", format_bytes(at_bytes))));
+                progress_clone.set_show_text(true);
+            }
+            BurnEvent::SurfaceReport {
+                bad_ranges,
+                total_bytes,
+            } => {
+                progress_clone.set_fraction(1.0);
+                status_clone.set_text(&if bad_ranges.is_empty() {
+                    tr!("Surface test passed: every block read back correctly.").to_string()
+                } else {
+                    tr!("Surface test found {} bad range(s).", bad_ranges.len())
+                });
+                show_surface_report(&window_c, &bad_ranges, total_bytes);
+            }
+            BurnEvent::CloneProgress {
+                read,
+                total,
+                written,
+                speed_mbps,
+                ..
+            } => {
+                let fraction = read as f64 / total.max(1) as f64;
+                progress_clone.set_fraction(fraction);
+                status_clone.set_text(&tr!(
+                    "{} MB/s | {}% | {} read → {} written",
+                    format!("{:.1}", speed_mbps),
+                    (fraction * 100.0) as u64,
+                    format_bytes(read),
+                    format_bytes(written)
+                ));
+            }
+            BurnEvent::BenchmarkProgress {
+                phase,
+                done,
+                total,
                 speed_mbps,
             } => {
-                let fraction = written as f64 / total as f64;
+                let fraction = done as f64 / total.max(1) as f64;
                 progress_clone.set_fraction(fraction);
-                status_clone.set_text(&format!(
-                    "{:.1} MB/s | {}%",
-                    speed_mbps,
-                    (fraction * 100.0) as u64
+                let percent = (fraction * 100.0) as u64;
+                let speed = format!("{:.1}", speed_mbps);
+                status_clone.set_text(&match phase {
+                    BenchmarkPhase::Read => tr!("Benchmark: reading… {}% | {} MB/s", percent, speed),
+                    BenchmarkPhase::Write => tr!("Benchmark: writing… {}% | {} MB/s", percent, speed),
+                });
+            }
+            BurnEvent::BenchmarkReport { read, write } => {
+                progress_clone.set_fraction(1.0);
+                status_clone.set_text(&tr!(
+                    "Benchmark done: read {} MB/s",
+                    format!("{:.1}", read.avg_mbps)
                 ));
+                show_benchmark_report(&window_c, &read, write.as_ref());
             }
-            BurnEvent::Finished => {
-                status_clone.set_text("Success! Drive is ready.");
+            BurnEvent::CapacityReport {
+                advertised,
+                verified,
+                wraps_at,
+            } => {
                 progress_clone.set_fraction(1.0);
+                status_clone.set_text(&tr!(
+                    "Usable capacity: {} of {} advertised",
+                    format_bytes(verified),
+                    format_bytes(advertised)
+                ));
+                // עד 1% סטייה - למשל בלוק אחרון חלקי. מעבר לזה הכונן משקר על הגודל שלו
+                if (advertised - verified) as f64 > advertised as f64 * 0.01 {
+                    let text = match wraps_at {
+                        Some(at) => tr!(
+                            "This drive is not the size it claims! Writes past {} wrap around and overwrite the start.",
+                            format_bytes(at)
+                        ),
+                        None => tr!(
+                            "This drive is not the size it claims! Only the first {} can hold data.",
+                            format_bytes(verified)
+                        ),
+                    };
+                    let label = Label::new(Some(&text));
+                    label.add_css_class("checksum-bad");
+                    label.set_wrap(true);
+                    label.set_xalign(0.0);
+                    warnings_list_c.append(&label);
+                    warnings_scroll_c.set_visible(true);
+                }
             }
-            BurnEvent::Error(e) => {
-                status_clone.set_text(&format!("Error: {}", e));
+            BurnEvent::Ejected => {
+                status_clone.set_text(tr!("Device ejected safely—remove now!"));
+            }
+            BurnEvent::EjectFailed { reason } => {
+                // הצריבה הצליחה, רק ההוצאה נכשלה
+                status_clone.set_text(&tr!(
+                    "Success! Drive is ready, but eject failed ({}).",
+                    reason
+                ));
+            }
+            BurnEvent::Cancelled => {
+                // hash הרקע שפינה מקום - המשתמש לא ביטל כלום
+                if current_job.is_none() || current_job != hash_job_c.get() {
+                    status_clone.set_text(tr!("Cancelled."));
+                }
+            }
+            BurnEvent::PersistenceStarted { label, size } => {
+                status_clone.set_text(&tr!(
+                    "Creating persistent storage \"{}\" ({})…",
+                    label,
+                    format_bytes(size)
+                ));
+            }
+            BurnEvent::PersistenceCreated { partition, size, .. } => {
+                status_clone.set_text(&tr!(
+                    "Persistent storage ready: {} on {}",
+                    format_bytes(size),
+                    partition.display()
+                ));
+            }
+            BurnEvent::DataPartitionAdded { partition, size } => {
+                status_clone.set_text(&tr!(
+                    "Formatting data partition {} ({})…",
+                    partition.display(),
+                    format_bytes(size)
+                ));
+            }
+            BurnEvent::DataPartitionFormatted { partition, filesystem } => {
+                status_clone.set_text(&tr!(
+                    "Data partition ready: {} ({})",
+                    partition.display(),
+                    filesystem
+                ));
+            }
+            BurnEvent::WindowsInstaller { .. } => {
+                status_clone.set_text(tr!("Partitioning and formatting FAT32…"));
+            }
+            BurnEvent::SplittingInstallImage => {
+                status_clone.set_text(tr!("Splitting install.wim for FAT32…"));
+            }
+            BurnEvent::CopyingFiles { files, total } => {
+                progress_clone.set_fraction(0.0);
+                status_clone.set_text(&tr!("Copying {} files ({})…", files, format_bytes(total)));
+            }
+            BurnEvent::MultibootPrepared { .. } => {
+                progress_clone.set_fraction(1.0);
+                status_clone.set_text(tr!("Multiboot stick ready — select an ISO and press ADD ISO TO STICK"));
+                refresh_multiboot_c();
+            }
+            BurnEvent::MultibootMenuUpdated { entries } => {
+                status_clone.set_text(&tr!("Boot menu updated: {} images", entries.len()));
+            }
+            BurnEvent::Partitioning { scheme } => {
+                status_clone.set_text(match scheme {
+                    PartitionScheme::Mbr => tr!("Writing a new MBR partition table…"),
+                    PartitionScheme::Gpt => tr!("Writing a new GPT partition table…"),
+                });
+            }
+            BurnEvent::Formatting { filesystem, label, .. } => {
+                status_clone.set_text(&tr!("Formatting {} \"{}\"…", filesystem, label));
+            }
+            // הפלט של mkfs: השורה האחרונה בסטטוס, הכל בלוג
+            BurnEvent::ToolOutput { line } => {
+                tracing::info!(target: "tool", "{}", line);
+                status_clone.set_text(&line);
+            }
+            BurnEvent::Discarded { elapsed_secs } => {
+                status_clone.set_text(&tr!("Device trimmed in {}s", format!("{:.1}", elapsed_secs)));
+            }
+            BurnEvent::Target { device, event } => {
+                let rows = multi_rows_c.borrow();
+                let Some((bar, label)) = rows.get(&device) else {
+                    return gtk4::glib::ControlFlow::Continue;
+                };
+                let name = device.display();
+                match *event {
+                    // המקור משותף לכל הכוננים, אז כאן רק מהירות הכתיבה של כל אחד
+                    BurnEvent::Progress {
+                        written,
+                        total,
+                        write_speed_mbps,
+                        ..
+                    } => {
+                        let fraction = written as f64 / total.max(1) as f64;
+                        bar.set_fraction(fraction);
+                        label.set_text(&format!(
+                            "{}: {:.1} MB/s | {:.1}%",
+                            name,
+                            write_speed_mbps,
+                            fraction * 100.0
+                        ));
+                    }
+                    BurnEvent::Verifying { checked, total } => {
+                        let fraction = checked as f64 / total.max(1) as f64;
+                        bar.set_fraction(fraction);
+                        label.set_text(&tr!("{}: verifying… {}%", name, (fraction * 100.0) as u64));
+                    }
+                    // שאר הכוננים ממשיכים - רק השורה הזו נצבעת
+                    BurnEvent::Error(e) => {
+                        label.set_text(&tr!("{}: error: {}", name, e));
+                        label.add_css_class("checksum-bad");
+                    }
+                    _ => {}
+                }
+            }
+            BurnEvent::MultiFinished {
+                targets,
+                elapsed_secs,
+            } => {
+                let rows = multi_rows_c.borrow();
+                for target in &targets {
+                    let Some((bar, label)) = rows.get(&target.device) else {
+                        continue;
+                    };
+                    let name = target.device.display();
+                    match &target.error {
+                        None => {
+                            bar.set_fraction(1.0);
+                            label.set_text(&tr!(
+                                "{}: ✔ {} at avg {} MB/s{}",
+                                name,
+                                format_bytes(target.bytes),
+                                format!("{:.1}", target.avg_mbps),
+                                if target.verified { tr!(", verified OK") } else { "" }
+                            ));
+                            label.add_css_class("checksum-ok");
+                        }
+                        Some(e) => {
+                            label.set_text(&format!("{}: ✘ {}", name, e));
+                            label.add_css_class("checksum-bad");
+                        }
+                    }
+                }
+                let ok = targets.iter().filter(|t| t.error.is_none()).count();
+                progress_clone.set_fraction(1.0);
+                status_clone.set_text(&tr!(
+                    "{} of {} drives done in {}",
+                    ok,
+                    targets.len(),
+                    format_duration(elapsed_secs)
+                ));
             }
             _ => {}
         }
         gtk4::glib::ControlFlow::Continue
     });
 
-    // כפתור בחירת ISO
+    let shortcuts_action = gtk4::gio::SimpleAction::new("shortcuts", None);
+    let window_c = window.clone();
+    let app_c = app.clone();
+    shortcuts_action.connect_activate(move |_, _| {
+        let shortcuts = shortcuts_window();
+        shortcuts.set_application(Some(&app_c));
+        shortcuts.set_transient_for(Some(&window_c));
+        shortcuts.present();
+    });
+    window.add_action(&shortcuts_action);
+    app.set_accels_for_action("win.shortcuts", &["<Control>question"]);
+
+    // VERIFY ONLY ו-START (הכפתור והקיצור) צריכים גם ISO וגם התקן
+    let verify_only_c = verify_only_btn.clone();
+    let start_action_c = start_action.clone();
+    let iso_path_c = iso_path.clone();
+    let drive_c = drive_combo.clone();
+    let update_verify_only = Rc::new(move || {
+        // "none" הוא שורת ה-placeholder של סריקה ריקה, לא התקן
+        let ready = iso_path_c.borrow().is_some() && drive_c.active_id().filter(|id| id != "none").is_some();
+        verify_only_c.set_sensitive(ready);
+        start_action_c.set_enabled(ready);
+    });
+    let update_c = update_verify_only.clone();
+    drive_combo.connect_changed(move |_| update_c());
+    update_verify_only();
+    let refresh_c = refresh_multiboot.clone();
+    drive_combo.connect_changed(move |_| refresh_c());
+    refresh_multiboot();
+
+    // בחירת ISO - מהדיאלוג או מגרירה לחלון
     let iso_label_c = iso_label.clone();
     let iso_path_c = iso_path.clone();
-    iso_btn.connect_clicked(move |_| {
+    let checksum_label_c = checksum_label.clone();
+    let checksum_ok_c = checksum_ok.clone();
+    let signature_label_c = signature_label.clone();
+    let signature_ok_c = signature_ok.clone();
+    let persistence_row_c = persistence_row.clone();
+    let windows_label_c = windows_label.clone();
+    let data_partition_c = data_partition_check.clone();
+    let image_info_c = image_info_label.clone();
+    let raw_boot_warning_c = raw_boot_warning.clone();
+    let windows_raw_c = windows_raw.clone();
+    let image_digest_c = image_digest_label.clone();
+    let hash_job_c = hash_job.clone();
+    let engine_c = engine.clone();
+    let window_c = window.clone();
+    let select_image = Rc::new(move |path: PathBuf| {
+        iso_label_c.set_text(&path.display().to_string());
+        window_c.set_title(Some(&window_title(Some(&path))));
+        let summary = burn_engine::image_summary(&path);
+        if let Some(summary) = &summary {
+            image_info_c.set_text(&describe_image(summary));
+            image_info_c.set_tooltip_text(
+                summary
+                    .iso
+                    .as_ref()
+                    .map(|iso| iso.application_id.as_str())
+                    .filter(|id| !id.is_empty()),
+            );
+        }
+        image_info_c.set_visible(summary.is_some());
+        persistence_row_c.set_visible(burn_engine::live_flavor(&path).is_some());
+        let windows = burn_engine::windows_installer(&path);
+        // ברירת המחדל היא העתקת הקבצים, גם אם לתמונה הקודמת בחרו raw
+        windows_raw_c.set(false);
+        if let Some(image) = &windows {
+            windows_label_c.set_text(windows_mode_text(image.needs_split(), false));
+            let windows_label = windows_label_c.clone();
+            let windows_raw = windows_raw_c.clone();
+            let split = image.needs_split();
+            ask_windows_mode(&window_c, move |raw| {
+                windows_raw.set(raw);
+                windows_label.set_text(windows_mode_text(split, raw));
+            });
+        }
+        windows_label_c.set_visible(windows.is_some());
+        // התקנת Windows מעתיקה קבצים ולא כותבת את ה-ISO כמו שהוא
+        raw_boot_warning_c.set_visible(
+            windows.is_none() && summary.as_ref().is_some_and(|s| s.may_not_boot_raw()),
+        );
+        // המחיצה היחידה כבר תופסת את כל הכונן
+        data_partition_c.set_sensitive(windows.is_none());
+        // hash של תמונה קודמת שעוד רץ מתבטל במנוע כשזה נכנס לתור
+        image_digest_c.set_text(tr!("SHA-256: computing…"));
+        image_digest_c.set_visible(true);
+        hash_job_c.set(Some(engine_c.hash_image(path.clone())));
+        *iso_path_c.borrow_mut() = Some(path);
+        // בדיקה קודמת שייכת לקובץ אחר
+        checksum_ok_c.set(None);
+        checksum_label_c.set_text("");
+        signature_ok_c.set(None);
+        signature_label_c.set_text("");
+        update_verify_only();
+    });
+
+    let select_image_c = select_image.clone();
+    select_image_action.connect_activate(move |_, _| {
         if let Some(path) = rfd::FileDialog::new()
-            .add_filter("ISO", &["iso"])
+            .add_filter(tr!("Disk images (*.iso, *.img, *.bin, *.raw)"), &IMAGE_EXTENSIONS[..4])
+            .add_filter(tr!("Compressed images"), &IMAGE_EXTENSIONS[4..])
             .pick_file()
         {
-            iso_label_c.set_text(&path.display().to_string());
-            *iso_path_c.borrow_mut() = Some(path);
+            select_image_c(path);
         }
     });
 
-    // כפתור התחלה
-    let engine_c = engine.clone();
-    let drive_c = drive_combo.clone();
-    start_btn.connect_clicked(move |_| {
-
+    let select_kiosk_image = select_image.clone();
+    // FileList מכסה גם text/uri-list - GTK ממיר בעצמו
+    let drop_target = gtk4::DropTarget::new(
+        gtk4::gdk::FileList::static_type(),
+        gtk4::gdk::DragAction::COPY,
+    );
+    let status_c = status_label.clone();
+    drop_target.connect_drop(move |_, value, _, _| {
+        let Ok(files) = value.get::<gtk4::gdk::FileList>() else {
+            return false;
+        };
+        let image = files
+            .files()
+            .into_iter()
+            .filter_map(|file| file.path())
+            .find(|path| is_image_path(path));
+        match image {
+            Some(path) => {
+                select_image(path);
+                true
+            }
+            None => {
+                status_c.set_text(tr!("That is not a disk image."));
+                false
+            }
+        }
+    });
+    window.add_controller(drop_target);
 
+    // בדיקת checksum - קודם מחפשים קובץ ליד ה-ISO, אחרת שואלים את המשתמש
+    let engine_c = engine.clone();
+    let iso_path_c = iso_path.clone();
+    let status_c = status_label.clone();
+    checksum_btn.connect_clicked(move |_| {
+        let Some(iso) = iso_path_c.borrow().clone() else {
+            status_c.set_text(tr!("Select an image first."));
+            return;
+        };
+        let sums_file = checksum::find_checksum_file(&iso).or_else(|| {
+            rfd::FileDialog::new()
+                .set_title(tr!("Select checksum file"))
+                .pick_file()
+        });
+        let Some(sums_file) = sums_file else {
+            return;
+        };
+        let file_name = iso
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let expected = std::fs::read_to_string(&sums_file)
+            .ok()
+            .and_then(|content| checksum::parse_checksum_file(&content, &file_name));
+        match expected {
+            Some(expected) => {
+                // אורך ה-hash כבר נבדק בפענוח
+                let algorithm =
+                    checksum::digest_algorithm(&expected).unwrap_or(HashAlgorithm::Sha256);
+                engine_c.verify_checksum(iso, expected, algorithm);
+            }
+            None => status_c.set_text(&tr!(
+                "No checksum for {} in {}",
+                file_name,
+                sums_file.display()
+            )),
+        }
+    });
 
+    // חתימת GPG - לרוב על SHA256SUMS, ואם אין כזה ליד החתימה אז על ה-ISO עצמו
+    let engine_c = engine.clone();
+    let iso_path_c = iso_path.clone();
+    let status_c = status_label.clone();
+    let fingerprint_c = fingerprint_entry.clone();
+    let signing_key_c = signing_key_view.clone();
+    signature_btn.connect_clicked(move |_| {
+        let Some(iso) = iso_path_c.borrow().clone() else {
+            status_c.set_text(tr!("Select an image first."));
+            return;
+        };
+        let Some(signature_path) = rfd::FileDialog::new()
+            .set_title(tr!("Select signature file"))
+            .add_filter(tr!("Signatures"), &["sig", "asc", "gpg", "sign"])
+            .pick_file()
+        else {
+            return;
+        };
+        let buffer = signing_key_c.buffer();
+        let (start, end) = buffer.bounds();
+        let pasted = buffer.text(&start, &end, false).to_string();
+        let key = if !pasted.trim().is_empty() {
+            signature::SigningKey::Pasted(pasted)
+        } else {
+            let Some(keyring) = rfd::FileDialog::new()
+                .set_title(tr!("Select the signing keyring"))
+                .add_filter(tr!("OpenPGP keys"), &["gpg", "asc", "pgp", "key"])
+                .pick_file()
+            else {
+                return;
+            };
+            signature::SigningKey::Keyring(keyring)
+        };
+        let data_path = signature::signed_file_for(&signature_path).unwrap_or(iso);
+        let fingerprint = fingerprint_c.text().to_string();
+        engine_c.verify_signature(
+            data_path,
+            signature_path,
+            key,
+            (!fingerprint.trim().is_empty()).then_some(fingerprint),
+        );
+    });
 
-        if let (Some(iso), Some(dev)) = (iso_path.borrow().clone(), drive_c.active_id()) {
-            engine_c.start(BurnConfig {
+    // בדיקה של כונן שכבר נצרב - בלי לכתוב עליו
+    let engine_c = engine.clone();
+    let iso_path_c = iso_path.clone();
+    let drive_c = drive_combo.clone();
+    let full_scan_c = full_scan_check.clone();
+    verify_only_btn.connect_clicked(move |_| {
+        if let (Some(iso), Some(dev)) = (iso_path_c.borrow().clone(), drive_c.active_id()) {
+            if dev == "none" {
+                return;
+            }
+            engine_c.verify_only(BurnConfig {
                 iso_path: iso,
                 device_path: PathBuf::from(dev.as_str()),
-                verify: true,
+                verify_full_scan: full_scan_c.is_active(),
+                ..Default::default()
             });
         }
     });
 
-    window.present();
-}
-
-fn update_device_list(combo: &gtk4::ComboBoxText) {
-    combo.remove_all();
-    
-    // הרצה של lsblk עם הגדרות רחבות יותר כדי לוודא שזה מוצא משהו
-    let output = std::process::Command::new("lsblk")
-        .args(["-dpno", "NAME,SIZE,MODEL"])
-        .output();
-
-    let mut found = false;
-
-    if let Ok(out) = output {
-        let list = String::from_utf8_lossy(&out.stdout);
-        println!("Scanning drives: \n{}", list); // הדפסה לטרמינל לדיבוג
+    // כפתור התחלה
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let trim_c = trim_check.clone();
+    let wipe_on_cancel_c = wipe_on_cancel_check.clone();
+    let wipe_before_c = wipe_before_check.clone();
+    let byte_compare_c = byte_compare_check.clone();
+    let fast_verify_c = fast_verify_check.clone();
+    let paranoid_c = paranoid_check.clone();
+    let verify_mode_c = verify_mode_combo.clone();
+    let hash_c = hash_combo.clone();
+    let full_scan_c = full_scan_check.clone();
+    let eject_c = eject_check.clone();
+    let checkpoint_c = checkpoint_check.clone();
+    let profile_c = profile.clone();
+    let speed_limit_c = speed_limit_spin.clone();
+    let persistence_row_c = persistence_row.clone();
+    let persistence_scale_c = persistence_scale.clone();
+    let data_partition_c = data_partition_check.clone();
+    let active_burns_c = active_burns.clone();
+    let iso_path_c = iso_path.clone();
+    let iso_path_multi = iso_path.clone();
+    let status_c = status_label.clone();
+    let start_burn = Rc::new(move || {
+        if let (Some(iso), Some(dev)) = (iso_path.borrow().clone(), drive_c.active_id()) {
+            // נבדק כאן ולא בלחיצה - גם דיאלוג האישור נותן זמן לשלוף את המקל
+            let label = drive_c.active_text().unwrap_or_default();
+            if !device_still_present(&dev, &label) {
+                status_c.set_text(tr!("Selected device is no longer present — rescan"));
+                return;
+            }
+            // שאר האפשרויות לא חלות על העתקת קובץ
+            if multiboot_mode.get() {
+                engine_c.add_to_multiboot(iso, PathBuf::from(dev.as_str()));
+                return;
+            }
+            let verify_mode = match verify_mode_c.active_id().as_deref() {
+                Some("quick") => VerifyMode::Quick,
+                Some("off") => VerifyMode::Off,
+                _ => VerifyMode::Full,
+            };
+            let hash_algorithm = match hash_c.active_id().as_deref() {
+                Some("sha256") => HashAlgorithm::Sha256,
+                Some("sha512") => HashAlgorithm::Sha512,
+                _ => HashAlgorithm::Blake3,
+            };
+            // שאר השדות של הפרופיל כבר עברו דרך האפשרויות, אולי עם שינויים של המשתמש
+            let overrides = profile_c.get().into_config_overrides();
+            let config = BurnConfig {
+                iso_path: iso,
+                device_path: PathBuf::from(dev.as_str()),
+                verify_mode,
+                pre_discard: trim_c.is_active(),
+                wipe_on_cancel: wipe_on_cancel_c.is_active(),
+                wipe_before_burn: wipe_before_c.is_active(),
+                byte_compare: byte_compare_c.is_active(),
+                fast_verify: fast_verify_c.is_active(),
+                verify_full_scan: full_scan_c.is_active(),
+                hash_algorithm,
+                eject_after: eject_c.is_active(),
+                sync_interval_bytes: checkpoint_c.is_active().then_some(
+                    overrides.sync_interval_bytes.unwrap_or(CHECKPOINT_INTERVAL),
+                ),
+                buffer_size: overrides.buffer_size,
+                max_speed_mbps: Some(speed_limit_c.value()).filter(|cap| *cap > 0.0),
+                persistence_bytes: Some(persistence_scale_c.value() as u64 * 1024 * 1024 * 1024)
+                    .filter(|size| *size > 0 && persistence_row_c.is_visible()),
+                data_partition: data_partition_c.is_active(),
+                paranoid: paranoid_c.is_active(),
+                // device_still_present כבר וידא שזה המקל שנבחר, אז הקישור שלו נכון עכשיו
+                device_link: burn_engine::stable_device_link(Path::new(dev.as_str())),
+                windows_raw: windows_raw.get(),
+                ..Default::default()
+            };
+            let burn = ActiveBurn {
+                device: dev.to_string(),
+                config: config.clone(),
+            };
+            let id = engine_c.start(config);
+            active_burns_c.borrow_mut().insert(id, burn);
+        }
+    });
 
-        for line in list.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let dev_path = parts[0];
-                let dev_info = line.trim();
-                
-                // אנחנו מסננים רק כוננים שלמים (בלי מחיצות כמו sda1)
-                if !dev_path.chars().last().unwrap_or(' ').is_numeric() {
-                    combo.append(Some(dev_path), dev_info);
-                    found = true;
-                }
+    let window_c = window.clone();
+    start_action.connect_activate(move |_, _| {
+        // checksum או חתימה שנכשלו, או קובץ שלא נראה כמו תמונה - צורבים רק אחרי אישור מפורש
+        let not_bootable = iso_path_c
+            .borrow()
+            .as_deref()
+            .is_some_and(|iso| matches!(burn_engine::looks_bootable(iso), Ok(false)));
+        let warning = if checksum_ok.get() == Some(false) || signature_ok.get() == Some(false) {
+            Some(tr!("The image failed checksum or signature verification. Burn it anyway?"))
+        } else if not_bootable {
+            Some(tr!("The selected file has no MBR or ISO 9660 signature and may not boot. Burn it anyway?"))
+        } else {
+            None
+        };
+        match warning {
+            Some(text) => {
+                let start_burn = start_burn.clone();
+                confirm_destructive(&window_c, text, move || start_burn());
             }
+            None => start_burn(),
         }
-    }
+    });
 
-    if !found {
-        println!("No USB drives found!");
-        combo.append(Some("none"), "No drives detected - Click SCAN");
-    }
-    
-    combo.set_active(Some(0));
-}
\ No newline at end of file
+    let engine_c = engine.clone();
+    let window_c = window.clone();
+    let status_c = status_label.clone();
+    let verify_mode_c = verify_mode_combo.clone();
+    let multi_targets_c = multi_targets_list.clone();
+    let multi_rows_box_c = multi_rows_box.clone();
+    multi_burn_btn.connect_clicked(move |_| {
+        let Some(iso) = iso_path_multi.borrow().clone() else {
+            return;
+        };
+        let device_paths = checked_targets(&multi_targets_c);
+        if device_paths.is_empty() {
+            status_c.set_text(tr!("Check at least one drive."));
+            return;
+        }
+        let names: Vec<String> = device_paths.iter().map(|p| p.display().to_string()).collect();
+        let cfg = MultiBurnConfig {
+            iso_path: iso,
+            device_paths,
+            verify: verify_mode_c.active_id().as_deref() != Some("off"),
+        };
+        let engine_c = engine_c.clone();
+        let multi_rows = multi_rows.clone();
+        let multi_rows_box_c = multi_rows_box_c.clone();
+        confirm_destructive(
+            &window_c,
+            &tr!("All data on {} will be overwritten. Continue?", names.join(", ")),
+            move || {
+                // שורה חדשה לכל יעד, לפי הסדר שנבחרו
+                while let Some(child) = multi_rows_box_c.first_child() {
+                    multi_rows_box_c.remove(&child);
+                }
+                let mut rows = multi_rows.borrow_mut();
+                rows.clear();
+                for path in &cfg.device_paths {
+                    let label = Label::new(Some(&path.display().to_string()));
+                    label.set_xalign(0.0);
+                    let bar = ProgressBar::new();
+                    multi_rows_box_c.append(&label);
+                    multi_rows_box_c.append(&bar);
+                    rows.insert(path.clone(), (bar, label));
+                }
+                engine_c.multi_burn(cfg.clone());
+            },
+        );
+    });
+
+    // כפתור מחיקה - דורס את כל ההתקן באפסים, אז מבקשים אישור קודם
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    let pattern_c = wipe_pattern_combo.clone();
+    wipe_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let pattern = match pattern_c.active_id().as_deref() {
+            Some("ones") => WipePattern::Ones,
+            Some("random") => WipePattern::Random,
+            Some("random-zeros") => WipePattern::RandomThenZeros,
+            _ => WipePattern::Zeros,
+        };
+        let engine_c = engine_c.clone();
+        let device_path = PathBuf::from(dev.as_str());
+        if refuse_system_disk(&window_c, &device_path) {
+            return;
+        }
+        // כמו בשחזור: מחיצה מעוגנת - שואלים אם לנתק אותה
+        let mounted = mount::mounted_under(&device_path).unwrap_or_default();
+        let text = match mounted.first() {
+            Some((_, target)) => tr!("{} is mounted at {}. Unmount it and wipe it?", dev, target.display()),
+            None => tr!("All data on {} will be overwritten. Continue?", dev),
+        };
+        let unmount = !mounted.is_empty();
+        confirm_destructive(&window_c, &text, move || {
+            engine_c.wipe(WipeConfig {
+                device_path: device_path.clone(),
+                pattern,
+                verify: true,
+                unmount,
+            });
+        });
+    });
+
+    // Secure Erase - פקודה נפרדת לגמרי מצריבה, עם אישור משלה
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    secure_erase_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let engine_c = engine_c.clone();
+        let device_path = PathBuf::from(dev.as_str());
+        confirm_destructive(
+            &window_c,
+            &tr!(
+                "The drive firmware will permanently erase every block on {}.\n\
+                 This cannot be cancelled once started. Continue?",
+                dev
+            ),
+            move || {
+                engine_c.secure_erase(device_path.clone());
+            },
+        );
+    });
+
+    // בדיקת משטח - הורסת את כל מה שעל הכונן, אז אותו אישור כמו לצריבה
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    surface_test_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let engine_c = engine_c.clone();
+        let device_path = PathBuf::from(dev.as_str());
+        confirm_destructive(
+            &window_c,
+            &tr!("All data on {} will be overwritten. Continue?", dev),
+            move || {
+                engine_c.surface_test(device_path.clone());
+            },
+        );
+    });
+
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    capacity_test_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let engine_c = engine_c.clone();
+        let device_path = PathBuf::from(dev.as_str());
+        confirm_destructive(
+            &window_c,
+            &tr!("All data on {} will be overwritten. Continue?", dev),
+            move || {
+                engine_c.capacity_test(device_path.clone());
+            },
+        );
+    });
+
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    let label_c = restore_label_entry.clone();
+    let scheme_c = restore_scheme_combo.clone();
+    restore_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let engine_c = engine_c.clone();
+        let mut cfg = RestoreConfig {
+            device_path: PathBuf::from(dev.as_str()),
+            scheme: match scheme_c.active_id().as_deref() {
+                Some("gpt") => PartitionScheme::Gpt,
+                _ => PartitionScheme::Mbr,
+            },
+            label: label_c.text().to_string(),
+            unmount: false,
+        };
+        if refuse_system_disk(&window_c, &cfg.device_path) {
+            return;
+        }
+        // כמו בפירמוט: מחיצה מעוגנת - שואלים אם לנתק אותה
+        let mounted = mount::mounted_under(&cfg.device_path).unwrap_or_default();
+        let text = match mounted.first() {
+            Some((_, target)) => {
+                cfg.unmount = true;
+                tr!("{} is mounted at {}. Unmount it and restore?", dev, target.display())
+            }
+            None => tr!("All data on {} will be overwritten. Continue?", dev),
+        };
+        confirm_destructive(&window_c, &text, move || {
+            engine_c.restore(cfg.clone());
+        });
+    });
+
+    // כלי הפירמוט מהתפריט, על הכונן שנבחר. מחיצה מעוגנת - שואלים אם לנתק אותה
+    let format_action = gtk4::gio::SimpleAction::new("format", None);
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    format_action.connect_activate(move |_, _| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let label = drive_c.active_text().unwrap_or_default();
+        let engine_c = engine_c.clone();
+        let window = window_c.clone();
+        show_format_dialog(&window_c, &label, PathBuf::from(dev.as_str()), move |mut cfg| {
+            if refuse_system_disk(&window, &cfg.device_path) {
+                return;
+            }
+            let mounted = mount::mounted_under(&cfg.device_path).unwrap_or_default();
+            let text = match mounted.first() {
+                Some((_, target)) => {
+                    cfg.unmount = true;
+                    tr!("{} is mounted at {}. Unmount it and format?", cfg.device_path.display(), target.display())
+                }
+                None => tr!("All data on {} will be overwritten. Continue?", cfg.device_path.display()),
+            };
+            let engine_c = engine_c.clone();
+            confirm_destructive(&window, &text, move || {
+                engine_c.format(cfg.clone());
+            });
+        });
+    });
+    window.add_action(&format_action);
+
+    // כונן multiboot - טבלה חדשה, אז אותו אישור כמו לצריבה
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    multiboot_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let engine_c = engine_c.clone();
+        let device_path = PathBuf::from(dev.as_str());
+        if refuse_system_disk(&window_c, &device_path) {
+            return;
+        }
+        // כמו בשחזור: מחיצה מעוגנת - שואלים אם לנתק אותה
+        let mounted = mount::mounted_under(&device_path).unwrap_or_default();
+        let text = match mounted.first() {
+            Some((_, target)) => tr!("{} is mounted at {}. Unmount it and set up multiboot?", dev, target.display()),
+            None => tr!("All data on {} will be overwritten. Continue?", dev),
+        };
+        let unmount = !mounted.is_empty();
+        confirm_destructive(&window_c, &text, move || {
+            engine_c.prepare_multiboot(device_path.clone(), unmount);
+        });
+    });
+
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let read_error_c = read_error_combo.clone();
+    let compression_level_c = compression_level_spin.clone();
+    clone_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        // ההתקן רק נקרא, אז אין אישור - אבל קובץ קיים ביעד נדרס (דיאלוג השמירה שואל על זה)
+        let Some(image_path) = rfd::FileDialog::new()
+            .set_file_name("drive.img")
+            .add_filter(tr!("Disk images (*.iso, *.img, *.bin, *.raw)"), &["img", "iso", "bin", "raw"])
+            .add_filter(tr!("Compressed images"), &["gz", "xz", "zst", "bz2"])
+            .save_file()
+        else {
+            return;
+        };
+        engine_c.clone_to_image(CloneConfig {
+            device_path: PathBuf::from(dev.as_str()),
+            target_path: image_path,
+            on_read_error: match read_error_c.active_id().as_deref() {
+                Some("zero-fill") => ReadErrorPolicy::ZeroFill,
+                _ => ReadErrorPolicy::Abort,
+            },
+            compression_level: Some(compression_level_c.value() as u32).filter(|level| *level > 0),
+            verify: true,
+            unmount: false,
+        });
+    });
+
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    backup_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let Some(output_path) = rfd::FileDialog::new()
+            .set_file_name("backup.img")
+            .add_filter(tr!("Disk images (*.iso, *.img, *.bin, *.raw)"), &["img", "iso", "bin", "raw"])
+            .save_file()
+        else {
+            return;
+        };
+        engine_c.start(BurnConfig {
+            device_path: PathBuf::from(dev.as_str()),
+            direction: BurnDirection::DeviceToFile { output_path },
+            ..Default::default()
+        });
+    });
+
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let copy_source_c = copy_source_combo.clone();
+    let read_error_c = read_error_combo.clone();
+    let status_c = status_label.clone();
+    let window_c = window.clone();
+    copy_btn.connect_clicked(move |_| {
+        let (Some(source), Some(target)) = (copy_source_c.active_id(), drive_c.active_id()) else {
+            return;
+        };
+        if source == "none" || target == "none" {
+            return;
+        }
+        // המנוע בודק גם לפי מספר ההתקן, אבל אין טעם לשאול על מחיקה כשזה אותו כונן
+        if source == target {
+            status_c.set_text(tr!("Source and destination must be different drives."));
+            return;
+        }
+        let engine_c = engine_c.clone();
+        let mut cfg = CloneConfig {
+            device_path: PathBuf::from(source.as_str()),
+            target_path: PathBuf::from(target.as_str()),
+            on_read_error: match read_error_c.active_id().as_deref() {
+                Some("zero-fill") => ReadErrorPolicy::ZeroFill,
+                _ => ReadErrorPolicy::Abort,
+            },
+            compression_level: None,
+            verify: true,
+            unmount: false,
+        };
+        if refuse_system_disk(&window_c, &cfg.target_path) {
+            return;
+        }
+        // כמו בשחזור: מחיצה מעוגנת ביעד - שואלים אם לנתק אותה
+        let mounted = mount::mounted_under(&cfg.target_path).unwrap_or_default();
+        let text = match mounted.first() {
+            Some((_, mount_point)) => {
+                cfg.unmount = true;
+                tr!("{} is mounted at {}. Unmount it and copy onto it?", target, mount_point.display())
+            }
+            None => tr!("All data on {} will be overwritten. Continue?", target),
+        };
+        confirm_destructive(&window_c, &text, move || {
+            engine_c.clone_to_image(cfg.clone());
+        });
+    });
+
+    let engine_c = engine.clone();
+    let drive_c = drive_combo.clone();
+    let window_c = window.clone();
+    let benchmark_write_c = benchmark_write_check.clone();
+    benchmark_btn.connect_clicked(move |_| {
+        let Some(dev) = drive_c.active_id() else {
+            return;
+        };
+        let cfg = BenchmarkConfig {
+            device_path: PathBuf::from(dev.as_str()),
+            max_bytes: BENCHMARK_BYTES,
+            write: benchmark_write_c.is_active(),
+        };
+        // קריאה בלבד לא נוגעת בתוכן, אין על מה לשאול
+        if !cfg.write {
+            engine_c.benchmark(cfg);
+            return;
+        }
+        let engine_c = engine_c.clone();
+        confirm_destructive(
+            &window_c,
+            &tr!("All data on {} will be overwritten. Continue?", dev),
+            move || {
+                engine_c.benchmark(cfg.clone());
+            },
+        );
+    });
+
+    let engine_c = engine.clone();
+    cancel_action.connect_activate(move |_, _| {
+        engine_c.cancel();
+    });
+
+    let window_c = window.clone();
+    history_btn.connect_clicked(move |_| {
+        show_history(&window_c, &history.borrow());
+    });
+
+    let kiosk = KioskPreset::from_env();
+    if let Some(iso) = kiosk.iso {
+        select_kiosk_image(iso);
+    }
+    // ב-combo יש /dev/sdX, והסביבה יכולה לתת קישור יציב מ-/dev/disk/by-id
+    let device_selected = kiosk.device.as_ref().is_some_and(|device| {
+        let node = std::fs::canonicalize(device).unwrap_or_else(|_| device.clone());
+        // הגדרה שגויה של העמדה לא תדרוס את הכונן של המחשב עצמו - גם לא נבחר
+        if refuse_system_disk(&window, &node) {
+            return false;
+        }
+        let found = drive_combo.set_active_id(node.to_str());
+        if !found {
+            status_label.set_text(&tr!("Kiosk device {} was not found", device.display()));
+        }
+        found
+    });
+    if kiosk.autostart && device_selected {
+        // אחרי שהחלון כבר על המסך, כדי שהדיאלוג יופיע מעליו
+        let window_c = window.clone();
+        gtk4::glib::idle_add_local_once(move || kiosk_countdown(&window_c));
+    }
+
+    window
+}
+
+// START בסוף הספירה עובר את כל האזהרות הרגילות (checksum, תמונה לא bootable)
+fn kiosk_countdown(window: &ApplicationWindow) {
+    let dialog = gtk4::MessageDialog::new(
+        Some(window),
+        gtk4::DialogFlags::MODAL,
+        gtk4::MessageType::Warning,
+        gtk4::ButtonsType::Cancel,
+        tr!("Burning will start automatically"),
+    );
+    let remaining = Cell::new(KIOSK_COUNTDOWN_SECS);
+    dialog.set_secondary_text(Some(&tr!("Starting in {} s…", remaining.get())));
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+
+    let window = window.clone();
+    gtk4::glib::timeout_add_seconds_local(1, move || {
+        // Cancel או סגירת הדיאלוג עוצרים את הספירה
+        if !dialog.is_visible() {
+            return gtk4::glib::ControlFlow::Break;
+        }
+        remaining.set(remaining.get() - 1);
+        if remaining.get() > 0 {
+            dialog.set_secondary_text(Some(&tr!("Starting in {} s…", remaining.get())));
+            return gtk4::glib::ControlFlow::Continue;
+        }
+        dialog.close();
+        ActionGroupExt::activate_action(&window, "start", None);
+        gtk4::glib::ControlFlow::Break
+    });
+}
+
+// חלון Adwaita לא מקבל titlebar/child - הכותרת נכנסת לתוך התוכן
+#[cfg(feature = "adwaita")]
+fn set_window_content(window: &ApplicationWindow, header: &HeaderBar, content: &Box) {
+    let root = Box::new(Orientation::Vertical, 0);
+    root.append(header);
+    root.append(content);
+    if let Some(adw_window) = window.downcast_ref::<adw::ApplicationWindow>() {
+        adw_window.set_content(Some(&root));
+    }
+}
+
+#[cfg(not(feature = "adwaita"))]
+fn set_window_content(window: &ApplicationWindow, header: &HeaderBar, content: &Box) {
+    window.set_titlebar(Some(header));
+    window.set_child(Some(content));
+}
+
+fn show_history(parent: &ApplicationWindow, history: &BurnHistory) {
+    // החדשות למעלה
+    let reports: Vec<BurnReport> = history.records.iter().rev().cloned().collect();
+    let entries: Vec<String> = reports
+        .iter()
+        .map(|r| {
+            let mark = match r.outcome {
+                BurnOutcome::Completed => "✔",
+                BurnOutcome::Failed => "✘",
+                BurnOutcome::Cancelled => "–",
+            };
+            format!(
+                "{} {}  {} → {}",
+                mark,
+                format_timestamp(r.timestamp),
+                r.image.file_name().unwrap_or_default().to_string_lossy(),
+                r.device.display()
+            )
+        })
+        .collect();
+    let entry_refs: Vec<&str> = entries.iter().map(String::as_str).collect();
+    let model = gtk4::StringList::new(&entry_refs);
+
+    let factory = gtk4::SignalListItemFactory::new();
+    factory.connect_setup(|_, item| {
+        let label = Label::new(None);
+        label.set_xalign(0.0);
+        item.downcast_ref::<gtk4::ListItem>()
+            .expect("ListItem")
+            .set_child(Some(&label));
+    });
+    factory.connect_bind(|_, item| {
+        let item = item.downcast_ref::<gtk4::ListItem>().expect("ListItem");
+        if let (Some(obj), Some(label)) = (
+            item.item().and_downcast::<gtk4::StringObject>(),
+            item.child().and_downcast::<Label>(),
+        ) {
+            label.set_text(&obj.string());
+        }
+    });
+
+    let selection = gtk4::SingleSelection::new(Some(model));
+    let list = gtk4::ListView::new(Some(selection.clone()), Some(factory));
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .child(&list)
+        .min_content_height(300)
+        .hexpand(true)
+        .build();
+
+    // הפרטים של השורה הנבחרת, בטקסט שאפשר לסמן ולהדביק בדיווח
+    let details = Label::new(None);
+    details.set_xalign(0.0);
+    details.set_yalign(0.0);
+    details.set_selectable(true);
+    details.set_wrap(true);
+    let copy_btn = Button::with_label(tr!("Copy Report"));
+    let details_box = Box::new(Orientation::Vertical, 12);
+    details_box.set_margin_start(12);
+    details_box.set_margin_end(12);
+    details_box.set_margin_top(12);
+    details_box.set_margin_bottom(12);
+    details_box.set_size_request(320, -1);
+    details_box.append(&details);
+    details_box.append(&copy_btn);
+
+    let reports = Rc::new(reports);
+    let show_selected = {
+        let reports = reports.clone();
+        let details = details.clone();
+        let copy_btn = copy_btn.clone();
+        move |selection: &gtk4::SingleSelection| {
+            let report = reports.get(selection.selected() as usize);
+            details.set_text(&report.map(report_text).unwrap_or_default());
+            copy_btn.set_sensitive(report.is_some());
+        }
+    };
+    show_selected(&selection);
+    selection.connect_selected_notify(show_selected);
+    copy_btn.connect_clicked(move |btn| {
+        if let Some(report) = reports.get(selection.selected() as usize) {
+            btn.clipboard().set_text(&report_text(report));
+        }
+    });
+
+    let content = Box::new(Orientation::Horizontal, 0);
+    content.append(&scrolled);
+    content.append(&gtk4::Separator::new(Orientation::Vertical));
+    content.append(&details_box);
+
+    gtk4::Window::builder()
+        .title(tr!("Burn History"))
+        .transient_for(parent)
+        .default_width(900)
+        .default_height(450)
+        .child(&content)
+        .build()
+        .present();
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    gtk4::glib::DateTime::from_unix_local(timestamp as i64)
+        .and_then(|dt| dt.format("%Y-%m-%d %H:%M"))
+        .map(|s| s.to_string())
+        .unwrap_or_default()
+}
+
+// שורה לכל שדה, בטקסט פשוט - אותו טקסט בסיכום שאחרי הצריבה, בהיסטוריה ובהעתקה
+fn report_text(report: &BurnReport) -> String {
+    let mut lines = vec![
+        tr!("Date: {}", format_timestamp(report.timestamp)),
+        tr!("Image: {}", report.image.display()),
+    ];
+    if let Some(digest) = &report.image_sha256 {
+        lines.push(tr!("SHA-256: {}", digest));
+    }
+    let mut drive = report.device.display().to_string();
+    if let Some(model) = &report.device_model {
+        drive.push_str(&format!(" — {}", model));
+    }
+    if let Some(serial) = &report.device_serial {
+        drive.push_str(&format!(" ({})", serial));
+    }
+    if let Some(size) = report.device_size {
+        drive.push_str(&format!(" · {}", format_bytes(size)));
+    }
+    lines.push(tr!("Drive: {}", drive));
+    lines.push(tr!(
+        "Result: {}",
+        match report.outcome {
+            BurnOutcome::Completed => tr!("completed"),
+            BurnOutcome::Failed => tr!("failed"),
+            BurnOutcome::Cancelled => tr!("cancelled"),
+        }
+    ));
+    lines.push(tr!(
+        "Written: {} in {} at avg {} MB/s",
+        format_bytes(report.bytes_written),
+        format_duration(report.duration_secs),
+        format!("{:.1}", report.avg_speed_mbps)
+    ));
+    lines.push(tr!(
+        "Verify: {}",
+        match report.verified {
+            Some(true) => tr!("passed"),
+            Some(false) => tr!("failed"),
+            None => tr!("not run"),
+        }
+    ));
+    if let Some(error) = &report.error {
+        lines.push(tr!("Error: {}", error));
+    }
+    lines.join("\n")
+}
+
+fn show_burn_report(parent: &ApplicationWindow, report: &BurnReport) {
+    let mut text = report_text(report);
+    // דיווח על באג צריך את הלוג, לא רק את הסיכום
+    if report.outcome == BurnOutcome::Failed {
+        if let Some(log) = logging::current_log_file() {
+            text.push_str("\n\n");
+            text.push_str(&tr!("Details are in the log: {}", log.display()));
+        }
+    }
+    let dialog = gtk4::MessageDialog::new(
+        Some(parent),
+        gtk4::DialogFlags::MODAL,
+        if report.outcome == BurnOutcome::Completed {
+            gtk4::MessageType::Info
+        } else {
+            gtk4::MessageType::Error
+        },
+        gtk4::ButtonsType::Close,
+        tr!("Burn summary"),
+    );
+    dialog.set_secondary_text(Some(&text));
+    dialog.add_button(tr!("Copy"), gtk4::ResponseType::Apply);
+    dialog.connect_response(move |dialog, response| {
+        // ההעתקה לא סוגרת את הדיאלוג
+        if response == gtk4::ResponseType::Apply {
+            dialog.clipboard().set_text(&text);
+        } else {
+            dialog.close();
+        }
+    });
+    dialog.present();
+}
+
+fn show_surface_report(parent: &ApplicationWindow, bad_ranges: &[BadRange], total_bytes: u64) {
+    // דיסק גוסס יכול להחזיר אלפי טווחים - מציגים את הראשונים
+    const MAX_LINES: usize = 50;
+
+    let mut text = tr!("Tested {} ({} sectors).", format_bytes(total_bytes), total_bytes / 512);
+    if bad_ranges.is_empty() {
+        text.push('\n');
+        text.push_str(tr!("No bad blocks found."));
+    } else {
+        let bad_sectors: u64 = bad_ranges
+            .iter()
+            .map(|r| r.last_lba - r.first_lba + 1)
+            .sum();
+        text.push('\n');
+        text.push_str(&tr!("Good sectors: {}", (total_bytes / 512).saturating_sub(bad_sectors)));
+        text.push('\n');
+        text.push_str(&tr!("Bad sectors: {}", bad_sectors));
+        text.push('\n');
+        for range in bad_ranges.iter().take(MAX_LINES) {
+            text.push_str(&format!("\nLBA {}–{}", range.first_lba, range.last_lba));
+        }
+        if bad_ranges.len() > MAX_LINES {
+            text.push('\n');
+            text.push_str(&tr!("…and {} more", bad_ranges.len() - MAX_LINES));
+        }
+    }
+
+    let dialog = gtk4::MessageDialog::new(
+        Some(parent),
+        gtk4::DialogFlags::MODAL,
+        if bad_ranges.is_empty() {
+            gtk4::MessageType::Info
+        } else {
+            gtk4::MessageType::Error
+        },
+        gtk4::ButtonsType::Close,
+        tr!("Surface test report"),
+    );
+    dialog.set_secondary_text(Some(&text));
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
+
+// שורה אחת לכל שלב, בטקסט פשוט שאפשר להדביק בדיווח על כונן איטי
+fn benchmark_report_text(read: &BenchmarkResult, write: Option<&BenchmarkResult>) -> String {
+    let mut text = String::new();
+    for (name, result) in [(tr!("Read"), Some(read)), (tr!("Write"), write)] {
+        let Some(result) = result else {
+            continue;
+        };
+        text.push_str(&tr!(
+            "{}: {} in {} — min {} / avg {} / max {} MB/s",
+            name,
+            format_bytes(result.bytes),
+            format_duration(result.elapsed_secs),
+            format!("{:.1}", result.min_mbps),
+            format!("{:.1}", result.avg_mbps),
+            format!("{:.1}", result.max_mbps)
+        ));
+        text.push('\n');
+        text.push_str(&sparkline(&result.samples));
+        text.push('\n');
+    }
+    text
+}
+
+// דגימה לכל שנייה, בגובה יחסי למקסימום
+fn sparkline(samples: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = samples.iter().copied().fold(0.0, f64::max);
+    samples
+        .iter()
+        .map(|rate| {
+            if max > 0.0 {
+                BARS[((rate / max) * (BARS.len() - 1) as f64).round() as usize]
+            } else {
+                BARS[0]
+            }
+        })
+        .collect()
+}
+
+// שורה לכל אירוע, ונגלל לסוף כדי שהאחרון תמיד נראה
+fn append_log(view: &gtk4::TextView, event: &BurnEvent) {
+    let time = gtk4::glib::DateTime::now_local()
+        .and_then(|dt| dt.format("%H:%M:%S.%f"))
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+    let buffer = view.buffer();
+    let mut end = buffer.end_iter();
+    buffer.insert(&mut end, &format!("{} {:?}\n", time, event));
+    view.scroll_to_iter(&mut end, 0.0, false, 0.0, 0.0);
+}
+
+fn show_benchmark_report(
+    parent: &ApplicationWindow,
+    read: &BenchmarkResult,
+    write: Option<&BenchmarkResult>,
+) {
+    let text = benchmark_report_text(read, write);
+    let dialog = gtk4::MessageDialog::new(
+        Some(parent),
+        gtk4::DialogFlags::MODAL,
+        gtk4::MessageType::Info,
+        gtk4::ButtonsType::Close,
+        tr!("Benchmark results"),
+    );
+    dialog.set_secondary_text(Some(&text));
+    dialog.add_button(tr!("Copy"), gtk4::ResponseType::Apply);
+    dialog.connect_response(move |dialog, response| {
+        // ההעתקה לא סוגרת את הדיאלוג
+        if response == gtk4::ResponseType::Apply {
+            dialog.clipboard().set_text(&text);
+        } else {
+            dialog.close();
+        }
+    });
+    dialog.present();
+}
+
+// "Ubuntu 24.04.1 LTS amd64 · 5.8 GiB · BIOS+UEFI bootable". תמונה שאינה ISO - רק הגודל
+fn describe_image(summary: &image_info::ImageSummary) -> String {
+    let Some(iso) = &summary.iso else {
+        return format_bytes(summary.size);
+    };
+    let boot = match (summary.boot.bios, summary.boot.uefi) {
+        (true, true) => tr!("BIOS+UEFI bootable"),
+        (true, false) => tr!("BIOS bootable"),
+        (false, true) => tr!("UEFI bootable"),
+        (false, false) => tr!("not bootable"),
+    };
+    let size = format_bytes(summary.size);
+    [iso.volume_id.as_str(), size.as_str(), boot]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+// 3m 42s / 1h 5m / 12s
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+// מערכת קבצים, תווית, גודל קלאסטר ופירמוט מלא. unmount נקבע אחר כך, לפי מה שמעוגן
+fn show_format_dialog(
+    parent: &ApplicationWindow,
+    label: &str,
+    device_path: PathBuf,
+    on_format: impl Fn(FormatConfig) + 'static,
+) {
+    let dialog = gtk4::Dialog::with_buttons(
+        Some(tr!("Format Drive")),
+        Some(parent),
+        gtk4::DialogFlags::MODAL,
+        &[
+            (tr!("Cancel"), gtk4::ResponseType::Cancel),
+            (tr!("Format"), gtk4::ResponseType::Accept),
+        ],
+    );
+    let filesystem_combo = ComboBoxText::new();
+    for filesystem in [Filesystem::Fat32, Filesystem::Exfat, Filesystem::Ntfs, Filesystem::Ext4] {
+        filesystem_combo.append(Some(filesystem.name()), filesystem.name());
+    }
+    filesystem_combo.set_active_id(Some(Filesystem::Exfat.name()));
+    let label_entry = gtk4::Entry::builder()
+        .placeholder_text(tr!("Volume label (optional)"))
+        .build();
+    // ext4 מקבל עד 4K - המנוע מסרב לגדול יותר
+    let cluster_combo = ComboBoxText::new();
+    cluster_combo.append(Some("default"), tr!("Cluster size: default"));
+    for size in [4096u32, 8192, 16384, 32768, 65536] {
+        cluster_combo.append(Some(&size.to_string()), &tr!("Cluster size: {}", format_bytes(size as u64)));
+    }
+    cluster_combo.set_active(Some(0));
+    let full_check = CheckButton::with_label(tr!("Full format (write zeros over the whole drive)"));
+
+    let content = dialog.content_area();
+    content.set_spacing(8);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.append(&Label::new(Some(label)));
+    content.append(&filesystem_combo);
+    content.append(&label_entry);
+    content.append(&cluster_combo);
+    content.append(&full_check);
+
+    dialog.connect_response(move |dialog, response| {
+        if response == gtk4::ResponseType::Accept {
+            let filesystem = match filesystem_combo.active_id().as_deref() {
+                Some("FAT32") => Filesystem::Fat32,
+                Some("NTFS") => Filesystem::Ntfs,
+                Some("ext4") => Filesystem::Ext4,
+                _ => Filesystem::Exfat,
+            };
+            on_format(FormatConfig {
+                device_path: device_path.clone(),
+                filesystem,
+                label: label_entry.text().to_string(),
+                full: full_check.is_active(),
+                cluster_size: cluster_combo.active_id().and_then(|id| id.parse().ok()),
+                unmount: false,
+            });
+        }
+        dialog.close();
+    });
+    dialog.present();
+}
+
+fn windows_mode_text(split: bool, raw: bool) -> &'static str {
+    match (raw, split) {
+        (true, _) => tr!("Windows installer — will be written raw and may not boot on most PCs"),
+        (false, true) => tr!("Windows installer — files will be copied to a FAT32 partition, install.wim split in parts"),
+        (false, false) => tr!("Windows installer — files will be copied to a FAT32 partition"),
+    }
+}
+
+// dd של ISO של Windows לא עולה ברוב המחשבים: אין בו מחיצה ש-UEFI מזהה. העתקה היא
+// ברירת המחדל ומסומנת מראש, raw נשאר למי שיודע למה הוא צריך אותו
+fn ask_windows_mode(window: &ApplicationWindow, on_choice: impl Fn(bool) + 'static) {
+    let dialog = gtk4::MessageDialog::new(
+        Some(window),
+        gtk4::DialogFlags::MODAL,
+        gtk4::MessageType::Info,
+        gtk4::ButtonsType::None,
+        tr!("This is a Windows installer image"),
+    );
+    dialog.set_secondary_text(Some(tr!(
+        "Written raw, a Windows ISO does not boot on most PCs. Copying its files to a FAT32 partition makes the drive bootable on UEFI machines; a large install.wim is split automatically."
+    )));
+    dialog.add_button(tr!("Write raw anyway"), gtk4::ResponseType::Reject);
+    dialog.add_button(tr!("Copy files to FAT32 (recommended)"), gtk4::ResponseType::Accept);
+    dialog.set_default_response(gtk4::ResponseType::Accept);
+    dialog.connect_response(move |dialog, response| {
+        // סגירת הדיאלוג בלי בחירה משאירה את ההעתקה
+        on_choice(response == gtk4::ResponseType::Reject);
+        dialog.close();
+    });
+    dialog.present();
+}
+
+// מה שה-thread של הצריבה עם ההרשאות מעביר ל-UI
+enum ElevatedUpdate {
+    // שורת JSON אחת מ---headless --json
+    Event(serde_json::Value),
+    Exited { success: bool },
+}
+
+// הצריבה רצה בתהליך נפרד כ-root. ההתקדמות מגיעה מהשורות שהוא מדפיס
+fn offer_elevated_retry(
+    window: &ApplicationWindow,
+    status: &Label,
+    progress: &ProgressBar,
+    path: &Path,
+    config: BurnConfig,
+) {
+    let Some(command) = headless::elevated_command(&config) else {
+        return;
+    };
+    let dialog = gtk4::MessageDialog::new(
+        Some(window),
+        gtk4::DialogFlags::MODAL,
+        gtk4::MessageType::Question,
+        gtk4::ButtonsType::None,
+        tr!("No permission to write to {}", path.display()),
+    );
+    dialog.add_button(tr!("Cancel"), gtk4::ResponseType::Cancel);
+    dialog.add_button(tr!("Retry with elevated privileges"), gtk4::ResponseType::Accept);
+    let command = RefCell::new(Some(command));
+    let status = status.clone();
+    let progress = progress.clone();
+    dialog.connect_response(move |dialog, response| {
+        dialog.close();
+        let command = command.borrow_mut().take();
+        let Some(mut command) = command.filter(|_| response == gtk4::ResponseType::Accept) else {
+            return;
+        };
+        let mut child = match command.stdout(std::process::Stdio::piped()).spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                status.set_text(&tr!("Error: {}", e));
+                return;
+            }
+        };
+        tracing::info!("burning {} through pkexec", config.device_path.display());
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let stdout = child.stdout.take();
+        std::thread::spawn(move || {
+            use std::io::BufRead;
+            if let Some(stdout) = stdout {
+                for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if let Ok(event) = serde_json::from_str(&line) {
+                        let _ = sender.send(ElevatedUpdate::Event(event));
+                    }
+                }
+            }
+            let success = child.wait().is_ok_and(|status| status.success());
+            let _ = sender.send(ElevatedUpdate::Exited { success });
+        });
+
+        let status = status.clone();
+        let progress = progress.clone();
+        let mut ended = false;
+        // ה-thread לא נוגע ב-widgets; ה-UI מושך ממנו את מה שהצטבר
+        gtk4::glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            for update in receiver.try_iter() {
+                match update {
+                    ElevatedUpdate::Event(event) => {
+                        let fraction = |done: &str| {
+                            let total = event["total"].as_u64().unwrap_or(0).max(1);
+                            event[done].as_u64().unwrap_or(0) as f64 / total as f64
+                        };
+                        match event["type"].as_str() {
+                            Some("Progress") => {
+                                let fraction = fraction("written");
+                                progress.set_fraction(fraction);
+                                status.set_text(&tr!("Burning with elevated privileges… {}%", (fraction * 100.0) as u64));
+                            }
+                            Some("Verifying") => {
+                                let fraction = fraction("checked");
+                                progress.set_fraction(fraction);
+                                status.set_text(&tr!("Verifying… {}%", (fraction * 100.0) as u64));
+                            }
+                            Some("Finished") => {
+                                ended = true;
+                                progress.set_fraction(1.0);
+                                status.set_text(tr!("Burn finished with elevated privileges"));
+                            }
+                            Some("Error") => {
+                                ended = true;
+                                status.set_text(&tr!("Error: {}", event["message"].as_str().unwrap_or_default()));
+                            }
+                            _ => {}
+                        }
+                    }
+                    // pkexec שבוטל בחלון הסיסמה יוצא בלי אף שורה
+                    ElevatedUpdate::Exited { success } => {
+                        if !ended && !success {
+                            status.set_text(tr!("The elevated burn did not run"));
+                        }
+                        return gtk4::glib::ControlFlow::Break;
+                    }
+                }
+            }
+            gtk4::glib::ControlFlow::Continue
+        });
+    });
+    dialog.present();
+}
+
+// ניתוק לא יעזור על כונן המערכת - unmount_all יוריד את /home ו-/boot לפני שייכשל על /.
+// true = נדחה והמשתמש ראה למה
+fn refuse_system_disk(window: &ApplicationWindow, device: &Path) -> bool {
+    let Ok(Some(reason)) = mount::system_use(device) else {
+        return false;
+    };
+    let dialog = gtk4::MessageDialog::new(
+        Some(window),
+        gtk4::DialogFlags::MODAL,
+        gtk4::MessageType::Error,
+        gtk4::ButtonsType::Close,
+        &tr!("{} — this is the system disk and cannot be erased", reason),
+    );
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+    true
+}
+
+fn confirm_destructive(window: &ApplicationWindow, text: &str, on_confirm: impl Fn() + 'static) {
+    let dialog = gtk4::MessageDialog::new(
+        Some(window),
+        gtk4::DialogFlags::MODAL,
+        gtk4::MessageType::Warning,
+        gtk4::ButtonsType::OkCancel,
+        text,
+    );
+    dialog.connect_response(move |dialog, response| {
+        if response == gtk4::ResponseType::Ok {
+            on_confirm();
+        }
+        dialog.close();
+    });
+    dialog.present();
+}
+
+fn button_action(
+    window: &ApplicationWindow,
+    name: &str,
+    button: &Button,
+    accel: &str,
+) -> gtk4::gio::SimpleAction {
+    let action = gtk4::gio::SimpleAction::new(name, None);
+    window.add_action(&action);
+    button.set_action_name(Some(&format!("win.{}", name)));
+    // הקיצורים נשמרים באפליקציה, אבל win. מפנה אותם לחלון שבפוקוס
+    if let Some(app) = window.application() {
+        app.set_accels_for_action(&format!("win.{}", name), &[accel]);
+    }
+    action
+}
+
+// אין API לבנות ShortcutsWindow מקוד לפני GTK 4.14, אז דרך Builder.
+// action-name מושך את הקיצור עצמו מה-Application, כך שהחלון לא יוצא מסונכרן
+fn shortcuts_window() -> gtk4::ShortcutsWindow {
+    let shortcut = |title: &str, action: &str| {
+        format!(
+            "<child><object class=\"GtkShortcutsShortcut\">\
+             <property name=\"title\">{}</property>\
+             <property name=\"action-name\">{}</property>\
+             </object></child>",
+            gtk4::glib::markup_escape_text(title),
+            action
+        )
+    };
+    let xml = format!(
+        "<interface><object class=\"GtkShortcutsWindow\" id=\"shortcuts\">\
+         <property name=\"modal\">1</property>\
+         <child><object class=\"GtkShortcutsSection\">\
+         <property name=\"section-name\">main</property>\
+         <child><object class=\"GtkShortcutsGroup\">\
+         <property name=\"title\">{}</property>{}{}{}{}\
+         </object></child>\
+         <child><object class=\"GtkShortcutsGroup\">\
+         <property name=\"title\">{}</property>{}\
+         </object></child>\
+         </object></child></object></interface>",
+        gtk4::glib::markup_escape_text(tr!("Burning")),
+        shortcut(tr!("Select image"), "win.select-image"),
+        shortcut(tr!("Scan devices"), "win.scan"),
+        shortcut(tr!("Start burning"), "win.start"),
+        shortcut(tr!("Cancel"), "win.cancel"),
+        gtk4::glib::markup_escape_text(tr!("General")),
+        shortcut(tr!("Keyboard Shortcuts"), "win.shortcuts"),
+    );
+    gtk4::Builder::from_string(&xml)
+        .object("shortcuts")
+        .expect("shortcuts window in builder XML")
+}
+
+fn theme_from_id(id: Option<&str>) -> Theme {
+    match id {
+        Some("dark") => Theme::Dark,
+        Some("light") => Theme::Light,
+        _ => Theme::System,
+    }
+}
+
+#[cfg(not(feature = "adwaita"))]
+fn apply_theme(display: &gtk4::gdk::Display, provider: &gtk4::CssProvider, theme: Theme) {
+    let dark = match theme {
+        Theme::Dark => true,
+        Theme::Light => false,
+        Theme::System => system_prefers_dark(),
+    };
+    let palette = if dark { DARK_PALETTE } else { LIGHT_PALETTE };
+    gtk4::style_context_remove_provider_for_display(display, provider);
+    provider.load_from_data(&format!("{}{}", palette, APP_CSS));
+    gtk4::style_context_add_provider_for_display(
+        display,
+        provider,
+        gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+}
+
+// Adwaita כבר עוקב אחרי ה-portal בעצמו, רק כופים כשהמשתמש בחר
+#[cfg(feature = "adwaita")]
+fn apply_theme(display: &gtk4::gdk::Display, provider: &gtk4::CssProvider, theme: Theme) {
+    adw::StyleManager::default().set_color_scheme(match theme {
+        Theme::System => adw::ColorScheme::Default,
+        Theme::Dark => adw::ColorScheme::ForceDark,
+        Theme::Light => adw::ColorScheme::ForceLight,
+    });
+    gtk4::style_context_remove_provider_for_display(display, provider);
+    provider.load_from_data(APP_CSS);
+    gtk4::style_context_add_provider_for_display(
+        display,
+        provider,
+        gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+}
+
+// color-scheme: 1 = כהה, 2 = בהיר, 0 = אין העדפה. בלי העדפה ב-portal קובעת ההגדרה של GTK
+#[cfg(not(feature = "adwaita"))]
+fn system_prefers_dark() -> bool {
+    use gtk4::gio;
+    use gtk4::glib::ToVariant;
+
+    let portal = gio::bus_get_sync(gio::BusType::Session, None::<&gio::Cancellable>)
+        .ok()
+        .and_then(|bus| {
+            bus.call_sync(
+                Some("org.freedesktop.portal.Desktop"),
+                "/org/freedesktop/portal/desktop",
+                "org.freedesktop.portal.Settings",
+                "Read",
+                Some(&("org.freedesktop.appearance", "color-scheme").to_variant()),
+                None,
+                gio::DBusCallFlags::NONE,
+                500,
+                None::<&gio::Cancellable>,
+            )
+            .ok()
+        })
+        .and_then(|reply| {
+            // התשובה היא (v), ובגרסאות ישנות של ה-portal הערך עטוף פעמיים
+            let mut value = reply.child_value(0);
+            while let Some(inner) = value.as_variant() {
+                value = inner;
+            }
+            value.get::<u32>()
+        });
+
+    match portal {
+        Some(1) => true,
+        Some(2) => false,
+        _ => gtk4::Settings::default()
+            .is_some_and(|s| s.is_gtk_application_prefer_dark_theme()),
+    }
+}
+
+// דרך GApplication: עובד גם ב-Flatpak דרך ה-portal, בלי תלות ב-libnotify
+fn notify_desktop(app: &Application, window: &ApplicationWindow, title: &str, body: &str) {
+    let notification = gtk4::gio::Notification::new(title);
+    notification.set_body(Some(body));
+    // הכפתור (וגם לחיצה על ההתראה) מחזיר לפוקוס את החלון שהצריבה שלו נגמרה
+    let window_id = window.id().to_variant();
+    notification.set_default_action_and_target_value("app.focus-window", Some(&window_id));
+    notification.add_button_with_target_value(tr!("Open Burn Station"), "app.focus-window", Some(&window_id));
+    // אותו id - התראה חדשה מחליפה את הקודמת במקום להיערם
+    app.send_notification(Some("burn-ended"), &notification);
+}
+
+// אותן סיומות כמו בדיאלוג: ארבע התמונות ואחריהן הדחוסות
+const IMAGE_EXTENSIONS: [&str; 8] = ["iso", "img", "bin", "raw", "gz", "xz", "bz2", "zst"];
+
+fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+// כמה חלונות פתוחים - כל אחד מראה איזו תמונה נבחרה בו. None = עוד לא נבחרה
+fn window_title(image: Option<&Path>) -> String {
+    let Some(path) = image else {
+        return tr!("Burn Station Pro 2026").to_string();
+    };
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    match std::fs::metadata(path) {
+        Ok(meta) => tr!("Burn Station — {} ({})", name, format_bytes(meta.len())),
+        Err(_) => tr!("Burn Station — {}", name),
+    }
+}
+
+fn app_settings() -> Option<gtk4::gio::Settings> {
+    // בלי סכמה מותקנת gio::Settings::new קורס, אז בודקים קודם
+    let source = gtk4::gio::SettingsSchemaSource::default()?;
+    source.lookup(APP_ID, true)?;
+    Some(gtk4::gio::Settings::new(APP_ID))
+}
+
+fn current_filter(removable_check: &CheckButton) -> BurnFilter {
+    if removable_check.is_active() {
+        BurnFilter::RemovableOnly
+    } else {
+        BurnFilter::All
+    }
+}
+
+// שורה של lsblk -P נראית כך: NAME="/dev/sdb" RM="1" TRAN="usb" SIZE="14.9G" MODEL="Ultra"
+fn parse_lsblk_pairs(line: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut rest = line.trim();
+    while let Some(eq) = rest.find("=\"") {
+        let key = rest[..eq].trim().to_string();
+        let after = &rest[eq + 2..];
+        let Some(end) = after.find('"') else {
+            break;
+        };
+        fields.insert(key, after[..end].trim().to_string());
+        rest = &after[end + 1..];
+    }
+    fields
+}
+
+fn update_device_list(combo: &gtk4::ComboBoxText, filter: BurnFilter) {
+    let _span = tracing::info_span!("scan", ?filter).entered();
+    let devices = scan_devices(filter);
+    tracing::info!(count = devices.len(), ?devices, "scanned drives");
+    fill_device_combo(combo, &devices);
+}
+
+// (נתיב, תיאור) לכל כונן שלם שעובר את הסינון
+fn scan_devices(filter: BurnFilter) -> Vec<(String, String)> {
+    // הרצה של lsblk עם הגדרות רחבות יותר כדי לוודא שזה מוצא משהו
+    let output = std::process::Command::new("lsblk")
+        .args(["-dpnP", "-o", "NAME,RM,TRAN,SIZE,MODEL"])
+        .output();
+
+    match output {
+        Ok(out) => {
+            if !out.status.success() {
+                tracing::error!(
+                    status = %out.status,
+                    stderr = %String::from_utf8_lossy(&out.stderr).trim(),
+                    "lsblk failed"
+                );
+            }
+            parse_devices(
+                &String::from_utf8_lossy(&out.stdout),
+                filter,
+                Path::new("/sys/block"),
+            )
+        }
+        Err(e) => {
+            tracing::error!("Cannot run lsblk: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn parse_devices(list: &str, filter: BurnFilter, sys_block: &Path) -> Vec<(String, String)> {
+    let mut devices = Vec::new();
+
+    for line in list.lines() {
+        let fields = parse_lsblk_pairs(line);
+        let (Some(dev_path), Some(size)) = (fields.get("NAME"), fields.get("SIZE")) else {
+            tracing::warn!(line, "lsblk line without NAME or SIZE");
+            continue;
+        };
+        let model = fields.get("MODEL").map(String::as_str).unwrap_or("");
+        let mut dev_info = format!("{} {} {}", dev_path, size, model).trim().to_string();
+        let tran = fields.get("TRAN").map(String::as_str);
+
+        // USB 2.0 מול 3.x זה פי עשרה בזמן הצריבה - כדאי לדעת לפני שמתחילים
+        let bus = get_usb_speed(sys_block, dev_path).or_else(|| match tran {
+            Some("sata") => Some("SATA".to_string()),
+            Some("nvme") => Some("NVMe".to_string()),
+            _ => None,
+        });
+        if let Some(bus) = bus {
+            dev_info.push_str(&format!(" [{}]", bus));
+        }
+
+        // שני מקלות מאותו דגם נראים זהים בלי המספר הסידורי
+        if let Some(serial) = read_serial(sys_block, dev_path) {
+            dev_info.push_str(&format!(" (S/N: {})", serial));
+        }
+
+        let removable = fields.get("RM").map(String::as_str) == Some("1") || tran == Some("usb");
+        if filter == BurnFilter::RemovableOnly && !removable {
+            continue;
+        }
+
+        // אנחנו מסננים רק כוננים שלמים (בלי מחיצות כמו sda1)
+        if !dev_path.chars().last().unwrap_or(' ').is_numeric() {
+            devices.push((dev_path.clone(), dev_info));
+        }
+    }
+
+    devices
+}
+
+// בין SCAN ל-START אפשר לשלוף את המקל, ומקל אחר יכול לקבל את אותו /dev/sdX. התווית
+// כוללת דגם, גודל ומספר סידורי, אז היא צריכה להיות זהה למה שנבחר
+fn device_still_present(dev_path: &str, label: &str) -> bool {
+    let is_block = std::fs::metadata(dev_path).is_ok_and(|m| m.file_type().is_block_device());
+    is_block
+        && scan_devices(BurnFilter::All)
+            .iter()
+            .any(|(path, info)| path == dev_path && info == label)
+}
+
+// /sys/block/sdX/device/serial - לא כל התקן מספק אותו
+fn read_serial(sys_block: &Path, dev_path: &str) -> Option<String> {
+    let name = Path::new(dev_path).file_name()?;
+    let serial = std::fs::read_to_string(sys_block.join(name).join("device/serial")).ok()?;
+    let serial = serial.trim();
+    (!serial.is_empty()).then(|| serial.to_string())
+}
+
+// המהירות שהמקל סיכם עם ה-hub, מההתקן ב-USB שמעל ה-SCSI: .../2-1/2-1:1.0/host6/target6:0:0/6:0:0:0.
+// ל-interface (2-1:1.0) אין speed, אז עולים עד ההורה הראשון שיש לו. לא USB = None
+fn get_usb_speed(sys_block: &Path, dev_path: &str) -> Option<String> {
+    let name = Path::new(dev_path).file_name()?;
+    let device = std::fs::canonicalize(sys_block.join(name).join("device")).ok()?;
+    let speed = device
+        .ancestors()
+        .find_map(|dir| std::fs::read_to_string(dir.join("speed")).ok())?;
+    // ב-Mbps, ו-"1.5" ב-low speed
+    let mbps: f64 = speed.trim().parse().ok()?;
+    let version = match mbps as u64 {
+        0..=12 => "USB 1.1",
+        480 => "USB 2.0",
+        5000 => "USB 3.2 Gen 1",
+        10000 => "USB 3.2 Gen 2",
+        20000 => "USB 3.2 Gen 2x2",
+        _ => "USB",
+    };
+    Some(if mbps >= 1000.0 {
+        format!("{} / {} Gbps", version, mbps / 1000.0)
+    } else {
+        format!("{} / {} Mbps", version, mbps)
+    })
+}
+
+fn fill_device_combo(combo: &gtk4::ComboBoxText, devices: &[(String, String)]) {
+    // רענון לא אמור להחליף למשתמש את הכונן שבחר
+    let previous = combo.active_id();
+    combo.remove_all();
+
+    for (dev_path, dev_info) in devices {
+        combo.append(Some(dev_path), dev_info);
+    }
+
+    if devices.is_empty() {
+        tracing::info!("no drives found");
+        combo.append(Some("none"), tr!("No drives detected - Click SCAN"));
+    }
+
+    if previous.map_or(true, |id| !combo.set_active_id(Some(&id))) {
+        combo.set_active(Some(0));
+    }
+}
+
+// CheckButton לכל כונן. מה שהיה מסומן נשאר מסומן אחרי רענון
+fn fill_target_checks(list: &Box, devices: &[(String, String)]) {
+    let checked = checked_targets(list);
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    for (dev_path, dev_info) in devices {
+        let check = CheckButton::with_label(dev_info);
+        check.set_widget_name(dev_path);
+        check.set_active(checked.iter().any(|p| p.as_path() == Path::new(dev_path)));
+        list.append(&check);
+    }
+}
+
+fn checked_targets(list: &Box) -> Vec<PathBuf> {
+    let mut checked = Vec::new();
+    let mut child = list.first_child();
+    while let Some(widget) = child {
+        if let Ok(check) = widget.clone().downcast::<CheckButton>() {
+            if check.is_active() {
+                checked.push(PathBuf::from(check.widget_name().as_str()));
+            }
+        }
+        child = widget.next_sibling();
+    }
+    checked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burn_engine::BurnError;
+    use crate::mock_engine::MockBurnEngine;
+    use std::time::{Duration, Instant};
+
+    fn find_widget<W: IsA<gtk4::Widget>>(root: &gtk4::Widget, name: &str) -> Option<W> {
+        if root.widget_name() == name {
+            return root.clone().downcast::<W>().ok();
+        }
+        let mut child = root.first_child();
+        while let Some(widget) = child {
+            if let Some(found) = find_widget(&widget, name) {
+                return Some(found);
+            }
+            child = widget.next_sibling();
+        }
+        None
+    }
+
+    // מריץ את ה-main loop עד שהתנאי מתקיים (או timeout)
+    fn pump_until(condition: impl Fn() -> bool) -> bool {
+        let context = gtk4::glib::MainContext::default();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            while context.iteration(false) {}
+            if condition() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    // GTK מותר רק מה-thread שאתחל אותו, לכן כל התרחישים בבדיקה אחת
+    #[test]
+    fn ui_follows_engine_events() {
+        if gtk4::init().is_err() {
+            eprintln!("no display, skipping UI test");
+            return;
+        }
+        let app = Application::builder()
+            .application_id("com.shay.icedburn.pro.tests")
+            .flags(gtk4::gio::ApplicationFlags::NON_UNIQUE)
+            .build();
+        app.register(None::<&gtk4::gio::Cancellable>).unwrap();
+
+        let mock = Arc::new(MockBurnEngine::new(vec![
+            (
+                Duration::from_millis(10),
+                BurnEvent::Progress {
+                    written: 50,
+                    total: 100,
+                    read_speed_mbps: 80.0,
+                    write_speed_mbps: 12.0,
+                    eta_secs: 4,
+                },
+            ),
+            (
+                Duration::from_millis(10),
+                BurnEvent::Finished {
+                    bytes: 100,
+                    elapsed_secs: 222.0,
+                    verified: true,
+                },
+            ),
+        ]));
+        let window = build_window(&app, mock.clone());
+        let root: gtk4::Widget = window.clone().upcast();
+
+        let status: Label = find_widget(&root, "status").unwrap();
+        let progress: ProgressBar = find_widget(&root, "progress").unwrap();
+        let start: Button = find_widget(&root, "start").unwrap();
+        let cancel: Button = find_widget(&root, "cancel").unwrap();
+        let verify_only: Button = find_widget(&root, "verify-only").unwrap();
+
+        // בלי ISO נבחר אין מה לצרוב ואין מה לבדוק
+        assert!(!verify_only.is_sensitive());
+        assert!(!start.is_sensitive());
+        start.emit_clicked();
+        ActionGroupExt::activate_action(&window, "start", None);
+        assert_eq!(mock.start_count(), 0);
+
+        mock.play();
+        assert!(pump_until(|| status.text().contains("| 50.0% | 4s")));
+        assert!(status.text().starts_with("Read 80.0 MB/s | Write 12.0 MB/s"));
+        assert!(pump_until(|| status.text()
+            == "Done in 3m 42s — 100 B at avg 0.0 MB/s, verified OK"));
+        assert_eq!(progress.fraction(), 1.0);
+
+        cancel.emit_clicked();
+        cancel.emit_clicked();
+        assert_eq!(mock.cancel_count(), 2);
+
+        mock.emit(BurnEvent::Error(BurnError::new(
+            ErrorKind::DeviceTooSmall,
+            "Device too small",
+        )));
+        assert!(pump_until(|| status.text().ends_with("pick a larger drive")));
+        assert_eq!(mock.command_count(), 0);
+
+        window.destroy();
+    }
+
+    #[test]
+    fn only_image_files_are_accepted_from_a_drop() {
+        assert!(is_image_path(Path::new("/tmp/debian-12.5.0-amd64-netinst.iso")));
+        assert!(is_image_path(Path::new("/tmp/raspios.img.XZ")));
+        assert!(!is_image_path(Path::new("/tmp/notes.txt")));
+        assert!(!is_image_path(Path::new("/tmp/iso")));
+    }
+
+    #[test]
+    fn title_names_the_selected_image() {
+        assert_eq!(window_title(None), "Burn Station Pro 2026");
+        let image = std::env::temp_dir().join(format!("gtkburn-title-{}.iso", std::process::id()));
+        std::fs::write(&image, vec![0u8; 1536]).unwrap();
+        let title = window_title(Some(&image));
+        std::fs::remove_file(&image).unwrap();
+        assert_eq!(
+            title,
+            format!("Burn Station — gtkburn-title-{}.iso (1.5 KiB)", std::process::id())
+        );
+        assert_eq!(
+            window_title(Some(Path::new("/tmp/gtkburn-missing.iso"))),
+            "Burn Station — gtkburn-missing.iso"
+        );
+    }
+
+    #[test]
+    fn kiosk_autostart_needs_both_image_and_device() {
+        let vars = |set: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                set.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(KioskPreset::from_vars(vars(&[])), KioskPreset::default());
+        let preset = KioskPreset::from_vars(vars(&[
+            ("ICEDBURN_ISO", "/srv/images/station.iso"),
+            ("ICEDBURN_DEVICE", "/dev/disk/by-id/usb-Kingston_DataTraveler-0:0"),
+            ("ICEDBURN_AUTOSTART", "1"),
+        ]));
+        assert_eq!(preset.iso.as_deref(), Some(Path::new("/srv/images/station.iso")));
+        assert!(preset.autostart);
+        // בלי כונן אין מה לצרוב, וערך ריק נחשב כלא מוגדר
+        let preset = KioskPreset::from_vars(vars(&[
+            ("ICEDBURN_ISO", "/srv/images/station.iso"),
+            ("ICEDBURN_DEVICE", ""),
+            ("ICEDBURN_AUTOSTART", "1"),
+        ]));
+        assert_eq!((preset.device, preset.autostart), (None, false));
+        let preset = KioskPreset::from_vars(vars(&[
+            ("ICEDBURN_ISO", "/srv/images/station.iso"),
+            ("ICEDBURN_DEVICE", "/dev/sdb"),
+            ("ICEDBURN_AUTOSTART", "yes"),
+        ]));
+        assert!(!preset.autostart);
+    }
+
+    #[test]
+    fn report_lists_only_what_is_known() {
+        let mut report = BurnReport {
+            timestamp: 0,
+            image: "/srv/images/debian.iso".into(),
+            image_sha256: None,
+            device: "/dev/sdb".into(),
+            device_model: Some("Ultra".into()),
+            device_serial: Some("4C530001".into()),
+            device_size: Some(1536),
+            bytes_written: 1536,
+            bytes_verified: 1536,
+            avg_speed_mbps: 12.0,
+            verified: Some(true),
+            duration_secs: 2.0,
+            outcome: BurnOutcome::Completed,
+            error: None,
+        };
+        let text = report_text(&report);
+        assert!(text.contains("Drive: /dev/sdb — Ultra (4C530001) · 1.5 KiB"), "{}", text);
+        assert!(text.contains("Verify: passed"), "{}", text);
+        assert!(!text.contains("SHA-256") && !text.contains("Error"), "{}", text);
+
+        report.image_sha256 = Some("ab".into());
+        report.verified = None;
+        report.outcome = BurnOutcome::Failed;
+        report.error = Some("Write timed out".into());
+        let text = report_text(&report);
+        assert!(text.contains("SHA-256: ab"), "{}", text);
+        assert!(text.contains("Result: failed\nWritten:"), "{}", text);
+        assert!(text.contains("Verify: not run"), "{}", text);
+        assert!(text.ends_with("Error: Write timed out"), "{}", text);
+    }
+
+    #[test]
+    fn unplugged_device_is_not_burned() {
+        // המקל נשלף: ה-node נעלם, או שנשאר ערך "none" מסריקה ריקה
+        assert!(!device_still_present("/dev/gtkburn-unplugged", "/dev/sdb 28.7G Ultra"));
+        assert!(!device_still_present("none", "No drives detected - Click SCAN"));
+        // קובץ רגיל במקום block device
+        let file = std::env::temp_dir().join(format!("gtkburn-notblock-{}", std::process::id()));
+        std::fs::write(&file, b"").unwrap();
+        assert!(!device_still_present(file.to_str().unwrap(), ""));
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn serial_is_appended_to_its_own_drive() {
+        let sys_block = std::env::temp_dir().join(format!("gtkburn-sysfs-{}", std::process::id()));
+        std::fs::create_dir_all(sys_block.join("sdb/device")).unwrap();
+        std::fs::create_dir_all(sys_block.join("sdc/device")).unwrap();
+        std::fs::write(sys_block.join("sdb/device/serial"), "4C530001230915117364\n").unwrap();
+
+        let list = "NAME=\"/dev/sdb\" RM=\"1\" TRAN=\"usb\" SIZE=\"28.7G\" MODEL=\"Ultra\"\n\
+                    NAME=\"/dev/sdc\" RM=\"1\" TRAN=\"usb\" SIZE=\"28.7G\" MODEL=\"Ultra\"\n";
+        let devices = parse_devices(list, BurnFilter::All, &sys_block);
+        let _ = std::fs::remove_dir_all(&sys_block);
+
+        assert_eq!(
+            devices,
+            vec![
+                (
+                    "/dev/sdb".to_string(),
+                    "/dev/sdb 28.7G Ultra (S/N: 4C530001230915117364)".to_string()
+                ),
+                // בלי קובץ serial - בלי השדה
+                ("/dev/sdc".to_string(), "/dev/sdc 28.7G Ultra".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bus_speed_comes_from_the_usb_device_above_the_disk() {
+        let sys = std::env::temp_dir().join(format!("gtkburn-usb-{}", std::process::id()));
+        let usb3 = sys.join("devices/usb2/2-1");
+        let usb2 = sys.join("devices/usb1/1-4");
+        let scsi = "host6/target6:0:0/6:0:0:0";
+        std::fs::create_dir_all(usb3.join("2-1:1.0").join(scsi)).unwrap();
+        std::fs::create_dir_all(usb2.join("1-4:1.0").join(scsi)).unwrap();
+        std::fs::create_dir_all(sys.join("devices/ata1/host0/target0:0:0/0:0:0:0")).unwrap();
+        std::fs::write(usb3.join("speed"), "5000\n").unwrap();
+        std::fs::write(usb2.join("speed"), "480\n").unwrap();
+        for (disk, device) in [
+            ("sdb", format!("devices/usb2/2-1/2-1:1.0/{}", scsi)),
+            ("sdc", format!("devices/usb1/1-4/1-4:1.0/{}", scsi)),
+            ("sda", "devices/ata1/host0/target0:0:0/0:0:0:0".to_string()),
+        ] {
+            let block = sys.join("block").join(disk);
+            std::fs::create_dir_all(&block).unwrap();
+            std::os::unix::fs::symlink(sys.join(device), block.join("device")).unwrap();
+        }
+
+        let list = "NAME=\"/dev/sda\" RM=\"0\" TRAN=\"sata\" SIZE=\"465.8G\" MODEL=\"Samsung SSD\"\n\
+                    NAME=\"/dev/sdb\" RM=\"1\" TRAN=\"usb\" SIZE=\"28.7G\" MODEL=\"Ultra\"\n\
+                    NAME=\"/dev/sdc\" RM=\"1\" TRAN=\"usb\" SIZE=\"7.5G\" MODEL=\"Cruzer\"\n";
+        let devices = parse_devices(list, BurnFilter::All, &sys.join("block"));
+        let _ = std::fs::remove_dir_all(&sys);
+
+        let labels: Vec<&str> = devices.iter().map(|(_, label)| label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "/dev/sda 465.8G Samsung SSD [SATA]",
+                "/dev/sdb 28.7G Ultra [USB 3.2 Gen 1 / 5 Gbps]",
+                "/dev/sdc 7.5G Cruzer [USB 2.0 / 480 Mbps]",
+            ]
+        );
+    }
+}
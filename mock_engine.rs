@@ -0,0 +1,86 @@
+use crate::burn_engine::{BurnBackend, BurnCommand, BurnEvent};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+// מנוע מזויף לבדיקות UI: רושם כל פקודה, ו-start משדר רצף אירועים שהוגדר מראש
+pub struct MockBurnEngine {
+    script: Mutex<Vec<(Duration, BurnEvent)>>,
+    commands: Mutex<Vec<BurnCommand>>,
+    cancels: AtomicU32,
+    event_tx: Sender<BurnEvent>,
+    event_rx: Receiver<BurnEvent>,
+}
+
+impl MockBurnEngine {
+    // כל אירוע נשלח אחרי ההשהיה שלו, ביחס לאירוע הקודם
+    pub fn new(script: Vec<(Duration, BurnEvent)>) -> Self {
+        let (event_tx, event_rx) = unbounded();
+        Self {
+            script: Mutex::new(script),
+            commands: Mutex::new(Vec::new()),
+            cancels: AtomicU32::new(0),
+            event_tx,
+            event_rx,
+        }
+    }
+
+    // מריץ את הרצף בלי לעבור דרך start (למשל כשאין ISO נבחר בבדיקה)
+    pub fn play(&self) {
+        let script = std::mem::take(&mut *self.script.lock().unwrap());
+        let event_tx = self.event_tx.clone();
+        thread::spawn(move || {
+            for (delay, event) in script {
+                thread::sleep(delay);
+                if event_tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    pub fn emit(&self, event: BurnEvent) {
+        let _ = self.event_tx.send(event);
+    }
+
+    pub fn start_count(&self) -> usize {
+        self.commands
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|cmd| matches!(cmd, BurnCommand::Start(_)))
+            .count()
+    }
+
+    pub fn command_count(&self) -> usize {
+        self.commands.lock().unwrap().len()
+    }
+
+    pub fn cancel_count(&self) -> u32 {
+        self.cancels.load(Ordering::Relaxed)
+    }
+}
+
+impl BurnBackend for MockBurnEngine {
+    fn submit(&self, cmd: BurnCommand) -> u64 {
+        let is_start = matches!(cmd, BurnCommand::Start(_));
+        let mut commands = self.commands.lock().unwrap();
+        commands.push(cmd);
+        let id = commands.len() as u64;
+        drop(commands);
+        if is_start {
+            self.play();
+        }
+        id
+    }
+
+    fn cancel(&self) {
+        self.cancels.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn events(&self) -> Receiver<BurnEvent> {
+        self.event_rx.clone()
+    }
+}
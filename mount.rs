@@ -0,0 +1,170 @@
+// mount זמני לתמונה או למחיצה שהמנוע עובד עליה (העתקת Windows, כונן multiboot)
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// mount בתיקייה זמנית. unmount מחזיר את השגיאה - ביעד זה הרגע שבו הנתונים נכתבים
+// בפועל. בכל יציאה אחרת (שגיאה, ביטול) ה-Drop מנקה
+pub struct Mount {
+    dir: PathBuf,
+    mounted: bool,
+}
+
+impl Mount {
+    pub fn new(source: &Path, name: &str, options: &str) -> io::Result<Self> {
+        let dir = std::env::temp_dir().join(format!("gtkburn-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let mut mount = Self {
+            dir,
+            mounted: false,
+        };
+        run(Command::new("mount")
+            .args(["-o", options])
+            .arg(source)
+            .arg(&mount.dir))?;
+        mount.mounted = true;
+        Ok(mount)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn unmount(mut self) -> io::Result<()> {
+        run(Command::new("umount").arg(&self.dir))?;
+        self.mounted = false;
+        Ok(())
+    }
+}
+
+impl Drop for Mount {
+    fn drop(&mut self) {
+        if self.mounted {
+            let _ = Command::new("umount").arg(&self.dir).output();
+        }
+        let _ = std::fs::remove_dir(&self.dir);
+    }
+}
+
+// ההתקן עצמו וכל מחיצה שלו שמעוגנת כרגע: (node, נקודת עיגון)
+pub fn mounted_under(device: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts")?;
+    Ok(parse_mounts(&mounts, device))
+}
+
+pub(crate) fn parse_mounts(mounts: &str, device: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let prefix = device.to_string_lossy();
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (source, target) = (fields.next()?, fields.next()?);
+            // /dev/sda לא תופס את /dev/sdab, ו-/dev/nvme0n1 לא את /dev/nvme0n10
+            let rest = source.strip_prefix(prefix.as_ref())?;
+            let ours = rest.is_empty()
+                || rest.trim_start_matches('p').parse::<u32>().is_ok_and(|i| {
+                    crate::partitions::partition_path(device, i) == Path::new(source)
+                });
+            ours.then(|| (PathBuf::from(source), PathBuf::from(unescape_octal(target))))
+        })
+        .collect()
+}
+
+// הקרנל כותב רווח, טאב ו-\ בנתיב כ-\040 וכו'
+fn unescape_octal(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(i) = rest.find('\\') {
+        unescaped.push_str(&rest[..i]);
+        match rest
+            .get(i + 1..i + 4)
+            .and_then(|code| u8::from_str_radix(code, 8).ok())
+        {
+            Some(byte) => {
+                unescaped.push(byte as char);
+                rest = &rest[i + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[i + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+// נקודות עיגון שבלעדיהן המערכת שרצה נופלת
+const SYSTEM_MOUNTS: [&str; 7] = ["/", "/boot", "/boot/efi", "/efi", "/usr", "/var", "/home"];
+
+// למה אסור לכתוב על הכונן הזה, או None. גם /dev/disk/by-id עובד - עובר canonicalize
+pub fn system_use(device: &Path) -> io::Result<Option<String>> {
+    let device = std::fs::canonicalize(device).unwrap_or_else(|_| device.to_path_buf());
+    let mounts = std::fs::read_to_string("/proc/self/mounts")?;
+    // בלי /proc/swaps (קונטיינר) אין swap לבדוק
+    let swaps = std::fs::read_to_string("/proc/swaps").unwrap_or_default();
+    Ok(system_use_in(&mounts, &swaps, &device).or_else(|| held_by(&device)))
+}
+
+pub(crate) fn system_use_in(mounts: &str, swaps: &str, device: &Path) -> Option<String> {
+    let system = parse_mounts(mounts, device)
+        .into_iter()
+        .find(|(_, target)| SYSTEM_MOUNTS.iter().any(|dir| target == Path::new(dir)));
+    if let Some((node, target)) = system {
+        return Some(format!(
+            "{} is mounted at {}",
+            node.display(),
+            target.display()
+        ));
+    }
+    // אותו פורמט: ה-node בשדה הראשון. שורת הכותרת לא מתחילה ב-/dev אז לא תתאים
+    parse_mounts(swaps, device)
+        .into_iter()
+        .next()
+        .map(|(node, _)| format!("{} is in use as swap", node.display()))
+}
+
+// LVM, RAID או LUKS מעל הכונן או מעל אחת המחיצות שלו. לא עוקבים אחרי השכבות -
+// מספיק שמשהו מחזיק אותו
+fn held_by(device: &Path) -> Option<String> {
+    let name = device.file_name()?.to_str()?;
+    let block = Path::new("/sys/block").join(name);
+    let partitions = std::fs::read_dir(&block).ok()?.filter_map(|entry| {
+        let entry = entry.ok()?;
+        entry
+            .file_name()
+            .to_str()?
+            .starts_with(name)
+            .then(|| entry.path())
+    });
+    std::iter::once(block.clone())
+        .chain(partitions)
+        .find_map(|dir| {
+            let holder = std::fs::read_dir(dir.join("holders")).ok()?.next()?.ok()?;
+            Some(format!(
+                "/dev/{} is held by {} (LVM, RAID or an encrypted volume)",
+                dir.file_name()?.to_string_lossy(),
+                holder.file_name().to_string_lossy()
+            ))
+        })
+}
+
+// לפי הסדר ההפוך: עיגון שיושב בתוך עיגון אחר של אותו כונן יורד קודם
+pub fn unmount_all(mounted: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    for (_, target) in mounted.iter().rev() {
+        run(Command::new("umount").arg(target))?;
+    }
+    Ok(())
+}
+
+fn run(command: &mut Command) -> io::Result<()> {
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "{} failed: {}",
+            command.get_program().to_string_lossy(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
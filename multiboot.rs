@@ -0,0 +1,135 @@
+// כונן multiboot: מחיצת FAT32 קטנה עם GRUB, ומחיצת exFAT שמעתיקים אליה קבצי ISO בלי
+// לצרוב מחדש. כל ISO מקבל רשומה בתפריט שעולה דרך ה-loopback.cfg שלו
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const BOOT_LABEL: &str = "MULTIBOOT";
+pub const DATA_LABEL: &str = "ISOS";
+// GRUB ל-EFI ול-BIOS עם כל המודולים תופס בערך 30MB
+pub const BOOT_PARTITION_SIZE: u64 = 256 * 1024 * 1024;
+
+// ב-FAT32 התווית והסוג יושבים בסוף ה-BPB המורחב, מרופדים ברווחים
+const FAT32_LABEL: std::ops::Range<usize> = 71..82;
+const FAT32_TYPE: std::ops::Range<usize> = 82..90;
+
+// הסקטור הראשון של מחיצה 1 - כך מזהים כונן שכבר הוכן
+pub fn is_boot_sector(sector: &[u8]) -> bool {
+    let (Some(label), Some(kind)) = (sector.get(FAT32_LABEL), sector.get(FAT32_TYPE)) else {
+        return false;
+    };
+    kind == b"FAT32   " && label == format!("{:<11}", BOOT_LABEL).as_bytes()
+}
+
+// כל ה-ISO בשורש מחיצת הנתונים, לפי השם
+pub fn list_isos(root: &Path) -> io::Result<Vec<String>> {
+    let mut isos = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_file() && name.to_ascii_lowercase().ends_with(".iso") {
+            isos.push(name);
+        }
+    }
+    isos.sort();
+    Ok(isos)
+}
+
+// מחרוזת במרכאות כפולות של GRUB: $ עדיין מתפרש, ו-\ ו-" צריכים escape
+fn grub_quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        if matches!(c, '"' | '\\' | '$') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// loopback.cfg הוא המוסכמה שרוב ההפצות תומכות בה לעלייה מקובץ ISO: הוא יודע איזה
+// kernel ואילו פרמטרים, ואנחנו רק צריכים להעביר את iso_path
+pub fn grub_config(isos: &[String]) -> String {
+    let mut config = format!(
+        "# Generated by Burn Station - rewritten whenever an ISO is added\n\
+         insmod part_msdos\n\
+         insmod exfat\n\
+         insmod loopback\n\
+         insmod iso9660\n\
+         search --no-floppy --set=isopart --label {}\n\
+         set timeout=10\n",
+        DATA_LABEL
+    );
+    for iso in isos {
+        config.push_str(&format!(
+            "\nmenuentry {} {{\n\
+             \x20   set iso_path={}\n\
+             \x20   export iso_path\n\
+             \x20   loopback loop \"($isopart)$iso_path\"\n\
+             \x20   set root=(loop)\n\
+             \x20   configfile /boot/grub/loopback.cfg\n\
+             }}\n",
+            grub_quote(iso),
+            grub_quote(&format!("/{}", iso))
+        ));
+    }
+    config
+}
+
+// Fedora קוראת לכלי grub2-install ושמה את הקבצים ב-grub2, השאר grub-install ו-grub
+fn grub_install() -> Option<&'static str> {
+    ["grub2-install", "grub-install"].into_iter().find(|tool| {
+        Command::new(tool)
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+pub fn grub_directory(boot: &Path) -> PathBuf {
+    let grub2 = boot.join("boot/grub2");
+    if grub2.is_dir() {
+        grub2
+    } else {
+        boot.join("boot/grub")
+    }
+}
+
+// EFI חובה. BIOS רק אם המודולים של i386-pc מותקנים - אחרת Some(הסיבה), והכונן
+// יעלה רק במחשבי UEFI
+pub fn install_grub(boot: &Path, device: &Path) -> io::Result<Option<String>> {
+    let tool = grub_install().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "grub-install is needed to prepare a multiboot drive",
+        )
+    })?;
+    let boot_directory = boot.join("boot");
+
+    let efi = Command::new(tool)
+        .args(["--target=x86_64-efi", "--removable", "--no-nvram"])
+        .arg(format!("--efi-directory={}", boot.display()))
+        .arg(format!("--boot-directory={}", boot_directory.display()))
+        .output()?;
+    if !efi.status.success() {
+        return Err(io::Error::other(format!(
+            "{} for EFI failed: {}",
+            tool,
+            String::from_utf8_lossy(&efi.stderr).trim()
+        )));
+    }
+
+    let bios = Command::new(tool)
+        .arg("--target=i386-pc")
+        .arg(format!("--boot-directory={}", boot_directory.display()))
+        .arg(device)
+        .output()?;
+    if !bios.status.success() {
+        return Ok(Some(
+            String::from_utf8_lossy(&bios.stderr).trim().to_string(),
+        ));
+    }
+    Ok(None)
+}
@@ -0,0 +1,504 @@
+// טבלת המחיצות בתחילת התמונה (MBR או GPT): קריאה, והוספת מחיצה אחרי תמונה שכבר נכתבה
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SECTOR: usize = 512;
+// אותו סקטור, לחישובי offset על ההתקן
+const DISK_SECTOR: u64 = SECTOR as u64;
+const MBR_TABLE_OFFSET: usize = 446;
+const MBR_PROTECTIVE: u8 = 0xEE;
+const GPT_SIGNATURE: &[u8] = b"EFI PART";
+// שם המחיצה ב-GPT: 36 תווי UTF-16
+const GPT_NAME_UNITS: usize = 36;
+
+// מחיצה חדשה מתחילה על גבול MB, כמו ב-parted וב-fdisk
+const PARTITION_ALIGN: u64 = 1024 * 1024;
+// פחות מזה לא שווה מחיצה
+const MIN_NEW_PARTITION: u64 = 64 * 1024 * 1024;
+
+const BLKRRPART: libc::c_ulong = 0x125F;
+// udev עוד מחזיק את ההתקן רגע אחרי הכתיבה
+const REREAD_ATTEMPTS: u32 = 10;
+const REREAD_RETRY: Duration = Duration::from_millis(500);
+const PARTITION_NODE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// סוג המחיצה החדשה בשתי הטבלאות. ה-GUID כמו שהוא יושב על הדיסק
+#[derive(Debug, Clone, Copy)]
+pub struct PartitionType {
+    pub mbr: u8,
+    pub gpt: [u8; 16],
+}
+
+// 0FC63DAF-8483-4772-8E79-3D69D8477DE4
+pub const LINUX_FILESYSTEM: PartitionType = PartitionType {
+    mbr: 0x83,
+    gpt: [
+        0xAF, 0x3D, 0xC6, 0x0F, 0x83, 0x84, 0x72, 0x47, 0x8E, 0x79, 0x3D, 0x69, 0xD8, 0x47, 0x7D,
+        0xE4,
+    ],
+};
+
+// EBD0A0A2-B9E5-4433-87C0-68B6B72699C7 - מה ש-Windows מצפה לו גם ב-GPT
+const BASIC_DATA_GUID: [u8; 16] = [
+    0xA2, 0xA0, 0xD0, 0xEB, 0xE5, 0xB9, 0x33, 0x44, 0x87, 0xC0, 0x68, 0xB6, 0xB7, 0x26, 0x99, 0xC7,
+];
+
+pub const FAT32_DATA: PartitionType = PartitionType {
+    mbr: 0x0C,
+    gpt: BASIC_DATA_GUID,
+};
+
+pub const EXFAT_DATA: PartitionType = PartitionType {
+    mbr: 0x07,
+    gpt: BASIC_DATA_GUID,
+};
+
+// אותו סוג MBR כמו exFAT - Windows מבדיל ביניהם לפי ה-boot sector
+pub const NTFS_DATA: PartitionType = EXFAT_DATA;
+
+// הטבלה של דיסק חדש. MBR עולה בכל מקום, GPT נדרש מעל 2TB
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum PartitionScheme {
+    #[default]
+    Mbr,
+    Gpt,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPartition {
+    pub index: u32,
+    // בבייטים
+    pub start: u64,
+    pub size: u64,
+}
+
+// LBA 0 (MBR) + LBA 1 (GPT header) + 128 רשומות של 128 בייט
+pub const TABLE_HEAD_LEN: usize = 2 * SECTOR + 128 * 128;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionInfo {
+    pub index: u32,
+    pub kind: String,
+    // בבייטים
+    pub start: u64,
+    pub size: u64,
+}
+
+pub(crate) fn u32_at(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+pub(crate) fn u64_at(buf: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+}
+
+// ריק = אין טבלה (מערכת קבצים אחת על כל התמונה)
+pub fn parse_partition_table(head: &[u8]) -> Vec<PartitionInfo> {
+    if head.len() < SECTOR || head[510..512] != [0x55, 0xAA] {
+        return Vec::new();
+    }
+
+    let entries: Vec<&[u8]> = (0..4)
+        .map(|i| &head[MBR_TABLE_OFFSET + i * 16..MBR_TABLE_OFFSET + (i + 1) * 16])
+        .collect();
+
+    if entries.iter().any(|e| e[4] == MBR_PROTECTIVE) {
+        if let Some(partitions) = parse_gpt(head) {
+            return partitions;
+        }
+    }
+
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e[4] != 0 && u32_at(e, 12) != 0)
+        .map(|(i, e)| PartitionInfo {
+            index: i as u32 + 1,
+            kind: mbr_type_name(e[4]),
+            start: u32_at(e, 8) as u64 * SECTOR as u64,
+            size: u32_at(e, 12) as u64 * SECTOR as u64,
+        })
+        .collect()
+}
+
+fn parse_gpt(head: &[u8]) -> Option<Vec<PartitionInfo>> {
+    let header = head.get(SECTOR..2 * SECTOR)?;
+    if &header[0..8] != GPT_SIGNATURE {
+        return None;
+    }
+
+    let entries_lba = u64_at(header, 72) as usize;
+    let count = u32_at(header, 80) as usize;
+    let entry_size = u32_at(header, 84) as usize;
+    if entry_size < 128 {
+        return None;
+    }
+
+    let mut partitions = Vec::new();
+    for i in 0..count {
+        let offset = entries_lba * SECTOR + i * entry_size;
+        // רשומות מעבר למה שנקרא - מוותרים עליהן
+        let Some(entry) = head.get(offset..offset + 128) else {
+            break;
+        };
+        let type_guid = &entry[0..16];
+        if type_guid.iter().all(|b| *b == 0) {
+            continue;
+        }
+        let first = u64_at(entry, 32);
+        let last = u64_at(entry, 40);
+        partitions.push(PartitionInfo {
+            index: i as u32 + 1,
+            kind: gpt_type_name(type_guid),
+            start: first * SECTOR as u64,
+            size: (last.saturating_sub(first) + 1) * SECTOR as u64,
+        });
+    }
+
+    Some(partitions)
+}
+
+fn mbr_type_name(kind: u8) -> String {
+    match kind {
+        0x01 => "FAT12".into(),
+        0x04 | 0x06 | 0x0E => "FAT16".into(),
+        0x07 => "NTFS/exFAT".into(),
+        0x0B | 0x0C => "FAT32".into(),
+        0x17 => "Hidden NTFS".into(),
+        0x82 => "Linux swap".into(),
+        0x83 => "Linux".into(),
+        0xEF => "EFI System".into(),
+        other => format!("MBR type 0x{:02X}", other),
+    }
+}
+
+// GUID בפורמט הטקסטואלי: שלושת השדות הראשונים little-endian
+fn guid_string(raw: &[u8]) -> String {
+    format!(
+        "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{}",
+        u32_at(raw, 0),
+        u16::from_le_bytes([raw[4], raw[5]]),
+        u16::from_le_bytes([raw[6], raw[7]]),
+        raw[8],
+        raw[9],
+        raw[10..16]
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<String>()
+    )
+}
+
+fn gpt_type_name(raw: &[u8]) -> String {
+    let guid = guid_string(raw);
+    match guid.as_str() {
+        "C12A7328-F81F-11D2-BA4B-00A0C93EC93B" => "EFI System".into(),
+        "21686148-6449-6E6F-744E-656564454649" => "BIOS boot".into(),
+        "EBD0A0A2-B9E5-4433-87C0-68B6B72699C7" => "Basic data".into(),
+        "0FC63DAF-8483-4772-8E79-3D69D8477DE4" => "Linux filesystem".into(),
+        "0657FD6D-A4AB-43C4-84E5-0933C84B4F4F" => "Linux swap".into(),
+        _ => guid,
+    }
+}
+
+// הטבלה נכתבה עם התמונה, אז מוסיפים לה רשומה אחרי המחיצה האחרונה (וגם אחרי סוף התמונה -
+// ב-ISO היברידי מחיצה 1 לא תמיד מכסה את כולה). המחיצה קטנה מ-size אם אין מספיק מקום
+pub fn append_partition(
+    device: &File,
+    device_size: u64,
+    image_len: u64,
+    size: u64,
+    kind: PartitionType,
+    label: &str,
+) -> io::Result<NewPartition> {
+    let mut head = vec![0u8; TABLE_HEAD_LEN];
+    device.read_exact_at(&mut head, 0)?;
+
+    let existing = parse_partition_table(&head);
+    if existing.is_empty() {
+        return Err(io::Error::other(
+            "The image has no partition table to extend",
+        ));
+    }
+    let used = existing
+        .iter()
+        .map(|p| p.start + p.size)
+        .max()
+        .unwrap_or(0)
+        .max(image_len);
+    let start = used.div_ceil(PARTITION_ALIGN) * PARTITION_ALIGN;
+
+    // אותו תנאי כמו ב-parse_partition_table: GPT רק עם רשומה מגינה וחתימה
+    let protective = (0..4).any(|i| head[MBR_TABLE_OFFSET + i * 16 + 4] == MBR_PROTECTIVE);
+    if protective && &head[SECTOR..SECTOR + 8] == b"EFI PART" {
+        add_gpt_entry(device, &mut head, device_size, start, size, kind, label)
+    } else {
+        add_mbr_entry(device, &head, device_size, start, size, kind)
+    }
+}
+
+// דיסק חדש: המחיצות לפי הסדר, כל אחת מיושרת ל-MB אחרי הקודמת (u64::MAX = כל השאר).
+// ב-MBR הראשונה מסומנת bootable. טבלה ישנה נמחקת משני הקצוות - אחרת הקושחה עלולה
+// להעדיף GPT ישן על ה-MBR החדש
+pub fn write_fresh_table(
+    device: &File,
+    device_size: u64,
+    scheme: PartitionScheme,
+    layout: &[(u64, PartitionType)],
+) -> io::Result<Vec<NewPartition>> {
+    let zeros = vec![0u8; PARTITION_ALIGN as usize];
+    device.write_all_at(&zeros, device_size.saturating_sub(PARTITION_ALIGN))?;
+    device.write_all_at(&zeros, 0)?;
+
+    let mut head = vec![0u8; TABLE_HEAD_LEN];
+    // Windows מזהה דיסקים לפי החתימה הזו, אפסים נראים לו כמו דיסק שלא אותחל
+    head[440..444].copy_from_slice(&random_guid()?[..4]);
+    head[510..512].copy_from_slice(&[0x55, 0xAA]);
+    if scheme == PartitionScheme::Gpt {
+        empty_gpt(&mut head)?;
+    }
+    device.write_all_at(&head, 0)?;
+
+    let mut created = Vec::new();
+    let mut start = PARTITION_ALIGN;
+    for (size, kind) in layout {
+        let partition = match scheme {
+            PartitionScheme::Mbr => add_mbr_entry(device, &head, device_size, start, *size, *kind)?,
+            PartitionScheme::Gpt => {
+                add_gpt_entry(device, &mut head, device_size, start, *size, *kind, "")?
+            }
+        };
+        // add_mbr_entry מחפש רשומה פנויה ב-head, אז הוא צריך לראות את מה שנכתב
+        device.read_exact_at(&mut head, 0)?;
+        start = (partition.start + partition.size).div_ceil(PARTITION_ALIGN) * PARTITION_ALIGN;
+        created.push(partition);
+    }
+    if scheme == PartitionScheme::Mbr {
+        device.write_all_at(&[0x80], MBR_TABLE_OFFSET as u64)?;
+    }
+    Ok(created)
+}
+
+// רשומה מגינה וכותרת GPT בלי מחיצות. את הגודל, העותק המשני וה-CRCs משלים
+// add_gpt_entry כשהוא מוסיף את המחיצה הראשונה
+fn empty_gpt(head: &mut [u8]) -> io::Result<()> {
+    let protective = &mut head[MBR_TABLE_OFFSET..MBR_TABLE_OFFSET + 16];
+    protective[1..4].copy_from_slice(&[0x00, 0x02, 0x00]);
+    protective[4] = MBR_PROTECTIVE;
+    protective[5..8].copy_from_slice(&[0xFF, 0xFF, 0xFF]);
+    protective[8..12].copy_from_slice(&1u32.to_le_bytes());
+
+    let header = &mut head[SECTOR..2 * SECTOR];
+    header[0..8].copy_from_slice(GPT_SIGNATURE);
+    header[8..12].copy_from_slice(&0x0001_0000u32.to_le_bytes());
+    header[12..16].copy_from_slice(&92u32.to_le_bytes());
+    header[24..32].copy_from_slice(&1u64.to_le_bytes());
+    // אחרי הכותרת ו-128 רשומות של 128 בייט
+    header[40..48].copy_from_slice(&34u64.to_le_bytes());
+    header[56..72].copy_from_slice(&random_guid()?);
+    header[72..80].copy_from_slice(&2u64.to_le_bytes());
+    header[80..84].copy_from_slice(&128u32.to_le_bytes());
+    header[84..88].copy_from_slice(&128u32.to_le_bytes());
+    Ok(())
+}
+
+fn add_mbr_entry(
+    device: &File,
+    head: &[u8],
+    device_size: u64,
+    start: u64,
+    size: u64,
+    kind: PartitionType,
+) -> io::Result<NewPartition> {
+    // ב-ISO של Debian מחיצה 1 היא מסוג 0 אבל עם גודל - פנויה רק רשומה ריקה לגמרי
+    let slot = (0..4)
+        .find(|i| {
+            head[MBR_TABLE_OFFSET + i * 16..MBR_TABLE_OFFSET + (i + 1) * 16]
+                .iter()
+                .all(|b| *b == 0)
+        })
+        .ok_or_else(|| io::Error::other("All four MBR partition slots are taken"))?;
+
+    let first = start / DISK_SECTOR;
+    let end = start.saturating_add(size).min(device_size);
+    if first > u32::MAX as u64 || end < start + MIN_NEW_PARTITION {
+        return Err(no_room());
+    }
+    // MBR סופר סקטורים ב-32 ביט
+    let sectors = ((end - start) / DISK_SECTOR).min(u32::MAX as u64);
+
+    let mut entry = [0u8; 16];
+    // כתובות CHS לא בשימוש - הערך המקובל ל"מעבר לטווח"
+    entry[1..4].copy_from_slice(&[0xFE, 0xFF, 0xFF]);
+    entry[4] = kind.mbr;
+    entry[5..8].copy_from_slice(&[0xFE, 0xFF, 0xFF]);
+    entry[8..12].copy_from_slice(&(first as u32).to_le_bytes());
+    entry[12..16].copy_from_slice(&(sectors as u32).to_le_bytes());
+    device.write_all_at(&entry, (MBR_TABLE_OFFSET + slot * 16) as u64)?;
+
+    Ok(NewPartition {
+        index: slot as u32 + 1,
+        start,
+        size: sectors * DISK_SECTOR,
+    })
+}
+
+// ב-dd ה-GPT המשני נשאר בסוף התמונה, אז כותבים אותו מחדש בסוף ההתקן ומעדכנים את
+// last usable LBA ואת ה-CRCs בשני העותקים
+fn add_gpt_entry(
+    device: &File,
+    head: &mut [u8],
+    device_size: u64,
+    start: u64,
+    size: u64,
+    kind: PartitionType,
+    label: &str,
+) -> io::Result<NewPartition> {
+    let header_offset = SECTOR;
+    let header = &head[header_offset..header_offset + SECTOR];
+    let header_size = u32_at(header, 12) as usize;
+    let entries_lba = u64_at(header, 72);
+    let count = u32_at(header, 80) as usize;
+    let entry_size = u32_at(header, 84) as usize;
+    let table_len = count * entry_size;
+
+    // הרשומות חייבות להיות במה שנקרא, מיד אחרי הכותרת
+    let entries_offset = 2 * SECTOR;
+    if entries_lba != 2
+        || !(92..=SECTOR).contains(&header_size)
+        || entry_size < 128
+        || entries_offset + table_len > head.len()
+    {
+        return Err(io::Error::other("Unsupported GPT layout"));
+    }
+
+    let last_lba = device_size / DISK_SECTOR - 1;
+    let backup_entries_lba = last_lba - (table_len as u64).div_ceil(DISK_SECTOR);
+    let last_usable = backup_entries_lba - 1;
+    let first = start / DISK_SECTOR;
+    let last = ((start.saturating_add(size)) / DISK_SECTOR)
+        .saturating_sub(1)
+        .min(last_usable);
+    if last < first || (last - first + 1) * DISK_SECTOR < MIN_NEW_PARTITION {
+        return Err(no_room());
+    }
+
+    let entries = &mut head[entries_offset..entries_offset + table_len];
+    let slot = (0..count)
+        .find(|i| {
+            entries[i * entry_size..i * entry_size + 16]
+                .iter()
+                .all(|b| *b == 0)
+        })
+        .ok_or_else(|| io::Error::other("The GPT has no free partition entry"))?;
+
+    let entry = &mut entries[slot * entry_size..(slot + 1) * entry_size];
+    entry.fill(0);
+    entry[0..16].copy_from_slice(&kind.gpt);
+    entry[16..32].copy_from_slice(&random_guid()?);
+    entry[32..40].copy_from_slice(&first.to_le_bytes());
+    entry[40..48].copy_from_slice(&last.to_le_bytes());
+    for (i, unit) in label.encode_utf16().take(GPT_NAME_UNITS).enumerate() {
+        entry[56 + i * 2..58 + i * 2].copy_from_slice(&unit.to_le_bytes());
+    }
+    let entries_crc = crc32fast::hash(entries);
+
+    let mut primary = head[header_offset..header_offset + SECTOR].to_vec();
+    primary[32..40].copy_from_slice(&last_lba.to_le_bytes());
+    primary[48..56].copy_from_slice(&last_usable.to_le_bytes());
+    primary[88..92].copy_from_slice(&entries_crc.to_le_bytes());
+    let mut backup = primary.clone();
+    backup[24..32].copy_from_slice(&last_lba.to_le_bytes());
+    backup[32..40].copy_from_slice(&1u64.to_le_bytes());
+    backup[72..80].copy_from_slice(&backup_entries_lba.to_le_bytes());
+    seal_gpt_header(&mut primary, header_size);
+    seal_gpt_header(&mut backup, header_size);
+
+    let entries = &head[entries_offset..entries_offset + table_len];
+    device.write_all_at(entries, backup_entries_lba * DISK_SECTOR)?;
+    device.write_all_at(&backup, last_lba * DISK_SECTOR)?;
+    device.write_all_at(entries, entries_offset as u64)?;
+    device.write_all_at(&primary, header_offset as u64)?;
+
+    // רשומה מגינה שמכסה את כל הדיסק גדלה איתו. ב-MBR היברידי לא נוגעים
+    for i in 0..4 {
+        let offset = MBR_TABLE_OFFSET + i * 16;
+        let entry = &head[offset..offset + 16];
+        if entry[4] == MBR_PROTECTIVE && u32_at(entry, 8) == 1 {
+            let sectors = last_lba.min(u32::MAX as u64) as u32;
+            device.write_all_at(&sectors.to_le_bytes(), offset as u64 + 12)?;
+        }
+    }
+
+    Ok(NewPartition {
+        index: slot as u32 + 1,
+        start: first * DISK_SECTOR,
+        size: (last - first + 1) * DISK_SECTOR,
+    })
+}
+
+// ה-CRC של הכותרת מחושב כששדה ה-CRC עצמו מאופס
+fn seal_gpt_header(header: &mut [u8], header_size: usize) {
+    header[16..20].fill(0);
+    let crc = crc32fast::hash(&header[..header_size]);
+    header[16..20].copy_from_slice(&crc.to_le_bytes());
+}
+
+fn random_guid() -> io::Result<[u8; 16]> {
+    let mut guid = [0u8; 16];
+    File::open("/dev/urandom")?.read_exact(&mut guid)?;
+    // גרסה 4 ו-variant של RFC 4122. השדה השלישי שמור little-endian
+    guid[7] = (guid[7] & 0x0F) | 0x40;
+    guid[8] = (guid[8] & 0x3F) | 0x80;
+    Ok(guid)
+}
+
+fn no_room() -> io::Error {
+    io::Error::other("Not enough free space after the image for a new partition")
+}
+
+pub fn reread_partitions(device: &File) -> io::Result<()> {
+    let mut attempt = 1;
+    loop {
+        let ret = unsafe { libc::ioctl(device.as_raw_fd(), BLKRRPART, 0) };
+        if ret == 0 {
+            return Ok(());
+        }
+        let e = io::Error::last_os_error();
+        if e.raw_os_error() != Some(libc::EBUSY) || attempt == REREAD_ATTEMPTS {
+            return Err(e);
+        }
+        attempt += 1;
+        thread::sleep(REREAD_RETRY);
+    }
+}
+
+// /dev/sdb -> /dev/sdb3, אבל /dev/nvme0n1 -> /dev/nvme0n1p3
+pub fn partition_path(device: &Path, index: u32) -> PathBuf {
+    let name = device.to_string_lossy();
+    if name.ends_with(|c: char| c.is_ascii_digit()) {
+        format!("{}p{}", name, index).into()
+    } else {
+        format!("{}{}", name, index).into()
+    }
+}
+
+// את ה-node יוצר udev, קצת אחרי BLKRRPART
+pub fn wait_for_node(node: &Path) -> io::Result<()> {
+    let started = Instant::now();
+    while !node.exists() {
+        if started.elapsed() > PARTITION_NODE_TIMEOUT {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} did not appear", node.display()),
+            ));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
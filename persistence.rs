@@ -0,0 +1,89 @@
+// מחיצת persistence לתמונות live של Ubuntu/Debian: זיהוי לפי תיקיית השורש של ה-ISO,
+// ו-ext4 עם התווית שה-initramfs מחפש. את המחיצה עצמה מוסיף partitions::append_partition
+use crate::partitions::u32_at;
+use serde::Serialize;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+const ISO_SECTOR: usize = 2048;
+// רשומת תיקיית השורש בתוך ה-Primary Volume Descriptor
+const PVD_ROOT_RECORD: usize = 156;
+const ISO_DIRECTORY_FLAG: u8 = 0x02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LiveFlavor {
+    // Ubuntu וכל מה שבנוי על casper
+    Casper,
+    // Debian live-boot
+    LiveBoot,
+}
+
+impl LiveFlavor {
+    // התווית שלפיה מערכת ה-live מוצאת את המחיצה
+    pub fn label(self) -> &'static str {
+        match self {
+            LiveFlavor::Casper => "casper-rw",
+            LiveFlavor::LiveBoot => "persistence",
+        }
+    }
+}
+
+// היכן בתמונה יושבת תיקיית השורש: (offset, אורך) בבייטים
+pub fn root_directory_extent(pvd: &[u8]) -> Option<(usize, usize)> {
+    let record = pvd.get(PVD_ROOT_RECORD..PVD_ROOT_RECORD + 34)?;
+    let lba = u32_at(record, 2) as usize;
+    let len = u32_at(record, 10) as usize;
+    Some((lba * ISO_SECTOR, len))
+}
+
+// casper ו-live-boot מחפשים כל אחד את התיקייה שלו בשורש ה-ISO
+pub fn live_flavor(root_dir: &[u8]) -> Option<LiveFlavor> {
+    let mut offset = 0;
+    while offset < root_dir.len() {
+        let len = root_dir[offset] as usize;
+        // רשומה לא חוצה סקטור: 0 = סוף הסקטור, ממשיכים בבא אחריו
+        if len == 0 {
+            offset = (offset / ISO_SECTOR + 1) * ISO_SECTOR;
+            continue;
+        }
+        let record = root_dir.get(offset..offset + len)?;
+        let flags = *record.get(25)?;
+        let name_len = *record.get(32)? as usize;
+        let name = record.get(33..33 + name_len)?;
+        if flags & ISO_DIRECTORY_FLAG != 0 {
+            if name.eq_ignore_ascii_case(b"casper") {
+                return Some(LiveFlavor::Casper);
+            }
+            if name.eq_ignore_ascii_case(b"live") {
+                return Some(LiveFlavor::LiveBoot);
+            }
+        }
+        offset += len;
+    }
+    None
+}
+
+pub fn format_ext4(partition: &Path, flavor: LiveFlavor) -> io::Result<()> {
+    let mut mkfs = Command::new("mkfs.ext4");
+    mkfs.args(["-F", "-q", "-L", flavor.label()]);
+
+    // live-boot מתעלם ממחיצה בלי persistence.conf בשורש. -d ממלא אותה בלי mount
+    let staging = std::env::temp_dir().join(format!("gtkburn-persistence-{}", std::process::id()));
+    if flavor == LiveFlavor::LiveBoot {
+        std::fs::create_dir_all(&staging)?;
+        std::fs::write(staging.join("persistence.conf"), "/ union\n")?;
+        mkfs.arg("-d").arg(&staging);
+    }
+
+    let output = mkfs.arg(partition).output();
+    let _ = std::fs::remove_dir_all(&staging);
+    let output = output?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "mkfs.ext4 failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use pgp::composed::{Deserializable, SignedPublicKey, StandaloneSignature};
+use pgp::types::{KeyId, PublicKeyTrait};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// סיומות של חתימות מנותקות - הקובץ החתום הוא אותו שם בלי הסיומת
+const SIGNATURE_EXTENSIONS: &[&str] = &["sig", "asc", "gpg", "sign"];
+
+// המפתחות שמולם בודקים: קובץ keyring (בינארי או armored), או מפתח שהמשתמש הדביק.
+// לא נוגעים ב-keyring של gpg - רק במה שנבחר במפורש
+pub enum SigningKey {
+    Keyring(PathBuf),
+    Pasted(String),
+}
+
+pub fn signed_file_for(signature: &Path) -> Option<PathBuf> {
+    let ext = signature.extension()?.to_str()?;
+    if !SIGNATURE_EXTENSIONS.contains(&ext) {
+        return None;
+    }
+    let data = signature.with_extension("");
+    data.is_file().then_some(data)
+}
+
+// מה שיצא מהבדיקה, לפני שהופך להודעה. השמות כמו קודי הסטטוס של gpg
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    GoodSig { signer: String, fingerprint: String },
+    BadSig { signer: String },
+    ExpKeySig { signer: String },
+    RevKeySig { signer: String },
+    NoPubkey { key_id: String },
+}
+
+pub fn verify_detached(
+    data: &Path,
+    signature: &Path,
+    key: &SigningKey,
+    fingerprint: Option<&str>,
+) -> Result<String, String> {
+    let keys = load_keys(key)?;
+    let signature = load_signature(signature)?;
+    describe(check(data, &signature, &keys)?, fingerprint)
+}
+
+fn load_keys(key: &SigningKey) -> Result<Vec<SignedPublicKey>, String> {
+    let bytes = match key {
+        SigningKey::Keyring(path) => std::fs::read(path)
+            .map_err(|e| format!("Could not read keyring {}: {}", path.display(), e))?,
+        SigningKey::Pasted(text) => text.trim().as_bytes().to_vec(),
+    };
+    let parsed = if is_armored(&bytes) {
+        SignedPublicKey::from_armor_many(Cursor::new(bytes))
+            .map(|(keys, _)| keys.collect::<Vec<_>>())
+    } else {
+        Ok(SignedPublicKey::from_bytes_many(Cursor::new(bytes)).collect())
+    };
+    // keyring של הפצה יכול להכיל מפתחות ישנים שלא מתפרסרים - מדלגים עליהם
+    let keys: Vec<_> = parsed
+        .map_err(|e| format!("Not an OpenPGP public key: {}", e))?
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|key| key.verify().is_ok())
+        .collect();
+    if keys.is_empty() {
+        return Err("No usable OpenPGP public key in the selected key".into());
+    }
+    Ok(keys)
+}
+
+fn load_signature(path: &Path) -> Result<StandaloneSignature, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let parsed = if is_armored(&bytes) {
+        StandaloneSignature::from_armor_single(Cursor::new(bytes)).map(|(sig, _)| sig)
+    } else {
+        StandaloneSignature::from_bytes(Cursor::new(bytes))
+    };
+    parsed.map_err(|e| format!("Not an OpenPGP signature: {}", e))
+}
+
+fn is_armored(bytes: &[u8]) -> bool {
+    bytes.trim_ascii_start().starts_with(b"-----BEGIN PGP")
+}
+
+// המפתח (או ה-subkey) שה-issuer של החתימה מצביע עליו. חתימה בלי issuer נבדקת מול כולם
+fn check(
+    data: &Path,
+    signature: &StandaloneSignature,
+    keys: &[SignedPublicKey],
+) -> Result<Outcome, String> {
+    let issuers = signature.signature.issuer();
+    let wanted = |id: KeyId| issuers.is_empty() || issuers.contains(&&id);
+    let mut signer_key = None;
+    for key in keys {
+        if wanted(key.primary_key.key_id()) {
+            if verifies(signature, &key.primary_key, data)? {
+                return Ok(good_or_expired(key));
+            }
+            signer_key.get_or_insert(key);
+        }
+        for subkey in &key.public_subkeys {
+            if wanted(subkey.key.key_id()) {
+                if verifies(signature, &subkey.key, data)? {
+                    return Ok(good_or_expired(key));
+                }
+                signer_key.get_or_insert(key);
+            }
+        }
+    }
+    Ok(match signer_key {
+        Some(key) => Outcome::BadSig {
+            signer: user_id(key),
+        },
+        None => Outcome::NoPubkey {
+            key_id: issuers
+                .first()
+                .map(|id| hex(id.as_ref()))
+                .unwrap_or_default(),
+        },
+    })
+}
+
+// הקובץ נקרא בזרם - ISO של כמה GB לא נטען לזיכרון
+fn verifies(
+    signature: &StandaloneSignature,
+    key: &impl PublicKeyTrait,
+    data: &Path,
+) -> Result<bool, String> {
+    let file = File::open(data).map_err(|e| format!("Could not read {}: {}", data.display(), e))?;
+    Ok(signature
+        .signature
+        .verify(key, BufReader::new(file))
+        .is_ok())
+}
+
+// חתימה תקינה של מפתח שבוטל או פג - gpg מדווח עליה בנפרד, וכך גם כאן
+fn good_or_expired(key: &SignedPublicKey) -> Outcome {
+    let signer = user_id(key);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    if !key.details.revocation_signatures.is_empty() {
+        Outcome::RevKeySig { signer }
+    } else if key.expires_at().is_some_and(|at| at.timestamp() <= now) {
+        Outcome::ExpKeySig { signer }
+    } else {
+        Outcome::GoodSig {
+            signer,
+            fingerprint: hex(key.fingerprint().as_bytes()),
+        }
+    }
+}
+
+fn user_id(key: &SignedPublicKey) -> String {
+    match key.details.users.first() {
+        Some(user) => user.id.id().to_string(),
+        None => hex(key.key_id().as_ref()),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+fn describe(outcome: Outcome, fingerprint: Option<&str>) -> Result<String, String> {
+    let (signer, actual) = match outcome {
+        Outcome::GoodSig {
+            signer,
+            fingerprint,
+        } => (signer, fingerprint),
+        Outcome::BadSig { signer } => {
+            return Err(format!(
+                "BAD signature from {} — the file was modified or corrupted",
+                signer
+            ))
+        }
+        Outcome::ExpKeySig { signer } => {
+            return Err(format!("Signed by {}, but that key has expired", signer))
+        }
+        Outcome::RevKeySig { signer } => {
+            return Err(format!("Signed by {}, but that key was revoked", signer))
+        }
+        Outcome::NoPubkey { key_id } => {
+            return Err(format!(
+                "Unknown signer (key {}) — select the distribution's signing key",
+                key_id
+            ))
+        }
+    };
+
+    // טביעת אצבע שהמשתמש הדביק - רווחים לא משנים
+    if let Some(expected) = fingerprint {
+        let expected: String = expected.split_whitespace().collect();
+        if !expected.is_empty() && !actual.eq_ignore_ascii_case(&expected) {
+            return Err(format!(
+                "Good signature from {}, but key {} is not the expected {}",
+                signer, actual, expected
+            ));
+        }
+    }
+
+    Ok(signer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNER: &str = "Ubuntu CD Image Automatic Signing Key (2012) <cdimage@ubuntu.com>";
+    const FINGERPRINT: &str = "843938DF228D22F7B3742BC0D94AA3F0EFE21092";
+
+    #[test]
+    fn good_signature_checks_the_pasted_fingerprint() {
+        let good = || Outcome::GoodSig {
+            signer: SIGNER.into(),
+            fingerprint: FINGERPRINT.into(),
+        };
+        assert_eq!(describe(good(), None), Ok(SIGNER.to_string()));
+        assert_eq!(
+            describe(
+                good(),
+                Some("8439 38DF 228D 22F7 B374  2BC0 D94A A3F0 EFE2 1092")
+            ),
+            Ok(SIGNER.to_string())
+        );
+        assert_eq!(
+            describe(
+                good(),
+                Some("0000 38DF 228D 22F7 B374  2BC0 D94A A3F0 EFE2 1092")
+            ),
+            Err(format!(
+                "Good signature from {}, but key {} is not the expected {}",
+                SIGNER, FINGERPRINT, "000038DF228D22F7B3742BC0D94AA3F0EFE21092"
+            ))
+        );
+    }
+
+    #[test]
+    fn bad_expired_and_unknown_signers_get_their_own_message() {
+        assert_eq!(
+            describe(
+                Outcome::BadSig {
+                    signer: SIGNER.into()
+                },
+                None
+            ),
+            Err(format!(
+                "BAD signature from {} — the file was modified or corrupted",
+                SIGNER
+            ))
+        );
+        assert_eq!(
+            describe(
+                Outcome::ExpKeySig {
+                    signer: SIGNER.into()
+                },
+                None
+            ),
+            Err(format!("Signed by {}, but that key has expired", SIGNER))
+        );
+        assert_eq!(
+            describe(
+                Outcome::RevKeySig {
+                    signer: SIGNER.into()
+                },
+                None
+            ),
+            Err(format!("Signed by {}, but that key was revoked", SIGNER))
+        );
+        // טביעת אצבע לא עוזרת כשאין מפתח בכלל
+        assert_eq!(
+            describe(
+                Outcome::NoPubkey {
+                    key_id: "D94AA3F0EFE21092".into()
+                },
+                Some(FINGERPRINT)
+            ),
+            Err(
+                "Unknown signer (key D94AA3F0EFE21092) — select the distribution's signing key"
+                    .to_string()
+            )
+        );
+    }
+}
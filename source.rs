@@ -0,0 +1,285 @@
+// הצד הקורא של לולאת הכתיבה: thread שממלא את הערוץ ב-chunks לפי הסדר
+use crate::aligned_buf::{AlignedBuffer, DIRECT_ALIGN};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use memmap2::{Advice, Mmap};
+use std::fs::File;
+use std::io::Read;
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+pub(crate) const CHANNEL_DEPTH: usize = 4;
+
+// באפרים שה-reader מחזיק במחזור: מה שבערוץ, מה שאצל הכותב ואחד שנקרא עכשיו, עם מרווח
+pub const POOL_BUFFERS: usize = CHANNEL_DEPTH * 2;
+
+// באפר מה-pool. חוזר אליו כשה-clone האחרון של ה-chunk נזרק (גם אחרי broadcast),
+// כך שה-reader לא מקצה 8MB חדשים על כל chunk
+struct Pooled {
+    data: Buffer,
+    len: usize,
+    pool: Sender<Buffer>,
+}
+
+// מיושר רק כשהכתיבה ב-O_DIRECT. אחרת Vec רגיל
+enum Buffer {
+    Heap(Vec<u8>),
+    Aligned(AlignedBuffer),
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Buffer::Heap(Vec::new())
+    }
+}
+
+impl Deref for Buffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Buffer::Heap(data) => data,
+            Buffer::Aligned(data) => data,
+        }
+    }
+}
+
+impl DerefMut for Buffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Buffer::Heap(data) => data,
+            Buffer::Aligned(data) => data,
+        }
+    }
+}
+
+impl Drop for Pooled {
+    fn drop(&mut self) {
+        // ה-pool בגודל כל הבאפרים אז לא מתמלא. אם ה-reader כבר יצא - הבאפר פשוט משתחרר
+        let _ = self.pool.try_send(std::mem::take(&mut self.data));
+    }
+}
+
+// Arc<[u8]> היה מחייב להעתיק את הבייטים להקצאה משלו, אז chunk ממופה מחזיק את ה-map
+// עצמו וטווח בתוכו. הכותב רואה רק &[u8] בשני המקרים. clone רק מעלה מונה
+#[derive(Clone)]
+enum Bytes {
+    Pooled(Arc<Pooled>),
+    Mapped { map: Arc<Mmap>, range: Range<usize> },
+}
+
+// crc = מה שה-reader חישב מיד אחרי הקריאה (paranoid). הכותב מחשב שוב לפני הכתיבה,
+// וכך תופס ביטים שהתהפכו בזיכרון בדרך
+#[derive(Clone)]
+pub struct Chunk {
+    bytes: Bytes,
+    pub crc: Option<u32>,
+}
+
+impl Chunk {
+    fn new(bytes: Bytes, checksum: bool) -> Self {
+        let mut chunk = Self { bytes, crc: None };
+        if checksum {
+            chunk.crc = Some(crc32fast::hash(&chunk));
+        }
+        chunk
+    }
+}
+
+impl Deref for Chunk {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.bytes {
+            Bytes::Pooled(buffer) => &buffer.data[..buffer.len],
+            Bytes::Mapped { map, range } => &map[range.clone()],
+        }
+    }
+}
+
+pub type ReaderHandle = JoinHandle<std::io::Result<()>>;
+
+// ה-reader מעדכן כמה קרא ותוך כמה זמן, והכותב מחשב מזה מהירות בכל עדכון התקדמות.
+// כשהכותב איטי ה-reader חוסם על ערוץ מלא, אז זה החסם העליון של המקור ולא יותר
+#[derive(Default)]
+pub struct ReadStats {
+    bytes: AtomicU64,
+    elapsed_nanos: AtomicU64,
+}
+
+impl ReadStats {
+    pub fn record(&self, bytes: u64, started: Instant) {
+        self.bytes.store(bytes, Ordering::Relaxed);
+        self.elapsed_nanos
+            .store(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    // ממוצע מתחילת הקריאה. 0 עד העדכון הראשון
+    pub fn speed_mbps(&self) -> f64 {
+        let nanos = self.elapsed_nanos.load(Ordering::Relaxed);
+        if nanos == 0 {
+            return 0.0;
+        }
+        self.bytes.load(Ordering::Relaxed) as f64 / (nanos as f64 / 1e9) / (1024.0 * 1024.0)
+    }
+}
+
+// read() רגיל לבאפר מה-pool בכל סיבוב - עובד עם כל מקור, גם דחוס.
+// direct_io: הבאפרים מיושרים לעמוד וכל chunk מלא עד הסוף, כך שרק האחרון קצר -
+// פענוח מחזיר חלקי chunks שהיו מזיזים כל כתיבה אחריהם מהיישור
+pub fn spawn_read_thread<R: Read + Send + 'static>(
+    mut source: R,
+    chunk_size: usize,
+    checksum: bool,
+    direct_io: bool,
+    cancel_flag: Arc<AtomicBool>,
+    stats: Arc<ReadStats>,
+) -> (Receiver<Chunk>, ReaderHandle) {
+    let (data_tx, data_rx) = bounded::<Chunk>(CHANNEL_DEPTH);
+    // ערוץ ולא תור בלי נעילה: כשכל הבאפרים בדרך ה-reader צריך לחכות שאחד יחזור,
+    // וזה בדיוק recv חוסם. ההקצאה היחידה היא כאן, לפני הסיבוב הראשון
+    let (pool_tx, pool_rx) = bounded::<Buffer>(POOL_BUFFERS);
+    for _ in 0..POOL_BUFFERS {
+        let _ = pool_tx.send(if direct_io {
+            Buffer::Aligned(AlignedBuffer::new(chunk_size, DIRECT_ALIGN))
+        } else {
+            Buffer::Heap(vec![0u8; chunk_size])
+        });
+    }
+
+    let reader = thread::spawn(move || -> std::io::Result<()> {
+        let started = Instant::now();
+        let mut read_total = 0;
+        loop {
+            if cancel_flag.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+
+            // חוסם עד שבאפר חוזר. גם כותב שנכשל מחזיר אותם - הערוץ שלו נזרק עם ה-chunks
+            // שבתוכו. pool_tx נשאר אצלנו, אז recv לא נכשל בפועל
+            let Ok(mut buffer) = pool_rx.recv() else {
+                return Ok(());
+            };
+
+            let read = if direct_io {
+                read_full(&mut source, &mut buffer)
+            } else {
+                source.read(&mut buffer)
+            };
+            let read_bytes = match read {
+                Ok(0) => return Ok(()),
+                Ok(n) => n,
+                // שגיאת קריאה (למשל ארכיון דחוס פגום) - לא לסיים "בהצלחה" עם תמונה קטועה
+                Err(e) => return Err(e),
+            };
+
+            read_total += read_bytes as u64;
+            stats.record(read_total, started);
+
+            // לא truncate - הבאפר חוזר ל-pool בגודל מלא. len מסמן כמה ממנו נקרא
+            let buffer = Pooled {
+                data: buffer,
+                len: read_bytes,
+                pool: pool_tx.clone(),
+            };
+            let chunk = Chunk::new(Bytes::Pooled(Arc::new(buffer)), checksum);
+            if data_tx.send(chunk).is_err() {
+                return Ok(());
+            }
+        }
+    });
+
+    (data_rx, reader)
+}
+
+// קוראים עד שהבאפר מלא או שהמקור נגמר. פחות מהבאפר = סוף המקור
+pub(crate) fn read_full<R: Read>(source: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match source.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+// ממפה את הקובץ כולו ושולח טווחים בתוכו - בלי העתקה ב-userspace. הקרנל מעתיק פעם אחת,
+// ישר מה-page cache ב-write של הכותב. רק לקובץ לא דחוס
+pub fn spawn_mmap_thread(
+    file: &File,
+    total: u64,
+    chunk_size: usize,
+    checksum: bool,
+    cancel_flag: Arc<AtomicBool>,
+    stats: Arc<ReadStats>,
+) -> std::io::Result<(Receiver<Chunk>, ReaderHandle)> {
+    // הקובץ עלול להשתנות מתחתינו - כמו כל read, מקבלים את מה שיש בו כרגע
+    let map = Arc::new(unsafe { Mmap::map(file)? });
+    map.advise(Advice::Sequential)?;
+    let total = (total as usize).min(map.len());
+
+    let (data_tx, data_rx) = bounded::<Chunk>(CHANNEL_DEPTH);
+
+    let reader = thread::spawn(move || -> std::io::Result<()> {
+        let started = Instant::now();
+        let mut offset = 0;
+        while offset < total {
+            if cancel_flag.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+
+            let end = (offset + chunk_size).min(total);
+            // הקריאה מהדיסק קורית כאן ברקע, ולא ב-page fault של הכותב
+            let _ = map.advise_range(Advice::WillNeed, offset, end - offset);
+            stats.record(end as u64, started);
+
+            let chunk = Chunk::new(
+                Bytes::Mapped {
+                    map: map.clone(),
+                    range: offset..end,
+                },
+                checksum,
+            );
+            if data_tx.send(chunk).is_err() {
+                return Ok(());
+            }
+            offset = end;
+        }
+        Ok(())
+    });
+
+    Ok((data_rx, reader))
+}
+
+// אותו chunk לכמה כותבים, כך שהמקור נקרא פעם אחת. כותב שנכשל סוגר את הערוץ שלו ויוצא
+// מהרשימה בלי לעצור את האחרים. ה-handle מחזיר את התוצאה של ה-reader המקורי
+pub fn spawn_broadcast(
+    data_rx: Receiver<Chunk>,
+    reader: ReaderHandle,
+    targets: usize,
+) -> (Vec<Receiver<Chunk>>, ReaderHandle) {
+    let (mut senders, receivers): (Vec<_>, Vec<_>) = (0..targets)
+        .map(|_| bounded::<Chunk>(CHANNEL_DEPTH))
+        .unzip();
+
+    let broadcaster = thread::spawn(move || -> std::io::Result<()> {
+        for chunk in data_rx {
+            senders.retain(|data_tx| data_tx.send(chunk.clone()).is_ok());
+            if senders.is_empty() {
+                break;
+            }
+        }
+        // הכותבים מסיימים רק אחרי שכל ה-chunks עברו
+        drop(senders);
+        reader.join().unwrap_or(Ok(()))
+    });
+
+    (receivers, broadcaster)
+}
@@ -0,0 +1,370 @@
+// ממשק טקסט לשרתים בלי X/Wayland: אותו BurnEngine, רק ציור אחר של האירועים.
+// בלי תרגום - רוב הטרמינלים לא מציגים עברית מימין לשמאל
+use crate::burn_engine::{
+    self, BurnBackend, BurnConfig, BurnEngine, BurnEvent, ErrorDetail, VerifyMode,
+};
+use crate::{device_still_present, format_bytes, format_duration, scan_devices, BurnFilter};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::ExecutableCommand;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph};
+use std::io::{self, Stdout};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// כל כמה זמן מציירים מחדש כשאין הקשה
+const TICK: Duration = Duration::from_millis(100);
+
+// נבדק לפני ש-GTK מפרסר את argv
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == "--tui")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Iso,
+    Devices,
+}
+
+// מה שמחכה ל-y/n
+enum Prompt {
+    // התווית של הכונן, כדי לוודא שזה עדיין אותו מקל
+    Erase(BurnConfig, String),
+    // מצב בטוח עצר את הצריבה - הפעם דורסים
+    Overwrite(BurnConfig),
+}
+
+struct App {
+    iso: String,
+    devices: Vec<(String, String)>,
+    selected: ListState,
+    focus: Focus,
+    prompt: Option<Prompt>,
+    // הצריבה שרצה עכשיו. None = אפשר להתחיל חדשה או לצאת
+    running: Option<BurnConfig>,
+    ratio: f64,
+    gauge_label: String,
+    status: String,
+    // התוצאה של הצריבה האחרונה, לקוד היציאה
+    succeeded: bool,
+}
+
+impl App {
+    fn new() -> Self {
+        let mut app = Self {
+            iso: String::new(),
+            devices: Vec::new(),
+            selected: ListState::default(),
+            focus: Focus::Iso,
+            prompt: None,
+            running: None,
+            ratio: 0.0,
+            gauge_label: String::new(),
+            status: "Type the image path, Tab to the drive list, Enter to burn".into(),
+            succeeded: false,
+        };
+        app.rescan();
+        app
+    }
+
+    // כמו ב-GUI: הכונן שנבחר נשאר נבחר אחרי רענון
+    fn rescan(&mut self) {
+        let previous = self
+            .selected
+            .selected()
+            .and_then(|i| self.devices.get(i))
+            .map(|(path, _)| path.clone());
+        self.devices = scan_devices(BurnFilter::RemovableOnly);
+        let index = previous
+            .and_then(|path| self.devices.iter().position(|(p, _)| *p == path))
+            .or((!self.devices.is_empty()).then_some(0));
+        self.selected.select(index);
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let Some(last) = self.devices.len().checked_sub(1) else {
+            return;
+        };
+        let i = self.selected.selected().unwrap_or(0);
+        self.selected.select(Some(if down {
+            (i + 1).min(last)
+        } else {
+            i.saturating_sub(1)
+        }));
+    }
+
+    fn ask_to_burn(&mut self) {
+        if self.running.is_some() {
+            return;
+        }
+        let iso = PathBuf::from(self.iso.trim());
+        if !iso.is_file() {
+            self.status = format!("{} is not a file", iso.display());
+            return;
+        }
+        let Some((device, label)) = self.selected.selected().and_then(|i| self.devices.get(i))
+        else {
+            self.status = "No drive selected — press r to rescan".into();
+            return;
+        };
+        self.status = format!("Erase everything on {}? (y/n)", label);
+        self.prompt = Some(Prompt::Erase(
+            BurnConfig {
+                iso_path: iso,
+                device_path: PathBuf::from(device),
+                device_link: burn_engine::stable_device_link(Path::new(device)),
+                verify_mode: VerifyMode::Full,
+                ..Default::default()
+            },
+            label.clone(),
+        ));
+    }
+
+    // y מתחיל את הצריבה, כל מקש אחר מבטל
+    fn answer(&mut self, engine: &BurnEngine, prompt: Prompt, yes: bool) {
+        if !yes {
+            self.status = "Cancelled — nothing was written".into();
+            return;
+        }
+        let config = match prompt {
+            Prompt::Erase(config, label) => {
+                let device = config.device_path.to_string_lossy();
+                if !device_still_present(&device, &label) {
+                    self.status = "Selected device is no longer present — rescan".into();
+                    return;
+                }
+                config
+            }
+            Prompt::Overwrite(config) => BurnConfig {
+                force_overwrite: true,
+                ..config
+            },
+        };
+        self.running = Some(config.clone());
+        engine.start(config);
+    }
+
+    fn handle_event(&mut self, event: BurnEvent) {
+        match event {
+            BurnEvent::Preparing => {
+                self.ratio = 0.0;
+                self.gauge_label.clear();
+                self.status = "Preparing…".into();
+            }
+            BurnEvent::Progress {
+                written,
+                total,
+                write_speed_mbps,
+                eta_secs,
+                ..
+            } => {
+                // total = 0: מקור דחוס בגודל לא ידוע, אין אחוזים ואין ETA
+                self.ratio = if total == 0 {
+                    0.0
+                } else {
+                    written as f64 / total as f64
+                };
+                self.gauge_label = if total == 0 {
+                    format!("{}  {:.1} MB/s", format_bytes(written), write_speed_mbps)
+                } else {
+                    format!(
+                        "{} / {}  {:.1} MB/s  ETA {}",
+                        format_bytes(written),
+                        format_bytes(total),
+                        write_speed_mbps,
+                        format_duration(eta_secs as f64)
+                    )
+                };
+                self.status = "Writing… (Esc to cancel)".into();
+            }
+            BurnEvent::Verifying { checked, total } => {
+                self.ratio = checked as f64 / total.max(1) as f64;
+                self.gauge_label = format!("{:.0}%", self.ratio * 100.0);
+                self.status = "Verifying… (Esc to cancel)".into();
+            }
+            BurnEvent::Warning { message } => self.status = format!("Warning: {}", message),
+            BurnEvent::Finished {
+                bytes,
+                elapsed_secs,
+                verified,
+            } => {
+                self.ratio = 1.0;
+                self.running = None;
+                self.succeeded = true;
+                self.status = format!(
+                    "Done in {} — {}{}",
+                    format_duration(elapsed_secs),
+                    format_bytes(bytes),
+                    if verified { ", verified OK" } else { "" }
+                );
+            }
+            BurnEvent::Cancelled => {
+                self.running = None;
+                self.succeeded = false;
+                self.status = "Cancelled".into();
+            }
+            BurnEvent::Error(e) => {
+                let running = self.running.take();
+                self.succeeded = false;
+                match (e.fs_type(), e.detail(), running) {
+                    (Some(fs_type), _, Some(config)) => {
+                        self.status = format!(
+                            "{} already has a {} filesystem — erase it and burn anyway? (y/n)",
+                            config.device_path.display(),
+                            fs_type
+                        );
+                        self.prompt = Some(Prompt::Overwrite(config));
+                    }
+                    // הדריסה מנתקת את המחיצות קודם
+                    (_, Some(ErrorDetail::Unmount { mounts }), Some(config))
+                        if !mounts.is_empty() =>
+                    {
+                        self.status = format!(
+                            "{} is mounted at {} — unmount it and burn? (y/n)",
+                            config.device_path.display(),
+                            mounts[0].display()
+                        );
+                        self.prompt = Some(Prompt::Overwrite(config));
+                    }
+                    _ => self.status = format!("Error: {}", e),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn run() -> bool {
+    if let Err(e) = enable_raw_mode() {
+        eprintln!("Failed to start the terminal UI: {}", e);
+        return false;
+    }
+    let result = io::stdout()
+        .execute(EnterAlternateScreen)
+        .and_then(|_| Terminal::new(CrosstermBackend::new(io::stdout())))
+        .and_then(|mut terminal| event_loop(&mut terminal));
+    // גם אחרי שגיאה - אחרת הטרמינל נשאר במצב raw
+    let _ = io::stdout().execute(LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+    result.unwrap_or_else(|e| {
+        eprintln!("Terminal UI failed: {}", e);
+        false
+    })
+}
+
+fn event_loop(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<bool> {
+    let engine = BurnEngine::new();
+    let events = engine.events();
+    let mut app = App::new();
+    loop {
+        for event in events.try_iter() {
+            app.handle_event(event);
+        }
+        terminal.draw(|frame| draw(frame, &mut app))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if let Some(prompt) = app.prompt.take() {
+            let yes = matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y'));
+            app.answer(&engine, prompt, yes);
+            continue;
+        }
+        match (key.code, app.focus) {
+            // Esc ראשון מבטל צריבה שרצה, השני יוצא
+            (KeyCode::Esc, _) | (KeyCode::Char('q'), Focus::Devices) => {
+                if app.running.is_none() {
+                    return Ok(app.succeeded);
+                }
+                engine.cancel();
+                app.status = "Cancelling…".into();
+            }
+            (KeyCode::Tab, Focus::Iso) => app.focus = Focus::Devices,
+            (KeyCode::Tab, Focus::Devices) => app.focus = Focus::Iso,
+            (KeyCode::Enter, _) => app.ask_to_burn(),
+            (KeyCode::Char(c), Focus::Iso) => app.iso.push(c),
+            (KeyCode::Backspace, Focus::Iso) => {
+                app.iso.pop();
+            }
+            (KeyCode::Up, Focus::Devices) => app.move_selection(false),
+            (KeyCode::Down, Focus::Devices) => app.move_selection(true),
+            (KeyCode::Char('r'), Focus::Devices) => app.rescan(),
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(4),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(frame.size());
+    let border = |focused: bool| {
+        if focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        }
+    };
+
+    let iso = Paragraph::new(app.iso.as_str()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border(app.focus == Focus::Iso))
+            .title(" Image "),
+    );
+    frame.render_widget(iso, rows[0]);
+    if app.focus == Focus::Iso {
+        frame.set_cursor(
+            rows[0].x + 1 + app.iso.chars().count() as u16,
+            rows[0].y + 1,
+        );
+    }
+
+    let items: Vec<ListItem> = if app.devices.is_empty() {
+        vec![ListItem::new("No USB drives detected")]
+    } else {
+        app.devices
+            .iter()
+            .map(|(_, label)| ListItem::new(label.as_str()))
+            .collect()
+    };
+    let devices = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border(app.focus == Focus::Devices))
+                .title(" Drives (r to rescan) "),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(devices, rows[1], &mut app.selected);
+
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title(" Progress "))
+        .gauge_style(Style::default().fg(Color::Green))
+        .ratio(app.ratio.clamp(0.0, 1.0))
+        .label(app.gauge_label.as_str());
+    frame.render_widget(gauge, rows[2]);
+
+    let status = Paragraph::new(app.status.as_str()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Tab: switch  Enter: burn  Esc: cancel / quit "),
+    );
+    frame.render_widget(status, rows[3]);
+}
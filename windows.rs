@@ -0,0 +1,316 @@
+// תמונות התקנה של Windows: dd שלהן לא עולה ברוב הקושחות, אז מעתיקים את הקבצים למחיצת
+// FAT32. ב-ISO של מיקרוסופט ה-ISO 9660 מכיל רק README, אז קוראים את עץ ה-UDF.
+// ההחלטה אם זו תמונת התקנה נמצאת ב-image_info
+use crate::partitions::{u32_at, u64_at};
+use serde::Serialize;
+use std::fs::File;
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+const UDF_SECTOR: u64 = 2048;
+const ANCHOR_SECTOR: u64 = 256;
+// מזהי ה-tag של ECMA-167
+const TAG_ANCHOR: u16 = 2;
+const TAG_PARTITION: u16 = 5;
+const TAG_LOGICAL_VOLUME: u16 = 6;
+const TAG_TERMINATOR: u16 = 8;
+const TAG_FILE_SET: u16 = 256;
+const TAG_FILE_ID: u16 = 257;
+const TAG_FILE_ENTRY: u16 = 261;
+const TAG_EXTENDED_FILE_ENTRY: u16 = 266;
+// ב-Volume Descriptor Sequence יש בפועל פחות מעשרה descriptors
+const MAX_DESCRIPTORS: u32 = 64;
+// תיקייה גדולה מזה היא זבל ולא תמונה אמיתית
+const MAX_DIRECTORY: u64 = 16 * 1024 * 1024;
+const FID_DIRECTORY: u8 = 0x02;
+const FID_DELETED: u8 = 0x04;
+const FID_PARENT: u8 = 0x08;
+
+// הקובץ הגדול ביותר ש-FAT32 מחזיק
+pub const FAT32_MAX_FILE: u64 = 4 * 1024 * 1024 * 1024 - 1;
+// הגודל ש-Microsoft ממליצה עליו לחלקי .swm
+const SPLIT_SIZE_MB: u64 = 3800;
+const FAT_LABEL_LEN: usize = 11;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstallImage {
+    // יחסית לשורש ה-ISO, באותיות כמו שהן בתמונה
+    pub name: String,
+    pub size: u64,
+}
+
+impl InstallImage {
+    pub fn needs_split(&self) -> bool {
+        self.size > FAT32_MAX_FILE
+    }
+}
+
+// File Entry: הגודל והיכן יושב התוכן
+pub(crate) struct Entry {
+    pub(crate) size: u64,
+    data: EntryData,
+}
+
+pub(crate) enum EntryData {
+    // (LBN בתוך המחיצה, אורך בבייטים)
+    Extents(Vec<(u32, u64)>),
+    // קבצים ותיקיות קטנים יושבים בתוך ה-File Entry עצמו
+    Embedded(Vec<u8>),
+}
+
+pub(crate) struct Udf<'a> {
+    iso: &'a File,
+    // הסקטור שבו מתחילה מחיצת ה-UDF
+    partition_start: u64,
+}
+
+fn tag(sector: &[u8]) -> u16 {
+    u16::from_le_bytes([sector[0], sector[1]])
+}
+
+fn read_sector(iso: &File, sector: u64) -> io::Result<Vec<u8>> {
+    let mut buf = vec![0u8; UDF_SECTOR as usize];
+    iso.read_exact_at(&mut buf, sector * UDF_SECTOR)?;
+    Ok(buf)
+}
+
+impl<'a> Udf<'a> {
+    // anchor -> Volume Descriptor Sequence -> File Set Descriptor -> תיקיית השורש.
+    // None לכל תמונה בלי UDF
+    pub(crate) fn open(iso: &'a File) -> io::Result<Option<(Self, Entry)>> {
+        let anchor = match read_sector(iso, ANCHOR_SECTOR) {
+            Ok(sector) => sector,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if tag(&anchor) != TAG_ANCHOR {
+            return Ok(None);
+        }
+        let sequence_len = u32_at(&anchor, 16) / UDF_SECTOR as u32;
+        let sequence_start = u32_at(&anchor, 20) as u64;
+
+        let mut partition_start = None;
+        let mut file_set = None;
+        for i in 0..sequence_len.min(MAX_DESCRIPTORS) {
+            let descriptor = read_sector(iso, sequence_start + i as u64)?;
+            match tag(&descriptor) {
+                TAG_PARTITION => partition_start = Some(u32_at(&descriptor, 188) as u64),
+                // long_ad של ה-File Set Descriptor. בתמונות של מחיצה אחת תמיד מחיצה 0
+                TAG_LOGICAL_VOLUME => file_set = Some(u32_at(&descriptor, 252) as u64),
+                TAG_TERMINATOR => break,
+                _ => {}
+            }
+        }
+        let (Some(partition_start), Some(file_set)) = (partition_start, file_set) else {
+            return Ok(None);
+        };
+
+        let udf = Udf {
+            iso,
+            partition_start,
+        };
+        let descriptor = read_sector(iso, partition_start + file_set)?;
+        if tag(&descriptor) != TAG_FILE_SET {
+            return Ok(None);
+        }
+        let Some(root) = udf.entry(u32_at(&descriptor, 404))? else {
+            return Ok(None);
+        };
+        Ok(Some((udf, root)))
+    }
+
+    fn entry(&self, lbn: u32) -> io::Result<Option<Entry>> {
+        let sector = read_sector(self.iso, self.partition_start + lbn as u64)?;
+        // ב-Extended File Entry יש עוד 40 בייט לפני שדות האורך
+        let lengths = match tag(&sector) {
+            TAG_FILE_ENTRY => 168,
+            TAG_EXTENDED_FILE_ENTRY => 208,
+            _ => return Ok(None),
+        };
+        let size = u64_at(&sector, 56);
+        let extended_attributes = u32_at(&sector, lengths) as usize;
+        let descriptors_len = u32_at(&sector, lengths + 4) as usize;
+        let start = lengths + 8 + extended_attributes;
+        let Some(descriptors) = sector.get(start..start + descriptors_len) else {
+            return Ok(None);
+        };
+
+        // שלושת הביטים התחתונים של ה-ICB flags: short_ad, long_ad או תוכן מוטמע
+        let stride = match u16::from_le_bytes([sector[34], sector[35]]) & 0x07 {
+            0 => 8,
+            1 => 16,
+            3 => {
+                return Ok(Some(Entry {
+                    size,
+                    data: EntryData::Embedded(descriptors.to_vec()),
+                }))
+            }
+            _ => return Ok(None),
+        };
+        let extents = descriptors
+            .chunks_exact(stride)
+            .map(|ad| (u32_at(ad, 0), u32_at(ad, 4)))
+            // שני הביטים העליונים: 0 = extent שנכתב. השאר הם שטח ריק או המשך הרשימה
+            .filter(|(len, _)| len >> 30 == 0 && *len > 0)
+            .map(|(len, lbn)| (lbn, len as u64))
+            .collect();
+        Ok(Some(Entry {
+            size,
+            data: EntryData::Extents(extents),
+        }))
+    }
+
+    // רק לתיקיות - קבצים לא נקראים כאן
+    fn read(&self, entry: &Entry) -> io::Result<Vec<u8>> {
+        if entry.size > MAX_DIRECTORY {
+            return Err(io::Error::other("UDF directory is implausibly large"));
+        }
+        let mut data = match &entry.data {
+            EntryData::Embedded(data) => data.clone(),
+            EntryData::Extents(extents) => {
+                let mut data = Vec::with_capacity(entry.size as usize);
+                for (lbn, len) in extents {
+                    let mut buf = vec![0u8; (*len).min(MAX_DIRECTORY) as usize];
+                    self.iso.read_exact_at(
+                        &mut buf,
+                        (self.partition_start + *lbn as u64) * UDF_SECTOR,
+                    )?;
+                    data.extend_from_slice(&buf);
+                }
+                data
+            }
+        };
+        data.truncate(entry.size as usize);
+        Ok(data)
+    }
+
+    // שם הרשומה בתיקייה בלי הבדל בין אותיות גדולות לקטנות, והשם כמו שהוא כתוב בתמונה
+    pub(crate) fn lookup(
+        &self,
+        directory: &Entry,
+        name: &str,
+        want_directory: bool,
+    ) -> io::Result<Option<(String, Entry)>> {
+        let data = self.read(directory)?;
+        let mut offset = 0;
+        while offset + 38 <= data.len() {
+            let fid = &data[offset..];
+            if tag(fid) != TAG_FILE_ID {
+                break;
+            }
+            let characteristics = fid[18];
+            let name_len = fid[19] as usize;
+            let implementation_len = u16::from_le_bytes([fid[36], fid[37]]) as usize;
+            let Some(raw) = fid.get(38 + implementation_len..38 + implementation_len + name_len)
+            else {
+                break;
+            };
+            let is_directory = characteristics & FID_DIRECTORY != 0;
+            if characteristics & (FID_PARENT | FID_DELETED) == 0 && is_directory == want_directory {
+                let found = decode_name(raw);
+                if found.eq_ignore_ascii_case(name) {
+                    return Ok(self.entry(u32_at(fid, 24))?.map(|entry| (found, entry)));
+                }
+            }
+            // כל רשומה מרופדת לכפולה של 4
+            offset += (38 + implementation_len + name_len + 3) & !3;
+        }
+        Ok(None)
+    }
+}
+
+// OSTA compressed unicode: בייט ראשון 8 = בייט לתו, 16 = UTF-16 big-endian
+fn decode_name(raw: &[u8]) -> String {
+    match raw.split_first() {
+        Some((8, name)) => name.iter().map(|b| *b as char).collect(),
+        Some((16, name)) => String::from_utf16_lossy(
+            &name
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect::<Vec<_>>(),
+        ),
+        _ => String::new(),
+    }
+}
+
+// תווית ה-FAT מה-Volume ID של ה-ISO (CCCOMA_X64FRE וכו'), קצוצה ל-11 תווים
+pub fn fat_label(pvd: &[u8]) -> String {
+    let label: String = pvd
+        .get(40..72)
+        .map(|id| String::from_utf8_lossy(id).into_owned())
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .take(FAT_LABEL_LEN)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if label.is_empty() {
+        "WINDOWS".into()
+    } else {
+        label
+    }
+}
+
+// כל הקבצים מתחת ל-root: נתיב יחסי וגודל. תיקיות ריקות לא חשובות להתקנה
+pub fn list_files(root: &Path) -> io::Result<Vec<(PathBuf, u64)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        for entry in std::fs::read_dir(root.join(&relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                pending.push(path);
+            } else {
+                files.push((path, metadata.len()));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// install.wim -> install.swm, install2.swm... Windows Setup מחבר אותם בעצמו.
+// false = בוטל באמצע
+pub fn split_wim(wim: &Path, target: &Path, cancel_flag: &AtomicBool) -> io::Result<bool> {
+    let mut child = Command::new("wimlib-imagex")
+        .arg("split")
+        .arg(wim)
+        .arg(target)
+        .arg(SPLIT_SIZE_MB.to_string())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                io::Error::other("wimlib-imagex is needed to split install.wim for FAT32")
+            } else {
+                e
+            }
+        })?;
+    loop {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(false);
+        }
+        if let Some(status) = child.try_wait()? {
+            if status.success() {
+                return Ok(true);
+            }
+            let output = child.wait_with_output()?;
+            return Err(io::Error::other(format!(
+                "wimlib-imagex split failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+}